@@ -0,0 +1,61 @@
+use entity::maps;
+use records_lib::{Database, map};
+use sea_orm::{ConnectionTrait, EntityTrait as _, QueryOrder as _, QuerySelect as _};
+
+#[derive(clap::Args)]
+pub struct CheckRecordCountsCmd {
+    /// Checks all the maps.
+    #[arg(long, conflicts_with = "map_id", required_unless_present = "map_id")]
+    all: bool,
+
+    /// The ID of the map to check.
+    map_id: Option<u32>,
+}
+
+async fn get_map_ids<C: ConnectionTrait>(
+    conn: &C,
+    cmd: CheckRecordCountsCmd,
+) -> anyhow::Result<Vec<u32>> {
+    if let Some(map_id) = cmd.map_id {
+        return Ok(vec![map_id]);
+    }
+
+    let map_ids = maps::Entity::find()
+        .select_only()
+        .column(maps::Column::Id)
+        .order_by_asc(maps::Column::Id)
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    Ok(map_ids)
+}
+
+pub async fn check(db: Database, cmd: CheckRecordCountsCmd) -> anyhow::Result<()> {
+    let map_ids = get_map_ids(&db.sql_conn, cmd).await?;
+
+    let mut table = prettytable::Table::init(vec![prettytable::row![
+        "Map ID",
+        "View count",
+        "Best count"
+    ]]);
+
+    for map_id in &map_ids {
+        if let Some((view_count, best_count)) =
+            map::record_count_mismatch(&db.sql_conn, *map_id).await?
+        {
+            table.add_row(prettytable::row![map_id, view_count, best_count]);
+        }
+    }
+
+    let n = table.len() - 1;
+
+    if n == 0 {
+        tracing::info!("No mismatch found among {} map(s)", map_ids.len());
+    } else {
+        println!("{table}");
+        tracing::warn!("Found {n} map(s) with mismatched record counts");
+    }
+
+    Ok(())
+}
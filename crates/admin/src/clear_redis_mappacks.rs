@@ -1,18 +1,118 @@
-use deadpool_redis::redis::AsyncCommands as _;
-use records_lib::Database;
+use deadpool_redis::redis;
+use records_lib::{Database, RedisConnection, redis_key};
+
+use crate::inspect_redis::{prefix_pattern, scan_step};
+
+/// Removes the keys starting with the provided prefix, and returns their amount.
+///
+/// The keys are removed with `UNLINK` by batches as they're scanned, so the Redis server isn't
+/// blocked, neither by the listing of the keys nor by the freeing of their memory.
+async fn clear_keys(redis_conn: &mut RedisConnection, prefix: &str) -> anyhow::Result<usize> {
+    let pattern = prefix_pattern(prefix);
+    let mut cursor = 0;
+    let mut n = 0;
+
+    loop {
+        let (next_cursor, keys) = scan_step(redis_conn, &pattern, cursor).await?;
+
+        // A key may be returned more than once during a scan, so we count the actually
+        // removed keys
+        if !keys.is_empty() {
+            let removed: usize = redis::cmd("UNLINK")
+                .arg(&keys)
+                .query_async(&mut *redis_conn)
+                .await?;
+            n += removed;
+        }
+
+        if next_cursor == 0 {
+            break;
+        }
+        cursor = next_cursor;
+    }
+
+    Ok(n)
+}
 
 pub async fn clear(db: Database) -> anyhow::Result<()> {
     let mut redis_conn = db.redis_pool.get().await?;
 
-    let keys: Vec<String> = redis_conn.keys("v3:mappack*").await?;
+    let n = clear_keys(&mut redis_conn, &redis_key::versioned_key("mappack")).await?;
+
+    tracing::info!("Removed {n} key{}", if n > 0 { "s" } else { "" });
 
-    let n = keys.len();
+    Ok(())
+}
+
+/// Removes the keys of the versions of their schema prior to the current one.
+pub async fn clear_prior_versions(db: Database) -> anyhow::Result<()> {
+    let mut redis_conn = db.redis_pool.get().await?;
 
-    for key in keys {
-        let _: () = redis_conn.del(key).await?;
+    let mut n = 0;
+    for prefix in redis_key::prior_prefixes() {
+        n += clear_keys(&mut redis_conn, &prefix.key("")).await?;
     }
 
-    tracing::info!("Removed {n} key{}", if n > 0 { "s" } else { "" });
+    tracing::info!(
+        "Removed {n} key{} prior to {}",
+        if n > 0 { "s" } else { "" },
+        redis_key::KeyPrefix(redis_key::KEY_VERSION)
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use deadpool_redis::redis::{self, AsyncCommands as _};
+    use records_lib::{RedisConnection, mappack::AnyMappackId, redis_key::mappack_key};
+
+    use super::clear_keys;
+
+    /// Returns the amount of calls to the `KEYS` command since the start of the Redis server.
+    async fn keys_calls(redis_conn: &mut RedisConnection) -> anyhow::Result<u64> {
+        let info: String = redis::cmd("INFO")
+            .arg("commandstats")
+            .query_async(redis_conn)
+            .await?;
+        let calls = info
+            .lines()
+            .find_map(|line| line.strip_prefix("cmdstat_keys:calls="))
+            .and_then(|stats| stats.split(',').next())
+            .and_then(|calls| calls.parse().ok())
+            .unwrap_or_default();
+        Ok(calls)
+    }
+
+    #[tokio::test]
+    async fn clear_many_mappack_keys() -> anyhow::Result<()> {
+        // The Redis database may be shared with the other tests, so the keys are made unique
+        let prefix = mappack_key(AnyMappackId::Id(&format!(
+            "clear_{}",
+            records_lib::gen_random_str(10)
+        )))
+        .to_string();
+
+        test_env::wrap(async |db| {
+            let mut redis_conn = db.redis_pool.get().await?;
+
+            let mut pipe = redis::pipe();
+            for i in 0..1000 {
+                pipe.set(format!("{prefix}:key_{i}"), i).ignore();
+            }
+            pipe.exec_async(&mut redis_conn).await?;
+
+            let keys_calls_before = keys_calls(&mut redis_conn).await?;
+
+            let n = clear_keys(&mut redis_conn, &prefix).await?;
+            assert_eq!(n, 1000);
+
+            let remaining: bool = redis_conn.exists(format!("{prefix}:key_0")).await?;
+            assert!(!remaining);
+            assert_eq!(keys_calls(&mut redis_conn).await?, keys_calls_before);
+
+            anyhow::Ok(())
+        })
+        .await
+    }
+}
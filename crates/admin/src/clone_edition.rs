@@ -0,0 +1,30 @@
+use records_lib::{Database, event, must};
+
+#[derive(clap::Args)]
+pub struct CloneCommand {
+    event_handle: String,
+    /// The ID of the edition to copy.
+    from_edition: u32,
+    /// The ID of the new edition.
+    to_edition: u32,
+}
+
+pub async fn clone(
+    db: Database,
+    CloneCommand {
+        event_handle,
+        from_edition,
+        to_edition,
+    }: CloneCommand,
+) -> anyhow::Result<()> {
+    let (event, edition) =
+        must::have_event_edition(&db.sql_conn, &event_handle, from_edition).await?;
+
+    event::clone_edition(&db.sql_conn, &event, &edition, to_edition).await?;
+
+    tracing::info!(
+        "Cloned edition {from_edition} of event {event_handle} into edition {to_edition}"
+    );
+
+    Ok(())
+}
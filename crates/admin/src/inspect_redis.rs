@@ -0,0 +1,223 @@
+use std::ffi::OsStr;
+
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use deadpool_redis::redis::{self, AsyncCommands as _};
+use records_lib::{Database, RedisConnection, redis_key};
+
+/// The amount of keys asked to Redis at each iteration of the scan.
+const SCAN_COUNT: usize = 100;
+
+#[derive(clap::Args)]
+pub struct RedisCommand {
+    /// The prefix of the keys to list, like `v3:mappack` or `v3:player_ranking`.
+    #[arg(value_parser = PrefixParser)]
+    prefix: String,
+
+    /// Prints the type, time-to-live and value of each key.
+    #[arg(long)]
+    values: bool,
+}
+
+/// Accepts any prefix, but suggests the namespaces of the keys for the completion.
+#[derive(Clone)]
+struct PrefixParser;
+
+impl TypedValueParser for PrefixParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            redis_key::namespaces()
+                .into_iter()
+                .map(|namespace| PossibleValue::new(namespace).hide(true)),
+        ))
+    }
+}
+
+/// Escapes the special characters of the glob-style patterns of Redis.
+fn escape_pattern(prefix: &str) -> String {
+    let mut out = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Returns the glob-style pattern matching the keys starting with the provided prefix.
+pub(crate) fn prefix_pattern(prefix: &str) -> String {
+    format!("{}*", escape_pattern(prefix))
+}
+
+/// Runs an iteration of the scan of the keys matching the pattern, starting at the provided
+/// cursor.
+///
+/// It returns the cursor of the next iteration, which is 0 once the scan is over, and the keys.
+/// This uses `SCAN` rather than `KEYS`, so the Redis server isn't blocked while iterating over
+/// a large amount of keys.
+pub(crate) async fn scan_step(
+    redis_conn: &mut RedisConnection,
+    pattern: &str,
+    cursor: u64,
+) -> anyhow::Result<(u64, Vec<String>)> {
+    let step = redis::cmd("SCAN")
+        .cursor_arg(cursor)
+        .arg("MATCH")
+        .arg(pattern)
+        .arg("COUNT")
+        .arg(SCAN_COUNT)
+        .query_async(redis_conn)
+        .await?;
+    Ok(step)
+}
+
+/// Returns the keys starting with the provided prefix, sorted.
+async fn scan_keys(redis_conn: &mut RedisConnection, prefix: &str) -> anyhow::Result<Vec<String>> {
+    let pattern = prefix_pattern(prefix);
+    let mut cursor = 0;
+    let mut keys = Vec::new();
+
+    loop {
+        let (next_cursor, batch) = scan_step(redis_conn, &pattern, cursor).await?;
+        keys.extend(batch);
+
+        if next_cursor == 0 {
+            break;
+        }
+        cursor = next_cursor;
+    }
+
+    // A key may be returned more than once during a scan
+    keys.sort_unstable();
+    keys.dedup();
+
+    Ok(keys)
+}
+
+/// Returns the value of the key displayed according to its type.
+async fn display_value(
+    redis_conn: &mut RedisConnection,
+    key: &str,
+    key_type: &str,
+) -> anyhow::Result<String> {
+    let value = match key_type {
+        "string" => redis_conn.get(key).await?,
+        "list" => redis_conn
+            .lrange::<_, Vec<String>>(key, 0, -1)
+            .await?
+            .join(", "),
+        "set" => redis_conn.smembers::<_, Vec<String>>(key).await?.join(", "),
+        "zset" => redis_conn
+            .zrange_withscores::<_, Vec<(String, f64)>>(key, 0, -1)
+            .await?
+            .into_iter()
+            .map(|(member, score)| format!("{member} ({score})"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "hash" => redis_conn
+            .hgetall::<_, Vec<(String, String)>>(key)
+            .await?
+            .into_iter()
+            .map(|(field, value)| format!("{field}: {value}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "none" => "<expired>".to_owned(),
+        other => format!("<{other}>"),
+    };
+
+    Ok(value)
+}
+
+pub async fn inspect(db: Database, cmd: RedisCommand) -> anyhow::Result<()> {
+    let mut redis_conn = db.redis_pool.get().await?;
+
+    let keys = scan_keys(&mut redis_conn, &cmd.prefix).await?;
+
+    if cmd.values {
+        let mut table =
+            prettytable::Table::init(vec![prettytable::row!["Key", "Type", "TTL", "Value"]]);
+
+        for key in &keys {
+            let key_type: String = redis::cmd("TYPE")
+                .arg(key)
+                .query_async(&mut redis_conn)
+                .await?;
+            let ttl: i64 = redis_conn.ttl(key).await?;
+            let value = display_value(&mut redis_conn, key, &key_type).await?;
+
+            let ttl = match ttl {
+                -1 => "never".to_owned(),
+                ttl if ttl < 0 => "-".to_owned(),
+                ttl => format!("{ttl}s"),
+            };
+
+            table.add_row(prettytable::row![key, key_type, ttl, value]);
+        }
+
+        println!("{table}");
+    } else {
+        for key in &keys {
+            println!("{key}");
+        }
+    }
+
+    let n = keys.len();
+    tracing::info!("Found {n} key{}", if n > 1 { "s" } else { "" });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use deadpool_redis::redis::AsyncCommands as _;
+    use records_lib::redis_key;
+
+    use super::{escape_pattern, scan_keys};
+
+    #[test]
+    fn escape_glob_characters() {
+        assert_eq!(escape_pattern("v3:mappack"), "v3:mappack");
+        assert_eq!(escape_pattern("v3:lb:[1]*?\\"), "v3:lb:\\[1\\]\\*\\?\\\\");
+    }
+
+    #[tokio::test]
+    async fn scan_finds_seeded_keys() -> anyhow::Result<()> {
+        // The Redis database may be shared with the other tests, so the keys are made unique
+        let prefix = redis_key::cached_key(format!("inspect_{}", records_lib::gen_random_str(10)))
+            .to_string();
+        let seeded = (0..5)
+            .map(|i| format!("{prefix}:key_{i}"))
+            .collect::<Vec<_>>();
+
+        test_env::wrap(async |db| {
+            let mut redis_conn = db.redis_pool.get().await?;
+
+            for key in &seeded {
+                let _: () = redis_conn.set(key, "value").await?;
+            }
+            // This one shares the beginning of the prefix, but not the whole prefix
+            let _: () = redis_conn.set(format!("{prefix}_other"), "value").await?;
+
+            let keys = scan_keys(&mut redis_conn, &format!("{prefix}:")).await?;
+            assert_eq!(keys, seeded);
+
+            for key in seeded.iter().cloned().chain([format!("{prefix}_other")]) {
+                let _: () = redis_conn.del(key).await?;
+            }
+
+            anyhow::Ok(())
+        })
+        .await
+    }
+}
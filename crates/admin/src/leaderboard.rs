@@ -1,4 +1,4 @@
-use records_lib::{Database, RedisPool, leaderboard, map, must, time::Time};
+use records_lib::{Database, RedisPool, leaderboard, must, time::Time};
 use sea_orm::{ConnectionTrait, StreamTrait};
 
 #[derive(clap::Subcommand)]
@@ -69,7 +69,7 @@ async fn full<C: ConnectionTrait + StreamTrait>(
     cmd: FullCmd,
 ) -> anyhow::Result<()> {
     let map = match cmd.map {
-        Map::MapId { map_id } => map::get_map_from_id(conn, map_id).await?,
+        Map::MapId { map_id } => must::have_map_by_id(conn, map_id).await?,
         Map::MapUid { map_uid } => must::have_map(conn, &map_uid).await?,
     };
     mariadb_lb(conn, redis_pool, map.id, cmd.offset, cmd.limit).await
@@ -2,12 +2,21 @@ use clap::Parser;
 use mkenv::prelude::*;
 use records_lib::{Database, DbEnv, LibEnv};
 
-use self::{clear::ClearCommand, leaderboard::LbCommand, populate::PopulateCommand};
+use self::{
+    check_record_counts::CheckRecordCountsCmd, clear::ClearCommand, clone_edition::CloneCommand,
+    inspect_redis::RedisCommand, leaderboard::LbCommand, map::MapCommand,
+    populate::PopulateCommand, record::RecordCommand,
+};
 
+mod check_record_counts;
 mod clear;
 mod clear_redis_mappacks;
+mod clone_edition;
+mod inspect_redis;
 mod leaderboard;
+mod map;
 mod populate;
+mod record;
 
 #[derive(clap::Parser)]
 enum Command {
@@ -16,12 +25,23 @@ enum Command {
     #[clap(subcommand)]
     Leaderboard(LbCommand),
     ClearRedisMappacks,
+    /// Removes the Redis keys of the versions prior to the current one.
+    ClearPriorRedisVersions,
+    /// Lists the Redis keys starting with a prefix.
+    Redis(RedisCommand),
+    CheckRecordCounts(CheckRecordCountsCmd),
+    #[clap(subcommand)]
+    Record(RecordCommand),
+    #[clap(subcommand)]
+    Map(MapCommand),
 }
 
 #[derive(clap::Subcommand)]
 enum EventCommand {
     Populate(PopulateCommand),
     Clear(ClearCommand),
+    /// Copies the structure of an edition into a new edition, without its records.
+    Clone(CloneCommand),
 }
 
 mkenv::make_config! {
@@ -44,7 +64,9 @@ async fn main() -> anyhow::Result<()> {
 
     let db = Database::from_db_url(
         env.db_env.db_url.db_url.get(),
+        env.db_env.db_url.replica_db_url.get(),
         env.db_env.redis_url.redis_url.get(),
+        env.db_env.redis_url.redis_health_check_interval.get(),
     )
     .await?;
 
@@ -56,8 +78,14 @@ async fn main() -> anyhow::Result<()> {
         Command::Event(event) => match event {
             EventCommand::Populate(cmd) => populate::populate(client, db, cmd).await,
             EventCommand::Clear(cmd) => clear::clear(db, cmd).await,
+            EventCommand::Clone(cmd) => clone_edition::clone(db, cmd).await,
         },
         Command::Leaderboard(cmd) => leaderboard::leaderboard(db, cmd).await,
         Command::ClearRedisMappacks => clear_redis_mappacks::clear(db).await,
+        Command::ClearPriorRedisVersions => clear_redis_mappacks::clear_prior_versions(db).await,
+        Command::Redis(cmd) => inspect_redis::inspect(db, cmd).await,
+        Command::CheckRecordCounts(cmd) => check_record_counts::check(db, cmd).await,
+        Command::Record(cmd) => record::record(db, cmd).await,
+        Command::Map(cmd) => map::map(db, cmd).await,
     }
 }
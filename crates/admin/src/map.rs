@@ -0,0 +1,44 @@
+use records_lib::{Database, map, must};
+
+#[derive(clap::Subcommand)]
+pub enum MapCommand {
+    /// Deletes a map, along with its records and its links to event editions.
+    Delete {
+        /// The UID of the map.
+        map_uid: String,
+
+        /// Confirms the deletion, which can't be undone.
+        #[arg(long)]
+        yes: bool,
+
+        /// Deletes the map even if it's used by an event edition that hasn't expired yet.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub async fn map(db: Database, cmd: MapCommand) -> anyhow::Result<()> {
+    match cmd {
+        MapCommand::Delete {
+            map_uid,
+            yes,
+            force,
+        } => {
+            let map = must::have_map(&db.sql_conn, &map_uid).await?;
+
+            anyhow::ensure!(
+                yes,
+                "this will delete the map `{}` ({map_uid}) and all its records, \
+                    run again with --yes to confirm",
+                map.name
+            );
+
+            let deleted_records =
+                map::delete_map(&db.sql_conn, &db.redis_pool, &map, force).await?;
+
+            tracing::info!("Deleted map {map_uid} with {deleted_records} record(s)");
+        }
+    }
+
+    Ok(())
+}
@@ -28,6 +28,10 @@ use crate::clear;
 pub struct PopulateCommand {
     event_handle: String,
     event_edition: u32,
+    /// Validates the input and prints the maps that would be inserted, without touching
+    /// the database.
+    #[clap(long)]
+    dry_run: bool,
     #[clap(subcommand)]
     kind: PopulateKind,
 }
@@ -40,11 +44,66 @@ enum PopulateKind {
         #[clap(default_value_t = true)]
         transitive_save: bool,
     },
+    /// Imports the map list and the medal times from a CSV or JSON file.
+    File {
+        #[clap(long)]
+        from_file: PathBuf,
+        /// The format of the file. It's guessed from the extension of the file if omitted.
+        #[clap(long)]
+        format: Option<FileFormat>,
+        #[clap(long)]
+        #[clap(default_value_t = true)]
+        transitive_save: bool,
+    },
     MxId {
         mx_id: Option<i64>,
     },
 }
 
+/// The format of the file used to populate an event edition.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum FileFormat {
+    /// A CSV file with a header, where the lines starting with `#` are ignored.
+    Csv,
+    /// A JSON array of objects, with the same fields as the CSV columns.
+    Json,
+}
+
+impl FileFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// The position of a row in the imported file.
+#[derive(Debug, Clone, Copy)]
+enum RowPos {
+    /// The line of a CSV row.
+    Line(u64),
+    /// The 1-based index of a JSON entry.
+    Entry(u64),
+}
+
+impl RowPos {
+    fn get(self) -> u64 {
+        match self {
+            Self::Line(n) | Self::Entry(n) => n,
+        }
+    }
+}
+
+impl fmt::Display for RowPos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line(line) => write!(f, "line {line}"),
+            Self::Entry(entry) => write!(f, "entry {entry}"),
+        }
+    }
+}
+
 #[derive(serde::Deserialize, Debug, Clone, Copy)]
 struct MedalTimes {
     champion_time: i32,
@@ -59,6 +118,15 @@ enum Id<'a> {
     MxId { mx_id: i64 },
 }
 
+impl fmt::Display for Id<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MapUid { map_uid } => write!(f, "`{map_uid}`"),
+            Self::MxId { mx_id } => write!(f, "with MX ID {mx_id}"),
+        }
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 struct Row {
     map_uid: Option<String>,
@@ -89,6 +157,24 @@ impl Row {
     fn get_original_id(&self) -> Option<Id<'_>> {
         get_id_impl(self.original_map_uid.as_deref(), self.original_mx_id)
     }
+
+    /// Checks the content of the row, without querying the database.
+    fn validate(&self) -> anyhow::Result<()> {
+        let id = self.get_id()?;
+
+        if let Some(true) = self.is_available
+            && let Some(true) = self.is_disabled
+        {
+            anyhow::bail!("Cannot set both flags is_available and is_disabled");
+        }
+
+        if let Some(times) = &self.times {
+            check_medal_times_consistency(times)
+                .with_context(|| format!("Inconsistent medal times of the map {id}"))?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -168,14 +254,11 @@ impl Iterator for MxIdIter {
     }
 }
 
-#[tracing::instrument(skip(client, conn, rows))]
-async fn populate_mx_maps<C: ConnectionTrait>(
+/// Fetches the information of the maps referenced by their MX ID in the provided rows.
+async fn fetch_mx_maps(
     client: &reqwest::Client,
-    conn: &C,
-    rows: &[(Row, u64)],
-) -> anyhow::Result<HashMap<i64, maps::Model>> {
-    tracing::info!("Populating maps from MX...");
-
+    rows: &[(Row, RowPos)],
+) -> anyhow::Result<Vec<MxMapItem>> {
     let mx_ids = rows
         .iter()
         .flat_map(|(row, _)| match (row.get_id(), row.get_original_id()) {
@@ -190,27 +273,40 @@ async fn populate_mx_maps<C: ConnectionTrait>(
         })
         .chunks(10);
 
-    let mx_ids: Vec<_> = stream::iter(&mx_ids)
+    let mx_maps: Vec<_> = stream::iter(&mx_ids)
         .map(|mut chunk| async move {
             let url = format!(
                 "https://sm.mania.exchange/api/maps/get_map_info/multi/{}",
                 chunk.join(",")
             );
             tracing::info!("Requesting MX ({})...", url);
-            let mx_maps = client
+            client
                 .get(url)
                 .header("User-Agent", "obstacle (discord @ahmadbky)")
                 .send()
                 .await?
                 .json::<Vec<MxMapItem>>()
-                .await?;
-            insert_mx_maps(conn, &mx_maps).await
+                .await
         })
         .buffer_unordered(rows.len())
         .try_collect()
         .await?;
 
-    Ok(mx_ids.into_iter().flatten().collect())
+    Ok(mx_maps.into_iter().flatten().collect())
+}
+
+#[tracing::instrument(skip(client, conn, rows))]
+async fn populate_mx_maps<C: ConnectionTrait>(
+    client: &reqwest::Client,
+    conn: &C,
+    rows: &[(Row, RowPos)],
+) -> anyhow::Result<HashMap<i64, maps::Model>> {
+    tracing::info!("Populating maps from MX...");
+
+    let mx_maps = fetch_mx_maps(client, rows).await?;
+    let mx_maps = insert_mx_maps(conn, &mx_maps).await?;
+
+    Ok(mx_maps.into_iter().collect())
 }
 
 async fn run_populate<C: TransactionTrait + ConnectionTrait>(
@@ -243,12 +339,30 @@ async fn run_populate<C: TransactionTrait + ConnectionTrait>(
                 csv_file,
                 transitive_save,
             } => {
-                populate_from_csv(
+                let rows = read_rows(&csv_file, FileFormat::Csv)?;
+                populate_from_rows(
                     txn,
                     redis_conn,
                     client,
                     (event, edition),
-                    &csv_file,
+                    rows,
+                    transitive_save,
+                )
+                .await
+            }
+            PopulateKind::File {
+                from_file,
+                format,
+                transitive_save,
+            } => {
+                let format = format.unwrap_or_else(|| FileFormat::from_path(&from_file));
+                let rows = read_rows(&from_file, format)?;
+                populate_from_rows(
+                    txn,
+                    redis_conn,
+                    client,
+                    (event, edition),
+                    rows,
                     transitive_save,
                 )
                 .await
@@ -285,14 +399,21 @@ pub async fn populate(
     PopulateCommand {
         event_handle,
         event_edition,
+        dry_run,
         kind,
     }: PopulateCommand,
 ) -> anyhow::Result<()> {
-    let mut redis_conn = db.redis_pool.get().await?;
-
     let (event, edition) =
         must::have_event_edition(&db.sql_conn, &event_handle, event_edition).await?;
 
+    if dry_run {
+        let planned_maps = plan_populate(&db.sql_conn, &client, &event, &edition, kind).await?;
+        print_planned_maps(&planned_maps);
+        return Ok(());
+    }
+
+    let mut redis_conn = db.redis_pool.get().await?;
+
     run_populate(
         &db.sql_conn,
         &mut redis_conn,
@@ -315,76 +436,320 @@ pub async fn populate(
     Ok(())
 }
 
-struct CsvReadErr {
-    line: u64,
+/// An event edition map that the populate command would insert.
+#[derive(Debug)]
+struct PlannedMap {
+    /// The position of the row in the imported file, if any.
+    pos: Option<RowPos>,
+    /// The UID of the map.
+    map_uid: String,
+    /// Whether the map is unknown and would be created from MX.
+    is_new: bool,
+    original_map_uid: Option<String>,
+    category_handle: Option<String>,
+    times: Option<MedalTimes>,
+}
+
+/// Returns the UID of the map with the provided ID, and whether it would be created from MX.
+///
+/// The maps are only resolved, nothing is inserted.
+async fn resolve_planned_map<C: ConnectionTrait>(
+    conn: &C,
+    id: Id<'_>,
+    mx_maps: &HashMap<i64, MxMapItem>,
+) -> anyhow::Result<(String, bool)> {
+    match id {
+        Id::MapUid { map_uid } => match map::get_map_from_uid(conn, map_uid).await? {
+            Some(map) => Ok((map.game_id, false)),
+            None => anyhow::bail!("Unknown map with UID `{map_uid}`"),
+        },
+        Id::MxId { mx_id } => {
+            let mx_map = mx_maps
+                .get(&mx_id)
+                .ok_or_else(|| anyhow::anyhow!("Missing map with MX ID {mx_id}"))?;
+            if map::get_map_from_uid(conn, &mx_map.map_uid)
+                .await?
+                .is_some()
+            {
+                return Ok((mx_map.map_uid.clone(), false));
+            }
+            must::have_player(conn, &mx_map.author_login)
+                .await
+                .with_context(|| format!("Unknown author of the map with MX ID {mx_id}"))?;
+            Ok((mx_map.map_uid.clone(), true))
+        }
+    }
+}
+
+/// Returns the maps that the populate command would insert, without touching the database.
+///
+/// The input is fully validated against the database, and all the unknown maps and players are
+/// reported at once.
+async fn plan_populate<C: ConnectionTrait>(
+    conn: &C,
+    client: &reqwest::Client,
+    event: &event::Model,
+    edition: &event_edition::Model,
+    populate_kind: PopulateKind,
+) -> anyhow::Result<Vec<PlannedMap>> {
+    let rows = match populate_kind {
+        PopulateKind::CsvFile { csv_file, .. } => read_rows(&csv_file, FileFormat::Csv)?,
+        PopulateKind::File {
+            from_file, format, ..
+        } => {
+            let format = format.unwrap_or_else(|| FileFormat::from_path(&from_file));
+            read_rows(&from_file, format)?
+        }
+        PopulateKind::MxId { mx_id } => {
+            let mx_id = resolve_mx_id(edition, mx_id)?;
+            let maps = map::fetch_mx_mappack_maps(client, mx_id as _, edition.mx_secret.as_deref())
+                .await?;
+
+            let mut planned_maps = Vec::with_capacity(maps.len());
+            let mut errors = Vec::new();
+
+            for map in maps {
+                let is_new = match map::get_map_from_uid(conn, &map.TrackUID).await? {
+                    Some(_) => false,
+                    None => {
+                        if let Err(e) = must::have_player(conn, &map.AuthorLogin).await {
+                            errors.push(format!(
+                                "Unknown author of the map with MX ID {}: {e}",
+                                map.MapID
+                            ));
+                        }
+                        true
+                    }
+                };
+                planned_maps.push(PlannedMap {
+                    pos: None,
+                    map_uid: map.TrackUID,
+                    is_new,
+                    original_map_uid: None,
+                    category_handle: None,
+                    times: None,
+                });
+            }
+
+            if !errors.is_empty() {
+                anyhow::bail!(
+                    "Found {} invalid map(s):\n{}",
+                    errors.len(),
+                    errors.join("\n")
+                );
+            }
+
+            return Ok(planned_maps);
+        }
+    };
+
+    let categories =
+        records_lib::event::get_categories_by_edition_id(conn, event.id, edition.id).await?;
+    let mx_maps = fetch_mx_maps(client, &rows)
+        .await?
+        .into_iter()
+        .map(|mx_map| (mx_map.mx_id, mx_map))
+        .collect::<HashMap<_, _>>();
+
+    let mut planned_maps = Vec::with_capacity(rows.len());
+    let mut errors = Vec::new();
+
+    for (row, pos) in &rows {
+        // The rows were validated when they were read
+        let map = resolve_planned_map(conn, row.get_id()?, &mx_maps).await;
+        let original_map = match row.get_original_id() {
+            Some(id) => resolve_planned_map(conn, id, &mx_maps).await.map(Some),
+            None => Ok(None),
+        };
+
+        match (map, original_map) {
+            (Ok((map_uid, is_new)), Ok(original_map)) => planned_maps.push(PlannedMap {
+                pos: Some(*pos),
+                map_uid,
+                is_new,
+                original_map_uid: original_map.map(|(uid, _)| uid),
+                category_handle: row
+                    .category_handle
+                    .as_ref()
+                    .filter(|handle| categories.iter().any(|c| &c.handle == *handle))
+                    .cloned(),
+                times: row.times,
+            }),
+            (Err(e), _) | (_, Err(e)) => errors.push(format!("{pos}: {e:#}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "Found {} invalid row(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+    }
+
+    Ok(planned_maps)
+}
+
+fn print_planned_maps(planned_maps: &[PlannedMap]) {
+    let mut table = prettytable::Table::init(vec![prettytable::row![
+        "Row", "Map", "New", "Original", "Category", "Bronze", "Silver", "Gold", "Champion"
+    ]]);
+
+    let opt = |s: Option<String>| s.unwrap_or_else(|| "-".to_owned());
+
+    for planned_map in planned_maps {
+        let times = planned_map.times;
+        table.add_row(prettytable::row![
+            opt(planned_map.pos.map(|pos| pos.to_string())),
+            planned_map.map_uid,
+            if planned_map.is_new { "yes" } else { "no" },
+            opt(planned_map.original_map_uid.clone()),
+            opt(planned_map.category_handle.clone()),
+            opt(times.map(|t| Time(t.bronze_time).to_string())),
+            opt(times.map(|t| Time(t.silver_time).to_string())),
+            opt(times.map(|t| Time(t.gold_time).to_string())),
+            opt(times.map(|t| Time(t.champion_time).to_string())),
+        ]);
+    }
+
+    println!("Dry run: the following map(s) would be inserted");
+    println!("{table}");
 }
 
-impl From<u64> for CsvReadErr {
+struct RowReadErr {
+    pos: RowPos,
+}
+
+impl From<RowPos> for RowReadErr {
     #[inline(always)]
-    fn from(line: u64) -> Self {
-        Self { line }
+    fn from(pos: RowPos) -> Self {
+        Self { pos }
     }
 }
 
-impl fmt::Display for CsvReadErr {
+impl fmt::Display for RowReadErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Failed to read the CSV on line {}", self.line)
+        write!(f, "Failed to read the row on {}", self.pos)
     }
 }
 
-fn check_inconsistent_medal_times(
-    line: u64,
-    (medal_time, medal_label): (i32, &str),
-    (next_medal_time, next_medal_label): (i32, &str),
-) {
-    if medal_time < next_medal_time {
-        tracing::warn!(
-            "Inconsistent medal times on line {line}: {medal_label} time is lower than {next_medal_label} time;\n\
-            {} < {} ({medal_time} < {next_medal_time})",
+/// The error returned when the medal times of a map aren't monotonic.
+#[derive(Debug)]
+struct InconsistentMedalTimes {
+    medal: (i32, &'static str),
+    next_medal: (i32, &'static str),
+}
+
+impl fmt::Display for InconsistentMedalTimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (medal_time, medal_label) = self.medal;
+        let (next_medal_time, next_medal_label) = self.next_medal;
+        write!(
+            f,
+            "{medal_label} time is lower than {next_medal_label} time: {} < {} \
+            ({medal_time} < {next_medal_time})",
             Time(medal_time),
-            Time(next_medal_time)
-        );
+            Time(next_medal_time),
+        )
     }
 }
 
-fn check_medal_times_consistency(
-    line: u64,
-    bronze_time: Option<i32>,
-    silver_time: Option<i32>,
-    gold_time: Option<i32>,
-    author_time: Option<i32>,
-) {
-    let (Some(bronze_time), Some(silver_time), Some(gold_time), Some(author_time)) =
-        (bronze_time, silver_time, gold_time, author_time)
-    else {
-        return;
-    };
+impl std::error::Error for InconsistentMedalTimes {}
+
+/// Checks that the medal times are monotonic, so that `champion <= gold <= silver <= bronze`.
+///
+/// The maps don't have any reversed mode, so a better medal always requires a lower time.
+fn check_medal_times_consistency(times: &MedalTimes) -> Result<(), InconsistentMedalTimes> {
+    let spans = [
+        (times.bronze_time, "bronze"),
+        (times.silver_time, "silver"),
+        (times.gold_time, "gold"),
+        (times.champion_time, "champion"),
+    ];
+
+    match spans
+        .into_iter()
+        .tuple_windows()
+        .find(|((medal_time, _), (next_medal_time, _))| medal_time < next_medal_time)
+    {
+        Some((medal, next_medal)) => Err(InconsistentMedalTimes { medal, next_medal }),
+        None => Ok(()),
+    }
+}
+
+/// Reads the rows of the provided file.
+///
+/// Each row is validated, and all the invalid rows are reported at once with their position
+/// in the file.
+fn read_rows(file: &Path, format: FileFormat) -> anyhow::Result<Vec<(Row, RowPos)>> {
+    tracing::info!("Collecting rows of `{}`...", file.display());
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    match format {
+        FileFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .comment(Some(b'#'))
+                .from_path(file)
+                .with_context(|| format!("Couldn't read CSV file `{}`", file.display()))?;
+            let mut rows_iter = reader.deserialize::<Row>();
+
+            loop {
+                let pos = RowPos::Line(rows_iter.reader().position().line());
+                match rows_iter.next() {
+                    Some(Ok(row)) => rows.push((row, pos)),
+                    Some(Err(e)) => errors.push((pos, e.to_string())),
+                    None => break,
+                }
+            }
+        }
+        FileFormat::Json => {
+            let reader = std::fs::File::open(file)
+                .map(std::io::BufReader::new)
+                .with_context(|| format!("Couldn't read JSON file `{}`", file.display()))?;
+            let entries: Vec<serde_json::Value> = serde_json::from_reader(reader)
+                .with_context(|| format!("Couldn't parse JSON file `{}`", file.display()))?;
+
+            for (i, entry) in entries.into_iter().enumerate() {
+                let pos = RowPos::Entry(i as u64 + 1);
+                match serde_json::from_value(entry) {
+                    Ok(row) => rows.push((row, pos)),
+                    Err(e) => errors.push((pos, e.to_string())),
+                }
+            }
+        }
+    }
 
-    let bronze_span = (bronze_time, "bronze");
-    let silver_span = (silver_time, "silver");
-    let gold_span = (gold_time, "gold");
-    let author_span = (author_time, "champion");
+    errors.extend(
+        rows.iter()
+            .filter_map(|(row, pos)| row.validate().err().map(|e| (*pos, format!("{e:#}")))),
+    );
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|(pos, _)| pos.get());
+        anyhow::bail!(
+            "Found {} invalid row(s) in `{}`:\n{}",
+            errors.len(),
+            file.display(),
+            errors
+                .iter()
+                .map(|(pos, error)| format!("{pos}: {error}"))
+                .join("\n")
+        );
+    }
 
-    check_inconsistent_medal_times(line, bronze_span, silver_span);
-    check_inconsistent_medal_times(line, silver_span, gold_span);
-    check_inconsistent_medal_times(line, gold_span, author_span);
+    Ok(rows)
 }
 
-async fn populate_from_csv<C: ConnectionTrait>(
+async fn populate_from_rows<C: ConnectionTrait>(
     conn: &C,
     redis_conn: &mut RedisConnection,
     client: &reqwest::Client,
     (event, edition): (&event::Model, &event_edition::Model),
-    csv_file: &Path,
+    rows: Vec<(Row, RowPos)>,
     default_transitive_save: bool,
 ) -> anyhow::Result<()> {
-    let mut reader = csv::ReaderBuilder::new()
-        .comment(Some(b'#'))
-        .from_path(csv_file)
-        .with_context(|| format!("Couldn't read CSV file `{}`", csv_file.display()))?;
-    let mut rows_iter = reader.deserialize::<Row>();
-    let mut rows = Vec::with_capacity(rows_iter.size_hint().0);
-
     tracing::info!("Querying event edition categories...");
 
     let categories =
@@ -399,19 +764,9 @@ async fn populate_from_csv<C: ConnectionTrait>(
         );
     }
 
-    tracing::info!("Collecting CSV rows...");
-
-    loop {
-        let line = rows_iter.reader().position().line();
-        match rows_iter.next() {
-            Some(row) => {
-                let row = row?;
-                if row.category_handle.is_none() && !categories.is_empty() {
-                    tracing::warn!("Missing map category at line: {line}");
-                }
-                rows.push((row, line));
-            }
-            None => break,
+    for (row, pos) in &rows {
+        if row.category_handle.is_none() && !categories.is_empty() {
+            tracing::warn!("Missing map category at {pos}");
         }
     }
 
@@ -424,35 +779,42 @@ async fn populate_from_csv<C: ConnectionTrait>(
 
     let mut maps_to_insert = Vec::with_capacity(rows.len());
 
-    for (row, i) in rows {
-        if let Some(true) = row.is_available
-            && let Some(true) = row.is_disabled
-        {
-            anyhow::bail!("Cannot set both flags is_available and is_disabled on line {i}");
-        }
-
-        let (mx_id, map) = match row.get_id().with_context(|| format!("Parsing row {i}"))? {
-            Id::MapUid { map_uid } => (None, must::have_map(conn, map_uid).await?),
+    for (row, pos) in rows {
+        // The rows were validated when they were read
+        let (mx_id, map) = match row.get_id()? {
+            Id::MapUid { map_uid } => (
+                None,
+                must::have_map(conn, map_uid)
+                    .await
+                    .with_context(|| RowReadErr::from(pos))?,
+            ),
             Id::MxId { mx_id } => (
                 Some(mx_id),
                 mx_maps
                     .get(&mx_id)
                     .ok_or_else(|| anyhow::anyhow!("Missing map with MX ID {mx_id}"))
-                    .with_context(|| CsvReadErr::from(i))?
+                    .with_context(|| RowReadErr::from(pos))?
                     .clone(),
             ),
         };
 
         let (original_mx_id, original_map) = match row.get_original_id() {
             Some(id) => match id {
-                Id::MapUid { map_uid } => (None, Some(must::have_map(conn, map_uid).await?)),
+                Id::MapUid { map_uid } => (
+                    None,
+                    Some(
+                        must::have_map(conn, map_uid)
+                            .await
+                            .with_context(|| RowReadErr::from(pos))?,
+                    ),
+                ),
                 Id::MxId { mx_id } => (
                     Some(mx_id),
                     Some(
                         mx_maps
                             .get(&mx_id)
                             .ok_or_else(|| anyhow::anyhow!("Missing map with MX ID {mx_id}"))
-                            .with_context(|| CsvReadErr::from(i))?
+                            .with_context(|| RowReadErr::from(pos))?
                             .clone(),
                     ),
                 ),
@@ -471,15 +833,13 @@ async fn populate_from_csv<C: ConnectionTrait>(
         let gold_time = row.times.map(|m| m.gold_time);
         let author_time = row.times.map(|m| m.champion_time);
 
-        check_medal_times_consistency(i, bronze_time, silver_time, gold_time, author_time);
-
         let new_map = event_edition_maps::ActiveModel {
             event_id: Set(event.id),
             edition_id: Set(edition.id),
             map_id: Set(map.id),
             category_id: Set(opt_category_id),
             mx_id: Set(mx_id),
-            order: Set(i as _),
+            order: Set(pos.get() as _),
             original_map_id: Set(original_map.as_ref().map(|m| m.id)),
             original_mx_id: Set(original_mx_id),
             transitive_save: Set(Some(
@@ -521,13 +881,8 @@ async fn populate_from_csv<C: ConnectionTrait>(
     Ok(())
 }
 
-async fn populate_from_mx_id<C: ConnectionTrait>(
-    conn: &C,
-    client: &reqwest::Client,
-    event: &event::Model,
-    edition: &event_edition::Model,
-    mx_id: Option<i64>,
-) -> anyhow::Result<()> {
+/// Returns the MX ID of the mappack of the edition, preferring the provided one.
+fn resolve_mx_id(edition: &event_edition::Model, mx_id: Option<i64>) -> anyhow::Result<i64> {
     let mx_id = match (mx_id, edition.mx_id.map(|x| x as i64)) {
         (Some(provided_id), Some(original_id)) if provided_id != original_id => {
             tracing::warn!(
@@ -538,6 +893,17 @@ async fn populate_from_mx_id<C: ConnectionTrait>(
         (Some(id), _) | (_, Some(id)) => id,
         (None, None) => anyhow::bail!("No MX id provided"),
     };
+    Ok(mx_id)
+}
+
+async fn populate_from_mx_id<C: ConnectionTrait>(
+    conn: &C,
+    client: &reqwest::Client,
+    event: &event::Model,
+    edition: &event_edition::Model,
+    mx_id: Option<i64>,
+) -> anyhow::Result<()> {
+    let mx_id = resolve_mx_id(edition, mx_id)?;
 
     let maps = map::fetch_mx_mappack_maps(client, mx_id as _, edition.mx_secret.as_deref()).await?;
 
@@ -578,3 +944,317 @@ async fn populate_from_mx_id<C: ConnectionTrait>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use entity::{event, event_edition, event_edition_maps, maps, players};
+    use records_lib::must;
+    use sea_orm::{
+        ActiveValue::Set, ColumnTrait as _, EntityTrait as _, PaginatorTrait as _,
+        QueryFilter as _, QueryOrder as _,
+    };
+
+    use super::{
+        FileFormat, MedalTimes, PopulateKind, check_medal_times_consistency, plan_populate,
+        read_rows, run_populate,
+    };
+
+    #[test]
+    fn monotonic_medal_times() {
+        let times = MedalTimes {
+            champion_time: 1000,
+            gold_time: 2000,
+            silver_time: 2000,
+            bronze_time: 3000,
+        };
+        assert!(check_medal_times_consistency(&times).is_ok());
+    }
+
+    #[test]
+    fn inverted_medal_times() {
+        let times = MedalTimes {
+            champion_time: 2500,
+            gold_time: 2000,
+            silver_time: 3000,
+            bronze_time: 4000,
+        };
+        let err = check_medal_times_consistency(&times).unwrap_err();
+        assert_eq!(err.medal, (2000, "gold"));
+        assert_eq!(err.next_medal, (2500, "champion"));
+
+        let times = MedalTimes {
+            silver_time: 5000,
+            ..times
+        };
+        let err = check_medal_times_consistency(&times).unwrap_err();
+        assert_eq!(err.medal, (4000, "bronze"));
+        assert_eq!(err.next_medal, (5000, "silver"));
+    }
+
+    #[test]
+    fn report_invalid_rows() -> anyhow::Result<()> {
+        let file =
+            std::env::temp_dir().join(format!("populate_{}.json", records_lib::gen_random_str(10)));
+        let entries = serde_json::json!([
+            { "map_uid": "map_1_uid" },
+            { "category_handle": "white" },
+            {
+                "map_uid": "map_3_uid",
+                "champion_time": 2500,
+                "gold_time": 2000,
+                "silver_time": 3000,
+                "bronze_time": 4000,
+            },
+            { "map_uid": 4 },
+        ]);
+        std::fs::write(&file, entries.to_string())?;
+
+        let result = read_rows(&file, FileFormat::Json);
+        std::fs::remove_file(&file)?;
+
+        let err = format!("{:#}", result.unwrap_err());
+        let mut lines = err.lines().skip(1);
+        assert!(err.starts_with("Found 3 invalid row(s)"), "{err}");
+        assert_eq!(
+            lines.next(),
+            Some("entry 2: You must provide either the map UID or the MX ID")
+        );
+        assert!(lines.next().is_some_and(|line| line.starts_with(
+            "entry 3: Inconsistent medal times of the map `map_3_uid`: \
+            gold time is lower than champion time"
+        )));
+        assert!(
+            lines
+                .next()
+                .is_some_and(|line| line.starts_with("entry 4: "))
+        );
+        assert_eq!(lines.next(), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn populate_from_json_file() -> anyhow::Result<()> {
+        let map_ids = std::iter::repeat_with(test_env::get_map_id)
+            .take(2)
+            .collect::<Vec<_>>();
+        // The ID of the map makes the event unique to this test
+        let event_handle = format!("event_{}_handle", map_ids[0]);
+
+        let event = event::ActiveModel {
+            id: Set(1),
+            handle: Set(event_handle.clone()),
+            ..Default::default()
+        };
+
+        let edition = event_edition::ActiveModel {
+            event_id: Set(1),
+            id: Set(1),
+            name: Set("event_1_1_name".to_owned()),
+            start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+            is_transparent: Set(0),
+            save_non_event_record: Set(0),
+            non_original_maps: Set(0),
+            ..Default::default()
+        };
+
+        let player = players::ActiveModel {
+            id: Set(1),
+            login: Set("player_1_login".to_owned()),
+            name: Set("player_1_name".to_owned()),
+            role: Set(0),
+            ..Default::default()
+        };
+
+        let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+            id: Set(*map_id),
+            game_id: Set(format!("map_{map_id}_uid")),
+            name: Set(format!("map_{map_id}_name")),
+            player_id: Set(1),
+            ..Default::default()
+        });
+
+        // The second map doesn't have any medal time
+        let file = std::env::temp_dir().join(format!("populate_{event_handle}.json"));
+        let entries = serde_json::json!([
+            {
+                "map_uid": format!("map_{}_uid", map_ids[0]),
+                "champion_time": 1000,
+                "gold_time": 2000,
+                "silver_time": 3000,
+                "bronze_time": 4000,
+            },
+            {
+                "map_uid": format!("map_{}_uid", map_ids[1]),
+                "transitive_save": false,
+            },
+        ]);
+        std::fs::write(&file, entries.to_string())?;
+
+        let result = test_env::wrap(async |db| {
+            event::Entity::insert(event).exec(&db.sql_conn).await?;
+            event_edition::Entity::insert(edition)
+                .exec(&db.sql_conn)
+                .await?;
+            players::Entity::insert(player).exec(&db.sql_conn).await?;
+            maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+
+            let (event, edition) = must::have_event_edition(&db.sql_conn, &event_handle, 1).await?;
+            let mut redis_conn = db.redis_pool.get().await?;
+
+            run_populate(
+                &db.sql_conn,
+                &mut redis_conn,
+                &event,
+                &edition,
+                &reqwest::Client::new(),
+                PopulateKind::File {
+                    from_file: file.clone(),
+                    format: None,
+                    transitive_save: true,
+                },
+            )
+            .await?;
+
+            let event_maps = event_edition_maps::Entity::find()
+                .filter(
+                    event_edition_maps::Column::EventId
+                        .eq(1)
+                        .and(event_edition_maps::Column::EditionId.eq(1)),
+                )
+                .order_by_asc(event_edition_maps::Column::Order)
+                .all(&db.sql_conn)
+                .await?;
+
+            itertools::assert_equal(
+                event_maps.iter().map(|event_map| {
+                    (
+                        event_map.map_id,
+                        event_map.order,
+                        event_map.transitive_save,
+                        event_map.bronze_time,
+                        event_map.silver_time,
+                        event_map.gold_time,
+                        event_map.author_time,
+                    )
+                }),
+                [
+                    (
+                        map_ids[0],
+                        1,
+                        Some(1),
+                        Some(4000),
+                        Some(3000),
+                        Some(2000),
+                        Some(1000),
+                    ),
+                    (map_ids[1], 2, Some(0), None, None, None, None),
+                ],
+            );
+
+            anyhow::Ok(())
+        })
+        .await;
+
+        std::fs::remove_file(&file)?;
+        result
+    }
+
+    #[tokio::test]
+    async fn dry_run_unknown_map() -> anyhow::Result<()> {
+        let map_id = test_env::get_map_id();
+        // The ID of the map makes the event unique to this test
+        let event_handle = format!("event_{map_id}_handle");
+
+        let event = event::ActiveModel {
+            id: Set(1),
+            handle: Set(event_handle.clone()),
+            ..Default::default()
+        };
+
+        let edition = event_edition::ActiveModel {
+            event_id: Set(1),
+            id: Set(1),
+            name: Set("event_1_1_name".to_owned()),
+            start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+            is_transparent: Set(0),
+            save_non_event_record: Set(0),
+            non_original_maps: Set(0),
+            ..Default::default()
+        };
+
+        let player = players::ActiveModel {
+            id: Set(1),
+            login: Set("player_1_login".to_owned()),
+            name: Set("player_1_name".to_owned()),
+            role: Set(0),
+            ..Default::default()
+        };
+
+        let map = maps::ActiveModel {
+            id: Set(map_id),
+            game_id: Set(format!("map_{map_id}_uid")),
+            name: Set(format!("map_{map_id}_name")),
+            player_id: Set(1),
+            ..Default::default()
+        };
+
+        // The second map has a typo in its UID
+        let file = std::env::temp_dir().join(format!("populate_{event_handle}.json"));
+        let entries = serde_json::json!([
+            { "map_uid": format!("map_{map_id}_uid") },
+            { "map_uid": format!("map_{map_id}_uld") },
+        ]);
+        std::fs::write(&file, entries.to_string())?;
+
+        let result = test_env::wrap(async |db| {
+            event::Entity::insert(event).exec(&db.sql_conn).await?;
+            event_edition::Entity::insert(edition)
+                .exec(&db.sql_conn)
+                .await?;
+            players::Entity::insert(player).exec(&db.sql_conn).await?;
+            maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+            let (event, edition) = must::have_event_edition(&db.sql_conn, &event_handle, 1).await?;
+
+            let result = plan_populate(
+                &db.sql_conn,
+                &reqwest::Client::new(),
+                &event,
+                &edition,
+                PopulateKind::File {
+                    from_file: file.clone(),
+                    format: None,
+                    transitive_save: true,
+                },
+            )
+            .await;
+
+            let err = format!("{:#}", result.unwrap_err());
+            assert!(err.starts_with("Found 1 invalid row(s)"), "{err}");
+            assert!(
+                err.contains(&format!("entry 2: Unknown map with UID `map_{map_id}_uld`")),
+                "{err}"
+            );
+
+            // Nothing was inserted
+            let event_maps_count = event_edition_maps::Entity::find()
+                .filter(
+                    event_edition_maps::Column::EventId
+                        .eq(1)
+                        .and(event_edition_maps::Column::EditionId.eq(1)),
+                )
+                .count(&db.sql_conn)
+                .await?;
+            assert_eq!(event_maps_count, 0);
+            let maps_count = maps::Entity::find().count(&db.sql_conn).await?;
+            assert_eq!(maps_count, 1);
+
+            anyhow::Ok(())
+        })
+        .await;
+
+        std::fs::remove_file(&file)?;
+        result
+    }
+}
@@ -0,0 +1,33 @@
+use records_lib::{Database, ranks};
+
+#[derive(clap::Subcommand)]
+pub enum RecordCommand {
+    /// Hides a record from the leaderboards, without deleting it.
+    Hide {
+        /// The record ID.
+        record_id: u32,
+    },
+
+    /// Makes a hidden record count again toward the leaderboards.
+    Unhide {
+        /// The record ID.
+        record_id: u32,
+    },
+}
+
+pub async fn record(db: Database, cmd: RecordCommand) -> anyhow::Result<()> {
+    let (record_id, hidden) = match cmd {
+        RecordCommand::Hide { record_id } => (record_id, true),
+        RecordCommand::Unhide { record_id } => (record_id, false),
+    };
+
+    let found = ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, record_id, hidden).await?;
+    anyhow::ensure!(found, "record with ID {record_id} not found");
+
+    tracing::info!(
+        "Record {record_id} is now {}",
+        if hidden { "hidden" } else { "visible" }
+    );
+
+    Ok(())
+}
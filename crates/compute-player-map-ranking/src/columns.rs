@@ -0,0 +1,162 @@
+//! The columns of the ranking CSV files.
+//!
+//! Each column is mapped to its name, and to a function returning its value for a row.
+
+use std::io::{self, Write};
+
+use player_map_ranking::{HashableMap, HashablePlayer};
+
+/// A column of a ranking file, made of its name and a function formatting its value from
+/// the item and its score.
+pub type Column<T> = (&'static str, fn(&T, f64) -> String);
+
+pub const PLAYER_COLUMNS: &[Column<HashablePlayer>] = &[
+    ("id", |player, _| player.inner.id.to_string()),
+    ("login", |player, _| format!("\"{}\"", player.inner.login)),
+    ("name", |player, _| format!("\"{}\"", player.unstyled_name)),
+    ("score", |_, score| score.to_string()),
+    ("player_link", |player, _| {
+        format!(
+            "https://obstacle.titlepack.io/player/{}",
+            player.inner.login
+        )
+    }),
+];
+
+pub const MAP_COLUMNS: &[Column<HashableMap>] = &[
+    ("id", |map, _| map.inner.id.to_string()),
+    ("map_uid", |map, _| format!("\"{}\"", map.inner.game_id)),
+    ("name", |map, _| format!("\"{}\"", map.unstyled_name)),
+    ("score", |_, score| score.to_string()),
+    ("average_score", |map, score| {
+        // The maps without any record would give a NaN average score
+        if map.stats.records_count > 0. {
+            (score / map.stats.records_count).to_string()
+        } else {
+            "0".to_owned()
+        }
+    }),
+    ("min_record", |map, _| map.stats.min_record.to_string()),
+    ("max_record", |map, _| map.stats.max_record.to_string()),
+    ("average_record", |map, _| {
+        map.stats.average_record.to_string()
+    }),
+    ("median_record", |map, _| {
+        map.stats.median_record.to_string()
+    }),
+    ("records_count", |map, _| {
+        map.stats.records_count.to_string()
+    }),
+    ("map_link", |map, _| {
+        format!("https://obstacle.titlepack.io/map/{}", map.inner.game_id)
+    }),
+];
+
+/// Returns the names of all the provided columns, in their default order.
+pub fn all_names<T>(columns: &[Column<T>]) -> Vec<String> {
+    columns.iter().map(|(name, _)| (*name).to_owned()).collect()
+}
+
+/// Returns the columns with the provided names, in the same order.
+pub fn select<T>(columns: &[Column<T>], names: &[String]) -> anyhow::Result<Vec<Column<T>>> {
+    names
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .find(|(column_name, _)| column_name == name)
+                .copied()
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "unknown column `{name}`, expected one of: {}",
+                        all_names(columns).join(", ")
+                    )
+                })
+        })
+        .collect()
+}
+
+pub fn write_header<T, W: Write>(out: &mut W, columns: &[Column<T>]) -> io::Result<()> {
+    let names = columns.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    writeln!(out, "{}", names.join(","))
+}
+
+pub fn write_row<T, W: Write>(
+    out: &mut W,
+    columns: &[Column<T>],
+    item: &T,
+    score: f64,
+) -> io::Result<()> {
+    let values = columns
+        .iter()
+        .map(|(_, value)| value(item, score))
+        .collect::<Vec<_>>();
+    writeln!(out, "{}", values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::maps;
+    use player_map_ranking::HashableMap;
+
+    use super::{MAP_COLUMNS, all_names, select, write_header, write_row};
+
+    fn map_without_records() -> HashableMap {
+        HashableMap {
+            inner: maps::Model {
+                id: 1,
+                game_id: "map_uid".to_owned(),
+                player_id: 1,
+                name: "map_name".to_owned(),
+                cps_number: None,
+                linked_map: None,
+                bronze_time: None,
+                silver_time: None,
+                gold_time: None,
+                author_time: None,
+                score: 0.,
+                max_respawn_count: None,
+                created_at: Default::default(),
+            },
+            stats: Default::default(),
+            unstyled_name: "map_name".to_owned(),
+        }
+    }
+
+    #[test]
+    fn map_without_records_row() {
+        let columns = select(MAP_COLUMNS, &all_names(MAP_COLUMNS)).unwrap();
+
+        let mut out = Vec::new();
+        write_row(&mut out, &columns, &map_without_records(), 0.).unwrap();
+        let row = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            row,
+            "1,\"map_uid\",\"map_name\",0,0,0,0,0,0,0,https://obstacle.titlepack.io/map/map_uid\n"
+        );
+    }
+
+    #[test]
+    fn custom_columns() {
+        let names = ["map_link", "score", "id"].map(ToOwned::to_owned);
+        let columns = select(MAP_COLUMNS, &names).unwrap();
+
+        let mut out = Vec::new();
+        write_header(&mut out, &columns).unwrap();
+        write_row(&mut out, &columns, &map_without_records(), 12.5).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            out,
+            "map_link,score,id\nhttps://obstacle.titlepack.io/map/map_uid,12.5,1\n"
+        );
+    }
+
+    #[test]
+    fn unknown_column() {
+        let names = ["id", "unknown"].map(ToOwned::to_owned);
+        let err = select(MAP_COLUMNS, &names).unwrap_err();
+        assert!(err.to_string().starts_with("unknown column `unknown`"));
+    }
+}
@@ -2,19 +2,24 @@ use std::{
     cmp::Ordering,
     fmt,
     fs::{File, OpenOptions},
-    io::{self, Write},
+    io::{self, BufWriter},
     path::Path,
     str::FromStr,
     time::Instant,
 };
 
 use anyhow::Context as _;
-use chrono::{DateTime, Days, Months, Utc};
+use chrono::{DateTime, Days, Duration, Months, Utc};
 use clap::Parser as _;
 use mkenv::prelude::*;
 use records_lib::{DbUrlEnv, time::Time};
 use sea_orm::Database;
 
+use crate::output::Output;
+
+mod columns;
+mod output;
+
 mkenv::make_config! {
     struct AppEnv {
         db_env: { DbUrlEnv },
@@ -26,35 +31,63 @@ struct SinceDuration {
     date: DateTime<Utc>,
 }
 
-#[derive(Debug)]
-struct InvalidSinceDuration;
+#[derive(Debug, PartialEq)]
+enum InvalidSinceDuration {
+    InvalidCount,
+    ZeroCount,
+    UnknownUnit(String),
+}
 
 impl fmt::Display for InvalidSinceDuration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("invalid \"since duration\" argument")
+        f.write_str("invalid \"since duration\" argument: ")?;
+        match self {
+            InvalidSinceDuration::InvalidCount => {
+                f.write_str("expected a count followed by a unit, e.g. `2w`")
+            }
+            InvalidSinceDuration::ZeroCount => f.write_str("the count must be greater than 0"),
+            InvalidSinceDuration::UnknownUnit(unit) => write!(
+                f,
+                "unknown unit `{unit}`, expected one of: min, h, d, w, m, y"
+            ),
+        }
     }
 }
 
 impl std::error::Error for InvalidSinceDuration {}
 
-impl FromStr for SinceDuration {
-    type Err = InvalidSinceDuration;
+impl SinceDuration {
+    fn parse_from(now: DateTime<Utc>, s: &str) -> Result<Self, InvalidSinceDuration> {
+        let unit_idx = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (n, unit) = s.split_at(unit_idx);
+        let n = n
+            .parse::<u32>()
+            .map_err(|_| InvalidSinceDuration::InvalidCount)?;
+        if n == 0 {
+            return Err(InvalidSinceDuration::ZeroCount);
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (n, unit) = s.split_at(s.len() - 1);
-        let n = n.parse::<u32>().map_err(|_| InvalidSinceDuration)?;
-        let now = Utc::now();
         let date = match unit {
+            "min" => now - Duration::minutes(n as _),
+            "h" => now - Duration::hours(n as _),
             "d" => now - Days::new(n as _),
             "w" => now - Days::new(n as u64 * 7),
             "m" => now - Months::new(n),
             "y" => now - Months::new(n * 12),
-            _ => return Err(InvalidSinceDuration),
+            _ => return Err(InvalidSinceDuration::UnknownUnit(unit.to_owned())),
         };
         Ok(Self { date })
     }
 }
 
+impl FromStr for SinceDuration {
+    type Err = InvalidSinceDuration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_from(Utc::now(), s)
+    }
+}
+
 #[derive(clap::Parser)]
 struct Args {
     #[arg(
@@ -65,8 +98,26 @@ struct Args {
     player_ranking_file: String,
     #[arg(short = 'm', long = "map-file", default_value = "map_ranking.csv")]
     map_ranking_file: String,
+    /// Only ranks the records made since this duration, e.g. `90min`, `6h`, `2w` or `1m`
     #[arg(long = "since", value_parser = clap::value_parser!(SinceDuration))]
     from_date: Option<SinceDuration>,
+    /// The comma-separated list of the columns of the player ranking file, in order
+    #[arg(
+        long = "player-columns",
+        value_delimiter = ',',
+        default_values_t = columns::all_names(columns::PLAYER_COLUMNS)
+    )]
+    player_columns: Vec<String>,
+    /// The comma-separated list of the columns of the map ranking file, in order
+    #[arg(
+        long = "map-columns",
+        value_delimiter = ',',
+        default_values_t = columns::all_names(columns::MAP_COLUMNS)
+    )]
+    map_columns: Vec<String>,
+    /// Where to write the player ranking
+    #[arg(long, value_enum, default_value_t = Output::File)]
+    output: Output,
 }
 
 fn open_file<P: AsRef<Path>>(path: P) -> io::Result<File> {
@@ -84,20 +135,24 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().context("couldn't get environment file")?;
     let args = Args::parse();
 
-    let mut player_ranking_file =
-        open_file(args.player_ranking_file).context("couldn't open player ranking output file")?;
+    let mut player_ranking_file = match args.output {
+        Output::File => Some(
+            open_file(args.player_ranking_file)
+                .context("couldn't open player ranking output file")?,
+        ),
+        Output::StdoutCsv => None,
+    };
     let mut map_ranking_file =
         open_file(args.map_ranking_file).context("couldn't open map ranking output file")?;
 
-    player_ranking_file
-        .write(b"id,login,name,score,player_link\n")
-        .context("couldn't write header to player ranking file")?;
-    map_ranking_file
-        .write(b"id,map_uid,name,score,average_score,min_record,")
-        .and_then(|_| {
-            map_ranking_file
-                .write(b"max_record,average_record,median_record,records_count,map_link\n")
-        })
+    let player_columns = columns::select(columns::PLAYER_COLUMNS, &args.player_columns)?;
+    let map_columns = columns::select(columns::MAP_COLUMNS, &args.map_columns)?;
+
+    if let Some(player_ranking_file) = &mut player_ranking_file {
+        columns::write_header(player_ranking_file, &player_columns)
+            .context("couldn't write header to player ranking file")?;
+    }
+    columns::write_header(&mut map_ranking_file, &map_columns)
         .context("couldn't write header to map ranking file")?;
 
     let app_config = AppEnv::define();
@@ -109,59 +164,110 @@ async fn main() -> anyhow::Result<()> {
         .await
         .context("couldn't connect to database")?;
 
-    println!(
+    eprintln!(
         "Calculating scores{}...",
         match &args.from_date {
-            Some(SinceDuration { date }) => format!(" since {}", date.format("%d/%m/%Y")),
+            Some(SinceDuration { date }) => format!(" since {}", date.format("%d/%m/%Y %H:%M")),
             None => "".to_owned(),
         }
     );
 
-    let scores = player_map_ranking::compute_scores(&db, args.from_date.map(|d| d.date))
-        .await
-        .context("couldn't compute the scores")?;
+    let scores =
+        player_map_ranking::compute_scores(&db, args.from_date.map(|d| d.date), Default::default())
+            .await
+            .context("couldn't compute the scores")?;
 
-    println!("Sorting them...");
+    eprintln!("Sorting them...");
 
     let mut player_ranking = scores.player_scores.into_iter().collect::<Vec<_>>();
     player_ranking.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
     let mut map_ranking = scores.map_scores.into_iter().collect::<Vec<_>>();
     map_ranking.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
 
-    println!("Writing to files...");
-
-    for (player, score) in player_ranking {
-        writeln!(
-            player_ranking_file,
-            "{},\"{login}\",\"{}\",{score},https://obstacle.titlepack.io/player/{login}",
-            player.inner.id,
-            player.unstyled_name,
-            login = player.inner.login,
-        )
-        .context("couldn't write a row to player ranking file")?;
+    eprintln!("Writing to files...");
+
+    match &mut player_ranking_file {
+        Some(player_ranking_file) => {
+            for (player, score) in player_ranking {
+                columns::write_row(player_ranking_file, &player_columns, &player, score)
+                    .context("couldn't write a row to player ranking file")?;
+            }
+        }
+        None => {
+            let stdout = BufWriter::new(io::stdout().lock());
+            output::stream_csv(
+                stdout,
+                &player_columns,
+                player_ranking,
+                output::FLUSH_INTERVAL,
+            )
+            .context("couldn't stream the player ranking to the standard output")?;
+        }
     }
 
     for (map, score) in map_ranking {
-        writeln!(
-            map_ranking_file,
-            "{},\"{map_uid}\",\"{}\",{score},{},{},{},{},{},{},https://obstacle.titlepack.io/map/{map_uid}",
-            map.inner.id,
-            map.unstyled_name,
-            score / map.stats.records_count,
-            map.stats.min_record,
-            map.stats.max_record,
-            map.stats.average_record,
-            map.stats.median_record,
-            map.stats.records_count,
-            map_uid = map.inner.game_id,
-        )
-        .context("couldn't write a row to map ranking file")?;
+        columns::write_row(&mut map_ranking_file, &map_columns, &map, score)
+            .context("couldn't write a row to map ranking file")?;
     }
 
-    println!(
+    eprintln!(
         "Finished. Time taken: {}",
         Time(now.elapsed().as_millis() as _)
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone as _, Utc};
+
+    use super::{InvalidSinceDuration, SinceDuration};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    fn parse(s: &str) -> Result<DateTime<Utc>, InvalidSinceDuration> {
+        SinceDuration::parse_from(now(), s).map(|d| d.date)
+    }
+
+    #[test]
+    fn since_hours() {
+        assert_eq!(
+            parse("6h"),
+            Ok(Utc.with_ymd_and_hms(2025, 6, 15, 6, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn since_minutes() {
+        assert_eq!(
+            parse("90min"),
+            Ok(Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn since_weeks() {
+        assert_eq!(
+            parse("2w"),
+            Ok(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn since_zero_count() {
+        assert_eq!(parse("0h"), Err(InvalidSinceDuration::ZeroCount));
+    }
+
+    #[test]
+    fn since_invalid() {
+        assert_eq!(
+            parse("3s"),
+            Err(InvalidSinceDuration::UnknownUnit("s".to_owned()))
+        );
+        assert_eq!(parse("h"), Err(InvalidSinceDuration::InvalidCount));
+        assert_eq!(parse(""), Err(InvalidSinceDuration::InvalidCount));
+    }
+}
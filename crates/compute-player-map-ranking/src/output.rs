@@ -0,0 +1,152 @@
+//! The streaming of a ranking as CSV, used to pipe it into other processes.
+
+use std::io::{self, Write};
+
+use crate::columns::{self, Column};
+
+/// The destination of the player ranking.
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum Output {
+    /// Writes the player ranking to the player ranking file.
+    File,
+    /// Streams the player ranking as CSV to the standard output.
+    StdoutCsv,
+}
+
+/// The amount of rows written between each flush of the stream.
+pub const FLUSH_INTERVAL: usize = 1000;
+
+/// Streams the header and the provided rows as CSV to the output.
+///
+/// The output is flushed every `flush_interval` rows, so the reading process receives them
+/// without waiting for the whole ranking. If the reading end is closed (e.g. `head` exited),
+/// the streaming stops without error.
+///
+/// It returns the amount of rows that were written.
+pub fn stream_csv<T, W, I>(
+    mut out: W,
+    columns: &[Column<T>],
+    rows: I,
+    flush_interval: usize,
+) -> io::Result<usize>
+where
+    W: Write,
+    I: IntoIterator<Item = (T, f64)>,
+{
+    let mut written = 0;
+
+    let result = (|| {
+        columns::write_header(&mut out, columns)?;
+
+        for (item, score) in rows {
+            columns::write_row(&mut out, columns, &item, score)?;
+            written += 1;
+            if written % flush_interval == 0 {
+                out.flush()?;
+            }
+        }
+
+        out.flush()
+    })();
+
+    match result {
+        Ok(()) => Ok(written),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(written),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use entity::players;
+    use player_map_ranking::HashablePlayer;
+
+    use crate::columns::{PLAYER_COLUMNS, select};
+
+    use super::stream_csv;
+
+    fn player(id: u32) -> HashablePlayer {
+        HashablePlayer {
+            inner: players::Model {
+                id,
+                login: format!("player_{id}_login"),
+                name: format!("player_{id}_name"),
+                join_date: None,
+                zone_path: None,
+                admins_note: None,
+                role: 0,
+                score: 0.,
+            },
+            unstyled_name: format!("player_{id}_name"),
+        }
+    }
+
+    /// Records the flushes, and fails with a broken pipe once the limit of bytes is reached.
+    #[derive(Default)]
+    struct Pipe {
+        out: Vec<u8>,
+        flushed: usize,
+        limit: Option<usize>,
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self
+                .limit
+                .is_some_and(|limit| self.out.len() + buf.len() > limit)
+            {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed = self.out.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stream_player_ranking() {
+        let names = ["id", "login", "score"].map(ToOwned::to_owned);
+        let columns = select(PLAYER_COLUMNS, &names).unwrap();
+        let rows = (1..=5).map(|id| (player(id), 100. - id as f64));
+
+        let mut pipe = Pipe::default();
+        let written = stream_csv(&mut pipe, &columns, rows, 2).unwrap();
+        assert_eq!(written, 5);
+        assert_eq!(pipe.flushed, pipe.out.len());
+
+        let out = String::from_utf8(pipe.out).unwrap();
+        let mut lines = out.lines().map(|line| line.split(',').collect::<Vec<_>>());
+
+        assert_eq!(lines.next(), Some(vec!["id", "login", "score"]));
+        for id in 1..=5 {
+            let row = lines.next().unwrap();
+            assert_eq!(row[0].parse::<u32>(), Ok(id));
+            assert_eq!(row[1], format!("\"player_{id}_login\""));
+            assert_eq!(row[2].parse::<f64>(), Ok(100. - id as f64));
+        }
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn stream_broken_pipe() {
+        let names = ["id"].map(ToOwned::to_owned);
+        let columns = select(PLAYER_COLUMNS, &names).unwrap();
+        let rows = (1..=5).map(|id| (player(id), 0.));
+
+        // Only the header and the 2 first rows fit before the pipe is closed
+        let mut pipe = Pipe {
+            limit: Some("id\n1\n2\n".len()),
+            ..Default::default()
+        };
+        let written = stream_csv(&mut pipe, &columns, rows, 1).unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(pipe.out, b"id\n1\n2\n");
+    }
+}
@@ -48,6 +48,13 @@ pub struct Model {
     /// A transparent event edition means that there is no records explicitly attached to this edition.
     /// Every record made on any map the edition contains is counted as being attached to this edition.
     pub is_transparent: i8,
+    /// The optional maximum amount of respawns of the records saved in the edition.
+    ///
+    /// The records with more respawns are rejected. If the map has its own limit, the lowest one
+    /// is used.
+    pub max_respawn_count: Option<u32>,
+    /// Whether the edition only accepts the records without any respawn.
+    pub no_respawn_only: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +34,12 @@ pub struct Model {
     pub author_time: Option<i32>,
     /// The score of the player, calculated periodically.
     pub score: f64,
+    /// The optional maximum amount of respawns of the records saved on the map.
+    ///
+    /// The records with more respawns are rejected.
+    pub max_respawn_count: Option<u32>,
+    /// The date when the map was added.
+    pub created_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -36,6 +36,10 @@ pub struct Model {
     pub event_record_id: Option<u32>,
     /// The version of the Obstacle mode in which the player made this record.
     pub modeversion: Option<ModeVersion>,
+    /// Whether the record was hidden by a moderator.
+    ///
+    /// Hidden records are kept for audit, but they don't count toward the leaderboards and ranks.
+    pub is_hidden: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
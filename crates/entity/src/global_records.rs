@@ -17,6 +17,7 @@ pub struct Model {
     pub flags: u32,
     pub try_count: Option<u32>,
     pub event_record_id: Option<u32>,
+    pub is_hidden: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -130,6 +131,7 @@ impl From<Model> for records::Model {
             event_record_id: value.event_record_id,
             // TODO: add mode version in global_event_records view
             modeversion: None,
+            is_hidden: value.is_hidden,
         }
     }
 }
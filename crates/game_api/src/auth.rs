@@ -243,12 +243,12 @@ impl AuthState {
     }
 }
 
-struct ExtAuthHeaders {
-    player_login: Option<String>,
-    authorization: Option<String>,
+pub(crate) struct ExtAuthHeaders {
+    pub(crate) player_login: Option<String>,
+    pub(crate) authorization: Option<String>,
 }
 
-fn ext_auth_headers(req: &HttpRequest) -> ExtAuthHeaders {
+pub(crate) fn ext_auth_headers(req: &HttpRequest) -> ExtAuthHeaders {
     fn ext_header(req: &HttpRequest, header: &str) -> Option<String> {
         req.headers()
             .get(header)
@@ -1,8 +1,14 @@
 pub mod slow_req_mw;
 
-use std::{fmt, time::Duration};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
-use actix_http::StatusCode;
+use actix_http::{
+    StatusCode,
+    header::{HeaderName, HeaderValue},
+};
 use actix_web::{
     Error, Responder,
     body::MessageBody,
@@ -114,6 +120,31 @@ pub async fn mask_internal_errors(
     }
 }
 
+/// The name of the header containing the time spent processing the request, in milliseconds.
+pub const PROCESSING_TIME_HEADER: &str = "x-processing-time-ms";
+
+/// Adds the [`PROCESSING_TIME_HEADER`] to the response.
+///
+/// This middleware is meant to be wrapped right inside the [`TracingLogger`][1], so the measured
+/// time matches the duration of the root span of the request.
+///
+/// [1]: tracing_actix_web::TracingLogger
+pub async fn processing_time(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let start = Instant::now();
+    let mut res = next.call(req).await?;
+
+    let elapsed = start.elapsed().as_millis();
+    res.headers_mut().insert(
+        HeaderName::from_static(PROCESSING_TIME_HEADER),
+        HeaderValue::from(elapsed as u64),
+    );
+
+    Ok(res)
+}
+
 pub(crate) fn send_internal_err_msg_detached<E>(
     client: reqwest::Client,
     head: actix_http::RequestHead,
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use std::error::Error;
+use std::{error::Error, fmt};
 
 use actix_web::cookie::Key;
 use mkenv::{make_config, prelude::*};
@@ -147,6 +147,50 @@ mkenv::make_config! {
             default_val_fmt: "10s",
         },
 
+        pub event_grace_period: {
+            var_name: "RECORDS_API_EVENT_GRACE_PERIOD_SECONDS",
+            layers: [
+                parsed<Duration>(|input| {
+                    input.parse::<u64>()
+                        .map(Duration::from_secs)
+                        .map_err(From::from)
+                }),
+                or_default_val(|| Duration::ZERO),
+            ],
+            description: "The duration, in seconds, after the expiry of an event edition during which the late submissions are still accepted",
+            default_val_fmt: "0",
+        },
+
+        pub default_thumbnail_url: {
+            var_name: "RECORDS_API_DEFAULT_THUMBNAIL_URL",
+            layers: [
+                parsed<Option<String>>(|s| Ok(Some(s.to_owned()))),
+                or_default(),
+            ],
+            description: "The URL of the image to redirect to when an event map has no thumbnail source",
+            default_val_fmt: "empty",
+        },
+
+        pub graphql_playground: {
+            var_name: "RECORDS_API_GRAPHQL_PLAYGROUND",
+            layers: [
+                parsed_from_str<bool>(),
+                or_default_val(|| cfg!(debug_assertions)),
+            ],
+            description: "Whether the GraphQL playground is served on the `/graphql` GET route (boolean)",
+            default_val_fmt: "true in debug, false in release",
+        },
+
+        pub processing_time_header: {
+            var_name: "RECORDS_API_PROCESSING_TIME_HEADER",
+            layers: [
+                parsed_from_str<bool>(),
+                or_default(),
+            ],
+            description: "Whether the responses include the `X-Processing-Time-Ms` header, with the time spent processing the request (boolean)",
+            default_val_fmt: "false",
+        },
+
         pub wh_request_timeout: {
             var_name: "WEBHOOK_REQUEST_TIMEOUT_URL",
             layers: [
@@ -173,6 +217,77 @@ pub fn env() -> &'static ApiEnv {
     ENV.get().unwrap()
 }
 
+/// The non-secret values of the configuration, paired with the name of their environment variable.
+///
+/// The database and webhook URLs, the session key and the ManiaPlanet client credentials
+/// aren't included.
+pub struct ConfigDump(Vec<(&'static str, String)>);
+
+impl ConfigDump {
+    /// Returns the value of the provided environment variable, if it's included in the dump.
+    pub fn get(&self, var_name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(name, _)| *name == var_name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for ConfigDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.0 {
+            writeln!(f, "{name} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the dump of the effective configuration of the API and of the libraries it uses.
+///
+/// **Caution**: To use this function, the [`init_env()`] function must have been called.
+pub fn config_dump() -> ConfigDump {
+    let env = env();
+
+    let mut dump = vec![
+        ("RECORDS_API_PORT", env.port.get().to_string()),
+        (
+            "RECORDS_API_TOKEN_TTL",
+            env.auth_token_ttl.get().to_string(),
+        ),
+        (
+            "REQUEST_TIMEOUT_MS",
+            env.request_timeout.get().as_millis().to_string(),
+        ),
+        (
+            "RECORDS_API_EVENT_GRACE_PERIOD_SECONDS",
+            env.event_grace_period.get().as_secs().to_string(),
+        ),
+        (
+            "RECORDS_API_GRAPHQL_PLAYGROUND",
+            env.graphql_playground.get().to_string(),
+        ),
+        (
+            "RECORDS_API_PROCESSING_TIME_HEADER",
+            env.processing_time_header.get().to_string(),
+        ),
+        (
+            "REDIS_HEALTH_CHECK_INTERVAL_SECONDS",
+            env.db_env
+                .redis_url
+                .redis_health_check_interval
+                .get()
+                .as_secs()
+                .to_string(),
+        ),
+    ];
+    #[cfg(not(debug_assertions))]
+    dump.push(("RECORDS_API_HOST", env.host.host.get()));
+    dump.extend(records_lib::env_dump());
+    dump.extend(graphql_api::config::config_dump());
+
+    ConfigDump(dump)
+}
+
 pub fn init_env() -> anyhow::Result<()> {
     let env = All::define();
     env.try_init().map_err(|e| anyhow::anyhow!("{e}"))?;
@@ -69,6 +69,14 @@ pub enum ApiErrorKind<E = records_lib::error::RecordsError> {
     InvalidTimes,
     #[error("event `{0}` {1} has expired")]
     EventHasExpired(String, u32),
+    #[error("no record found for player with login: `{0}` on map with uid: `{1}`")]
+    NoRecordFound(String, String),
+    #[error("too many respawns ({0}), the maximum is {1}")]
+    TooManyRespawns(i32, u32),
+    #[error("no thumbnail found for map with uid: `{0}`")]
+    NoThumbnailFound(String),
+    #[error("invalid medal percentiles")]
+    InvalidMedalPercentiles,
 
     #[error(transparent)]
     Lib(E),
@@ -141,6 +149,7 @@ where
             E::Lib(e) if matches!(e.as_ref(), LE::PlayerNotFound(_)) => (302, S::BAD_REQUEST),
             E::PlayerNotBanned(_) => (303, S::BAD_REQUEST),
             E::Lib(e) if matches!(e.as_ref(), LE::MapNotFound(_)) => (304, S::BAD_REQUEST),
+            E::Lib(e) if matches!(e.as_ref(), LE::MapIdNotFound(_)) => (304, S::BAD_REQUEST),
             E::Lib(e) if matches!(e.as_ref(), LE::UnknownRole(_, _)) => {
                 (305, S::INTERNAL_SERVER_ERROR)
             }
@@ -157,6 +166,13 @@ where
             E::InvalidTimes => (313, S::BAD_REQUEST),
             E::Lib(e) if matches!(e.as_ref(), LE::InvalidMappackId(_)) => (314, S::BAD_REQUEST),
             E::EventHasExpired(_, _) => (315, S::BAD_REQUEST),
+            E::NoRecordFound(_, _) => (316, S::NOT_FOUND),
+            E::TooManyRespawns(_, _) => (317, S::BAD_REQUEST),
+            E::Lib(e) if matches!(e.as_ref(), LE::MapNotAvailable(_, _, _)) => {
+                (318, S::BAD_REQUEST)
+            }
+            E::NoThumbnailFound(_) => (319, S::NOT_FOUND),
+            E::InvalidMedalPercentiles => (320, S::BAD_REQUEST),
 
             E::Lib(_) => (199, S::INTERNAL_SERVER_ERROR),
         }
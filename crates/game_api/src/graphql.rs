@@ -8,13 +8,17 @@ use async_graphql::{ErrorExtensionValues, Executor};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLSubscription};
 use futures::StreamExt;
 use futures::stream::BoxStream;
+use graphql_api::auth::{AdminAuth, LazyAdminAuth};
+use graphql_api::config::query_timeout;
 use graphql_api::error::{ApiGqlError, ApiGqlErrorKind};
-use graphql_api::schema::{Schema, create_schema};
+use graphql_api::schema::{Schema, create_schema, execute_with_timeout};
 use records_lib::Database;
+use records_lib::error::RecordsError;
 use records_lib::records_notifier::LatestRecordsSubscription;
 use reqwest::Client;
 use tracing_actix_web::RequestId;
 
+use crate::auth::{self, ExtAuthHeaders, privilege};
 use crate::{ApiErrorKind, RecordsResult, Res, configure};
 
 #[derive(Clone)]
@@ -38,6 +42,15 @@ impl ExecutorInventory {
                 .extensions
                 .get_or_insert_with(ErrorExtensionValues::default);
 
+            if let Some(err) = &api_error {
+                match err.kind() {
+                    ApiGqlErrorKind::Unauthorized => extensions.set("error_code", 201),
+                    ApiGqlErrorKind::Forbidden => extensions.set("error_code", 202),
+                    ApiGqlErrorKind::Timeout { .. } => extensions.set("error_code", 208),
+                    _ => (),
+                }
+            }
+
             // Don't expose internal server errors
             if let Some(err) = api_error
                 && let ApiGqlErrorKind::Lib(records_err) = err.kind()
@@ -78,7 +91,7 @@ struct GraphqlApiExecutor {
 
 impl Executor for GraphqlApiExecutor {
     async fn execute(&self, request: async_graphql::Request) -> async_graphql::Response {
-        let result = Executor::execute(&self.schema, request).await;
+        let result = execute_with_timeout(&self.schema, request, query_timeout()).await;
         self.inventory.mask_internal_errors(result)
     }
 
@@ -94,13 +107,58 @@ impl Executor for GraphqlApiExecutor {
     }
 }
 
+/// Checks the authentication headers of the request for the admin-only operations.
+async fn get_admin_auth(
+    db: Database,
+    player_login: Option<String>,
+    authorization: Option<String>,
+) -> Result<AdminAuth, RecordsError> {
+    let Some(login) = player_login else {
+        return Ok(AdminAuth::Unauthorized);
+    };
+
+    let mut redis_conn = db.redis_pool.get().await?;
+
+    let result = auth::check_auth_for(
+        &db.sql_conn,
+        &mut redis_conn,
+        &login,
+        authorization.as_deref(),
+        privilege::ADMIN,
+    )
+    .await;
+
+    match result {
+        Ok(player_id) => Ok(AdminAuth::Admin { player_id }),
+        Err(ApiErrorKind::Forbidden | ApiErrorKind::BannedPlayer(_)) => Ok(AdminAuth::Forbidden),
+        Err(ApiErrorKind::Unauthorized | ApiErrorKind::Lib(RecordsError::PlayerNotFound(_))) => {
+            Ok(AdminAuth::Unauthorized)
+        }
+        Err(ApiErrorKind::Lib(e)) => Err(e),
+        Err(e) => Err(records_lib::internal!(
+            "unexpected error when checking admin auth: {e}"
+        )),
+    }
+}
+
 async fn index_graphql(
     request_id: RequestId,
     client: Res<reqwest::Client>,
+    db: Res<Database>,
     req: HttpRequest,
     schema: Res<Schema>,
     GraphQLRequest(request): GraphQLRequest,
 ) -> RecordsResult<impl Responder> {
+    // The admin authentication is only checked by the mutations that require it
+    let ExtAuthHeaders {
+        player_login,
+        authorization,
+    } = auth::ext_auth_headers(&req);
+    let db = db.0;
+    let request = request.data(LazyAdminAuth::new(move || {
+        get_admin_auth(db.clone(), player_login.clone(), authorization.clone())
+    }));
+
     let executor = GraphqlApiExecutor {
         schema: schema.0,
         inventory: ExecutorInventory {
@@ -115,13 +173,18 @@ async fn index_graphql(
     Ok(web::Json(result))
 }
 
-async fn index_playground() -> impl Responder {
-    HttpResponse::Ok()
+async fn index_playground() -> RecordsResult<impl Responder> {
+    // The route is kept when disabled, otherwise GET requests would get a 405 from the POST route
+    if !crate::env().graphql_playground.get() {
+        return Err(ApiErrorKind::EndpointNotFound);
+    }
+
+    Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(playground_source(
             GraphQLPlaygroundConfig::new("/graphql")
                 .subscription_endpoint("/graphql/subscriptions"),
-        ))
+        )))
 }
 
 async fn index_subscriptions(
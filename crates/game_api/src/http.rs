@@ -7,7 +7,7 @@ pub mod event;
 pub mod map;
 pub mod player;
 
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 use mkenv::prelude::*;
 
@@ -23,7 +23,7 @@ use serde::Serialize;
 #[cfg(auth)]
 use self::admin::admin_scope;
 use self::event::event_scope;
-use self::map::map_scope;
+use self::map::{map_scope, maps_scope};
 use self::player::player_scope;
 use self::staggered::staggered_scope;
 use crate::utils::{self, ApiStatus, ExtractDbConn, get_api_status, json};
@@ -62,6 +62,7 @@ pub fn api_route() -> Scope<
         .service(staggered_scope())
         .service(player_scope())
         .service(map_scope())
+        .service(maps_scope())
         .service(event_scope());
 
     let json_config = JsonConfig::default().limit(1024 * 16);
@@ -69,6 +70,7 @@ pub fn api_route() -> Scope<
     web::scope("")
         .app_data(json_config)
         .route("/info", web::get().to(info))
+        .route("/info/features", web::get().to(info_features))
         .service(scope)
 }
 
@@ -209,6 +211,18 @@ async fn info(ExtractDbConn(conn): ExtractDbConn) -> RecordsResult<impl Responde
     })
 }
 
+/// Returns the optional features of the API, with whether they're enabled, either at compile time
+/// or by the environment.
+async fn info_features() -> RecordsResult<impl Responder> {
+    json(BTreeMap::from([
+        ("auth", cfg!(auth)),
+        ("request_filter", cfg!(feature = "request_filter")),
+        ("graphql_subscriptions", true),
+        ("graphql_playground", crate::env().graphql_playground.get()),
+        ("hide_banned_players", records_lib::hide_banned_players()),
+    ]))
+}
+
 async fn overview(
     db: Res<Database>,
     Query(query): overview::OverviewReq,
@@ -2,12 +2,16 @@ use actix_web::{
     HttpResponse, Responder, Scope,
     web::{self, Json},
 };
-use entity::{banishments, current_bans, players, role};
+use entity::{banishments, current_bans, maps, players, role};
 use futures::TryStreamExt;
+use records_lib::{
+    event::{self, MedalPercentiles},
+    sync,
+};
 use sea_orm::{
     ActiveValue::Set,
-    ColumnTrait as _, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter as _, QuerySelect,
-    RelationTrait as _,
+    ColumnTrait as _, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter as _,
+    QueryOrder as _, QuerySelect, RelationTrait as _,
     prelude::Expr,
     sea_query::{ExprTrait, Func, Query},
 };
@@ -20,6 +24,8 @@ use crate::{
     utils::{ExtractDbConn, json},
 };
 
+use super::player::{self, PlayerInfoNetBody};
+
 pub fn admin_scope() -> Scope {
     web::scope("/admin")
         .route("/del_note", web::post().to(del_note))
@@ -28,6 +34,12 @@ pub fn admin_scope() -> Scope {
         .route("/ban", web::post().to(ban))
         .route("/unban", web::post().to(unban))
         .route("/player_note", web::get().to(player_note))
+        .route("/anonymize", web::post().to(anonymize))
+        .route("/maps/register", web::post().to(register_maps))
+        .route(
+            "/event/{handle}/{id}/recompute-medals",
+            web::post().to(recompute_medals),
+        )
 }
 
 #[derive(Deserialize)]
@@ -373,3 +385,153 @@ pub async fn player_note(
         admins_note,
     })
 }
+
+#[derive(Deserialize)]
+pub struct AnonymizeBody {
+    player_login: String,
+}
+
+#[derive(Serialize)]
+struct AnonymizeResponse {
+    player_login: String,
+}
+
+pub async fn anonymize(
+    _: MPAuthGuard<{ privilege::ADMIN }>,
+    ExtractDbConn(conn): ExtractDbConn,
+    Json(body): Json<AnonymizeBody>,
+) -> RecordsResult<impl Responder> {
+    let player_id = records_lib::must::have_player(&conn, &body.player_login)
+        .await?
+        .id;
+
+    let player_login = records_lib::player::anonymize(&conn, player_id)
+        .await
+        .with_api_err()?;
+
+    json(AnonymizeResponse { player_login })
+}
+
+#[derive(Deserialize)]
+pub struct RegisterMapBody {
+    uid: String,
+    name: String,
+    author_login: String,
+}
+
+#[derive(Serialize, Default)]
+struct RegisterMapsResponse {
+    created: Vec<String>,
+    existing: Vec<String>,
+}
+
+/// Inserts the provided maps that aren't saved yet, along with their missing authors.
+///
+/// The authors that don't exist yet are saved with their login as name, which is updated when
+/// they join a server.
+pub async fn register_maps(
+    _: MPAuthGuard<{ privilege::ADMIN }>,
+    ExtractDbConn(conn): ExtractDbConn,
+    Json(body): Json<Vec<RegisterMapBody>>,
+) -> RecordsResult<impl Responder> {
+    let res = sync::transaction(&conn, async |txn| {
+        let mut res = RegisterMapsResponse::default();
+
+        for map in body {
+            if records_lib::map::get_map_from_uid(txn, &map.uid)
+                .await?
+                .is_some()
+            {
+                res.existing.push(map.uid);
+                continue;
+            }
+
+            let author = player::get_or_insert(
+                txn,
+                &PlayerInfoNetBody {
+                    name: map.author_login.clone(),
+                    login: map.author_login,
+                    zone_path: None,
+                },
+            )
+            .await?;
+
+            maps::Entity::insert(maps::ActiveModel {
+                game_id: Set(map.uid.clone()),
+                player_id: Set(author.id),
+                name: Set(map.name),
+                ..Default::default()
+            })
+            .exec(txn)
+            .await
+            .with_api_err()?;
+
+            res.created.push(map.uid);
+        }
+
+        RecordsResult::Ok(res)
+    })
+    .await?;
+
+    json(res)
+}
+
+#[derive(Serialize)]
+struct RecomputedMedalTimes {
+    map_uid: String,
+    bronze_time: i32,
+    silver_time: i32,
+    gold_time: i32,
+    champion_time: i32,
+}
+
+#[derive(Serialize)]
+struct RecomputeMedalsResponse {
+    maps: Vec<RecomputedMedalTimes>,
+}
+
+/// Recomputes the medal times of the maps of an event edition from the best times of the
+/// players, at the percentiles provided in the body.
+pub async fn recompute_medals(
+    _: MPAuthGuard<{ privilege::ADMIN }>,
+    ExtractDbConn(conn): ExtractDbConn,
+    path: web::Path<(String, u32)>,
+    Json(percentiles): Json<MedalPercentiles>,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id) = path.into_inner();
+
+    if !percentiles.is_valid() {
+        return Err(ApiErrorKind::InvalidMedalPercentiles);
+    }
+
+    let (event, edition) =
+        records_lib::must::have_event_edition(&conn, &event_handle, edition_id).await?;
+
+    let maps_medal_times = sync::transaction(&conn, async |txn| {
+        event::recompute_medal_times(txn, event.id, edition.id, percentiles)
+            .await
+            .with_api_err()
+    })
+    .await?;
+
+    let maps = maps::Entity::find()
+        .filter(maps::Column::Id.is_in(maps_medal_times.keys().copied()))
+        .order_by_asc(maps::Column::GameId)
+        .all(&conn)
+        .await
+        .with_api_err()?
+        .into_iter()
+        .filter_map(|map| {
+            let medal_times = maps_medal_times.get(&map.id)?;
+            Some(RecomputedMedalTimes {
+                map_uid: map.game_id,
+                bronze_time: medal_times.bronze_time,
+                silver_time: medal_times.silver_time,
+                gold_time: medal_times.gold_time,
+                champion_time: medal_times.champion_time,
+            })
+        })
+        .collect();
+
+    json(RecomputeMedalsResponse { maps })
+}
@@ -2,10 +2,10 @@ use std::collections::HashMap;
 
 use actix_web::{
     HttpResponse, Responder, Scope,
+    http::header,
     web::{self, Path},
 };
 use chrono::{DateTime, Utc};
-use deadpool_redis::redis::AsyncCommands as _;
 use entity::{
     event_edition, event_edition_maps, event_edition_records, global_event_records, global_records,
     in_game_event_edition_params, maps, players, records,
@@ -19,10 +19,7 @@ use records_lib::{
     error::RecordsError,
     event::{self, EventMap},
     opt_event::OptEvent,
-    player,
     records_notifier::RecordsNotifier,
-    redis_key::alone_map_key,
-    sync,
 };
 
 use sea_orm::{
@@ -42,7 +39,7 @@ use crate::{
 };
 
 use super::{
-    overview, pb,
+    map, overview, pb,
     player::PlayerInfoNetBody,
     player_finished::{self as pf, ExpandedInsertRecordParams},
 };
@@ -54,6 +51,23 @@ pub fn event_scope() -> Scope {
                 .service(
                     web::scope("/{edition_id}")
                         .route("/overview", web::get().to(edition_overview))
+                        .route(
+                            "/medal-distribution",
+                            web::get().to(edition_medal_distribution),
+                        )
+                        .route("/leaderboard", web::get().to(edition_leaderboard))
+                        .route(
+                            "/map/{map_uid}/first-record",
+                            web::get().to(edition_first_record),
+                        )
+                        .route(
+                            "/map/{map_uid}/thumbnail",
+                            web::get().to(edition_map_thumbnail),
+                        )
+                        .route(
+                            "/map/{map_uid}/player/{login}/record",
+                            web::get().to(edition_player_record),
+                        )
                         .service(
                             web::scope("/player")
                                 .route("/finished", web::post().to(edition_finished))
@@ -76,6 +90,7 @@ struct MapWithCategory {
     source: Option<String>,
     thumbnail_source: Option<String>,
     is_available: Option<bool>,
+    is_disabled: Option<bool>,
 }
 
 async fn get_maps_by_edition_id<C: ConnectionTrait>(
@@ -102,6 +117,7 @@ async fn get_maps_by_edition_id<C: ConnectionTrait>(
             event_edition_maps::Column::Source,
             event_edition_maps::Column::ThumbnailSource,
             event_edition_maps::Column::IsAvailable,
+            event_edition_maps::Column::IsDisabled,
         ])
         .into_model()
         .all(conn)
@@ -174,6 +190,7 @@ struct Map {
     source: NullableText,
     thumbnail_source: NullableText,
     is_available: bool,
+    is_disabled: bool,
 }
 
 #[derive(Serialize, Default)]
@@ -463,6 +480,10 @@ async fn edition(
         .map(|map| (map.map.id, map))
         .collect::<HashMap<_, _>>();
 
+    let maps_medal_times = event::medal_times_for_edition(&conn, event.id, edition.id)
+        .await
+        .with_api_err()?;
+
     let mut output_categories = Vec::with_capacity(input_categories.len());
 
     for (cat_id, cat_maps) in maps {
@@ -481,6 +502,7 @@ async fn edition(
                 source,
                 thumbnail_source,
                 is_available,
+                is_disabled,
                 ..
             } = cat_map;
 
@@ -653,9 +675,7 @@ async fn edition(
                 }
             };
 
-            let medal_times = event::get_medal_times_of(&conn, event.id, edition.id, map.id)
-                .await
-                .with_api_err()?;
+            let medal_times = maps_medal_times.get(&map.id).copied();
 
             let original_map = original_map_id
                 .and_then(|id| original_maps.get(&id))
@@ -681,6 +701,7 @@ async fn edition(
                 source: source.into(),
                 thumbnail_source: thumbnail_source.into(),
                 is_available: is_available.unwrap_or(true),
+                is_disabled: is_disabled.unwrap_or_default(),
             });
         }
 
@@ -843,59 +864,26 @@ async fn edition_finished_impl<C>(
 where
     C: ConnectionTrait + TransactionTrait,
 {
-    // We insert the record for the global records
-    let res = pf::finished(
+    // We insert the record for the global records, and on the original map if any
+    pf::finished(
         conn,
         redis_pool,
         params,
         player_login,
         map,
+        original_map_id,
         records_notifier,
     )
-    .await?;
-
-    if let Some(original_map_id) = original_map_id {
-        // Get the previous time of the player on the original map to check if it's a PB
-        let time_on_previous =
-            player::get_time_on_map(conn, res.player_id, original_map_id, Default::default())
-                .await
-                .with_api_err()?;
-        let is_pb =
-            time_on_previous.is_none() || time_on_previous.is_some_and(|t| t > params.body.time);
-
-        sync::transaction(conn, async |txn| {
-            // Here, we don't provide the event instances, because we don't want to save in event mode.
-            pf::insert_record(
-                txn,
-                ExpandedInsertRecordParams {
-                    event: Default::default(),
-                    ..params
-                },
-                original_map_id,
-                res.player_id,
-                Some(res.record_id),
-            )
-            .await
-        })
-        .await?;
-
-        // Update the rank on the original map
-        if is_pb {
-            let _: () = redis_pool
-                .get()
-                .await
-                .with_api_err()?
-                .zadd(
-                    alone_map_key(original_map_id),
-                    res.player_id,
-                    params.body.time,
-                )
-                .await
-                .with_api_err()?;
-        }
-    }
+    .await
+}
 
-    Ok(res)
+#[derive(Serialize)]
+struct EditionFinishedResponse {
+    #[serde(flatten)]
+    res: pf::HasFinishedResponse,
+    /// Whether the submission was accepted within the grace period after the edition expired.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_grace_period: bool,
 }
 
 pub async fn edition_finished_at(
@@ -917,8 +905,9 @@ pub async fn edition_finished_at(
         EventMap {
             map,
             original_map_id,
+            is_disabled,
         },
-    ) = records_lib::must::have_event_edition_with_map(
+    ) = records_lib::must::have_event_edition_with_map_including_disabled(
         &db.sql_conn,
         &body.map_uid,
         &event_handle,
@@ -926,6 +915,10 @@ pub async fn edition_finished_at(
     )
     .await?;
 
+    if edition.no_respawn_only && body.rest.respawn_count > 0 {
+        return Err(ApiErrorKind::TooManyRespawns(body.rest.respawn_count, 0));
+    }
+
     // The edition is transparent, so we save the record for the map directly.
     if edition.is_transparent != 0 {
         let res = super::player::finished_at(
@@ -941,16 +934,38 @@ pub async fn edition_finished_at(
         return Ok(res);
     }
 
+    let grace_period = chrono::Duration::from_std(crate::env().event_grace_period.get())
+        .unwrap_or(chrono::Duration::zero());
+    let expire_date = edition.expire_date();
+
     if edition.has_expired()
         && !(edition.start_date <= at.naive_utc()
-            && edition
-                .expire_date()
-                .filter(|date| at.naive_utc() > *date)
+            && expire_date
+                .filter(|date| at.naive_utc() > *date + grace_period)
                 .is_none())
     {
         return Err(ApiErrorKind::EventHasExpired(event.handle, edition.id));
     }
 
+    if is_disabled {
+        return Err(RecordsError::MapNotAvailable(
+            map.game_id,
+            event.handle,
+            edition.id,
+        ))
+        .with_api_err();
+    }
+
+    // The submission is still accepted, but it happened after the expiry of the edition
+    let is_grace_period = expire_date.is_some_and(|date| at.naive_utc() > date);
+    if is_grace_period {
+        tracing::info!(
+            "Accepting late submission of {login} on {}/{} within the grace period",
+            event.handle,
+            edition.id
+        );
+    }
+
     let params = EditionFinishedParams {
         player_login: &login,
         map: &map,
@@ -965,7 +980,10 @@ pub async fn edition_finished_at(
 
     let res = edition_finished_impl(&db.sql_conn, &db.redis_pool, params, records_notifier).await?;
 
-    json(res.res)
+    json(EditionFinishedResponse {
+        res: res.res,
+        is_grace_period,
+    })
 }
 
 pub async fn insert_event_record<C: ConnectionTrait>(
@@ -1010,3 +1028,167 @@ async fn edition_pb(
 
     utils::json(res)
 }
+
+async fn edition_medal_distribution(
+    path: Path<(String, u32)>,
+    ExtractDbConn(conn): ExtractDbConn,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id) = path.into_inner();
+
+    let (event, edition) =
+        records_lib::must::have_event_edition(&conn, &event_handle, edition_id).await?;
+
+    let res = event::medal_distribution(&conn, event.id, edition.id).await?;
+
+    utils::json(res)
+}
+
+const DEFAULT_LEADERBOARD_LIMIT: usize = 50;
+const MAX_LEADERBOARD_LIMIT: usize = 100;
+
+#[derive(serde::Deserialize)]
+struct LeaderboardQuery {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct LeaderboardPlayer {
+    rank: u32,
+    login: String,
+    name: String,
+    rank_avg: f64,
+    maps_finished: usize,
+}
+
+#[derive(Serialize)]
+struct LeaderboardResponse {
+    total: usize,
+    players: Vec<LeaderboardPlayer>,
+}
+
+async fn edition_leaderboard(
+    path: Path<(String, u32)>,
+    db: Res<Database>,
+    web::Query(LeaderboardQuery { offset, limit }): web::Query<LeaderboardQuery>,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id) = path.into_inner();
+    let limit = limit
+        .unwrap_or(DEFAULT_LEADERBOARD_LIMIT)
+        .min(MAX_LEADERBOARD_LIMIT);
+
+    let (event, edition) =
+        records_lib::must::have_event_edition(&db.sql_conn, &event_handle, edition_id).await?;
+
+    let event::EditionStandings {
+        total,
+        players: scores,
+    } = event::edition_standings(
+        &db.sql_conn,
+        &db.redis_pool,
+        &event,
+        &edition,
+        offset,
+        limit,
+    )
+    .await?;
+
+    let mut players = players::Entity::find()
+        .filter(players::Column::Id.is_in(scores.iter().map(|score| score.player_id)))
+        .all(&db.sql_conn)
+        .await
+        .with_api_err()?
+        .into_iter()
+        .map(|player| (player.id, player))
+        .collect::<HashMap<_, _>>();
+
+    let players = scores
+        .into_iter()
+        .map(|score| {
+            let player = players
+                .remove(&score.player_id)
+                .ok_or_else(|| internal!("Player with ID {} not found", score.player_id))?;
+            RecordsResult::Ok(LeaderboardPlayer {
+                rank: score.rank,
+                login: player.login,
+                name: player.name,
+                rank_avg: score.rank_avg,
+                maps_finished: score.maps_finished,
+            })
+        })
+        .collect::<RecordsResult<_>>()?;
+
+    utils::json(LeaderboardResponse { total, players })
+}
+
+async fn edition_first_record(
+    path: Path<(String, u32, String)>,
+    ExtractDbConn(conn): ExtractDbConn,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id, map_uid) = path.into_inner();
+
+    let (event, edition, EventMap { map, .. }) =
+        records_lib::must::have_event_edition_with_map(&conn, &map_uid, &event_handle, edition_id)
+            .await?;
+
+    let res = map::first_record_impl(&conn, map.id, OptEvent::new(&event, &edition)).await?;
+
+    utils::json(res)
+}
+
+async fn edition_map_thumbnail(
+    path: Path<(String, u32, String)>,
+    ExtractDbConn(conn): ExtractDbConn,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id, map_uid) = path.into_inner();
+
+    let (event, edition, EventMap { map, .. }) =
+        records_lib::must::have_event_edition_with_map(&conn, &map_uid, &event_handle, edition_id)
+            .await?;
+
+    let thumbnail_source: Option<String> =
+        event_edition_maps::Entity::find_by_id((event.id, edition.id, map.id))
+            .select_only()
+            .column(event_edition_maps::Column::ThumbnailSource)
+            .into_tuple()
+            .one(&conn)
+            .await
+            .with_api_err()?
+            .flatten();
+
+    let location = thumbnail_source
+        .or_else(|| crate::env().default_thumbnail_url.get())
+        .ok_or(ApiErrorKind::NoThumbnailFound(map.game_id))?;
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, location))
+        .finish())
+}
+
+async fn edition_player_record(
+    path: Path<(String, u32, String, String)>,
+    db: Res<Database>,
+) -> RecordsResult<impl Responder> {
+    let (event_handle, edition_id, map_uid, login) = path.into_inner();
+
+    let (event, edition, EventMap { map, .. }) = records_lib::must::have_event_edition_with_map(
+        &db.sql_conn,
+        &map_uid,
+        &event_handle,
+        edition_id,
+    )
+    .await?;
+    let player = records_lib::must::have_player(&db.sql_conn, &login).await?;
+
+    let res = map::player_record_impl(
+        &db.sql_conn,
+        &db.redis_pool,
+        &map,
+        &player,
+        OptEvent::new(&event, &edition),
+    )
+    .await?;
+
+    utils::json(res)
+}
@@ -8,12 +8,13 @@ use actix_web::{
     HttpResponse, Responder, Scope,
     web::{self, Json},
 };
-use entity::{maps, player_rating, players, rating, rating_kind};
+use entity::{event_edition_records, maps, player_rating, players, rating, rating_kind, records};
 use futures::{StreamExt, future::try_join_all};
-use records_lib::Database;
+use records_lib::{Database, RedisPool, opt_event::OptEvent, ranks};
 use sea_orm::{
-    ActiveModelTrait as _, ActiveValue::Set, ColumnTrait as _, EntityTrait as _, FromQueryResult,
-    PaginatorTrait, QueryFilter, QuerySelect, prelude::Expr, sea_query::Func,
+    ActiveModelTrait as _, ActiveValue::Set, ColumnTrait as _, ConnectionTrait, EntityTrait as _,
+    FromQueryResult, JoinType, PaginatorTrait, QueryFilter, QueryOrder as _, QuerySelect,
+    QueryTrait as _, RelationTrait as _, StreamTrait, prelude::Expr, sea_query::Func,
 };
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +28,16 @@ pub fn map_scope() -> Scope {
         .route("/rating", web::get().to(rating))
         .route("/rate", web::post().to(rate))
         .route("/reset_ratings", web::post().to(reset_ratings))
+        .route("/{map_uid}/first-record", web::get().to(first_record))
+        .route("/{map_uid}/rank-for", web::get().to(rank_for))
+        .route(
+            "/{map_uid}/player/{login}/record",
+            web::get().to(player_record),
+        )
+}
+
+pub fn maps_scope() -> Scope {
+    web::scope("/maps").route("/recent", web::get().to(recent_maps))
 }
 
 #[derive(Deserialize)]
@@ -623,3 +634,185 @@ pub async fn reset_ratings(
         author_login,
     })
 }
+
+#[derive(Serialize)]
+pub struct FirstRecordResponse {
+    login: String,
+    nickname: String,
+    time: i32,
+    respawn_count: i32,
+    record_date: chrono::NaiveDateTime,
+}
+
+pub async fn first_record_impl<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    event: OptEvent<'_>,
+) -> RecordsResult<Option<FirstRecordResponse>> {
+    let Some(record) = records_lib::map::get_first_record(conn, map_id, event).await? else {
+        return Ok(None);
+    };
+
+    let player = records_lib::player::get_player_from_id(conn, record.record_player_id).await?;
+
+    Ok(Some(FirstRecordResponse {
+        login: player.login,
+        nickname: player.name,
+        time: record.time,
+        respawn_count: record.respawn_count,
+        record_date: record.record_date,
+    }))
+}
+
+async fn first_record(
+    ExtractDbConn(conn): ExtractDbConn,
+    map_uid: web::Path<String>,
+) -> RecordsResult<impl Responder> {
+    let map = records_lib::must::have_map(&conn, &map_uid).await?;
+    let res = first_record_impl(&conn, map.id, Default::default()).await?;
+    json(res)
+}
+
+#[derive(Serialize)]
+pub struct PlayerRecordResponse {
+    record_id: u32,
+    rank: i32,
+    time: i32,
+    respawn_count: i32,
+    record_date: chrono::NaiveDateTime,
+    flags: u32,
+}
+
+pub async fn player_record_impl<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map: &maps::Model,
+    player: &players::Model,
+    event: OptEvent<'_>,
+) -> RecordsResult<PlayerRecordResponse> {
+    let record = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map.id)
+                .and(records::Column::RecordPlayerId.eq(player.id))
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .apply_if(event.get(), |query, (ev, ed)| {
+            query.reverse_join(event_edition_records::Entity).filter(
+                event_edition_records::Column::EventId
+                    .eq(ev.id)
+                    .and(event_edition_records::Column::EditionId.eq(ed.id)),
+            )
+        })
+        .order_by_asc(records::Column::Time)
+        .order_by_asc(records::Column::RecordDate)
+        .one(conn)
+        .await
+        .with_api_err()?
+        .ok_or_else(|| ApiErrorKind::NoRecordFound(player.login.clone(), map.game_id.clone()))?;
+
+    ranks::update_leaderboard(conn, redis_pool, map.id, event).await?;
+    let mut redis_conn = redis_pool.get().await.with_api_err()?;
+    let rank = ranks::get_rank(&mut redis_conn, map.id, record.time, event).await?;
+
+    Ok(PlayerRecordResponse {
+        record_id: record.record_id,
+        rank,
+        time: record.time,
+        respawn_count: record.respawn_count,
+        record_date: record.record_date,
+        flags: record.flags,
+    })
+}
+
+async fn player_record(
+    db: Res<Database>,
+    path: web::Path<(String, String)>,
+) -> RecordsResult<impl Responder> {
+    let (map_uid, login) = path.into_inner();
+    let map = records_lib::must::have_map(&db.sql_conn, &map_uid).await?;
+    let player = records_lib::must::have_player(&db.sql_conn, &login).await?;
+    let res = player_record_impl(
+        &db.sql_conn,
+        &db.redis_pool,
+        &map,
+        &player,
+        Default::default(),
+    )
+    .await?;
+    json(res)
+}
+
+#[derive(Deserialize)]
+struct RankForQuery {
+    time: i32,
+}
+
+#[derive(Serialize)]
+struct RankForResponse {
+    rank: i32,
+}
+
+async fn rank_for(
+    db: Res<Database>,
+    map_uid: web::Path<String>,
+    web::Query(RankForQuery { time }): web::Query<RankForQuery>,
+) -> RecordsResult<impl Responder> {
+    // A record time is always positive, and the rank is computed from the time just before it
+    if time <= 0 {
+        return Err(ApiErrorKind::InvalidTimes);
+    }
+
+    let map = records_lib::must::have_map(&db.sql_conn, &map_uid).await?;
+    let rank = ranks::hypothetical_rank(
+        &db.sql_conn,
+        &db.redis_pool,
+        map.id,
+        time,
+        Default::default(),
+    )
+    .await?;
+    json(RankForResponse { rank })
+}
+
+const DEFAULT_RECENT_MAPS_LIMIT: u64 = 10;
+const MAX_RECENT_MAPS_LIMIT: u64 = 100;
+
+#[derive(Deserialize)]
+struct RecentMapsQuery {
+    limit: Option<u64>,
+}
+
+#[derive(Serialize, FromQueryResult)]
+struct RecentMap {
+    map_uid: String,
+    name: String,
+    author_login: String,
+    created_at: chrono::NaiveDateTime,
+}
+
+async fn recent_maps(
+    ExtractDbConn(conn): ExtractDbConn,
+    web::Query(RecentMapsQuery { limit }): web::Query<RecentMapsQuery>,
+) -> RecordsResult<impl Responder> {
+    let limit = limit
+        .unwrap_or(DEFAULT_RECENT_MAPS_LIMIT)
+        .min(MAX_RECENT_MAPS_LIMIT);
+
+    let maps = maps::Entity::find()
+        .join(JoinType::InnerJoin, maps::Relation::Players.def())
+        .select_only()
+        .column_as(maps::Column::GameId, "map_uid")
+        .column(maps::Column::Name)
+        .column_as(players::Column::Login, "author_login")
+        .column(maps::Column::CreatedAt)
+        .order_by_desc(maps::Column::CreatedAt)
+        .order_by_desc(maps::Column::Id)
+        .limit(limit)
+        .into_model::<RecentMap>()
+        .all(&conn)
+        .await
+        .with_api_err()?;
+
+    json(maps)
+}
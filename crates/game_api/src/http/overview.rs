@@ -26,24 +26,42 @@ pub struct OverviewQuery {
 
 pub type OverviewReq = web::Query<OverviewQuery>;
 
+/// Extends the records with the provided range of the leaderboard.
+///
+/// If the Redis pool isn't provided, the leaderboard is retrieved from the SQL database only.
 async fn extend_range<C: ConnectionTrait + StreamTrait>(
     conn: &C,
-    redis_pool: &RedisPool,
+    redis_pool: Option<&RedisPool>,
     records: &mut Vec<Row>,
     (start, end): (i32, i32),
     map_id: u32,
     event: OptEvent<'_>,
 ) -> RecordsResult<()> {
-    leaderboard::leaderboard_into(
-        conn,
-        redis_pool,
-        map_id,
-        Some(start),
-        Some(end - 1),
-        records,
-        event,
-    )
-    .await
+    match redis_pool {
+        Some(redis_pool) => {
+            leaderboard::leaderboard_into(
+                conn,
+                redis_pool,
+                map_id,
+                Some(start),
+                Some(end - 1),
+                records,
+                event,
+            )
+            .await
+        }
+        None => {
+            leaderboard::leaderboard_from_db_into(
+                conn,
+                map_id,
+                Some(start),
+                Some(end - 1),
+                records,
+                event,
+            )
+            .await
+        }
+    }
     .with_api_err()?;
     Ok(())
 }
@@ -52,11 +70,36 @@ async fn extend_range<C: ConnectionTrait + StreamTrait>(
 #[cfg_attr(test, derive(serde::Deserialize))]
 pub struct ResponseBody {
     pub response: Vec<Row>,
+    /// Whether the response was built without the Redis database, because it was unavailable.
+    pub degraded: bool,
+    /// The record just ahead of the record of the player, if both are in the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_opponent: Option<Row>,
+    /// The difference between the time of the player and the first time of the leaderboard,
+    /// in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_to_first: Option<i32>,
+    /// The difference between the time of the player and their previous personal best,
+    /// in milliseconds. It's negative, as their current time is better.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_to_previous: Option<i32>,
+}
+
+/// Returns the record just ahead of the record of the player with the provided login, meaning
+/// the slowest record with a better time, if both are in the provided rows.
+fn next_opponent_of(rows: &[Row], login: &str) -> Option<Row> {
+    let idx = rows.iter().position(|row| row.login == login)?;
+    let time = rows[idx].time;
+    rows[..idx]
+        .iter()
+        .rev()
+        .find(|row| row.time < time)
+        .cloned()
 }
 
 async fn build_records_array<C: ConnectionTrait + StreamTrait>(
     conn: &C,
-    redis_pool: &RedisPool,
+    redis_pool: Option<&RedisPool>,
     player_rank: Option<i32>,
     records_count: i32,
     map_id: u32,
@@ -145,12 +188,15 @@ async fn get_rank<C: ConnectionTrait + StreamTrait>(
     map: &maps::Model,
     event: OptEvent<'_>,
     p: &entity::players::Model,
-) -> Result<Option<i32>, crate::ApiErrorKind> {
+) -> Result<Option<ranks::Rank>, crate::ApiErrorKind> {
     let min_time = records::Entity::find()
         .filter(
             records::Column::RecordPlayerId
                 .eq(p.id)
-                .and(records::Column::MapId.eq(map.id)),
+                .and(records::Column::MapId.eq(map.id))
+                .and(
+                    records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()),
+                ),
         )
         .select_only()
         .expr(records::Column::Time.min())
@@ -169,8 +215,7 @@ async fn get_rank<C: ConnectionTrait + StreamTrait>(
 
     match min_time {
         Some(time) => {
-            let mut redis_conn = redis_pool.get().await.with_api_err()?;
-            let rank = ranks::get_rank(&mut redis_conn, map.id, time, event)
+            let rank = ranks::get_rank_or_fallback(conn, redis_pool, map.id, time, event)
                 .await
                 .with_api_err()?;
             Ok(Some(rank))
@@ -179,6 +224,59 @@ async fn get_rank<C: ConnectionTrait + StreamTrait>(
     }
 }
 
+/// Returns the personal best of the player before their current one, meaning the best time of
+/// their records made before their first record with their current time.
+async fn get_previous_pb<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    event: OptEvent<'_>,
+    player_id: u32,
+    pb_time: i32,
+) -> RecordsResult<Option<i32>> {
+    let player_records = || {
+        records::Entity::find()
+            .filter(
+                records::Column::RecordPlayerId
+                    .eq(player_id)
+                    .and(records::Column::MapId.eq(map_id))
+                    .and(records::Column::IsHidden.eq(false)),
+            )
+            .apply_if(event.get(), |query, (ev, ed)| {
+                query.reverse_join(event_edition_records::Entity).filter(
+                    event_edition_records::Column::EventId
+                        .eq(ev.id)
+                        .and(event_edition_records::Column::EditionId.eq(ed.id)),
+                )
+            })
+    };
+
+    let pb_date = player_records()
+        .filter(records::Column::Time.eq(pb_time))
+        .select_only()
+        .expr(records::Column::RecordDate.min())
+        .into_tuple::<Option<chrono::NaiveDateTime>>()
+        .one(conn)
+        .await
+        .with_api_err()?
+        .flatten();
+
+    let Some(pb_date) = pb_date else {
+        return Ok(None);
+    };
+
+    let previous_pb = player_records()
+        .filter(records::Column::RecordDate.lt(pb_date))
+        .select_only()
+        .expr(records::Column::Time.min())
+        .into_tuple::<Option<i32>>()
+        .one(conn)
+        .await
+        .with_api_err()?
+        .flatten();
+
+    Ok(previous_pb)
+}
+
 pub async fn overview(
     db: Database,
     player_login: &str,
@@ -190,24 +288,68 @@ pub async fn overview(
         .with_api_err()?;
 
     // Update redis if needed
-    let count = update_leaderboard(&db.sql_conn, &db.redis_pool, map.id, event).await? as _;
+    let (count, mut degraded) =
+        match update_leaderboard(&db.sql_conn, &db.redis_pool, map.id, event).await {
+            Ok(count) => (count as _, false),
+            Err(e) if e.is_redis_unavailable() => {
+                tracing::warn!(
+                    "Redis unavailable, building the overview from the SQL database: {e}"
+                );
+                let count = ranks::count_records_map(&db.sql_conn, map.id, event)
+                    .await
+                    .with_api_err()?;
+                (count as _, true)
+            }
+            Err(e) => return Err(e.into()),
+        };
 
     let player_rank = match player {
-        Some(ref p) => get_rank(&db.sql_conn, &db.redis_pool, map, event, p).await?,
+        Some(ref p) => get_rank(&db.sql_conn, &db.redis_pool, map, event, p)
+            .await?
+            .map(|rank| {
+                degraded |= rank.degraded;
+                rank.rank
+            }),
         None => None,
     };
 
+    let redis_pool = (!degraded).then_some(&db.redis_pool);
+
     let ranked_records = sync::transaction_with_config(
         &db.sql_conn,
         Some(sea_orm::IsolationLevel::RepeatableRead),
         Some(sea_orm::AccessMode::ReadOnly),
-        async |txn| {
-            build_records_array(txn, &db.redis_pool, player_rank, count, map.id, event).await
-        },
+        async |txn| build_records_array(txn, redis_pool, player_rank, count, map.id, event).await,
     )
     .await?;
 
+    let next_opponent = player
+        .as_ref()
+        .and_then(|p| next_opponent_of(&ranked_records, &p.login));
+
+    let player_time = player.as_ref().and_then(|p| {
+        ranked_records
+            .iter()
+            .find(|row| row.login == p.login)
+            .map(|row| row.time)
+    });
+
+    let gap_to_first = player_time
+        .zip(ranked_records.first())
+        .map(|(time, first)| time - first.time);
+
+    let gap_to_previous = match (&player, player_time) {
+        (Some(p), Some(time)) => get_previous_pb(&db.sql_conn, map.id, event, p.id, time)
+            .await?
+            .map(|previous_pb| time - previous_pb),
+        _ => None,
+    };
+
     Ok(ResponseBody {
         response: ranked_records,
+        degraded,
+        next_opponent,
+        gap_to_first,
+        gap_to_previous,
     })
 }
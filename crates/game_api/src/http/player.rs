@@ -43,10 +43,13 @@ pub fn player_scope() -> Scope {
     let scope = web::scope("/player")
         .route("/update", web::post().to(update))
         .route("/finished", web::post().to(finished))
+        .route("/finished/validate", web::post().to(finished_validate))
         .route("/get_token", web::post().to(auth::get_token))
         .route("/pb", web::get().to(pb))
         .route("/times", web::post().to(times))
         .route("/info", web::get().to(info))
+        .route("/export", web::get().to(export))
+        .route("/{login}/events", web::get().to(events))
         .route("/report_error", web::post().to(report_error))
         .route("/ac", web::post().to(ac));
 
@@ -219,6 +222,7 @@ where
         params,
         player_login,
         map,
+        None,
         records_notifier,
     )
     .await?;
@@ -347,6 +351,28 @@ async fn finished(
     .await
 }
 
+async fn finished_validate(
+    _: ApiAvailable,
+    mode_version: Option<crate::ModeVersion>,
+    MPAuthGuard { login }: MPAuthGuard,
+    db: Res<Database>,
+    body: pf::PlayerFinishedBody,
+) -> RecordsResult<impl Responder> {
+    let map = must::have_map(&db.sql_conn, &body.map_uid)
+        .await
+        .with_api_err()?;
+
+    let params = ExpandedInsertRecordParams {
+        body: &body.rest,
+        at: chrono::Utc::now(),
+        event: Default::default(),
+        mode_version: mode_version.map(|x| x.0),
+    };
+
+    let res = pf::validate(&db.sql_conn, &db.redis_pool, params, &login, &map).await?;
+    json(res)
+}
+
 async fn pb(
     _: ApiAvailable,
     MPAuthGuard { login }: MPAuthGuard,
@@ -439,6 +465,23 @@ pub async fn info(
     json(info)
 }
 
+async fn export(
+    MPAuthGuard { login }: MPAuthGuard,
+    ExtractDbConn(conn): ExtractDbConn,
+) -> RecordsResult<impl Responder> {
+    let player_id = must::have_player(&conn, &login).await?.id;
+    let export = player::export_all(&conn, player_id).await?;
+    json(export)
+}
+
+async fn events(db: Res<Database>, login: web::Path<String>) -> RecordsResult<impl Responder> {
+    let player = must::have_player(&db.sql_conn, &login).await?;
+    let editions =
+        records_lib::event::player_records_by_edition(&db.sql_conn, &db.redis_pool, player.id)
+            .await?;
+    json(editions)
+}
+
 #[derive(Deserialize)]
 struct ReportErrorBody {
     on_route: String,
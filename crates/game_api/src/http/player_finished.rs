@@ -1,19 +1,19 @@
 use crate::{ApiErrorKind, RecordsResult, RecordsResultExt};
 use actix_web::web::Json;
 use chrono::{DateTime, Utc};
-use deadpool_redis::redis;
-use entity::{checkpoint_times, event_edition_records, maps, records, types};
+use deadpool_redis::redis::{self, AsyncCommands as _};
+use entity::{checkpoint_times, event_edition_records, maps, players, records, types};
 use records_lib::{
     NullableInteger, RedisPool,
     opt_event::OptEvent,
     ranks,
     records_notifier::{NewRecordEvent, NewRecordMap, NewRecordPlayer, RecordsNotifier},
-    redis_key::map_key,
+    redis_key::{alone_map_key, map_key},
     sync,
 };
 use sea_orm::{
     ActiveValue::Set, ColumnTrait as _, ConnectionTrait, EntityTrait, QueryFilter as _, QueryOrder,
-    QuerySelect, QueryTrait, TransactionTrait,
+    QuerySelect, QueryTrait, StreamTrait, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
@@ -159,7 +159,8 @@ async fn get_old_record<C: ConnectionTrait>(
         .filter(
             records::Column::MapId
                 .eq(map_id)
-                .and(records::Column::RecordPlayerId.eq(player_id)),
+                .and(records::Column::RecordPlayerId.eq(player_id))
+                .and(records::Column::IsHidden.eq(false)),
         )
         .order_by_asc(records::Column::Time)
         .limit(1)
@@ -194,27 +195,23 @@ async fn lock_map_records<C: ConnectionTrait>(conn: &C, map_id: u32) -> RecordsR
 struct RecordSaveOutput {
     old_record: Option<records::Model>,
     record_id: u32,
+    /// The ID of the original map, if the record is a personal best on it.
+    original_pb_map_id: Option<u32>,
 }
 
-pub async fn finished<C>(
+/// Checks that the provided record can be saved, and returns the player who made it.
+async fn check_record<C: ConnectionTrait>(
     conn: &C,
-    redis_pool: &RedisPool,
     params: ExpandedInsertRecordParams<'_>,
     player_login: &str,
     map: &maps::Model,
-    records_notifier: &RecordsNotifier,
-) -> RecordsResult<FinishedOutput>
-where
-    C: ConnectionTrait + TransactionTrait,
-{
-    // First, we retrieve all what we need to save the record
+) -> RecordsResult<players::Model> {
     let player = records_lib::must::have_player(conn, player_login)
         .await
         .with_api_err()?;
-    let player_id = player.id;
 
     // Return an error if the player was banned at the time.
-    if let Some(ban) = super::player::get_ban_during(conn, player_id, params.at).await? {
+    if let Some(ban) = super::player::get_ban_during(conn, player.id, params.at).await? {
         return Err(ApiErrorKind::BannedPlayer(ban));
     }
 
@@ -225,6 +222,93 @@ where
         return Err(ApiErrorKind::InvalidTimes);
     }
 
+    // We check that the respawn count doesn't exceed the limits of the map and the event edition
+    let max_respawn_count = [
+        map.max_respawn_count,
+        params.event.get().and_then(|(_, ed)| ed.max_respawn_count),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    if let Some(max) = max_respawn_count
+        && params.body.respawn_count > max as i32
+    {
+        return Err(ApiErrorKind::TooManyRespawns(
+            params.body.respawn_count,
+            max,
+        ));
+    }
+
+    Ok(player)
+}
+
+/// Runs the same validation as [`finished`], and returns the response the player would get if
+/// the record was saved, without saving anything.
+///
+/// The leaderboard of the map is updated if needed before reading the ranks from it.
+pub async fn validate<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    params: ExpandedInsertRecordParams<'_>,
+    player_login: &str,
+    map: &maps::Model,
+) -> RecordsResult<HasFinishedResponse> {
+    let player = check_record(conn, params, player_login, map).await?;
+
+    let old_record = get_old_record(conn, player.id, map.id, params.event).await?;
+
+    ranks::update_leaderboard(conn, redis_pool, map.id, params.event).await?;
+    let mut redis_conn = redis_pool.get().await.with_api_err()?;
+
+    let (old, new, has_improved, old_rank) = match old_record {
+        Some(records::Model { time: old, .. }) => (
+            old,
+            params.body.time,
+            params.body.time < old,
+            Some(ranks::get_rank(&mut redis_conn, map.id, old, params.event).await?),
+        ),
+        None => (params.body.time, params.body.time, true, None),
+    };
+
+    // The time of the player isn't updated, but the rank of a better time doesn't depend on it
+    let current_rank = ranks::get_rank(
+        &mut redis_conn,
+        map.id,
+        if has_improved { new } else { old },
+        params.event,
+    )
+    .await
+    .with_api_err()?;
+
+    Ok(HasFinishedResponse {
+        has_improved,
+        old,
+        new,
+        current_rank,
+        old_rank: old_rank.into(),
+    })
+}
+
+/// Saves the provided record of the player on the map.
+///
+/// If an original map ID is provided, the record is also saved on it outside of the event mode,
+/// in the same transaction.
+pub async fn finished<C>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    params: ExpandedInsertRecordParams<'_>,
+    player_login: &str,
+    map: &maps::Model,
+    original_map_id: Option<u32>,
+    records_notifier: &RecordsNotifier,
+) -> RecordsResult<FinishedOutput>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    // First, we retrieve all what we need to save the record
+    let player = check_record(conn, params, player_login, map).await?;
+    let player_id = player.id;
+
     let result = sync::transaction(conn, async |txn| {
         // Lock the rows related to the map
         lock_map_records(txn, map.id).await?;
@@ -232,15 +316,57 @@ where
         let old_record = get_old_record(txn, player_id, map.id, params.event).await?;
         let new_record_id = insert_record(txn, params, map.id, player_id, None).await?;
 
+        let original_pb_map_id = match original_map_id {
+            Some(original_map_id) => {
+                // Get the previous time of the player on the original map to check if it's a PB
+                let time_on_previous = records_lib::player::get_time_on_map(
+                    txn,
+                    player_id,
+                    original_map_id,
+                    Default::default(),
+                )
+                .await
+                .with_api_err()?;
+
+                // Here, we don't provide the event instances, because we don't want to save in
+                // event mode.
+                insert_record(
+                    txn,
+                    ExpandedInsertRecordParams {
+                        event: Default::default(),
+                        ..params
+                    },
+                    original_map_id,
+                    player_id,
+                    Some(new_record_id),
+                )
+                .await?;
+
+                time_on_previous
+                    .is_none_or(|t| t > params.body.time)
+                    .then_some(original_map_id)
+            }
+            None => None,
+        };
+
         RecordsResult::Ok(RecordSaveOutput {
             old_record,
             record_id: new_record_id,
+            original_pb_map_id,
         })
     })
     .await?;
 
     let mut redis_conn = redis_pool.get().await.with_api_err()?;
 
+    // Update the rank on the original map
+    if let Some(original_map_id) = result.original_pb_map_id {
+        let _: () = redis_conn
+            .zadd(alone_map_key(original_map_id), player_id, params.body.time)
+            .await
+            .with_api_err()?;
+    }
+
     let (old, new, has_improved, old_rank) = match result.old_record {
         Some(records::Model { time: old, .. }) => (
             old,
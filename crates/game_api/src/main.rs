@@ -36,7 +36,13 @@ async fn main() -> anyhow::Result<()> {
 
     let db = Database::from_db_url(
         game_api_lib::env().db_env.db_url.db_url.get(),
+        game_api_lib::env().db_env.db_url.replica_db_url.get(),
         game_api_lib::env().db_env.redis_url.redis_url.get(),
+        game_api_lib::env()
+            .db_env
+            .redis_url
+            .redis_health_check_interval
+            .get(),
     )
     .await
     .context("Cannot initialize database connection")?;
@@ -76,6 +82,7 @@ async fn main() -> anyhow::Result<()> {
     };
 
     tracing::info!("Using max connections: {max_connections}");
+    tracing::info!("Using configuration:\n{}", game_api_lib::config_dump());
 
     let request_timeout_wh_handler_client = reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
@@ -105,6 +112,10 @@ async fn main() -> anyhow::Result<()> {
                     request_timeout_wh_handler_client.clone(),
                 )),
             ))
+            .wrap(middleware::Condition::new(
+                game_api_lib::env().processing_time_header.get(),
+                middleware::from_fn(configure::processing_time),
+            ))
             .wrap(TracingLogger::<configure::RootSpanBuilder>::new())
             .wrap(
                 SessionMiddleware::builder(
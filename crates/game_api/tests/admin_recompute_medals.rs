@@ -0,0 +1,208 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{
+    event, event_edition, event_edition_maps, event_edition_records, maps, players, records,
+};
+use game_api_lib::TracedError;
+use records_lib::event as event_utils;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct MedalTimes {
+    map_uid: String,
+    bronze_time: i32,
+    silver_time: i32,
+    gold_time: i32,
+    champion_time: i32,
+}
+
+#[derive(serde::Deserialize)]
+struct Response {
+    maps: Vec<MedalTimes>,
+}
+
+#[tokio::test]
+async fn recompute_medals_from_percentiles() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    // The first player is an admin
+    let players = (1..=5).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(if player_id == 1 { 2 } else { 0 }),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The second map doesn't have any record, so its medal times are left untouched
+    let event_maps =
+        map_ids
+            .iter()
+            .enumerate()
+            .map(|(i, map_id)| event_edition_maps::ActiveModel {
+                event_id: Set(1),
+                edition_id: Set(1),
+                map_id: Set(*map_id),
+                order: Set(i as _),
+                bronze_time: Set(Some(40000)),
+                silver_time: Set(Some(30000)),
+                gold_time: Set(Some(20000)),
+                author_time: Set(Some(10000)),
+                ..Default::default()
+            });
+
+    // (player_id, time)
+    // Only the best time of the player 2 is counted, and the hidden record is ignored.
+    let records_info = [(2, 1000), (2, 9000), (3, 2000), (4, 3000), (5, 4000)];
+
+    let records = records_info
+        .iter()
+        .enumerate()
+        .map(|(i, (player_id, time))| records::ActiveModel {
+            record_id: Set(i as u32 + 1),
+            record_player_id: Set(*player_id),
+            map_id: Set(map_ids[0]),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .chain([records::ActiveModel {
+            record_id: Set(records_info.len() as u32 + 1),
+            record_player_id: Set(5),
+            map_id: Set(map_ids[0]),
+            time: Set(500),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            is_hidden: Set(true),
+            ..Default::default()
+        }]);
+
+    let event_records =
+        (1..=records_info.len() as u32 + 1).map(|record_id| event_edition_records::ActiveModel {
+            record_id: Set(record_id),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/event/event_handle/1/recompute-medals")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .set_json(serde_json::json!({
+                "bronze": 100.,
+                "silver": 75.,
+                "gold": 50.,
+                "champion": 25.,
+            }))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body.maps,
+            [MedalTimes {
+                map_uid: format!("map_{}_uid", map_ids[0]),
+                bronze_time: 4000,
+                silver_time: 3000,
+                gold_time: 2000,
+                champion_time: 1000,
+            }]
+        );
+
+        for (map_id, expected) in map_ids
+            .iter()
+            .zip([(4000, 3000, 2000, 1000), (40000, 30000, 20000, 10000)])
+        {
+            let medal_times = event_utils::get_medal_times_of(&db.sql_conn, 1, 1, *map_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("the map {map_id} should have medal times"))?;
+            assert_eq!(
+                (
+                    medal_times.bronze_time,
+                    medal_times.silver_time,
+                    medal_times.gold_time,
+                    medal_times.champion_time,
+                ),
+                expected
+            );
+        }
+
+        // The champion medal can't require a slower time than the gold medal
+        let req = test::TestRequest::post()
+            .uri("/admin/event/event_handle/1/recompute-medals")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .set_json(serde_json::json!({
+                "bronze": 100.,
+                "silver": 75.,
+                "gold": 50.,
+                "champion": 60.,
+            }))
+            .to_request();
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Invalid medal percentiles
+        assert_eq!(traced_err.r#type, Some(320));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,107 @@
+use actix_web::test;
+use entity::{maps, players};
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    created: Vec<String>,
+    existing: Vec<String>,
+}
+
+#[tokio::test]
+async fn register_new_and_existing_maps() -> anyhow::Result<()> {
+    // The first player is an admin, and the second one is the author of the existing map
+    let players = [(1, 2), (2, 0)].map(|(player_id, role)| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(role),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(2),
+        ..Default::default()
+    };
+
+    let new_uids = [
+        format!("map_{map_id}_new_1_uid"),
+        format!("map_{map_id}_new_2_uid"),
+    ];
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        // The first new map is made by an existing author, and the second one by a new author
+        let req = test::TestRequest::post()
+            .uri("/admin/maps/register")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .set_json(serde_json::json!([
+                {
+                    "uid": format!("map_{map_id}_uid"),
+                    "name": "renamed_map",
+                    "author_login": "player_2_login",
+                },
+                {
+                    "uid": new_uids[0],
+                    "name": "new_map_1",
+                    "author_login": "player_2_login",
+                },
+                {
+                    "uid": new_uids[1],
+                    "name": "new_map_2",
+                    "author_login": "new_author_login",
+                },
+            ]))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.created, new_uids);
+        assert_eq!(body.existing, [format!("map_{map_id}_uid")]);
+
+        // The existing map is left untouched
+        let existing = maps::Entity::find_by_id(map_id)
+            .one(&db.sql_conn)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the existing map should still exist"))?;
+        assert_eq!(existing.name, format!("map_{map_id}_name"));
+
+        let new_author = players::Entity::find()
+            .filter(players::Column::Login.eq("new_author_login"))
+            .one(&db.sql_conn)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the new author should be saved"))?;
+
+        for (uid, name, author_id) in [
+            (&new_uids[0], "new_map_1", 2),
+            (&new_uids[1], "new_map_2", new_author.id),
+        ] {
+            let map = maps::Entity::find()
+                .filter(maps::Column::GameId.eq(uid.as_str()))
+                .one(&db.sql_conn)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("the map {uid} should be saved"))?;
+            assert_eq!(map.name, name);
+            assert_eq!(map.player_id, author_id);
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,99 @@
+use entity::{banishments, maps, players, records};
+use records_lib::{leaderboard, ranks};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn banned_player_hidden_from_leaderboard() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The third player has the best time, but is banned
+    let records = [(1, 6000), (2, 5000), (3, 4000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let ban = banishments::ActiveModel {
+        date_ban: Set(chrono::Utc::now().naive_utc()),
+        duration: Set(None),
+        was_reprieved: Set(0),
+        reason: Set("cheating".to_owned()),
+        player_id: Set(Some(3)),
+        banished_by: Set(Some(1)),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        banishments::Entity::insert(ban).exec(&db.sql_conn).await?;
+
+        let count =
+            ranks::update_leaderboard(&db.sql_conn, &db.redis_pool, map_id, Default::default())
+                .await?;
+        assert_eq!(count, 2);
+
+        let lb = leaderboard::leaderboard(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            None,
+            None,
+            Default::default(),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter().map(|row| (row.rank, row.login.as_str())),
+            [(1, "player_2_login"), (2, "player_1_login")],
+        );
+
+        let mut lb = Vec::new();
+        leaderboard::leaderboard_from_db_into(
+            &db.sql_conn,
+            map_id,
+            None,
+            None,
+            &mut lb,
+            Default::default(),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter().map(|row| (row.rank, row.login.as_str())),
+            [(1, "player_2_login"), (2, "player_1_login")],
+        );
+
+        let rank = ranks::get_rank_from_db(&db.sql_conn, map_id, 5000, Default::default()).await?;
+        assert_eq!(rank, 1);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -14,6 +14,7 @@ use test_env::IntoResult;
 use tracing_actix_web::TracingLogger;
 
 use game_api_lib::{configure, init_env};
+use mkenv::prelude::*;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct ErrorResponse {
@@ -40,6 +41,10 @@ pub async fn get_app(
     test::init_service(
         App::new()
             .wrap(middleware::from_fn(configure::fit_request_id))
+            .wrap(middleware::Condition::new(
+                game_api_lib::env().processing_time_header.get(),
+                middleware::from_fn(configure::processing_time),
+            ))
             .wrap(TracingLogger::<configure::RootSpanBuilder>::new())
             .configure(|cfg| configure::configure(cfg, db.clone(), RecordsNotifier::default())),
     )
@@ -0,0 +1,31 @@
+use mkenv::prelude::*;
+
+mod base;
+
+#[tokio::test]
+async fn config_dump_includes_values() -> anyhow::Result<()> {
+    base::with_db(async |_| {
+        let dump = game_api_lib::config_dump();
+
+        let port = game_api_lib::env().port.get().to_string();
+        assert_eq!(dump.get("RECORDS_API_PORT"), Some(port.as_str()));
+        assert!(
+            dump.to_string()
+                .contains(&format!("RECORDS_API_PORT = {port}\n"))
+        );
+
+        let hide_banned_players = records_lib::hide_banned_players().to_string();
+        assert_eq!(
+            dump.get("RECORDS_API_HIDE_BANNED_PLAYERS"),
+            Some(hide_banned_players.as_str())
+        );
+        assert!(dump.get("GQL_API_CURSOR_MAX_LIMIT").is_some());
+
+        // The secrets aren't included
+        assert_eq!(dump.get("DATABASE_URL"), None);
+        assert_eq!(dump.get("REDIS_URL"), None);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,45 @@
+use entity::players;
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{DbBackend, MockDatabase};
+
+mod base;
+
+#[tokio::test]
+async fn read_queries_sent_to_replica() -> anyhow::Result<()> {
+    // The player only exists in the replica
+    let replica_player = players::Model {
+        id: 1,
+        login: "replica_player_login".to_owned(),
+        name: "replica_player_name".to_owned(),
+        join_date: None,
+        zone_path: None,
+        admins_note: None,
+        role: 0,
+        score: 0.,
+    };
+
+    base::with_db(async |mut db| {
+        db.replica_sql_conn = Some(
+            MockDatabase::new(DbBackend::MySql)
+                .append_query_results([[replica_player]])
+                .into_connection(),
+        );
+
+        let schema = graphql_api::schema::create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute("{ player(login: \"replica_player_login\") { name } }")
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        assert_eq!(data["player"]["name"], "replica_player_name");
+
+        anyhow::Ok(())
+    })
+    .await
+}
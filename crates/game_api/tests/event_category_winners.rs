@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use entity::{
+    event, event_category, event_edition, event_edition_categories, event_edition_maps,
+    event_edition_records, maps, players, records,
+};
+use records_lib::event as event_utils;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn category_winners_per_category() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let categories =
+        ["white", "red"]
+            .iter()
+            .enumerate()
+            .map(|(i, handle)| event_category::ActiveModel {
+                id: Set(i as u32 + 1),
+                handle: Set((*handle).to_owned()),
+                name: Set(format!("{handle}_name")),
+                ..Default::default()
+            });
+
+    let edition_categories = (1..=2).map(|category_id| event_edition_categories::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(1),
+        category_id: Set(category_id),
+    });
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The 2 first maps are in the first category, and the last one in the second category
+    let event_maps =
+        map_ids
+            .iter()
+            .enumerate()
+            .map(|(i, map_id)| event_edition_maps::ActiveModel {
+                event_id: Set(1),
+                edition_id: Set(1),
+                map_id: Set(*map_id),
+                category_id: Set(Some(if i < 2 { 1 } else { 2 })),
+                order: Set(i as _),
+                ..Default::default()
+            });
+
+    // (map index, player_id, time)
+    // In the first category, the player 1 has the best rank average on the maps they both
+    // finished, and the player 3 only finished one map. In the second category, the player 3
+    // is first.
+    let records_info = [
+        (0, 1, 5000),
+        (0, 2, 6000),
+        (1, 1, 3500),
+        (1, 2, 4000),
+        (1, 3, 3000),
+        (2, 1, 3000),
+        (2, 3, 2000),
+    ];
+
+    let records = records_info
+        .iter()
+        .enumerate()
+        .map(|(record_id, (i, player_id, time))| records::ActiveModel {
+            record_id: Set(record_id as u32 + 1),
+            record_player_id: Set(*player_id),
+            map_id: Set(map_ids[*i]),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    let event_records =
+        (1..=records_info.len() as u32).map(|record_id| event_edition_records::ActiveModel {
+            record_id: Set(record_id),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        event_category::Entity::insert_many(categories)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_categories::Entity::insert_many(edition_categories)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let winners = event_utils::category_winners(&db.sql_conn, &db.redis_pool, 1, 1).await?;
+
+        assert_eq!(
+            winners,
+            HashMap::from([("white".to_owned(), 1), ("red".to_owned(), 3)])
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn category_winners_edition_id_differs_from_event_id() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(3),
+        name: Set("event_1_3_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let category = event_category::ActiveModel {
+        id: Set(1),
+        handle: Set("white".to_owned()),
+        name: Set("white_name".to_owned()),
+        ..Default::default()
+    };
+
+    let edition_category = event_edition_categories::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(3),
+        category_id: Set(1),
+    };
+
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let event_map = event_edition_maps::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(3),
+        map_id: Set(map_id),
+        category_id: Set(Some(1)),
+        order: Set(0),
+        ..Default::default()
+    };
+
+    // The player 2 has the best time
+    let records = [(1, 6000), (2, 5000)].map(|(player_id, time)| records::ActiveModel {
+        record_id: Set(player_id),
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let event_records = (1..=2).map(|record_id| event_edition_records::ActiveModel {
+        record_id: Set(record_id),
+        event_id: Set(1),
+        edition_id: Set(3),
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        event_category::Entity::insert(category)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_categories::Entity::insert(edition_category)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert(event_map)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let winners = event_utils::category_winners(&db.sql_conn, &db.redis_pool, 1, 3).await?;
+
+        assert_eq!(winners, HashMap::from([("white".to_owned(), 2)]));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,197 @@
+use entity::{
+    event, event_category, event_edition, event_edition_categories, event_edition_maps,
+    event_edition_records, in_game_event_edition_params, maps, players, records,
+};
+use records_lib::{error::RecordsError, event as event_utils};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, EntityTrait, PaginatorTrait as _, QueryFilter as _,
+    QueryOrder as _,
+};
+
+mod base;
+
+#[tokio::test]
+async fn clone_edition_structure() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let category = event_category::ActiveModel {
+        id: Set(1),
+        handle: Set("category_handle".to_owned()),
+        name: Set("category_name".to_owned()),
+        ..Default::default()
+    };
+
+    let params = in_game_event_edition_params::ActiveModel {
+        id: Set(1),
+        titles_pos_x: Set(Some(12.5)),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        ingame_params_id: Set(Some(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let edition_category = event_edition_categories::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(1),
+        category_id: Set(1),
+    };
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids.map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    let event_maps =
+        map_ids
+            .iter()
+            .enumerate()
+            .map(|(i, map_id)| event_edition_maps::ActiveModel {
+                event_id: Set(1),
+                edition_id: Set(1),
+                map_id: Set(*map_id),
+                category_id: Set(Some(1)),
+                order: Set(i as _),
+                author_time: Set(Some(10000)),
+                ..Default::default()
+            });
+
+    let record = records::ActiveModel {
+        record_id: Set(1),
+        record_player_id: Set(1),
+        map_id: Set(map_ids[0]),
+        time: Set(5000),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let event_record = event_edition_records::ActiveModel {
+        record_id: Set(1),
+        event_id: Set(1),
+        edition_id: Set(1),
+    };
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_category::Entity::insert(category)
+            .exec(&db.sql_conn)
+            .await?;
+        in_game_event_edition_params::Entity::insert(params)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_categories::Entity::insert(edition_category)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert(record).exec(&db.sql_conn).await?;
+        event_edition_records::Entity::insert(event_record)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let (event, from) =
+            records_lib::must::have_event_edition(&db.sql_conn, "event_handle", 1).await?;
+
+        let cloned = event_utils::clone_edition(&db.sql_conn, &event, &from, 2).await?;
+        assert_eq!(cloned.id, 2);
+        assert_eq!(cloned.name, from.name);
+
+        let (_, saved) =
+            records_lib::must::have_event_edition(&db.sql_conn, "event_handle", 2).await?;
+        assert_eq!(saved, cloned);
+
+        // The in-game parameters are copied into a new row
+        let params_id = saved
+            .ingame_params_id
+            .ok_or_else(|| anyhow::anyhow!("the in-game parameters should be copied"))?;
+        assert_ne!(params_id, 1);
+        let params = in_game_event_edition_params::Entity::find_by_id(params_id)
+            .one(&db.sql_conn)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the in-game parameters should exist"))?;
+        assert_eq!(params.titles_pos_x, Some(12.5));
+
+        let categories = event_edition_categories::Entity::find()
+            .filter(
+                event_edition_categories::Column::EventId
+                    .eq(1)
+                    .and(event_edition_categories::Column::EditionId.eq(2)),
+            )
+            .all(&db.sql_conn)
+            .await?;
+        itertools::assert_equal(categories.iter().map(|c| c.category_id), [1]);
+
+        let cloned_maps = event_edition_maps::Entity::find()
+            .filter(
+                event_edition_maps::Column::EventId
+                    .eq(1)
+                    .and(event_edition_maps::Column::EditionId.eq(2)),
+            )
+            .order_by_asc(event_edition_maps::Column::Order)
+            .all(&db.sql_conn)
+            .await?;
+        itertools::assert_equal(
+            cloned_maps
+                .iter()
+                .map(|map| (map.map_id, map.category_id, map.author_time)),
+            map_ids.map(|map_id| (map_id, Some(1), Some(10000))),
+        );
+
+        // The records aren't copied
+        let record_count = event_edition_records::Entity::find()
+            .filter(
+                event_edition_records::Column::EventId
+                    .eq(1)
+                    .and(event_edition_records::Column::EditionId.eq(2)),
+            )
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(record_count, 0);
+        assert_eq!(records::Entity::find().count(&db.sql_conn).await?, 1);
+
+        // The existing edition isn't overwritten
+        let err = event_utils::clone_edition(&db.sql_conn, &event, &from, 2)
+            .await
+            .err()
+            .ok_or_else(|| anyhow::anyhow!("cloning into an existing edition should fail"))?;
+        assert!(
+            matches!(err, RecordsError::EventEditionAlreadyExists(ref handle, 2) if handle == "event_handle"),
+            "unexpected error: {err:?}"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,203 @@
+use actix_web::test;
+use entity::{
+    event, event_edition, event_edition_maps, event_edition_records, maps, players, records,
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Player {
+    rank: u32,
+    login: String,
+    maps_finished: usize,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Response {
+    total: usize,
+    players: Vec<Player>,
+}
+
+#[tokio::test]
+async fn edition_leaderboard_aggregated() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    let event_maps =
+        map_ids
+            .iter()
+            .enumerate()
+            .map(|(i, map_id)| event_edition_maps::ActiveModel {
+                event_id: Set(1),
+                edition_id: Set(1),
+                map_id: Set(*map_id),
+                order: Set(i as _),
+                ..Default::default()
+            });
+
+    // (map index, player_id, time)
+    // The player 1 is first on both maps, the player 3 finished both maps behind them, and the
+    // player 2 only finished the first map, so they're last despite their better time on it.
+    let records_info = [
+        (0, 1, 4000),
+        (0, 2, 5000),
+        (0, 3, 6000),
+        (1, 1, 4000),
+        (1, 3, 5000),
+    ];
+
+    let records = records_info
+        .iter()
+        .enumerate()
+        .map(|(record_id, (i, player_id, time))| records::ActiveModel {
+            record_id: Set(record_id as u32 + 1),
+            record_player_id: Set(*player_id),
+            map_id: Set(map_ids[*i]),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    let event_records =
+        (1..=records_info.len() as u32).map(|record_id| event_edition_records::ActiveModel {
+            record_id: Set(record_id),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/leaderboard")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.total, 3);
+        itertools::assert_equal(
+            body.players,
+            [(1, 1, 2), (2, 3, 2), (3, 2, 1)].map(|(rank, player_id, maps_finished)| Player {
+                rank,
+                login: format!("player_{player_id}_login"),
+                maps_finished,
+            }),
+        );
+
+        // The leaderboard is paginated
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/leaderboard?offset=1&limit=1")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(body.total, 3);
+        itertools::assert_equal(
+            body.players,
+            [Player {
+                rank: 2,
+                login: "player_3_login".to_owned(),
+                maps_finished: 2,
+            }],
+        );
+
+        // The standings are cached, so a new record doesn't change them until they're refreshed
+        records::Entity::insert(records::ActiveModel {
+            record_id: Set(records_info.len() as u32 + 1),
+            record_player_id: Set(2),
+            map_id: Set(map_ids[1]),
+            time: Set(3000),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .exec(&db.sql_conn)
+        .await?;
+        event_edition_records::Entity::insert(event_edition_records::ActiveModel {
+            record_id: Set(records_info.len() as u32 + 1),
+            event_id: Set(1),
+            edition_id: Set(1),
+        })
+        .exec(&db.sql_conn)
+        .await?;
+
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/leaderboard?offset=2")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(body.total, 3);
+        itertools::assert_equal(
+            body.players,
+            [Player {
+                rank: 3,
+                login: "player_2_login".to_owned(),
+                maps_finished: 1,
+            }],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
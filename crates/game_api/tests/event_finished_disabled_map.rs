@@ -0,0 +1,133 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use game_api_lib::TracedError;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::player_finished_base::{Request, Response};
+
+mod base;
+mod player_finished_base;
+
+#[tokio::test]
+async fn finished_on_disabled_map() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The second edition is transparent, so it doesn't check whether the map is disabled
+    let editions =
+        [(1, 0), (2, 1)].map(|(edition_id, is_transparent)| event_edition::ActiveModel {
+            event_id: Set(1),
+            id: Set(edition_id),
+            name: Set(format!("event_1_{edition_id}_name")),
+            start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+            is_transparent: Set(is_transparent),
+            non_original_maps: Set(0),
+            save_non_event_record: Set(0),
+            ..Default::default()
+        });
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let maps = (1..=2).map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (edition_id, map_id, is_disabled)
+    let event_maps =
+        [(1, 1, false), (1, 2, true), (2, 2, true)].map(|(edition_id, map_id, is_disabled)| {
+            event_edition_maps::ActiveModel {
+                event_id: Set(1),
+                edition_id: Set(edition_id),
+                map_id: Set(map_id),
+                order: Set(map_id),
+                is_disabled: Set(is_disabled),
+                ..Default::default()
+            }
+        });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert_many(editions)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let request = |edition_id: u32, map_id: u32| {
+            test::TestRequest::post()
+                .uri(&format!("/event/event_handle/{edition_id}/player/finished"))
+                .insert_header(("PlayerLogin", "player_login"))
+                .set_json(Request {
+                    map_uid: format!("map_{map_id}_uid"),
+                    time: 10000,
+                    flags: Some(682),
+                    respawn_count: 0,
+                    cps: vec![10000],
+                })
+                .to_request()
+        };
+
+        // The map is enabled, so the record is saved
+        let res = test::call_service(&app, request(1, 1)).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.new, 10000);
+
+        // The map is disabled, so the record is rejected
+        let res = test::try_call_service(&app, request(1, 2)).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Map not available
+        assert_eq!(traced_err.r#type, Some(318));
+
+        // Apart from the submission of records, the disabled map isn't part of the edition
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/map/map_2_uid/first-record")
+            .to_request();
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Map not in event edition
+        assert_eq!(traced_err.r#type, Some(312));
+
+        // The edition is transparent, so the record is saved on the map directly
+        let res = test::call_service(&app, request(2, 2)).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.new, 10000);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,123 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use game_api_lib::TracedError;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::player_finished_base::Request;
+
+mod base;
+mod player_finished_base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    new: i32,
+    #[serde(default)]
+    is_grace_period: bool,
+}
+
+#[tokio::test]
+async fn finished_within_grace_period() -> anyhow::Result<()> {
+    // This is the only test of this file, so the environment isn't shared with other tests
+    // SAFETY: no other thread is reading the environment at this point
+    unsafe {
+        std::env::set_var("RECORDS_API_EVENT_GRACE_PERIOD_SECONDS", "300");
+    }
+
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The first edition expired a minute ago, so within the grace period, and the second one
+    // expired an hour ago.
+    let editions =
+        [(1, 60), (2, 3600)].map(|(edition_id, expired_since)| event_edition::ActiveModel {
+            event_id: Set(1),
+            id: Set(edition_id),
+            name: Set(format!("event_1_{edition_id}_name")),
+            start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+            ttl: Set(Some(24 * 3600 - expired_since)),
+            is_transparent: Set(0),
+            non_original_maps: Set(0),
+            save_non_event_record: Set(0),
+            ..Default::default()
+        });
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let event_maps = [1, 2].map(|edition_id| event_edition_maps::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(edition_id),
+        map_id: Set(map_id),
+        order: Set(0),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert_many(editions)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let request = |edition_id: u32| {
+            test::TestRequest::post()
+                .uri(&format!("/event/event_handle/{edition_id}/player/finished"))
+                .insert_header(("PlayerLogin", "player_login"))
+                .set_json(Request {
+                    map_uid: format!("map_{map_id}_uid"),
+                    time: 10000,
+                    flags: Some(682),
+                    respawn_count: 0,
+                    cps: vec![10000],
+                })
+                .to_request()
+        };
+
+        // Within the grace period, the submission is accepted but flagged
+        let res = test::call_service(&app, request(1)).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.new, 10000);
+        assert!(body.is_grace_period);
+
+        // Beyond the grace period, the submission is rejected
+        let res = test::try_call_service(&app, request(2)).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Event has expired
+        assert_eq!(traced_err.r#type, Some(315));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,118 @@
+use actix_web::test;
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Map {
+    map_uid: String,
+    is_available: bool,
+    is_disabled: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Category {
+    maps: Vec<Map>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EventEditionResponse {
+    categories: Vec<Category>,
+}
+
+#[tokio::test]
+async fn event_edition_maps_availability() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let map_author = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let maps = (1..=3).map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        player_id: Set(1),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        ..Default::default()
+    });
+
+    // (map_id, is_available, is_disabled)
+    // The first map is playable, the second one isn't available yet, and the third one
+    // has been disabled.
+    let event_maps = [(1, true, false), (2, false, false), (3, true, true)].map(
+        |(map_id, is_available, is_disabled)| event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(map_id),
+            order: Set(map_id),
+            is_available: Set(is_available),
+            is_disabled: Set(is_disabled),
+            ..Default::default()
+        },
+    );
+
+    base::with_db(async |db| {
+        players::Entity::insert(map_author)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1")
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<EventEditionResponse>(&body)?;
+
+        assert_eq!(status, 200);
+        itertools::assert_equal(
+            body.categories
+                .into_iter()
+                .flat_map(|category| category.maps),
+            [
+                ("map_1_uid", true, false),
+                ("map_2_uid", false, false),
+                ("map_3_uid", true, true),
+            ]
+            .map(|(map_uid, is_available, is_disabled)| Map {
+                map_uid: map_uid.to_owned(),
+                is_available,
+                is_disabled,
+            }),
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,104 @@
+use actix_http::StatusCode;
+use actix_web::{http::header, test};
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use game_api_lib::TracedError;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn event_map_thumbnail_redirect() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let map_author = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let maps = (1..=2).map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        player_id: Set(1),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        ..Default::default()
+    });
+
+    // Only the first map has a thumbnail source
+    let event_maps = [
+        (1, Some("https://example.com/thumbnails/map_1.jpg")),
+        (2, None),
+    ]
+    .map(
+        |(map_id, thumbnail_source)| event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(map_id),
+            order: Set(map_id),
+            thumbnail_source: Set(thumbnail_source.map(ToOwned::to_owned)),
+            ..Default::default()
+        },
+    );
+
+    base::with_db(async |db| {
+        players::Entity::insert(map_author)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/map/map_1_uid/thumbnail")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::FOUND);
+        assert_eq!(
+            res.headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok()),
+            Some("https://example.com/thumbnails/map_1.jpg")
+        );
+
+        // No default thumbnail is configured, so the missing thumbnail isn't found
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/map/map_2_uid/thumbnail")
+            .to_request();
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::NOT_FOUND));
+        // No thumbnail found
+        assert_eq!(traced_err.r#type, Some(319));
+
+        anyhow::Ok(())
+    })
+    .await
+}
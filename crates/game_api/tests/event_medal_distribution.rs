@@ -0,0 +1,171 @@
+use actix_web::test;
+use entity::{
+    event, event_edition, event_edition_maps, event_edition_records, maps, players, records,
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Response {
+    bronze: u64,
+    silver: u64,
+    gold: u64,
+    champion: u64,
+    no_medal: u64,
+}
+
+#[tokio::test]
+async fn medal_distribution_over_maps() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        is_transparent: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=6).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The last map doesn't have any medal time, so its records are ignored
+    let event_maps = map_ids.iter().enumerate().map(|(i, map_id)| {
+        let medal_time = |time| Set((i < 2).then_some(time));
+        event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(*map_id),
+            order: Set(i as _),
+            author_time: medal_time(10000),
+            gold_time: medal_time(11000),
+            silver_time: medal_time(12000),
+            bronze_time: medal_time(13000),
+            ..Default::default()
+        }
+    });
+
+    // (map index, player_id, time, is_hidden, is_event_record)
+    // Each player is counted once, with the best medal they earned on any map.
+    let records_info = [
+        // Player 1: champion, and only the best time of the player is counted
+        (0, 1, 9000, false, true),
+        (0, 1, 12500, false, true),
+        // Player 2: gold
+        (0, 2, 10500, false, true),
+        // Player 3: silver
+        (0, 3, 11500, false, true),
+        // Player 4: no medal
+        (0, 4, 14000, false, true),
+        // Not made in the event, so player 5 isn't counted
+        (0, 5, 9000, false, false),
+        // Player 1 only earns the bronze medal, but already has the champion medal
+        (1, 1, 12500, false, true),
+        // Player 2: gold again, on the threshold
+        (1, 2, 11000, false, true),
+        // Hidden
+        (1, 5, 9000, true, true),
+        // Player 6: bronze
+        (1, 6, 12500, false, true),
+        // No medal times
+        (2, 1, 1000, false, true),
+    ];
+
+    let records =
+        records_info
+            .iter()
+            .enumerate()
+            .map(
+                |(record_id, (i, player_id, time, is_hidden, _))| records::ActiveModel {
+                    record_id: Set(record_id as u32 + 1),
+                    record_player_id: Set(*player_id),
+                    map_id: Set(map_ids[*i]),
+                    time: Set(*time),
+                    respawn_count: Set(0),
+                    flags: Set(682),
+                    record_date: Set(chrono::Utc::now().naive_utc()),
+                    is_hidden: Set(*is_hidden),
+                    ..Default::default()
+                },
+            );
+
+    let event_records = records_info
+        .iter()
+        .enumerate()
+        .filter(|(_, (.., is_event_record))| *is_event_record)
+        .map(|(record_id, _)| event_edition_records::ActiveModel {
+            record_id: Set(record_id as u32 + 1),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri("/event/event_handle/1/medal-distribution")
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            Response {
+                bronze: 1,
+                silver: 1,
+                gold: 1,
+                champion: 1,
+                no_medal: 1,
+            }
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,106 @@
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use records_lib::event as event_utils;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn medal_times_of_all_edition_maps() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let editions = (1..=2).map(|edition_id| event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(edition_id),
+        name: Set(format!("event_1_{edition_id}_name")),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    });
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (edition_id, map index, author time)
+    // The map without an author time is omitted, and so is the map of the other edition.
+    let event_maps_info = [
+        (1, 0, Some(1000)),
+        (1, 1, Some(2000)),
+        (1, 2, None),
+        (2, 0, Some(3000)),
+    ];
+
+    let event_maps = event_maps_info.iter().map(|(edition_id, i, author_time)| {
+        event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(*edition_id),
+            map_id: Set(map_ids[*i]),
+            order: Set(*i as _),
+            bronze_time: Set(author_time.map(|time| time * 4)),
+            silver_time: Set(author_time.map(|time| time * 3)),
+            gold_time: Set(author_time.map(|time| time * 2)),
+            author_time: Set(*author_time),
+            ..Default::default()
+        }
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert_many(editions)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let medal_times = event_utils::medal_times_for_edition(&db.sql_conn, 1, 1).await?;
+
+        let mut medal_times = medal_times
+            .into_iter()
+            .map(|(map_id, times)| {
+                (
+                    map_id,
+                    times.bronze_time,
+                    times.silver_time,
+                    times.gold_time,
+                    times.champion_time,
+                )
+            })
+            .collect::<Vec<_>>();
+        medal_times.sort_unstable();
+
+        assert_eq!(
+            medal_times,
+            [
+                (map_ids[0], 4000, 3000, 2000, 1000),
+                (map_ids[1], 8000, 6000, 4000, 2000),
+            ]
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
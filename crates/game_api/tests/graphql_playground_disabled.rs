@@ -0,0 +1,45 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use game_api_lib::TracedError;
+
+mod base;
+
+#[tokio::test]
+async fn playground_disabled_not_found() -> anyhow::Result<()> {
+    // This is the only test of this file, so the environment isn't shared with other tests
+    // SAFETY: no other thread is reading the environment at this point
+    unsafe {
+        std::env::set_var("RECORDS_API_GRAPHQL_PLAYGROUND", "false");
+    }
+
+    base::with_db(async |db| {
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get().uri("/graphql").to_request();
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Response should be error");
+        let err = err
+            .as_error::<TracedError>()
+            .expect("Response should be a traced error");
+
+        assert_eq!(err.status_code, Some(StatusCode::NOT_FOUND));
+        // Not found error type
+        assert_eq!(err.r#type, Some(301));
+
+        // The GraphQL endpoint itself is still available
+        let req = test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(serde_json::json!({ "query": "{ __typename }" }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<serde_json::Value>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["__typename"], "QueryRoot");
+
+        anyhow::Ok(())
+    })
+    .await
+}
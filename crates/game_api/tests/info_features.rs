@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use actix_web::test;
+
+mod base;
+
+#[tokio::test]
+async fn features_enabled() -> anyhow::Result<()> {
+    base::with_db(async |db| {
+        let app = base::get_app(db).await;
+        let req = test::TestRequest::get().uri("/info/features").to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<HashMap<String, bool>>(&body)?;
+
+        assert_eq!(status, 200);
+        // The GraphQL subscriptions are always served
+        assert_eq!(body.get("graphql_subscriptions"), Some(&true));
+        assert_eq!(
+            body.get("request_filter"),
+            Some(&cfg!(feature = "request_filter"))
+        );
+        assert_eq!(
+            body.get("hide_banned_players"),
+            Some(&records_lib::hide_banned_players())
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
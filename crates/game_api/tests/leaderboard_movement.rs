@@ -0,0 +1,105 @@
+use entity::{maps, players, records};
+use records_lib::leaderboard::{self, PlayerMovement};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn movement_over_snapshot() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    let now = chrono::Utc::now().naive_utc();
+    let since = now - chrono::Duration::days(1);
+    let before = now - chrono::Duration::days(2);
+
+    // (map index, player_id, time, record_date)
+    // On the first map, the player 3 overtakes the player 1, and the player 4 appears between the
+    // players 1 and 2. On the second map, the player 1 only has a record after the snapshot.
+    let records_info = [
+        (0, 1, 1000, before),
+        (0, 2, 2000, before),
+        (0, 3, 3000, before),
+        (0, 3, 500, now),
+        (0, 4, 1500, now),
+        (1, 2, 1000, before),
+        (1, 1, 2000, now),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(map, player_id, time, record_date)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_ids[*map]),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(*record_date),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let movements = leaderboard::movement(&db.sql_conn, &db.redis_pool, &[1, 3], since).await?;
+
+        let mut expected = vec![
+            PlayerMovement {
+                player_id: 1,
+                map_id: map_ids[0],
+                previous_rank: Some(1),
+                rank: 2,
+            },
+            PlayerMovement {
+                player_id: 1,
+                map_id: map_ids[1],
+                previous_rank: None,
+                rank: 2,
+            },
+            PlayerMovement {
+                player_id: 3,
+                map_id: map_ids[0],
+                previous_rank: Some(3),
+                rank: 1,
+            },
+        ];
+        expected.sort_by_key(|movement| (movement.player_id, movement.map_id));
+
+        assert_eq!(movements, expected);
+
+        let delta_of = |player_id, map_id| {
+            movements
+                .iter()
+                .find(|movement| movement.player_id == player_id && movement.map_id == map_id)
+                .and_then(PlayerMovement::delta)
+        };
+        assert_eq!(delta_of(1, map_ids[0]), Some(-1));
+        assert_eq!(delta_of(1, map_ids[1]), None);
+        assert_eq!(delta_of(3, map_ids[0]), Some(2));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,98 @@
+use entity::{maps, players, records};
+use records_lib::leaderboard;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn neighbors_of_middle_player() -> anyhow::Result<()> {
+    let players = (1..=7).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The rank of each player is their ID
+    let records = (1..=7).map(|player_id| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(player_id as i32 * 1000),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let ranks_and_logins = |rows: &[leaderboard::Row]| {
+            rows.iter()
+                .map(|row| (row.rank, row.login.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let neighbors = leaderboard::neighbors(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            4,
+            2,
+            Default::default(),
+        )
+        .await?;
+        assert_eq!(
+            ranks_and_logins(&neighbors.above),
+            [
+                (2, "player_2_login".to_owned()),
+                (3, "player_3_login".to_owned())
+            ]
+        );
+        assert_eq!(
+            ranks_and_logins(&neighbors.below),
+            [
+                (5, "player_5_login".to_owned()),
+                (6, "player_6_login".to_owned())
+            ]
+        );
+
+        // The window is cut at the top of the leaderboard
+        let neighbors = leaderboard::neighbors(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            1,
+            2,
+            Default::default(),
+        )
+        .await?;
+        assert!(neighbors.above.is_empty());
+        assert_eq!(
+            ranks_and_logins(&neighbors.below),
+            [
+                (2, "player_2_login".to_owned()),
+                (3, "player_3_login".to_owned())
+            ]
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,132 @@
+use chrono::{Days, SubsecRound as _};
+use entity::{maps, players, records};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn biggest_improvement_single_player() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+
+    // (player ID, time, days ago)
+    let records_info = [
+        // Player 1 improves slowly
+        (1, 10000, 5),
+        (1, 9500, 4),
+        (1, 9000, 3),
+        // Player 2 makes a worse run before improving a lot, then a small improvement
+        (2, 20000, 6),
+        (2, 25000, 5),
+        (2, 12000, 2),
+        (2, 11500, 1),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time, days_ago)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(now - Days::new(*days_ago)),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let improvement = records_lib::map::biggest_improvement(&db.sql_conn, map_id)
+            .await?
+            .expect("the map should have an improvement");
+
+        assert_eq!(improvement.player.login, "player_2_login");
+        assert_eq!(improvement.from_time, 20000);
+        assert_eq!(improvement.to_time, 12000);
+        assert_eq!(improvement.delta, 8000);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn hidden_records_ignored() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+
+    // (time, days ago, hidden)
+    // The improvement from the hidden record would be the biggest one
+    let records_info = [(20000, 3, true), (12000, 2, false), (11000, 1, false)];
+
+    let records = records_info
+        .iter()
+        .map(|(time, days_ago, is_hidden)| records::ActiveModel {
+            record_player_id: Set(1),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(now - Days::new(*days_ago)),
+            is_hidden: Set(*is_hidden),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let improvement = records_lib::map::biggest_improvement(&db.sql_conn, map_id)
+            .await?
+            .expect("the map should have an improvement");
+
+        assert_eq!(improvement.from_time, 12000);
+        assert_eq!(improvement.to_time, 11000);
+        assert_eq!(improvement.delta, 1000);
+
+        anyhow::Ok(())
+    })
+    .await
+}
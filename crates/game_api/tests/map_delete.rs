@@ -0,0 +1,176 @@
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{checkpoint_times, event, event_edition, event_edition_maps, maps, players, records};
+use records_lib::{
+    error::RecordsError,
+    map, ranks,
+    redis_key::{alone_map_key, event_map_key},
+};
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, PaginatorTrait as _, QueryFilter};
+
+mod base;
+
+#[tokio::test]
+async fn delete_map_cascade() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The edition has no TTL, so it never expires
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        non_original_maps: Set(1),
+        save_non_event_record: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // Another event map of the edition uses the deleted map as its original map
+    let other_map_id = test_env::get_map_id();
+    let other_map = maps::ActiveModel {
+        id: Set(other_map_id),
+        game_id: Set(format!("map_{other_map_id}_uid")),
+        name: Set(format!("map_{other_map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let event_maps = [
+        event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(map_id),
+            order: Set(0),
+            ..Default::default()
+        },
+        event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(other_map_id),
+            original_map_id: Set(Some(map_id)),
+            order: Set(1),
+            ..Default::default()
+        },
+    ];
+
+    let records = (1..=2).map(|player_id| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(10000 - player_id as i32 * 1000),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many([map, other_map])
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let record_ids: Vec<u32> = records::Entity::find()
+            .filter(records::Column::MapId.eq(map_id))
+            .all(&db.sql_conn)
+            .await?
+            .into_iter()
+            .map(|record| record.record_id)
+            .collect();
+        checkpoint_times::Entity::insert_many(record_ids.iter().map(|record_id| {
+            checkpoint_times::ActiveModel {
+                cp_num: Set(0),
+                map_id: Set(map_id),
+                record_id: Set(*record_id),
+                time: Set(5000),
+            }
+        }))
+        .exec(&db.sql_conn)
+        .await?;
+
+        ranks::update_leaderboard(&db.sql_conn, &db.redis_pool, map_id, Default::default())
+            .await?;
+
+        let map = map::get_map_from_id(&db.sql_conn, map_id).await?;
+
+        // The edition is still active, so the map isn't deleted
+        let err = map::delete_map(&db.sql_conn, &db.redis_pool, &map, false)
+            .await
+            .expect_err("the map is used by an active event edition");
+        assert!(
+            matches!(err, RecordsError::MapInActiveEvent(_, ref handle, 1) if handle == "event_handle"),
+            "unexpected error: {err}"
+        );
+        assert!(map::get_map_from_uid(&db.sql_conn, &map.game_id).await?.is_some());
+
+        let deleted = map::delete_map(&db.sql_conn, &db.redis_pool, &map, true).await?;
+        assert_eq!(deleted, 2);
+
+        assert!(map::get_map_from_uid(&db.sql_conn, &map.game_id).await?.is_none());
+        let records_count = records::Entity::find()
+            .filter(records::Column::MapId.eq(map_id))
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(records_count, 0);
+        let cp_times_count = checkpoint_times::Entity::find()
+            .filter(checkpoint_times::Column::RecordId.is_in(record_ids))
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(cp_times_count, 0);
+        let links_count = event_edition_maps::Entity::find()
+            .filter(event_edition_maps::Column::MapId.eq(map_id))
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(links_count, 0);
+
+        let other_link = event_edition_maps::Entity::find()
+            .filter(event_edition_maps::Column::MapId.eq(other_map_id))
+            .one(&db.sql_conn)
+            .await?
+            .expect("the other event map should stay in its edition");
+        assert_eq!(other_link.original_map_id, None);
+
+        let mut redis_conn = db.redis_pool.get().await?;
+        let exists: bool = redis_conn.exists(alone_map_key(map_id)).await?;
+        assert!(!exists);
+        let exists: bool = redis_conn
+            .exists(event_map_key(map_id, "event_handle", 1))
+            .await?;
+        assert!(!exists);
+
+        anyhow::Ok(())
+    })
+    .await
+}
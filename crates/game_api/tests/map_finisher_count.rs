@@ -0,0 +1,63 @@
+use entity::{maps, players, records};
+use records_lib::map;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn finisher_count_distinct_players() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The second player finished the map twice, and the record of the fourth one is hidden
+    let records_info = [
+        (1, 5000, false),
+        (2, 8000, false),
+        (2, 6000, false),
+        (3, 4000, false),
+        (4, 3000, true),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time, is_hidden)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            is_hidden: Set(*is_hidden),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let count = map::finisher_count(&db.sql_conn, map_id).await?;
+        assert_eq!(count, 3);
+
+        anyhow::Ok(())
+    })
+    .await
+}
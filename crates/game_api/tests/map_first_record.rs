@@ -0,0 +1,134 @@
+use actix_web::test;
+use chrono::{Days, SubsecRound as _};
+use entity::{maps, players, records};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    login: String,
+    time: i32,
+    record_date: chrono::NaiveDateTime,
+}
+
+#[tokio::test]
+async fn first_record_is_oldest() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+
+    // The second player made the oldest record, even though it isn't the best time
+    let records_info = [(1, 5000, 3), (2, 8000, 10), (3, 4000, 1), (2, 6000, 2)];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time, days_ago)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(now - Days::new(*days_ago)),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/map/map_{map_id}_uid/first-record"))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Option<Response>>(&body)?;
+
+        assert_eq!(status, 200);
+        let body = body.expect("the map should have a first record");
+        assert_eq!(body.login, "player_2_login");
+        assert_eq!(body.time, 8000);
+        assert_eq!(body.record_date, now - Days::new(10));
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn hidden_first_record_ignored() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+
+    // The oldest record is hidden
+    let records = [(1, 10, true), (2, 5, false)].map(|(player_id, days_ago, is_hidden)| {
+        records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(map_id),
+            time: Set(5000),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(now - Days::new(days_ago)),
+            is_hidden: Set(is_hidden),
+            ..Default::default()
+        }
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let record = records_lib::map::get_first_record(&db.sql_conn, map_id, Default::default())
+            .await?
+            .expect("the map should have a first record");
+        assert_eq!(record.record_player_id, 2);
+
+        anyhow::Ok(())
+    })
+    .await
+}
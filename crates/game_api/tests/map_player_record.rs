@@ -0,0 +1,122 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{maps, players, records};
+use game_api_lib::TracedError;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    rank: i32,
+    time: i32,
+}
+
+#[tokio::test]
+async fn player_record_is_best_with_rank() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The second player has two records, and only the best one is returned
+    let records_info = [(1, 5000), (2, 8000), (2, 6000), (3, 4000)];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/map/map_{map_id}_uid/player/player_2_login/record"
+            ))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.time, 6000);
+        assert_eq!(body.rank, 3);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn player_record_not_found() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/map/map_{map_id}_uid/player/player_login/record"))
+            .to_request();
+
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Response should be error");
+        let err = err
+            .as_error::<TracedError>()
+            .expect("Response should be a traced error");
+
+        assert_eq!(err.status_code, Some(StatusCode::NOT_FOUND));
+        // No record found error type
+        assert_eq!(err.r#type, Some(316));
+
+        anyhow::Ok(())
+    })
+    .await
+}
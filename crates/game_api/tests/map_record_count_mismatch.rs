@@ -0,0 +1,129 @@
+use chrono::SubsecRound as _;
+use entity::{event, event_edition, event_edition_records, maps, players, records};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn consistent_map_not_flagged() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records =
+        [(1, 10000), (1, 9000), (2, 12000)].map(|(player_id, time)| records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mismatch = records_lib::map::record_count_mismatch(&db.sql_conn, map_id).await?;
+        assert_eq!(mismatch, None);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn inconsistent_map_flagged() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc().trunc_subsecs(0)),
+        is_transparent: Set(0),
+        non_original_maps: Set(0),
+        save_non_event_record: Set(0),
+        ..Default::default()
+    };
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = [(1, 10000), (2, 9000)].map(|(record_id, time)| records::ActiveModel {
+        record_id: Set(record_id),
+        record_player_id: Set(1),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    // The best time of the player is saved for an edition that excludes it from the view,
+    // which hides the player from the view even though they have another record on the map.
+    let event_record = event_edition_records::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(1),
+        record_id: Set(2),
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert(event_record)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mismatch = records_lib::map::record_count_mismatch(&db.sql_conn, map_id).await?;
+        assert_eq!(mismatch, Some((0, 1)));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,118 @@
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{maps, players, records};
+use records_lib::{
+    mappack::{self, AnyMappackId},
+    redis_key::{
+        mappack_key, mappack_lb_key, mappack_nb_map_key, mappack_player_map_finished_key,
+        mappack_player_rank_avg_key, mappack_player_worst_rank_key, mappack_scores_key,
+    },
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn ad_hoc_scores_match_stored_mappack() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The player 3 only finished the second map
+    let records = [
+        (0, 1, 5000),
+        (0, 2, 6000),
+        (1, 1, 7000),
+        (1, 2, 4000),
+        (1, 3, 3000),
+    ]
+    .map(|(i, player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_ids[i]),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let map_uids = map_ids
+            .iter()
+            .map(|map_id| format!("map_{map_id}_uid"))
+            .collect::<Vec<_>>();
+
+        let ad_hoc = mappack::calc_ad_hoc_scores(&db.sql_conn, &db.redis_pool, &map_uids).await?;
+        assert_eq!(ad_hoc.maps.len(), 2);
+        assert_eq!(ad_hoc.scores.len(), 3);
+
+        // The ID of the first map makes the mappack unique to this test
+        let mappack_id = format!("mappack_{}", map_ids[0]);
+        let mappack = AnyMappackId::Id(&mappack_id);
+
+        let mut redis_conn = db.redis_pool.get().await?;
+        let _: () = redis_conn.sadd(mappack_key(mappack), &map_uids).await?;
+        mappack::update_mappack(&db.sql_conn, &db.redis_pool, mappack, Default::default()).await?;
+
+        let nb_map: usize = redis_conn.get(mappack_nb_map_key(mappack)).await?;
+        assert_eq!(nb_map, ad_hoc.maps.len());
+
+        for score in &ad_hoc.scores {
+            let rank: u32 = redis_conn
+                .zscore(mappack_lb_key(mappack), score.player_id)
+                .await?;
+            let rank_avg: f64 = redis_conn
+                .get(mappack_player_rank_avg_key(mappack, score.player_id))
+                .await?;
+            let maps_finished: usize = redis_conn
+                .get(mappack_player_map_finished_key(mappack, score.player_id))
+                .await?;
+            let worst_rank: i32 = redis_conn
+                .get(mappack_player_worst_rank_key(mappack, score.player_id))
+                .await?;
+
+            assert_eq!(rank, score.rank);
+            assert_eq!(rank_avg, score.rank_avg);
+            assert_eq!(maps_finished, score.maps_finished);
+            assert_eq!(worst_rank, score.worst_rank);
+        }
+
+        // The result is cached regardless of the order of the UIDs
+        let hash = AnyMappackId::hash_of(&map_uids);
+        let cache_key = mappack_scores_key(AnyMappackId::Hashed(&hash));
+        let ttl: i64 = redis_conn.ttl(&cache_key).await?;
+        assert!(
+            ttl > 0 && ttl <= mappack::AD_HOC_MAPPACK_TTL as i64,
+            "unexpected TTL: {ttl}"
+        );
+
+        let reversed = map_uids.iter().rev().cloned().collect::<Vec<_>>();
+        let cached = mappack::calc_ad_hoc_scores(&db.sql_conn, &db.redis_pool, &reversed).await?;
+        assert_eq!(cached, ad_hoc);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,89 @@
+use std::time::{Duration, SystemTime};
+
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{maps, players, records};
+use mkenv::prelude::*;
+use records_lib::{
+    mappack::{self, AnyMappackId},
+    redis_key::{mappack_key, mappack_time_key},
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn mappack_ttl_and_staleness() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let record = records::ActiveModel {
+        record_player_id: Set(1),
+        map_id: Set(map_id),
+        time: Set(5000),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert(record).exec(&db.sql_conn).await?;
+
+        // The ID of the map makes the mappack unique to this test
+        let mappack_id = format!("mappack_{map_id}");
+        let mappack = AnyMappackId::Id(&mappack_id);
+
+        let mut redis_conn = db.redis_pool.get().await?;
+        let _: () = redis_conn
+            .sadd(mappack_key(mappack), format!("map_{map_id}_uid"))
+            .await?;
+
+        let before = SystemTime::UNIX_EPOCH.elapsed()?.as_secs();
+        let rows =
+            mappack::update_mappack(&db.sql_conn, &db.redis_pool, mappack, Default::default())
+                .await?;
+        assert_eq!(rows, 1);
+
+        // The scores are written with the configured TTL
+        let max_ttl = records_lib::env().mappack_ttl.get();
+        let ttl: i64 = redis_conn.ttl(mappack_time_key(mappack)).await?;
+        assert!(ttl > 0 && ttl <= max_ttl, "unexpected TTL: {ttl}");
+
+        let last_computed = mappack::last_computed(&mut redis_conn, mappack)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the computation time should be saved"))?;
+        assert!(last_computed >= before);
+
+        let refresh_interval = Duration::from_secs(3600);
+        assert!(!mappack::is_stale(
+            Some(last_computed),
+            last_computed + 3600,
+            refresh_interval
+        ));
+        assert!(mappack::is_stale(
+            Some(last_computed),
+            last_computed + 3601,
+            refresh_interval
+        ));
+        assert!(mappack::is_stale(None, last_computed, refresh_interval));
+
+        anyhow::Ok(())
+    })
+    .await
+}
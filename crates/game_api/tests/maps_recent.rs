@@ -0,0 +1,69 @@
+use actix_web::test;
+use entity::{maps, players};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct RecentMap {
+    map_uid: String,
+    author_login: String,
+}
+
+#[tokio::test]
+async fn recent_maps_by_creation_date() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+
+    // The second map is the newest one, and the first map is the oldest one
+    let now = chrono::Utc::now().naive_utc();
+    let maps = map_ids
+        .iter()
+        .zip([3, 1, 2])
+        .map(|(map_id, hours_ago)| maps::ActiveModel {
+            id: Set(*map_id),
+            game_id: Set(format!("map_{map_id}_uid")),
+            name: Set(format!("map_{map_id}_name")),
+            player_id: Set(1),
+            created_at: Set(now - chrono::Duration::hours(hours_ago)),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri("/maps/recent?limit=2")
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Vec<RecentMap>>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body.iter().map(|map| &map.map_uid).collect::<Vec<_>>(),
+            [
+                &format!("map_{}_uid", map_ids[1]),
+                &format!("map_{}_uid", map_ids[2]),
+            ]
+        );
+        assert!(body.iter().all(|map| map.author_login == "player_login"));
+
+        anyhow::Ok(())
+    })
+    .await
+}
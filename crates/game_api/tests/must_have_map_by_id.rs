@@ -0,0 +1,55 @@
+use entity::{maps, players};
+use records_lib::{error::RecordsError, must};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn have_map_by_id() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let map = must::have_map_by_id(&db.sql_conn, map_id).await?;
+        assert_eq!(map.game_id, format!("map_{map_id}_uid"));
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn have_map_by_id_missing() -> anyhow::Result<()> {
+    let map_id = test_env::get_map_id();
+
+    base::with_db(async |db| {
+        let err = must::have_map_by_id(&db.sql_conn, map_id)
+            .await
+            .expect_err("the map shouldn't exist");
+
+        assert!(
+            matches!(err, RecordsError::MapIdNotFound(id) if id == map_id),
+            "unexpected error: {err}"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
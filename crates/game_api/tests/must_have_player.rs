@@ -0,0 +1,20 @@
+use records_lib::{error::RecordsError, must};
+
+mod base;
+
+#[tokio::test]
+async fn have_player_missing() -> anyhow::Result<()> {
+    base::with_db(async |db| {
+        let err = must::have_player(&db.sql_conn, "unknown_login")
+            .await
+            .expect_err("the player shouldn't exist");
+
+        assert!(
+            matches!(&err, RecordsError::PlayerNotFound(login) if login == "unknown_login"),
+            "unexpected error: {err}"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
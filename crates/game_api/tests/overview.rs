@@ -403,6 +403,148 @@ async fn competition_ranking() -> anyhow::Result<()> {
     .await
 }
 
+#[tokio::test]
+async fn next_opponent_mid_leaderboard() -> anyhow::Result<()> {
+    base::with_db(async |db| {
+        let players = (1..=5).map(player_id_to_player_active_model);
+
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert players")?;
+
+        let player_ids = 1..=5;
+        let times = [1000, 3000, 3000, 5000, 7000];
+        let datetime_offsets = [0, 1, 2, 0, 0];
+
+        let map_id = insert_sample_map(&db.sql_conn).await?;
+        let records = player_ids
+            .zip(times)
+            .zip(datetime_offsets)
+            .map(|((player_id, time), offset)| new_record(map_id, player_id, time, offset));
+
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert records")?;
+
+        let app = base::get_app(db.clone()).await;
+
+        // (player_id, next opponent (player_id, rank, time))
+        // The players 2 and 3 are tied, so they both have the player 1 as next opponent, and the
+        // first player doesn't have any.
+        let expected = [
+            (1, None),
+            (2, Some((1, 1, 1000))),
+            (3, Some((1, 1, 1000))),
+            (4, Some((3, 2, 3000))),
+            (5, Some((4, 4, 5000))),
+        ];
+
+        for (player_id, next_opponent) in expected {
+            let req = test::TestRequest::get()
+                .uri(&format!(
+                    "/overview?mapId=test_map_uid&playerId=player_{player_id}_login"
+                ))
+                .to_request();
+
+            let resp = test::call_service(&app, req).await;
+            let status = resp.status();
+
+            let body = test::read_body(resp).await;
+            let body = base::try_from_slice::<Response>(&body)?;
+
+            assert_eq!(status, 200);
+            assert_eq!(
+                body.next_opponent,
+                next_opponent.map(|(player_id, rank, time)| Row {
+                    login: PlayerLogin(player_id).to_string(),
+                    nickname: PlayerName(player_id).to_string(),
+                    rank,
+                    time,
+                }),
+                "unexpected next opponent for player {player_id}"
+            );
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn gaps_to_first_and_previous_pb() -> anyhow::Result<()> {
+    base::with_db(async |db| {
+        let players = (1..=3).map(player_id_to_player_active_model);
+
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert players")?;
+
+        // (player_id, time, datetime offset)
+        // The player 2 improved their time from 6s to 5s.
+        let records_info = [(1, 4000, 0), (2, 6000, 0), (2, 5000, 1), (3, 7000, 0)];
+
+        let map_id = insert_sample_map(&db.sql_conn).await?;
+        let records = records_info
+            .into_iter()
+            .map(|(player_id, time, offset)| new_record(map_id, player_id, time, offset));
+
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert records")?;
+
+        let app = base::get_app(db.clone()).await;
+
+        // (player_id, gap to first, gap to previous PB)
+        let expected = [
+            (1, Some(0), None),
+            (2, Some(1000), Some(-1000)),
+            (3, Some(3000), None),
+        ];
+
+        for (player_id, gap_to_first, gap_to_previous) in expected {
+            let req = test::TestRequest::get()
+                .uri(&format!(
+                    "/overview?mapId=test_map_uid&playerId=player_{player_id}_login"
+                ))
+                .to_request();
+
+            let resp = test::call_service(&app, req).await;
+            let status = resp.status();
+
+            let body = test::read_body(resp).await;
+            let body = base::try_from_slice::<Response>(&body)?;
+
+            assert_eq!(status, 200);
+            assert_eq!(
+                (body.gap_to_first, body.gap_to_previous),
+                (gap_to_first, gap_to_previous),
+                "unexpected gaps for player {player_id}"
+            );
+        }
+
+        // An unknown player doesn't get any gap
+        let req = test::TestRequest::get()
+            .uri("/overview?mapId=test_map_uid&playerId=unknown_player_login")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status();
+
+        let body = test::read_body(resp).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!((body.gap_to_first, body.gap_to_previous), (None, None));
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
 /// Setup: a player, a map X, an event with its edition, which contains a map which has map X
 /// as the original one (e.g. map "Solexium - Benchmark" has "Solexium" as original map).
 ///
@@ -563,3 +705,59 @@ async fn overview_event_version_map_non_empty() -> anyhow::Result<()> {
     })
     .await
 }
+
+#[tokio::test]
+async fn degraded_without_redis() -> anyhow::Result<()> {
+    #[derive(serde::Deserialize)]
+    struct DegradedResponse {
+        response: Vec<Row>,
+        degraded: bool,
+    }
+
+    base::with_db(async |db| {
+        let players = (1..=5).map(player_id_to_player_active_model);
+
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert players")?;
+
+        let map_id = insert_sample_map(&db.sql_conn).await?;
+
+        let records = (1..=5).map(player_id_to_record_active_model(map_id));
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await
+            .context("couldn't insert records")?;
+
+        // Simulate an unreachable Redis database
+        let db = records_lib::Database {
+            redis_pool: records_lib::pool::get_redis_pool(
+                "redis://127.0.0.1:1".to_owned(),
+                records_lib::pool::DEFAULT_REDIS_HEALTH_CHECK_INTERVAL,
+            )?,
+            ..db
+        };
+
+        let app = base::get_app(db).await;
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/overview?mapId=test_map_uid&playerId={}",
+                PlayerLogin(3)
+            ))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status();
+
+        let body = test::read_body(resp).await;
+        let body = base::try_from_slice::<DegradedResponse>(&body)?;
+
+        assert_eq!(status, 200);
+        assert!(body.degraded);
+        itertools::assert_equal(body.response, (1..=5).map(player_id_to_row));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -9,4 +9,10 @@ pub struct Row {
 #[derive(Debug, serde::Deserialize)]
 pub struct Response {
     pub response: Vec<Row>,
+    #[serde(default)]
+    pub next_opponent: Option<Row>,
+    #[serde(default)]
+    pub gap_to_first: Option<i32>,
+    #[serde(default)]
+    pub gap_to_previous: Option<i32>,
 }
@@ -0,0 +1,109 @@
+use actix_web::test;
+use entity::{maps, players, players_ips, records};
+use records_lib::player;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, EntityTrait, PaginatorTrait as _, QueryFilter as _,
+};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    player_login: String,
+}
+
+#[tokio::test]
+async fn anonymize_keeps_records() -> anyhow::Result<()> {
+    // The first player is an admin
+    let players = [(1, 2), (2, 0)].map(|(player_id, role)| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        zone_path: Set(Some("World|Europe|France".to_owned())),
+        role: Set(role),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = [(1, 5000), (2, 6000), (2, 4000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let ip = players_ips::ActiveModel {
+        player_id: Set(2),
+        ip_hash: Set(vec![0; 32]),
+        ip_date: Set(chrono::Utc::now().naive_utc()),
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        players_ips::Entity::insert(ip).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/anonymize")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .set_json(serde_json::json!({ "player_login": "player_2_login" }))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.player_login, player::anonymized_login(2));
+
+        let player = players::Entity::find_by_id(2u32)
+            .one(&db.sql_conn)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the player should still exist"))?;
+        assert_eq!(player.login, body.player_login);
+        assert_ne!(player.name, "player_2_name");
+        assert_eq!(player.zone_path, None);
+
+        let record_count = records::Entity::find()
+            .filter(records::Column::RecordPlayerId.eq(2))
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(record_count, 2);
+
+        let ip_count = players_ips::Entity::find()
+            .filter(players_ips::Column::PlayerId.eq(2))
+            .count(&db.sql_conn)
+            .await?;
+        assert_eq!(ip_count, 0);
+
+        // The other player is left untouched
+        let admin = players::Entity::find_by_id(1u32)
+            .one(&db.sql_conn)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the admin should exist"))?;
+        assert_eq!(admin.login, "player_1_login");
+        assert_eq!(admin.zone_path.as_deref(), Some("World|Europe|France"));
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,187 @@
+use actix_web::test;
+use entity::{
+    event, event_edition, event_edition_maps, event_edition_records, maps, players, records,
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Record {
+    map_uid: String,
+    time: i32,
+    rank: i32,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Edition {
+    event_handle: String,
+    edition_id: u32,
+    edition_name: String,
+    records_count: usize,
+    best_rank: Option<i32>,
+    records: Vec<Record>,
+}
+
+#[tokio::test]
+async fn records_grouped_by_edition() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The second edition is the most recent one
+    let editions = [(1, 10), (2, 1)].map(|(edition_id, days_ago)| event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(edition_id),
+        name: Set(format!("event_1_{edition_id}_name")),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(days_ago)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    });
+
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (edition ID, map index)
+    let event_maps =
+        [(1, 0), (2, 0), (2, 1)].map(|(edition_id, i)| event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(edition_id),
+            map_id: Set(map_ids[i]),
+            order: Set(i as _),
+            ..Default::default()
+        });
+
+    // (edition ID, map index, player ID, time)
+    // In the first edition, the player 1 improved their time on the first map, but stays behind
+    // the player 2. In the second one, they're first on both maps. Their record outside of any
+    // event is ignored.
+    let records_info = [
+        (Some(1), 0, 1, 4000),
+        (Some(1), 0, 1, 3500),
+        (Some(1), 0, 2, 3000),
+        (Some(2), 0, 1, 5000),
+        (Some(2), 1, 1, 2000),
+        (Some(2), 1, 2, 2500),
+        (None, 1, 1, 1000),
+    ];
+
+    let records = records_info
+        .iter()
+        .enumerate()
+        .map(
+            |(record_id, (_, i, player_id, time))| records::ActiveModel {
+                record_id: Set(record_id as u32 + 1),
+                record_player_id: Set(*player_id),
+                map_id: Set(map_ids[*i]),
+                time: Set(*time),
+                respawn_count: Set(0),
+                flags: Set(682),
+                record_date: Set(chrono::Utc::now().naive_utc()),
+                ..Default::default()
+            },
+        );
+
+    let event_records =
+        records_info
+            .iter()
+            .enumerate()
+            .filter_map(|(record_id, (edition_id, ..))| {
+                edition_id.map(|edition_id| event_edition_records::ActiveModel {
+                    record_id: Set(record_id as u32 + 1),
+                    event_id: Set(1),
+                    edition_id: Set(edition_id),
+                })
+            });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert_many(editions)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::get()
+            .uri("/player/player_1_login/events")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Vec<Edition>>(&body)?;
+
+        assert_eq!(status, 200);
+        itertools::assert_equal(
+            body,
+            [
+                Edition {
+                    event_handle: "event_handle".to_owned(),
+                    edition_id: 2,
+                    edition_name: "event_1_2_name".to_owned(),
+                    records_count: 2,
+                    best_rank: Some(1),
+                    records: vec![
+                        Record {
+                            map_uid: format!("map_{}_uid", map_ids[0]),
+                            time: 5000,
+                            rank: 1,
+                        },
+                        Record {
+                            map_uid: format!("map_{}_uid", map_ids[1]),
+                            time: 2000,
+                            rank: 1,
+                        },
+                    ],
+                },
+                Edition {
+                    event_handle: "event_handle".to_owned(),
+                    edition_id: 1,
+                    edition_name: "event_1_1_name".to_owned(),
+                    records_count: 1,
+                    best_rank: Some(2),
+                    records: vec![Record {
+                        map_uid: format!("map_{}_uid", map_ids[0]),
+                        time: 3500,
+                        rank: 2,
+                    }],
+                },
+            ],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,120 @@
+use actix_web::test;
+use entity::{banishments, maps, players, records};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct ExportedPlayer {
+    login: String,
+}
+
+#[derive(serde::Deserialize)]
+struct ExportedRecord {
+    map_uid: String,
+    time: i32,
+}
+
+#[derive(serde::Deserialize)]
+struct ExportedBan {
+    reason: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Response {
+    player: ExportedPlayer,
+    records: Vec<ExportedRecord>,
+    bans: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[tokio::test]
+async fn export_only_player_data() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The second player has a record on the same map, which must not be exported
+    let records_info = [(1, 6000), (1, 5000), (2, 4000)];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    // The first player was banned by the second one
+    let ban = banishments::ActiveModel {
+        date_ban: Set(chrono::Utc::now().naive_utc()),
+        duration: Set(Some(3600)),
+        was_reprieved: Set(0),
+        reason: Set("cheating".to_owned()),
+        player_id: Set(Some(1)),
+        banished_by: Set(Some(2)),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        banishments::Entity::insert(ban).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::get()
+            .uri("/player/export")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.player.login, "player_1_login");
+
+        let mut times = body
+            .records
+            .iter()
+            .map(|record| {
+                assert_eq!(record.map_uid, format!("map_{map_id}_uid"));
+                record.time
+            })
+            .collect::<Vec<_>>();
+        times.sort();
+        assert_eq!(times, [5000, 6000]);
+
+        // The author of the ban isn't exported
+        assert_eq!(body.bans.len(), 1);
+        assert!(!body.bans[0].contains_key("banished_by"));
+        let ban = serde_json::from_value::<ExportedBan>(body.bans[0].clone().into())?;
+        assert_eq!(ban.reason, "cheating");
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -1,10 +1,12 @@
 use std::{array, iter};
 
+use actix_http::StatusCode;
 use actix_web::test;
 use entity::{
     checkpoint_times, event, event_edition, event_edition_maps, global_event_records,
     global_records, maps, players, records,
 };
+use game_api_lib::TracedError;
 use sea_orm::{
     ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
 };
@@ -716,3 +718,85 @@ async fn save_record_for_related_non_transparent_event() -> anyhow::Result<()> {
     })
     .await
 }
+
+/// Setup: one player, one map with a maximum respawn count of 5
+/// Test: /player/finished of that player on the map, at the limit, then above it
+/// Expected: the first record should be saved, and the second one rejected.
+#[tokio::test]
+async fn max_respawn_count() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        max_respawn_count: Set(Some(5)),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 5,
+                cps: vec![10000],
+            })
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+
+        let req = test::TestRequest::post()
+            .uri("/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 9000,
+                flags: Some(682),
+                respawn_count: 6,
+                cps: vec![9000],
+            })
+            .to_request();
+
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Response should be error");
+        let err = err
+            .as_error::<TracedError>()
+            .expect("Response should be a traced error");
+
+        assert_eq!(err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Too many respawns error type
+        assert_eq!(err.r#type, Some(317));
+
+        // Only the first record is saved
+        let times = records::Entity::find()
+            .filter(records::Column::MapId.eq(map_id))
+            .select_only()
+            .column(records::Column::Time)
+            .into_tuple::<i32>()
+            .all(&db.sql_conn)
+            .await?;
+        assert_eq!(times, [10000]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -694,3 +694,237 @@ async fn event_finish_save_non_event_record() -> anyhow::Result<()> {
     })
     .await
 }
+
+#[tokio::test]
+async fn event_finish_max_respawn_count() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        non_original_maps: Set(1),
+        save_non_event_record: Set(0),
+        max_respawn_count: Set(Some(3)),
+        ..Default::default()
+    };
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+
+    // The map has its own limit, but the one of the edition is lower
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        max_respawn_count: Set(Some(10)),
+        ..Default::default()
+    };
+
+    let event_map = event_edition_maps::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(1),
+        map_id: Set(map_id),
+        order: Set(0),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert(event_map)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/event/event_handle/1/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 3,
+                cps: vec![10000],
+            })
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+
+        let req = test::TestRequest::post()
+            .uri("/event/event_handle/1/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 9000,
+                flags: Some(682),
+                respawn_count: 4,
+                cps: vec![9000],
+            })
+            .to_request();
+
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Response should be error");
+        let err = err
+            .as_error::<TracedError>()
+            .expect("Response should be a traced error");
+
+        assert_eq!(err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Too many respawns error type
+        assert_eq!(err.r#type, Some(317));
+
+        // Only the first record is saved in the edition
+        let times = global_event_records::Entity::find()
+            .filter(
+                global_event_records::Column::MapId
+                    .eq(map_id)
+                    .and(global_event_records::Column::EventId.eq(1))
+                    .and(global_event_records::Column::EditionId.eq(1)),
+            )
+            .all(&db.sql_conn)
+            .await?
+            .into_iter()
+            .map(|record| record.time)
+            .collect::<Vec<_>>();
+        assert_eq!(times, [10000]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn event_finish_no_respawn_only() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        non_original_maps: Set(1),
+        save_non_event_record: Set(0),
+        no_respawn_only: Set(true),
+        ..Default::default()
+    };
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let event_map = event_edition_maps::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(1),
+        map_id: Set(map_id),
+        order: Set(0),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert(event_map)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/event/event_handle/1/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 9000,
+                flags: Some(682),
+                respawn_count: 1,
+                cps: vec![9000],
+            })
+            .to_request();
+
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Response should be error");
+        let err = err
+            .as_error::<TracedError>()
+            .expect("Response should be a traced error");
+
+        assert_eq!(err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Too many respawns error type
+        assert_eq!(err.r#type, Some(317));
+
+        let req = test::TestRequest::post()
+            .uri("/event/event_handle/1/player/finished")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: "map_uid".to_owned(),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 0,
+                cps: vec![10000],
+            })
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+
+        // Only the clean record is saved in the edition
+        let times = global_event_records::Entity::find()
+            .filter(
+                global_event_records::Column::MapId
+                    .eq(map_id)
+                    .and(global_event_records::Column::EventId.eq(1))
+                    .and(global_event_records::Column::EditionId.eq(1)),
+            )
+            .all(&db.sql_conn)
+            .await?
+            .into_iter()
+            .map(|record| record.time)
+            .collect::<Vec<_>>();
+        assert_eq!(times, [10000]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,171 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{maps, players, records};
+use game_api_lib::TracedError;
+use sea_orm::{ActiveValue::Set, EntityTrait, PaginatorTrait as _};
+
+use crate::player_finished_base::{Request, Response};
+
+mod base;
+mod player_finished_base;
+
+#[tokio::test]
+async fn validate_finished_without_saving() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        cps_number: Set(Some(1)),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        // The sum of the checkpoint times doesn't match the final time
+        let req = test::TestRequest::post()
+            .uri("/player/finished/validate")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: format!("map_{map_id}_uid"),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 0,
+                cps: vec![4000, 5000],
+            })
+            .to_request();
+
+        let res = test::try_call_service(&app, req).await;
+        let err = res.err().expect("Request should return error");
+        let traced_err = err
+            .as_error::<TracedError>()
+            .expect("Returned error should be a traced error");
+        assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+        // Invalid times
+        assert_eq!(traced_err.r#type, Some(313));
+
+        // The valid payload returns the would-be result
+        let req = test::TestRequest::post()
+            .uri("/player/finished/validate")
+            .insert_header(("PlayerLogin", "player_login"))
+            .set_json(Request {
+                map_uid: format!("map_{map_id}_uid"),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 0,
+                cps: vec![4000, 6000],
+            })
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            Response {
+                has_improved: true,
+                old: 10000,
+                new: 10000,
+                current_rank: 1,
+                old_rank: -1,
+            }
+        );
+
+        // No record was saved
+        let record_count = records::Entity::find().count(&db.sql_conn).await?;
+        assert_eq!(record_count, 0);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn validate_finished_syncs_leaderboard() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        cps_number: Set(Some(1)),
+        ..Default::default()
+    };
+
+    // The record is only in the database, the Redis leaderboard of the map doesn't exist yet
+    let record = records::ActiveModel {
+        record_player_id: Set(2),
+        map_id: Set(map_id),
+        time: Set(8000),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert(record).exec(&db.sql_conn).await?;
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/player/finished/validate")
+            .insert_header(("PlayerLogin", "player_1_login"))
+            .set_json(Request {
+                map_uid: format!("map_{map_id}_uid"),
+                time: 10000,
+                flags: Some(682),
+                respawn_count: 0,
+                cps: vec![4000, 6000],
+            })
+            .to_request();
+
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            Response {
+                has_improved: true,
+                old: 10000,
+                new: 10000,
+                current_rank: 2,
+                old_rank: -1,
+            }
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,39 @@
+use entity::players;
+use records_lib::player;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn resolve_known_and_unknown_logins() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let resolved = player::resolve_logins(
+            &db.sql_conn,
+            &["player_1_login", "unknown_login", "player_3_login"],
+        )
+        .await?;
+
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved.get("player_1_login").map(|p| p.id), Some(1));
+        assert_eq!(resolved.get("player_3_login").map(|p| p.id), Some(3));
+        assert!(!resolved.contains_key("unknown_login"));
+
+        let resolved = player::resolve_logins(&db.sql_conn, &[]).await?;
+        assert!(resolved.is_empty());
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,70 @@
+use entity::{maps, players, records};
+use records_lib::player;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn total_best_time_sums_maps() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (map index, player_id, time, is_hidden)
+    // Only the best time of the player 1 on each map counts, and their hidden record is ignored
+    let records_info = [
+        (0, 1, 5000, false),
+        (0, 1, 4000, false),
+        (1, 1, 7000, false),
+        (1, 1, 1000, true),
+        (0, 2, 2000, false),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(i, player_id, time, is_hidden)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_ids[*i]),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            is_hidden: Set(*is_hidden),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let total = player::total_best_time(&db.sql_conn, 1).await?;
+        assert_eq!(total, 11000);
+
+        // The player 3 doesn't have any record
+        let total = player::total_best_time(&db.sql_conn, 3).await?;
+        assert_eq!(total, 0);
+
+        anyhow::Ok(())
+    })
+    .await
+}
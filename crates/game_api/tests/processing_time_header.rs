@@ -0,0 +1,37 @@
+use actix_web::test;
+use game_api_lib::configure::PROCESSING_TIME_HEADER;
+
+mod base;
+
+#[tokio::test]
+async fn processing_time_header_present() -> anyhow::Result<()> {
+    // This is the only test of this file, so the environment isn't shared with other tests
+    // SAFETY: no other thread is reading the environment at this point
+    unsafe {
+        std::env::set_var("RECORDS_API_PROCESSING_TIME_HEADER", "true");
+    }
+
+    base::with_db(async |db| {
+        let app = base::get_app(db).await;
+
+        let req = test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(serde_json::json!({ "query": "{ __typename }" }))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+
+        let processing_time = res
+            .headers()
+            .get(PROCESSING_TIME_HEADER)
+            .expect("the response should have the processing time header")
+            .to_str()?;
+        assert!(
+            processing_time.parse::<u64>().is_ok(),
+            "the processing time should be numeric, got {processing_time:?}"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
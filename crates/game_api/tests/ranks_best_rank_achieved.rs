@@ -0,0 +1,78 @@
+use entity::{maps, players, records};
+use itertools::iproduct;
+use records_lib::ranks;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn best_rank_achieved_on_map() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The player 1 is ranked 4th on the first map, 2nd on the second one, and 3rd on the last one
+    let player_1_ranks = [4, 2, 3];
+    let records = iproduct!(map_ids.iter().enumerate(), 1..=4).map(|((i, map_id), player_id)| {
+        let rank = if player_id == 1 {
+            player_1_ranks[i]
+        } else if player_id <= player_1_ranks[i] {
+            player_id - 1
+        } else {
+            player_id
+        };
+        records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(1000 * rank as i32),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    });
+
+    let player_5 = players::ActiveModel {
+        id: Set(5),
+        login: Set("player_5_login".to_owned()),
+        name: Set("player_5_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player_5).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let best = ranks::best_rank_achieved(&db.sql_conn, &db.redis_pool, 1).await?;
+        assert_eq!(best, Some((map_ids[1], 2)));
+
+        // The fifth player doesn't have any record
+        let best = ranks::best_rank_achieved(&db.sql_conn, &db.redis_pool, 5).await?;
+        assert_eq!(best, None);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,110 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use entity::{maps, players, records};
+use game_api_lib::TracedError;
+use records_lib::ranks;
+use sea_orm::{ActiveValue::Set, EntityTrait, PaginatorTrait as _};
+
+mod base;
+
+#[derive(serde::Deserialize)]
+struct Response {
+    rank: i32,
+}
+
+#[tokio::test]
+async fn hypothetical_rank_between_records() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = [(1, 4000), (2, 5000), (3, 6000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // The time is interposed between the first and the second records
+        let rank = ranks::hypothetical_rank(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            4500,
+            Default::default(),
+        )
+        .await?;
+        assert_eq!(rank, 2);
+
+        // An equal time shares the rank of the existing one
+        let rank = ranks::hypothetical_rank(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            5000,
+            Default::default(),
+        )
+        .await?;
+        assert_eq!(rank, 2);
+
+        let app = base::get_app(db.clone()).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/map/map_{map_id}_uid/rank-for?time=5500"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        let body = base::try_from_slice::<Response>(&body)?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body.rank, 3);
+
+        // A time that isn't positive is rejected
+        for time in [0, -1000] {
+            let req = test::TestRequest::get()
+                .uri(&format!("/map/map_{map_id}_uid/rank-for?time={time}"))
+                .to_request();
+            let res = test::try_call_service(&app, req).await;
+            let err = res.err().expect("Request should return error");
+            let traced_err = err
+                .as_error::<TracedError>()
+                .expect("Returned error should be a traced error");
+            assert_eq!(traced_err.status_code, Some(StatusCode::BAD_REQUEST));
+            // Invalid times
+            assert_eq!(traced_err.r#type, Some(313));
+        }
+
+        // Nothing was saved
+        let record_count = records::Entity::find().count(&db.sql_conn).await?;
+        assert_eq!(record_count, 3);
+
+        anyhow::Ok(())
+    })
+    .await
+}
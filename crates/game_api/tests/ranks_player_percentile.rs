@@ -0,0 +1,76 @@
+use entity::{maps, players, records};
+use itertools::iproduct;
+use records_lib::ranks;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn player_percentile_aggregates_maps() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // Player 1 is the fastest on the first map, and the slowest on the second one
+    let records = iproduct!(map_ids.iter().enumerate(), 1..=4).map(|((i, map_id), player_id)| {
+        let time = if i == 0 {
+            1000 * player_id as i32
+        } else {
+            10000 - 1000 * player_id as i32
+        };
+        records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    });
+
+    let player_5 = players::ActiveModel {
+        id: Set(5),
+        login: Set("player_5_login".to_owned()),
+        name: Set("player_5_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player_5).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // 100% of the players are ranked at or below them on the first map, and 25% on the second
+        let percentile = ranks::player_percentile(&db.sql_conn, &db.redis_pool, 1).await?;
+        assert_eq!(percentile, Some(62.5));
+
+        // The fifth player doesn't have any record
+        let percentile = ranks::player_percentile(&db.sql_conn, &db.redis_pool, 5).await?;
+        assert_eq!(percentile, None);
+
+        anyhow::Ok(())
+    })
+    .await
+}
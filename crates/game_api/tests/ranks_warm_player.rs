@@ -0,0 +1,63 @@
+use entity::{maps, players, records};
+use itertools::iproduct;
+use records_lib::ranks;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn warm_player_caches_leaderboards() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // Player 1 is always the slowest one
+    let records =
+        iproduct!(map_ids.iter(), 1..=3).map(|(map_id, player_id)| records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(10000 - player_id as i32 * 1000),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let warmed = ranks::warm_player(&db.sql_conn, &db.redis_pool, 1).await?;
+        assert_eq!(warmed, map_ids.len());
+
+        // The ranks are read from Redis only, so they're only right if the leaderboards are cached
+        let mut redis_conn = db.redis_pool.get().await?;
+        for map_id in &map_ids {
+            let rank = ranks::get_rank(&mut redis_conn, *map_id, 9000, Default::default()).await?;
+            assert_eq!(rank, 3);
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
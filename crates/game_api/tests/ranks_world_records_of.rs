@@ -0,0 +1,135 @@
+use entity::{banishments, maps, players, records};
+use itertools::iproduct;
+use records_lib::ranks;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn world_records_held_on_maps() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let mut map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    map_ids.sort_unstable();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The player 1 is the fastest on the first and the last maps, and the player 2 is the
+    // fastest on the second one
+    let records = iproduct!(map_ids.iter().enumerate(), 1..=3).map(|((i, map_id), player_id)| {
+        let time = match (i, player_id) {
+            (1, 1) => 3000,
+            (1, 2) => 1000,
+            _ => 1000 * player_id as i32,
+        };
+        records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let world_records = ranks::world_records_of(&db.sql_conn, &db.redis_pool, 1).await?;
+        assert_eq!(world_records, [map_ids[0], map_ids[2]]);
+
+        let world_records = ranks::world_records_of(&db.sql_conn, &db.redis_pool, 2).await?;
+        assert_eq!(world_records, [map_ids[1]]);
+
+        // The player 3 is never the fastest
+        let world_records = ranks::world_records_of(&db.sql_conn, &db.redis_pool, 3).await?;
+        assert!(world_records.is_empty());
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn banned_player_has_no_world_record() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The player 2 has the best time, but is banned
+    let records = [(1, 5000), (2, 4000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let ban = banishments::ActiveModel {
+        date_ban: Set(chrono::Utc::now().naive_utc()),
+        duration: Set(None),
+        was_reprieved: Set(0),
+        reason: Set("cheating".to_owned()),
+        player_id: Set(Some(2)),
+        banished_by: Set(Some(1)),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        banishments::Entity::insert(ban).exec(&db.sql_conn).await?;
+
+        let world_records = ranks::world_records_of(&db.sql_conn, &db.redis_pool, 2).await?;
+        assert!(world_records.is_empty());
+
+        let percentile = ranks::player_percentile(&db.sql_conn, &db.redis_pool, 2).await?;
+        assert_eq!(percentile, None);
+
+        let world_records = ranks::world_records_of(&db.sql_conn, &db.redis_pool, 1).await?;
+        assert_eq!(world_records, [map_id]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
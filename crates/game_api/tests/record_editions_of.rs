@@ -0,0 +1,82 @@
+use entity::{event, event_edition, event_edition_records, maps, players, records};
+use records_lib::record;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn editions_of_records() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let editions = (1..=2).map(|edition_id| event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(edition_id),
+        name: Set(format!("event_1_{edition_id}_name")),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    });
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = (1..=3).map(|record_id| records::ActiveModel {
+        record_id: Set(record_id),
+        record_player_id: Set(1),
+        map_id: Set(map_id),
+        time: Set(5000 + record_id as i32),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    // The third record isn't made in any edition
+    let event_records = (1..=2).map(|id| event_edition_records::ActiveModel {
+        record_id: Set(id),
+        event_id: Set(1),
+        edition_id: Set(id),
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert_many(editions)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        assert_eq!(record::editions_of(&db.sql_conn, 1).await?, [(1, 1)]);
+        assert_eq!(record::editions_of(&db.sql_conn, 2).await?, [(1, 2)]);
+        assert!(record::editions_of(&db.sql_conn, 3).await?.is_empty());
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,69 @@
+use entity::{maps, players, records};
+use records_lib::{map, player, record};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+mod base;
+
+#[tokio::test]
+async fn get_full_matches_separate_fetches() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The record isn't made by the author of the map
+    let record = records::ActiveModel {
+        record_player_id: Set(2),
+        map_id: Set(map_id),
+        time: Set(10000),
+        respawn_count: Set(3),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        let record_id = records::Entity::insert(record)
+            .exec(&db.sql_conn)
+            .await?
+            .last_insert_id;
+
+        let (full_record, full_map, full_player) = record::get_full(&db.sql_conn, record_id)
+            .await?
+            .expect("the record should exist");
+
+        let expected_record = records::Entity::find_by_id(record_id)
+            .one(&db.sql_conn)
+            .await?
+            .expect("the record should exist");
+        let expected_map = map::get_map_from_id(&db.sql_conn, expected_record.map_id).await?;
+        let expected_player =
+            player::get_player_from_id(&db.sql_conn, expected_record.record_player_id).await?;
+
+        assert_eq!(full_record, expected_record);
+        assert_eq!(full_map, expected_map);
+        assert_eq!(full_player, expected_player);
+
+        let missing = record::get_full(&db.sql_conn, record_id + 1).await?;
+        assert!(missing.is_none());
+
+        anyhow::Ok(())
+    })
+    .await
+}
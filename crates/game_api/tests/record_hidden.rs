@@ -0,0 +1,287 @@
+use entity::{
+    event, event_edition, event_edition_maps, event_edition_records, global_records, maps, players,
+    records,
+};
+use records_lib::{leaderboard, opt_event::OptEvent, ranks};
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+mod base;
+
+#[tokio::test]
+async fn hidden_record_dropped_from_leaderboard() -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The first player has the best time
+    let records = [(1, 5000), (2, 6000), (3, 7000)].map(|(player_id, time)| records::ActiveModel {
+        record_id: Set(player_id),
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let found = ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, 1, true).await?;
+        assert!(found);
+
+        let lb = leaderboard::leaderboard(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            None,
+            None,
+            Default::default(),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter()
+                .map(|row| (row.rank, row.login.as_str(), row.time)),
+            [(1, "player_2_login", 6000), (2, "player_3_login", 7000)],
+        );
+
+        let rank = ranks::get_rank_from_db(&db.sql_conn, map_id, 6000, Default::default()).await?;
+        assert_eq!(rank, 1);
+
+        // The record is still in the database
+        let record = records::Entity::find_by_id(1u32)
+            .one(&db.sql_conn)
+            .await?
+            .expect("hidden record should still exist");
+        assert!(record.is_hidden);
+
+        ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, 1, false).await?;
+
+        let lb = leaderboard::leaderboard(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            None,
+            None,
+            Default::default(),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter()
+                .map(|row| (row.rank, row.login.as_str(), row.time)),
+            [
+                (1, "player_1_login", 5000),
+                (2, "player_2_login", 6000),
+                (3, "player_3_login", 7000),
+            ],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn hidden_best_record_replaced_in_view() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = [(1, 6000), (2, 5000)].map(|(record_id, time)| records::ActiveModel {
+        record_id: Set(record_id),
+        record_player_id: Set(1),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, 2, true).await?;
+
+        // The previous record of the player becomes their best one
+        let view_records = global_records::Entity::find()
+            .filter(global_records::Column::MapId.eq(map_id))
+            .all(&db.sql_conn)
+            .await?;
+        itertools::assert_equal(
+            view_records
+                .iter()
+                .map(|record| (record.record_id, record.time, record.is_hidden)),
+            [(1, 6000, false)],
+        );
+
+        let mismatch = records_lib::map::record_count_mismatch(&db.sql_conn, map_id).await?;
+        assert_eq!(mismatch, None);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn hidden_event_record_dropped_from_edition_leaderboard() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The edition ID differs from the event ID
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(2),
+        name: Set("event_1_2_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let event_map = event_edition_maps::ActiveModel {
+        event_id: Set(1),
+        edition_id: Set(2),
+        map_id: Set(map_id),
+        order: Set(0),
+        ..Default::default()
+    };
+
+    let records = [(1, 5000), (2, 6000)].map(|(player_id, time)| records::ActiveModel {
+        record_id: Set(player_id),
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let event_records = (1..=2).map(|record_id| event_edition_records::ActiveModel {
+        record_id: Set(record_id),
+        event_id: Set(1),
+        edition_id: Set(2),
+    });
+
+    base::with_db(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert(event_map)
+            .exec(&db.sql_conn)
+            .await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let found = ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, 1, true).await?;
+        assert!(found);
+
+        let event = event::Entity::find_by_id(1u32)
+            .one(&db.sql_conn)
+            .await?
+            .expect("event should exist");
+        let edition = event_edition::Entity::find_by_id((2u32, 1u32))
+            .one(&db.sql_conn)
+            .await?
+            .expect("edition should exist");
+
+        let lb = leaderboard::leaderboard(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            None,
+            None,
+            OptEvent::new(&event, &edition),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter()
+                .map(|row| (row.rank, row.login.as_str(), row.time)),
+            [(1, "player_2_login", 6000)],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn hide_unknown_record() -> anyhow::Result<()> {
+    base::with_db(async |db| {
+        let found = ranks::set_record_hidden(&db.sql_conn, &db.redis_pool, 1, true).await?;
+        assert!(!found);
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use deadpool_redis::redis;
+use mkenv::prelude::*;
+use records_lib::{RedisConnection, RedisUrlEnv, pool::get_redis_pool};
+
+async fn client_id(conn: &mut RedisConnection) -> anyhow::Result<u64> {
+    let id = redis::cmd("CLIENT").arg("ID").query_async(conn).await?;
+    Ok(id)
+}
+
+#[tokio::test]
+async fn stale_connection_replaced_on_acquire() -> anyhow::Result<()> {
+    test_env::init_env()?;
+    let env = RedisUrlEnv::define();
+
+    let pool = get_redis_pool(env.redis_url.get(), Duration::from_millis(100))?;
+
+    let first_id = {
+        let mut conn = pool.get().await?;
+        client_id(&mut conn).await?
+    };
+
+    // The connection is reused if it was used recently
+    let second_id = {
+        let mut conn = pool.get().await?;
+        client_id(&mut conn).await?
+    };
+    assert_eq!(first_id, second_id);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The connection is now stale, so it's replaced by a new one
+    let third_id = {
+        let mut conn = pool.get().await?;
+        client_id(&mut conn).await?
+    };
+    assert_ne!(second_id, third_id);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use entity::{maps, players, records};
+use records_lib::sync;
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+mod base;
+
+fn record(map_id: u32, time: i32) -> records::ActiveModel {
+    records::ActiveModel {
+        record_player_id: Set(1),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn savepoint_rollback_keeps_outer_transaction() -> anyhow::Result<()> {
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    base::with_db(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+
+        let result = sync::transaction(&db.sql_conn, async |txn| {
+            records::Entity::insert(record(map_id, 10000))
+                .exec(txn)
+                .await?;
+
+            let result = sync::savepoint(txn, async |txn| {
+                records::Entity::insert(record(map_id, 5000))
+                    .exec(txn)
+                    .await?;
+                anyhow::Result::<()>::Err(anyhow::anyhow!("inner operation failed"))
+            })
+            .await?;
+
+            anyhow::Ok(result)
+        })
+        .await?;
+
+        assert!(result.is_rolled_back());
+
+        let times = records::Entity::find()
+            .filter(records::Column::MapId.eq(map_id))
+            .all(&db.sql_conn)
+            .await?
+            .into_iter()
+            .map(|record| record.time)
+            .collect::<Vec<_>>();
+
+        assert_eq!(times, [10000]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
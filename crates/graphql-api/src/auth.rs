@@ -0,0 +1,79 @@
+use async_graphql::Context;
+use futures::{FutureExt as _, future::BoxFuture};
+use records_lib::error::RecordsResult;
+use tokio::sync::OnceCell;
+
+use crate::error::{ApiGqlError, GqlResult};
+
+/// The authentication state of the player who sent a GraphQL request, as an admin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAuth {
+    /// The request doesn't contain valid authentication headers.
+    Unauthorized,
+    /// The player is authenticated, but isn't allowed to perform admin actions.
+    Forbidden,
+    /// The player is authenticated as an admin.
+    Admin {
+        /// The ID of the admin player.
+        player_id: u32,
+    },
+}
+
+impl AdminAuth {
+    /// Returns the ID of the admin who sent the request, or the corresponding error.
+    ///
+    /// The authentication is checked the first time this is called during the request.
+    /// When the [`LazyAdminAuth`] request data is missing, the request is considered
+    /// unauthenticated.
+    pub(crate) async fn require(ctx: &Context<'_>) -> GqlResult<u32> {
+        let auth = match ctx.data_opt::<LazyAdminAuth>() {
+            Some(auth) => auth.get().await?,
+            None => AdminAuth::Unauthorized,
+        };
+
+        match auth {
+            AdminAuth::Admin { player_id } => Ok(player_id),
+            AdminAuth::Forbidden => Err(ApiGqlError::from_forbidden_error()),
+            AdminAuth::Unauthorized => Err(ApiGqlError::from_unauthorized_error()),
+        }
+    }
+}
+
+type AdminAuthCheck = dyn Fn() -> BoxFuture<'static, RecordsResult<AdminAuth>> + Send + Sync;
+
+/// The admin authentication of a GraphQL request, provided to the schema as request data.
+///
+/// The check is only run by the operations that require an admin, so the other requests
+/// don't pay for it. Its result is then reused for the rest of the request.
+pub struct LazyAdminAuth {
+    check: Box<AdminAuthCheck>,
+    auth: OnceCell<AdminAuth>,
+}
+
+impl LazyAdminAuth {
+    /// Returns the admin authentication of a request, checked with the provided function
+    /// when it's first required.
+    pub fn new<F, Fut>(check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RecordsResult<AdminAuth>> + Send + 'static,
+    {
+        Self {
+            check: Box::new(move || check().boxed()),
+            auth: OnceCell::new(),
+        }
+    }
+
+    async fn get(&self) -> RecordsResult<AdminAuth> {
+        self.auth.get_or_try_init(|| (self.check)()).await.copied()
+    }
+}
+
+impl From<AdminAuth> for LazyAdminAuth {
+    fn from(auth: AdminAuth) -> Self {
+        Self {
+            check: Box::new(move || futures::future::ready(Ok(auth)).boxed()),
+            auth: OnceCell::new_with(Some(auth)),
+        }
+    }
+}
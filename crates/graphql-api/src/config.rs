@@ -1,6 +1,6 @@
 use hmac::Hmac;
 use sha2::{Sha224, digest::KeyInit};
-use std::{error::Error, fmt, sync::OnceLock};
+use std::{error::Error, fmt, sync::OnceLock, time::Duration};
 
 use mkenv::{ConfigDescriptor, exec::ConfigInitializer};
 
@@ -57,6 +57,40 @@ mkenv::make_config! {
             ],
         },
 
+        pub(crate) records_default_limit: {
+            var_name: "GQL_API_RECORDS_DEFAULT_LIMIT",
+            layers: [
+                parsed<Option<usize>>(|input| input.parse().map(Some).map_err(From::from)),
+                or_default(),
+            ],
+        },
+
+        pub(crate) players_default_limit: {
+            var_name: "GQL_API_PLAYERS_DEFAULT_LIMIT",
+            layers: [
+                parsed<Option<usize>>(|input| input.parse().map(Some).map_err(From::from)),
+                or_default(),
+            ],
+        },
+
+        pub(crate) maps_default_limit: {
+            var_name: "GQL_API_MAPS_DEFAULT_LIMIT",
+            layers: [
+                parsed<Option<usize>>(|input| input.parse().map(Some).map_err(From::from)),
+                or_default(),
+            ],
+        },
+
+        pub(crate) query_timeout: {
+            var_name: "GQL_API_QUERY_TIMEOUT_SECONDS",
+            layers: [
+                parsed<Duration>(|input| {
+                    input.parse().map(Duration::from_secs).map_err(From::from)
+                }),
+                or_default_val(|| Duration::from_secs(30)),
+            ],
+        },
+
         pub(crate) cursor_secret_key: { SecretKey },
     }
 }
@@ -102,3 +136,75 @@ pub fn init_config() -> Result<(), InitError> {
 pub(crate) fn config() -> &'static ApiConfig {
     CONFIG.get().unwrap()
 }
+
+/// Returns the default page size of the records connections.
+///
+/// It falls back to the global default page size if it isn't configured.
+pub(crate) fn records_default_limit() -> usize {
+    let config = config();
+    config
+        .records_default_limit
+        .get()
+        .unwrap_or_else(|| config.cursor_default_limit.get())
+}
+
+/// Returns the default page size of the players connections.
+///
+/// It falls back to the global default page size if it isn't configured.
+pub(crate) fn players_default_limit() -> usize {
+    let config = config();
+    config
+        .players_default_limit
+        .get()
+        .unwrap_or_else(|| config.cursor_default_limit.get())
+}
+
+/// Returns the default page size of the maps connections.
+///
+/// It falls back to the global default page size if it isn't configured.
+pub(crate) fn maps_default_limit() -> usize {
+    let config = config();
+    config
+        .maps_default_limit
+        .get()
+        .unwrap_or_else(|| config.cursor_default_limit.get())
+}
+
+/// Returns the non-secret values of the configuration, paired with the name of their
+/// environment variable.
+///
+/// The cursor secret key isn't included.
+pub fn config_dump() -> Vec<(&'static str, String)> {
+    let config = config();
+    vec![
+        (
+            "GQL_API_CURSOR_MAX_LIMIT",
+            config.cursor_max_limit.get().to_string(),
+        ),
+        (
+            "GQL_API_CURSOR_DEFAULT_LIMIT",
+            config.cursor_default_limit.get().to_string(),
+        ),
+        (
+            "GQL_API_RECORDS_DEFAULT_LIMIT",
+            records_default_limit().to_string(),
+        ),
+        (
+            "GQL_API_PLAYERS_DEFAULT_LIMIT",
+            players_default_limit().to_string(),
+        ),
+        (
+            "GQL_API_MAPS_DEFAULT_LIMIT",
+            maps_default_limit().to_string(),
+        ),
+        (
+            "GQL_API_QUERY_TIMEOUT_SECONDS",
+            config.query_timeout.get().as_secs().to_string(),
+        ),
+    ]
+}
+
+/// Returns the maximum execution time of a GraphQL request.
+pub fn query_timeout() -> Duration {
+    config().query_timeout.get()
+}
@@ -218,6 +218,101 @@ where
     }
 }
 
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordTimeCursor<T = u32> {
+    pub time: i32,
+    pub data: T,
+}
+
+impl<T> CursorType for RecordTimeCursor<T>
+where
+    T: serde::Serialize + for<'de> serde::Deserialize<'de>,
+{
+    type Error = CursorDecodeError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        decode_cursor("record_time", s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        encode_cursor("record_time", self)
+    }
+}
+
+impl<T> IntoExprTuple for &RecordTimeCursor<T>
+where
+    T: Into<SimpleExpr> + Clone,
+{
+    fn into_expr_tuple(self) -> ExprTuple {
+        (self.time, self.data.clone()).into_expr_tuple()
+    }
+}
+
+impl<T> IntoValueTuple for &RecordTimeCursor<T>
+where
+    T: Into<Value> + Clone,
+{
+    fn into_value_tuple(self) -> ValueTuple {
+        (self.time, self.data.clone()).into_value_tuple()
+    }
+}
+
+/// The value of a record for one of the keys it's sorted by.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SortKeyValue {
+    Map(u32),
+    Time(i32),
+    Date(#[serde(with = "datetime_serde")] DateTime<Utc>),
+}
+
+impl From<&SortKeyValue> for SimpleExpr {
+    fn from(value: &SortKeyValue) -> Self {
+        match value {
+            SortKeyValue::Map(map_id) => (*map_id).into(),
+            SortKeyValue::Time(time) => (*time).into(),
+            SortKeyValue::Date(date) => (*date).into(),
+        }
+    }
+}
+
+/// The cursor of the records sorted by many keys, holding the value of each key in the order
+/// of the sort.
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordSortCursor<T = u32> {
+    pub keys: Vec<SortKeyValue>,
+    pub data: T,
+}
+
+impl<T> CursorType for RecordSortCursor<T>
+where
+    T: serde::Serialize + for<'de> serde::Deserialize<'de>,
+{
+    type Error = CursorDecodeError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        decode_cursor("record_sort", s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        encode_cursor("record_sort", self)
+    }
+}
+
+impl<T> IntoExprTuple for &RecordSortCursor<T>
+where
+    T: Into<SimpleExpr> + Clone,
+{
+    fn into_expr_tuple(self) -> ExprTuple {
+        ExprTuple::Many(
+            self.keys
+                .iter()
+                .map(SimpleExpr::from)
+                .chain([self.data.clone().into()])
+                .collect(),
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TextCursor<T = u32> {
     pub text: String,
@@ -296,6 +391,45 @@ where
     }
 }
 
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordCountCursor<T = u32> {
+    pub record_count: u64,
+    pub data: T,
+}
+
+impl<T> CursorType for RecordCountCursor<T>
+where
+    T: serde::Serialize + for<'de> serde::Deserialize<'de>,
+{
+    type Error = CursorDecodeError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        decode_cursor("record_count", s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        encode_cursor("record_count", self)
+    }
+}
+
+impl<T> IntoExprTuple for &RecordCountCursor<T>
+where
+    T: Into<SimpleExpr> + Clone,
+{
+    fn into_expr_tuple(self) -> ExprTuple {
+        (self.record_count, self.data.clone()).into_expr_tuple()
+    }
+}
+
+impl<T> IntoValueTuple for &RecordCountCursor<T>
+where
+    T: Into<Value> + Clone,
+{
+    fn into_value_tuple(self) -> ValueTuple {
+        (self.record_count, self.data.clone()).into_value_tuple()
+    }
+}
+
 pub struct ConnectionParameters<C = ID> {
     pub before: Option<C>,
     pub after: Option<C>,
@@ -325,11 +459,13 @@ mod tests {
 
     use crate::{
         config::InitError,
-        cursors::{F64Cursor, TextCursor},
+        cursors::{F64Cursor, RecordCountCursor, TextCursor},
         error::{CursorDecodeError, CursorDecodeErrorKind},
     };
 
-    use super::{RecordDateCursor, RecordRankCursor};
+    use super::{
+        RecordDateCursor, RecordRankCursor, RecordSortCursor, RecordTimeCursor, SortKeyValue,
+    };
 
     fn setup() {
         match crate::init_config() {
@@ -427,6 +563,18 @@ mod tests {
         test_decode_cursor_errors::<RecordRankCursor>();
     }
 
+    #[test]
+    fn decode_time_cursor_errors() {
+        setup();
+        test_decode_cursor_errors::<RecordTimeCursor>();
+    }
+
+    #[test]
+    fn decode_sort_cursor_errors() {
+        setup();
+        test_decode_cursor_errors::<RecordSortCursor>();
+    }
+
     #[test]
     fn decode_text_cursor_errors() {
         setup();
@@ -439,6 +587,12 @@ mod tests {
         test_decode_cursor_errors::<F64Cursor>();
     }
 
+    #[test]
+    fn decode_record_count_cursor_errors() {
+        setup();
+        test_decode_cursor_errors::<RecordCountCursor>();
+    }
+
     #[test]
     fn encode_rank_cursor() {
         setup();
@@ -472,6 +626,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_time_cursor() {
+        setup();
+        test_encode_cursor(
+            &RecordTimeCursor {
+                time: 1000,
+                data: 24,
+            },
+            r#"record_time:{"time":1000,"data":24}"#,
+        );
+    }
+
+    #[test]
+    fn time_cursor_round_trip() {
+        setup();
+        test_cursor_round_trip(
+            &RecordTimeCursor {
+                time: 2000,
+                data: 34,
+            },
+            &RecordTimeCursor {
+                time: 2000,
+                data: 34,
+            },
+        );
+    }
+
+    #[test]
+    fn encode_sort_cursor() {
+        setup();
+        test_encode_cursor(
+            &RecordSortCursor {
+                keys: vec![
+                    SortKeyValue::Map(12),
+                    SortKeyValue::Date(DateTime::from_timestamp_millis(26).unwrap()),
+                ],
+                data: 24,
+            },
+            r#"record_sort:{"keys":[{"Map":12},{"Date":26}],"data":24}"#,
+        );
+    }
+
+    #[test]
+    fn sort_cursor_round_trip() {
+        setup();
+
+        let now = Utc::now();
+        test_cursor_round_trip(
+            &RecordSortCursor {
+                keys: vec![SortKeyValue::Time(1000), SortKeyValue::Date(now)],
+                data: 34,
+            },
+            &RecordSortCursor {
+                keys: vec![
+                    SortKeyValue::Time(1000),
+                    SortKeyValue::Date(now.trunc_subsecs(3)),
+                ],
+                data: 34,
+            },
+        );
+    }
+
     #[test]
     fn encode_text_cursor() {
         setup();
@@ -530,4 +746,31 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn encode_record_count_cursor() {
+        setup();
+        test_encode_cursor(
+            &RecordCountCursor {
+                record_count: 42,
+                data: 2445,
+            },
+            r#"record_count:{"record_count":42,"data":2445}"#,
+        );
+    }
+
+    #[test]
+    fn record_count_cursor_round_trip() {
+        setup();
+        test_cursor_round_trip(
+            &RecordCountCursor {
+                record_count: 17,
+                data: 123,
+            },
+            &RecordCountCursor {
+                record_count: 17,
+                data: 123,
+            },
+        );
+    }
 }
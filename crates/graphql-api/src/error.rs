@@ -1,4 +1,4 @@
-use std::{error::Error, fmt, sync::Arc};
+use std::{error::Error, fmt, sync::Arc, time::Duration};
 
 use records_lib::error::RecordsError;
 use sha2::digest::MacError;
@@ -85,6 +85,9 @@ pub enum ApiGqlErrorKind {
     RecordNotFound { record_id: u32 },
     MapNotFound { map_uid: String },
     PlayerNotFound { login: String },
+    Unauthorized,
+    Forbidden,
+    Timeout { timeout: Duration },
 }
 
 impl fmt::Display for ApiGqlErrorKind {
@@ -106,6 +109,15 @@ impl fmt::Display for ApiGqlErrorKind {
             ApiGqlErrorKind::PlayerNotFound { login } => {
                 write!(f, "player with login `{login}` not found")
             }
+            ApiGqlErrorKind::Unauthorized => f.write_str("unauthorized"),
+            ApiGqlErrorKind::Forbidden => {
+                f.write_str("you don't have the permission to perform this action")
+            }
+            ApiGqlErrorKind::Timeout { timeout } => write!(
+                f,
+                "the request took longer than the limit of {}ms to execute",
+                timeout.as_millis()
+            ),
         }
     }
 }
@@ -119,6 +131,9 @@ impl std::error::Error for ApiGqlErrorKind {
             ApiGqlErrorKind::RecordNotFound { .. } => None,
             ApiGqlErrorKind::MapNotFound { .. } => None,
             ApiGqlErrorKind::PlayerNotFound { .. } => None,
+            ApiGqlErrorKind::Unauthorized => None,
+            ApiGqlErrorKind::Forbidden => None,
+            ApiGqlErrorKind::Timeout { .. } => None,
         }
     }
 }
@@ -164,6 +179,24 @@ impl ApiGqlError {
             inner: Arc::new(ApiGqlErrorKind::PlayerNotFound { login }),
         }
     }
+
+    pub(crate) fn from_unauthorized_error() -> Self {
+        Self {
+            inner: Arc::new(ApiGqlErrorKind::Unauthorized),
+        }
+    }
+
+    pub(crate) fn from_forbidden_error() -> Self {
+        Self {
+            inner: Arc::new(ApiGqlErrorKind::Forbidden),
+        }
+    }
+
+    pub(crate) fn from_timeout_error(timeout: Duration) -> Self {
+        Self {
+            inner: Arc::new(ApiGqlErrorKind::Timeout { timeout }),
+        }
+    }
 }
 
 impl ApiGqlError {
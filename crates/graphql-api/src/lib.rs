@@ -1,5 +1,7 @@
+pub mod auth;
 pub mod error;
 pub mod loaders;
+pub mod mutations;
 pub mod objects;
 pub mod schema;
 pub mod subscriptions;
@@ -0,0 +1,86 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::dataloader::Loader;
+use entity::event_edition_maps;
+use records_lib::event;
+use sea_orm::{
+    ColumnTrait as _, Condition, DbConn, DbErr, EntityTrait as _, QueryFilter as _,
+    QuerySelect as _,
+};
+
+use crate::objects::medal_times::MedalTimes;
+
+/// Loads the medal times of the maps of event editions, with keys made of the event ID, the
+/// edition ID, and the map ID.
+pub struct MedalTimesLoader(pub DbConn);
+
+type Row = (
+    u32,
+    u32,
+    u32,
+    Option<i32>,
+    Option<i32>,
+    Option<i32>,
+    Option<i32>,
+);
+
+impl Loader<(u32, u32, u32)> for MedalTimesLoader {
+    type Value = MedalTimes;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[(u32, u32, u32)],
+    ) -> Result<HashMap<(u32, u32, u32), Self::Value>, Self::Error> {
+        let condition = keys.iter().fold(
+            Condition::any(),
+            |condition, (event_id, edition_id, map_id)| {
+                condition.add(
+                    event_edition_maps::Column::EventId
+                        .eq(*event_id)
+                        .and(event_edition_maps::Column::EditionId.eq(*edition_id))
+                        .and(event_edition_maps::Column::MapId.eq(*map_id)),
+                )
+            },
+        );
+
+        let hashmap = event_edition_maps::Entity::find()
+            .filter(condition)
+            .select_only()
+            .columns([
+                event_edition_maps::Column::EventId,
+                event_edition_maps::Column::EditionId,
+                event_edition_maps::Column::MapId,
+                event_edition_maps::Column::BronzeTime,
+                event_edition_maps::Column::SilverTime,
+                event_edition_maps::Column::GoldTime,
+                event_edition_maps::Column::AuthorTime,
+            ])
+            .into_tuple::<Row>()
+            .all(&self.0)
+            .await?
+            .into_iter()
+            .filter_map(
+                |(
+                    event_id,
+                    edition_id,
+                    map_id,
+                    bronze_time,
+                    silver_time,
+                    gold_time,
+                    author_time,
+                )| {
+                    let medal_times = event::MedalTimes::from_optional_times((
+                        bronze_time,
+                        silver_time,
+                        gold_time,
+                        author_time,
+                    ))?;
+                    Some(((event_id, edition_id, map_id), medal_times.into()))
+                },
+            )
+            .collect();
+
+        Ok(hashmap)
+    }
+}
@@ -1,4 +1,6 @@
 pub mod event;
 pub mod event_category;
 pub mod map;
+pub mod medal_times;
 pub mod player;
+pub mod record_editions;
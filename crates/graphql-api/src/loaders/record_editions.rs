@@ -0,0 +1,52 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::dataloader::Loader;
+use entity::{event, event_edition, event_edition_records};
+use sea_orm::{
+    ColumnTrait as _, DbConn, DbErr, EntityTrait as _, QueryFilter as _, QueryOrder as _,
+};
+
+use crate::objects::event_edition::EventEdition;
+
+/// Loads the event editions each record counts toward, with the record IDs as keys.
+pub struct RecordEditionsLoader(pub DbConn);
+
+impl Loader<u32> for RecordEditionsLoader {
+    type Value = Vec<EventEdition<'static>>;
+    type Error = Arc<DbErr>;
+
+    async fn load(&self, keys: &[u32]) -> Result<HashMap<u32, Self::Value>, Self::Error> {
+        let rows = event_edition_records::Entity::find()
+            .filter(event_edition_records::Column::RecordId.is_in(keys.iter().copied()))
+            .find_also_related(event_edition::Entity)
+            .order_by_asc(event_edition_records::Column::EventId)
+            .order_by_asc(event_edition_records::Column::EditionId)
+            .all(&self.0)
+            .await?;
+
+        let events = event::Entity::find()
+            .filter(event::Column::Id.is_in(rows.iter().map(|(row, _)| row.event_id)))
+            .all(&self.0)
+            .await?
+            .into_iter()
+            .map(|event| (event.id, event))
+            .collect::<HashMap<_, _>>();
+
+        let mut hashmap = HashMap::<_, Vec<_>>::with_capacity(keys.len());
+
+        for (row, edition) in rows {
+            let Some(edition) = edition else {
+                continue;
+            };
+            let Some(event) = events.get(&edition.event_id).cloned() else {
+                continue;
+            };
+            hashmap
+                .entry(row.record_id)
+                .or_default()
+                .push(EventEdition::new(event.into(), edition));
+        }
+
+        Ok(hashmap)
+    }
+}
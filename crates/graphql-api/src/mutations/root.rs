@@ -0,0 +1,121 @@
+use entity::{banishments, current_bans};
+use records_lib::{Database, must, ranks, sync};
+use sea_orm::{
+    ActiveModelTrait as _,
+    ActiveValue::Set,
+    ColumnTrait as _, EntityTrait as _, QueryFilter as _,
+    sea_query::{Expr, Func},
+};
+
+use crate::{auth::AdminAuth, error::GqlResult, objects::banishment::Banishment};
+
+pub struct MutationRoot;
+
+#[async_graphql::Object]
+impl MutationRoot {
+    /// Bans the player with the provided login.
+    ///
+    /// If the player is already banned, their current banishment is updated instead.
+    async fn ban_player(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        login: String,
+        reason: String,
+        #[graphql(desc = "The duration of the banishment in days (default: permanent)")]
+        duration_days: Option<u32>,
+    ) -> GqlResult<Banishment> {
+        let admin_id = AdminAuth::require(ctx).await?;
+        let db = ctx.data_unchecked::<Database>();
+
+        let duration = duration_days.map(|days| days as i64 * 24 * 60 * 60);
+        let now = chrono::Utc::now().naive_utc();
+
+        sync::transaction(&db.sql_conn, async |txn| {
+            let player = must::have_player(txn, &login).await?;
+
+            let current_ban = current_bans::Entity::find()
+                .filter(current_bans::Column::PlayerId.eq(player.id))
+                .one(txn)
+                .await?;
+
+            let ban = match current_ban {
+                Some(current_ban) => {
+                    banishments::ActiveModel {
+                        id: Set(current_ban.id),
+                        date_ban: Set(now),
+                        duration: Set(duration),
+                        reason: Set(reason),
+                        banished_by: Set(Some(admin_id)),
+                        ..Default::default()
+                    }
+                    .update(txn)
+                    .await?
+                }
+                None => {
+                    let was_reprieved = banishments::Entity::find()
+                        .filter(banishments::Column::PlayerId.eq(player.id))
+                        .one(txn)
+                        .await?
+                        .is_some();
+
+                    banishments::ActiveModel {
+                        date_ban: Set(now),
+                        duration: Set(duration),
+                        was_reprieved: Set(if was_reprieved { 1 } else { 0 }),
+                        reason: Set(reason),
+                        player_id: Set(Some(player.id)),
+                        banished_by: Set(Some(admin_id)),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?
+                }
+            };
+
+            GqlResult::Ok(ban.into())
+        })
+        .await
+    }
+
+    /// Lifts the current banishment of the player with the provided login.
+    ///
+    /// It returns whether the player was actually banned.
+    async fn unban_player(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        login: String,
+    ) -> GqlResult<bool> {
+        AdminAuth::require(ctx).await?;
+        let db = ctx.data_unchecked::<Database>();
+
+        let player = must::have_player(&db.sql_conn, &login).await?;
+
+        let Some(current_ban) = current_bans::Entity::find()
+            .filter(current_bans::Column::PlayerId.eq(player.id))
+            .one(&db.sql_conn)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        // The banishment ends now
+        banishments::Entity::update_many()
+            .col_expr(
+                banishments::Column::Duration,
+                Func::cust("TIMESTAMPDIFF")
+                    .arg(Expr::custom_keyword("SECOND"))
+                    .arg(Expr::col(banishments::Column::DateBan))
+                    .arg(Func::cust("NOW"))
+                    .into(),
+            )
+            .filter(banishments::Column::Id.eq(current_ban.id))
+            .exec(&db.sql_conn)
+            .await?;
+
+        // Refresh the leaderboards of the player's maps, in case their records were excluded
+        // while they were banned
+        ranks::warm_player(&db.sql_conn, &db.redis_pool, player.id).await?;
+
+        Ok(true)
+    }
+}
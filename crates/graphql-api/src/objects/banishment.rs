@@ -0,0 +1,65 @@
+use async_graphql::{Context, dataloader::DataLoader};
+use entity::banishments;
+
+use crate::{error::GqlResult, loaders::player::PlayerLoader, objects::player::Player};
+
+#[derive(Debug, Clone)]
+pub struct Banishment {
+    pub inner: banishments::Model,
+}
+
+impl From<banishments::Model> for Banishment {
+    fn from(inner: banishments::Model) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_graphql::Object]
+impl Banishment {
+    async fn id(&self) -> u32 {
+        self.inner.id
+    }
+
+    async fn date_ban(&self) -> chrono::DateTime<chrono::Utc> {
+        self.inner.date_ban.and_utc()
+    }
+
+    /// The duration of the banishment in seconds, or null if it is permanent.
+    async fn duration(&self) -> Option<i64> {
+        self.inner.duration
+    }
+
+    async fn was_reprieved(&self) -> bool {
+        self.inner.was_reprieved != 0
+    }
+
+    async fn reason(&self) -> &str {
+        &self.inner.reason
+    }
+
+    async fn player(&self, ctx: &Context<'_>) -> GqlResult<Option<Player>> {
+        let Some(player_id) = self.inner.player_id else {
+            return Ok(None);
+        };
+
+        let player = ctx
+            .data_unchecked::<DataLoader<PlayerLoader>>()
+            .load_one(player_id)
+            .await?;
+
+        Ok(player)
+    }
+
+    async fn banished_by(&self, ctx: &Context<'_>) -> GqlResult<Option<Player>> {
+        let Some(player_id) = self.inner.banished_by else {
+            return Ok(None);
+        };
+
+        let player = ctx
+            .data_unchecked::<DataLoader<PlayerLoader>>()
+            .load_one(player_id)
+            .await?;
+
+        Ok(player)
+    }
+}
@@ -31,6 +31,15 @@ pub struct EventEdition<'a> {
     pub(super) inner: event_edition::Model,
 }
 
+impl EventEdition<'static> {
+    pub(crate) fn new(event: Event, inner: event_edition::Model) -> Self {
+        Self {
+            event: Cow::Owned(event),
+            inner,
+        }
+    }
+}
+
 impl EventEdition<'_> {
     pub(super) async fn from_inner<C: ConnectionTrait>(
         conn: &C,
@@ -64,6 +73,7 @@ impl EventEdition<'_> {
         Ok(last_update_time.map(|_| Mappack {
             mappack_id: mappack_id.mappack_id().to_string(),
             event_has_expired: self.inner.has_expired(),
+            event: Some((self.event.inner.clone(), self.inner.clone())),
         }))
     }
 
@@ -101,6 +111,13 @@ impl EventEdition<'_> {
         self.inner.expires_in()
     }
 
+    async fn participant_count(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<u64> {
+        let conn = ctx.data_unchecked::<DbConn>();
+        let count =
+            event_utils::participant_count(conn, self.inner.event_id, self.inner.id).await?;
+        Ok(count)
+    }
+
     async fn player(
         &self,
         ctx: &async_graphql::Context<'_>,
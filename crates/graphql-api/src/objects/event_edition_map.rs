@@ -1,14 +1,19 @@
-use async_graphql::{ID, connection, dataloader::DataLoader};
+use async_graphql::dataloader::DataLoader;
 use entity::event_edition_maps;
-use records_lib::{event as event_utils, internal, opt_event::OptEvent};
+use records_lib::{internal, opt_event::OptEvent};
 use sea_orm::{DbConn, EntityTrait as _, QuerySelect as _};
 
 use crate::{
     error::GqlResult,
-    loaders::map::MapLoader,
+    loaders::{map::MapLoader, medal_times::MedalTimesLoader},
     objects::{
-        event_edition::EventEdition, map::Map, medal_times::MedalTimes,
-        ranked_record::RankedRecord, records_filter::RecordsFilter, sort::MapRecordSort,
+        event_edition::EventEdition,
+        map::{MAP_RECORDS_MAX_LIMIT, Map},
+        medal_times::MedalTimes,
+        ranked_record::RankedRecord,
+        records_connection::RecordsConnection,
+        records_filter::RecordsFilter,
+        sort::MapRecordSort,
         sort_state::SortState,
     },
 };
@@ -76,6 +81,7 @@ impl EventEditionMap<'_> {
                 OptEvent::new(&self.edition.event.inner, &self.edition.inner),
                 rank_sort_by,
                 date_sort_by,
+                MAP_RECORDS_MAX_LIMIT,
             )
             .await
     }
@@ -93,7 +99,7 @@ impl EventEditionMap<'_> {
         #[graphql(desc = "Number of records to fetch from the end (for backward pagination)")] last: Option<i32>,
         sort: Option<MapRecordSort>,
         filter: Option<RecordsFilter>,
-    ) -> GqlResult<connection::Connection<ID, RankedRecord>> {
+    ) -> GqlResult<RecordsConnection> {
         self.map
             .get_records_connection(
                 ctx,
@@ -109,16 +115,15 @@ impl EventEditionMap<'_> {
     }
 
     async fn medal_times(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<Option<MedalTimes>> {
-        let conn = ctx.data_unchecked::<DbConn>();
-
-        let medal_times = event_utils::get_medal_times_of(
-            conn,
-            self.edition.inner.event_id,
-            self.edition.inner.id,
-            self.map.inner.id,
-        )
-        .await?;
+        let medal_times = ctx
+            .data_unchecked::<DataLoader<MedalTimesLoader>>()
+            .load_one((
+                self.edition.inner.event_id,
+                self.edition.inner.id,
+                self.map.inner.id,
+            ))
+            .await?;
 
-        Ok(medal_times.map(From::from))
+        Ok(medal_times)
     }
 }
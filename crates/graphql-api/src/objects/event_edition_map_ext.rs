@@ -1,11 +1,10 @@
+use async_graphql::dataloader::DataLoader;
 use deadpool_redis::redis::AsyncCommands as _;
-use records_lib::{
-    RedisPool, event as event_utils, mappack::AnyMappackId, redis_key::mappack_map_last_rank,
-};
-use sea_orm::DbConn;
+use records_lib::{RedisPool, mappack::AnyMappackId, redis_key::mappack_map_last_rank};
 
 use crate::{
     error::GqlResult,
+    loaders::medal_times::MedalTimesLoader,
     objects::{event_edition_player::EventEditionPlayer, map::Map, medal_times::MedalTimes},
 };
 
@@ -38,16 +37,15 @@ impl EventEditionMapExt<'_> {
     }
 
     async fn medal_times(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<Option<MedalTimes>> {
-        let conn = ctx.data_unchecked::<DbConn>();
-
-        let medal_times = event_utils::get_medal_times_of(
-            conn,
-            self.edition_player.edition.inner.event_id,
-            self.edition_player.edition.inner.id,
-            self.inner.inner.id,
-        )
-        .await?;
+        let medal_times = ctx
+            .data_unchecked::<DataLoader<MedalTimesLoader>>()
+            .load_one((
+                self.edition_player.edition.inner.event_id,
+                self.edition_player.edition.inner.id,
+                self.inner.inner.id,
+            ))
+            .await?;
 
-        Ok(medal_times.map(From::from))
+        Ok(medal_times)
     }
 }
@@ -1,44 +1,58 @@
 use async_graphql::{
-    ID,
+    ID, OutputType,
     connection::{self, CursorType},
     dataloader::DataLoader,
 };
 use deadpool_redis::redis::AsyncCommands as _;
 use entity::{
-    event_edition, event_edition_maps, global_event_records, global_records, maps, player_rating,
-    records,
+    event_edition, event_edition_maps, functions, global_event_records, global_records, maps,
+    player_rating, players, records,
 };
 use records_lib::{
-    Database, RedisPool, internal,
+    Database, RedisPool, internal, leaderboard,
     opt_event::OptEvent,
     ranks::{self, update_leaderboard},
     redis_key::map_key,
     sync,
 };
 use sea_orm::{
-    ColumnTrait as _, ConnectionTrait, DbConn, EntityTrait as _, FromQueryResult, QueryFilter as _,
-    QueryOrder as _, QuerySelect as _, StreamTrait,
+    ColumnTrait as _, ConnectionTrait, DbConn, EntityTrait, FromQueryResult, Identity, JoinType,
+    QueryFilter as _, QueryOrder as _, QuerySelect, QueryTrait as _, RelationTrait as _, Select,
+    SelectModel, StreamTrait,
     prelude::Expr,
-    sea_query::{Asterisk, ExprTrait as _, Func, IntoValueTuple, Query},
+    sea_query::{
+        Asterisk, ExprTrait as _, Func, IntoCondition, IntoIden as _, IntoValueTuple, Query,
+        SelectStatement,
+    },
 };
 
 use crate::{
     cursors::{
-        ConnectionParameters, RecordDateCursor, RecordRankCursor, expr_tuple::IntoExprTuple,
+        ConnectionParameters, RecordCountCursor, RecordDateCursor, RecordRankCursor,
+        RecordTimeCursor, TextCursor, expr_tuple::IntoExprTuple, query_builder::CursorQueryBuilder,
         query_trait::CursorPaginable,
     },
     error::{self, ApiGqlError, CursorDecodeError, CursorDecodeErrorKind, GqlResult},
     loaders::{map::MapLoader, player::PlayerLoader},
     objects::{
-        event_edition::EventEdition, player::Player, player_rating::PlayerRating,
-        ranked_record::RankedRecord, records_filter::RecordsFilter,
-        related_edition::RelatedEdition, sort::MapRecordSort, sort_order::SortOrder,
-        sort_state::SortState, sortable_fields::MapRecordSortableField,
+        event_edition::EventEdition,
+        map_stats::MapStats,
+        map_with_record_count::MapWithRecordCount,
+        player::Player,
+        player_rating::PlayerRating,
+        ranked_record::RankedRecord,
+        records_connection::{RecordsConnection, RecordsConnectionFields},
+        records_filter::RecordsFilter,
+        related_edition::RelatedEdition,
+        sort::MapRecordSort,
+        sort_order::SortOrder,
+        sort_state::SortState,
+        sortable_fields::MapRecordSortableField,
     },
     utils::{
         page_input::{PaginationInput, apply_cursor_input},
         pagination_result::{PaginationResult, get_paginated},
-        records_filter::apply_filter,
+        records_filter::{RecordsTableFilterConstructor, apply_filter},
     },
 };
 
@@ -61,6 +75,9 @@ impl From<maps::Model> for Map {
     }
 }
 
+/// The maximum amount of records returned by the lists of the records of a map.
+pub(crate) const MAP_RECORDS_MAX_LIMIT: usize = 100;
+
 async fn get_map_records<C: ConnectionTrait + StreamTrait>(
     conn: &C,
     redis_pool: &RedisPool,
@@ -68,7 +85,12 @@ async fn get_map_records<C: ConnectionTrait + StreamTrait>(
     event: OptEvent<'_>,
     rank_sort_by: Option<SortState>,
     date_sort_by: Option<SortState>,
+    limit: usize,
 ) -> GqlResult<Vec<RankedRecord>> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
     let key = map_key(map_id, event);
 
     update_leaderboard(conn, redis_pool, map_id, event).await?;
@@ -77,10 +99,11 @@ async fn get_map_records<C: ConnectionTrait + StreamTrait>(
     let record_ids: Vec<i32> = {
         let mut redis_conn = redis_pool.get().await?;
 
+        let stop = limit as isize - 1;
         if to_reverse {
-            redis_conn.zrevrange(&key, 0, 99)
+            redis_conn.zrevrange(&key, 0, stop)
         } else {
-            redis_conn.zrange(&key, 0, 99)
+            redis_conn.zrange(&key, 0, stop)
         }
         .await?
     };
@@ -100,7 +123,11 @@ async fn get_map_records<C: ConnectionTrait + StreamTrait>(
         None => select.from_as(global_records::Entity, "r"),
     }
     .column(Asterisk)
-    .and_where(Expr::col(("r", records::Column::MapId)).eq(map_id));
+    .and_where(Expr::col(("r", records::Column::MapId)).eq(map_id))
+    .and_where(
+        Expr::col(("r", records::Column::RecordPlayerId))
+            .not_in_subquery(ranks::banned_players_query()),
+    );
 
     if let Some(ref s) = date_sort_by {
         select.order_by_expr(
@@ -128,7 +155,7 @@ async fn get_map_records<C: ConnectionTrait + StreamTrait>(
     }
 
     if date_sort_by.is_some() {
-        select.limit(100);
+        select.limit(limit as _);
     }
 
     let stmt = conn.get_database_backend().build(&*select);
@@ -154,6 +181,8 @@ async fn get_map_records<C: ConnectionTrait + StreamTrait>(
 pub(crate) enum MapRecordCursor {
     Date(RecordDateCursor),
     Rank(RecordRankCursor),
+    Time(RecordTimeCursor),
+    Player(TextCursor),
 }
 
 impl From<RecordDateCursor> for MapRecordCursor {
@@ -170,26 +199,53 @@ impl From<RecordRankCursor> for MapRecordCursor {
     }
 }
 
+impl From<RecordTimeCursor> for MapRecordCursor {
+    #[inline]
+    fn from(value: RecordTimeCursor) -> Self {
+        Self::Time(value)
+    }
+}
+
+impl From<TextCursor> for MapRecordCursor {
+    #[inline]
+    fn from(value: TextCursor) -> Self {
+        Self::Player(value)
+    }
+}
+
+/// Falls back to the provided decoding function if the cursor has another prefix.
+fn or_other_prefix<T>(
+    result: Result<T, CursorDecodeError>,
+    f: impl FnOnce() -> Result<T, CursorDecodeError>,
+) -> Result<T, CursorDecodeError> {
+    match result {
+        Err(CursorDecodeError {
+            kind: CursorDecodeErrorKind::InvalidPrefix,
+        }) => f(),
+        other => other,
+    }
+}
+
 impl CursorType for MapRecordCursor {
     type Error = CursorDecodeError;
 
     fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
-        match RecordRankCursor::decode_cursor(s) {
-            Ok(rank) => Ok(rank.into()),
-            Err(CursorDecodeError {
-                kind: CursorDecodeErrorKind::InvalidPrefix,
-            }) => match RecordDateCursor::decode_cursor(s) {
-                Ok(date) => Ok(date.into()),
-                Err(e) => Err(e),
-            },
-            Err(e) => Err(e),
-        }
+        let result = RecordRankCursor::decode_cursor(s).map(From::from);
+        let result = or_other_prefix(result, || {
+            RecordDateCursor::decode_cursor(s).map(From::from)
+        });
+        let result = or_other_prefix(result, || {
+            RecordTimeCursor::decode_cursor(s).map(From::from)
+        });
+        or_other_prefix(result, || TextCursor::decode_cursor(s).map(From::from))
     }
 
     fn encode_cursor(&self) -> String {
         match self {
             MapRecordCursor::Date(record_date_cursor) => record_date_cursor.encode_cursor(),
             MapRecordCursor::Rank(record_rank_cursor) => record_rank_cursor.encode_cursor(),
+            MapRecordCursor::Time(record_time_cursor) => record_time_cursor.encode_cursor(),
+            MapRecordCursor::Player(text_cursor) => text_cursor.encode_cursor(),
         }
     }
 }
@@ -203,6 +259,10 @@ impl IntoExprTuple for &MapRecordCursor {
             MapRecordCursor::Rank(record_rank_cursor) => {
                 IntoExprTuple::into_expr_tuple(record_rank_cursor)
             }
+            MapRecordCursor::Time(record_time_cursor) => {
+                IntoExprTuple::into_expr_tuple(record_time_cursor)
+            }
+            MapRecordCursor::Player(text_cursor) => IntoExprTuple::into_expr_tuple(text_cursor),
         }
     }
 }
@@ -216,10 +276,61 @@ impl IntoValueTuple for &MapRecordCursor {
             MapRecordCursor::Rank(record_rank_cursor) => {
                 IntoValueTuple::into_value_tuple(record_rank_cursor)
             }
+            MapRecordCursor::Time(record_time_cursor) => {
+                IntoValueTuple::into_value_tuple(record_time_cursor)
+            }
+            MapRecordCursor::Player(text_cursor) => IntoValueTuple::into_value_tuple(text_cursor),
         }
     }
 }
 
+/// A record fetched by the records connection of a map.
+#[derive(FromQueryResult)]
+struct RecordWithPlayerName {
+    #[sea_orm(nested)]
+    record: records::Model,
+    /// The unstyled name of the player of the record, only selected when sorting by player.
+    unstyled_player_name: Option<String>,
+}
+
+/// Returns the query of the records, paginated by the unstyled name of their player, then by the
+/// ID of their player.
+///
+/// The query is wrapped in a subquery, so the pagination can use the unstyled name.
+fn paginate_by_player_name<E>(query: Select<E>) -> CursorQueryBuilder<SelectModel<E::Model>>
+where
+    E: RecordsTableFilterConstructor + EntityTrait,
+{
+    let mut query = query
+        .join_as(
+            JoinType::InnerJoin,
+            E::get_players_relation(),
+            "record_player",
+        )
+        .expr_as(
+            functions::unstyled(Expr::col(("record_player", players::Column::Name))),
+            "unstyled_player_name",
+        );
+    let query = SelectStatement::new()
+        .expr(Expr::col(("record", Asterisk)))
+        .from_subquery(QuerySelect::query(&mut query).take(), "record")
+        .take();
+
+    CursorQueryBuilder::new(
+        query,
+        "record".into_iden(),
+        Identity::Binary(
+            "unstyled_player_name".into_iden(),
+            "record_player_id".into_iden(),
+        ),
+    )
+}
+
+/// Selects the unstyled name of the player of the records as `NULL`, when it isn't needed.
+fn without_player_name<E: EntityTrait>(query: Select<E>) -> Select<E> {
+    query.expr_as(Expr::value(Option::<String>::None), "unstyled_player_name")
+}
+
 pub(crate) async fn get_map_records_connection<C: ConnectionTrait + StreamTrait>(
     conn: &C,
     redis_pool: &RedisPool,
@@ -228,75 +339,124 @@ pub(crate) async fn get_map_records_connection<C: ConnectionTrait + StreamTrait>
     connection_parameters: ConnectionParameters<MapRecordCursor>,
     sort: Option<MapRecordSort>,
     filter: Option<RecordsFilter>,
-) -> GqlResult<connection::Connection<ID, RankedRecord>> {
-    let pagination_input = PaginationInput::try_from_input(connection_parameters)?;
+) -> GqlResult<RecordsConnection> {
+    let pagination_input = PaginationInput::try_from_input(
+        connection_parameters,
+        crate::config::records_default_limit(),
+    )?;
     let cursor_encoder = match sort.map(|s| s.field) {
-        Some(MapRecordSortableField::Date) => |record: &records::Model| {
+        Some(MapRecordSortableField::Date) => |row: &RecordWithPlayerName| {
             RecordDateCursor {
-                record_date: record.record_date.and_utc(),
-                data: record.record_id,
+                record_date: row.record.record_date.and_utc(),
+                data: row.record.record_id,
+            }
+            .encode_cursor()
+        },
+        Some(MapRecordSortableField::Time) => |row: &RecordWithPlayerName| {
+            RecordTimeCursor {
+                time: row.record.time,
+                data: row.record.record_id,
             }
             .encode_cursor()
         },
-        _ => |record: &records::Model| {
+        Some(MapRecordSortableField::Player) => |row: &RecordWithPlayerName| {
+            TextCursor {
+                text: row.unstyled_player_name.clone().unwrap_or_default(),
+                data: row.record.record_player_id,
+            }
+            .encode_cursor()
+        },
+        _ => |row: &RecordWithPlayerName| {
             RecordRankCursor {
-                time: record.time,
-                record_date: record.record_date.and_utc(),
-                data: record.record_id,
+                time: row.record.time,
+                record_date: row.record.record_date.and_utc(),
+                data: row.record.record_id,
             }
             .encode_cursor()
         },
     };
 
-    let mut query =
-        match event.get() {
-            Some((ev, ed)) => {
-                let base_query = apply_filter(
-                    global_event_records::Entity::find().filter(
-                        global_event_records::Column::MapId
-                            .eq(map_id)
-                            .and(global_event_records::Column::EventId.eq(ev.id))
-                            .and(global_event_records::Column::EditionId.eq(ed.id)),
-                    ),
-                    filter.as_ref(),
-                );
+    let (mut query, fields) = match event.get() {
+        Some((ev, ed)) => {
+            let base_query = apply_filter(
+                global_event_records::Entity::find().filter(
+                    global_event_records::Column::MapId
+                        .eq(map_id)
+                        .and(global_event_records::Column::EventId.eq(ev.id))
+                        .and(global_event_records::Column::EditionId.eq(ed.id))
+                        .and(
+                            global_event_records::Column::RecordPlayerId
+                                .not_in_subquery(ranks::banned_players_query()),
+                        ),
+                ),
+                filter.as_ref(),
+            );
+            let fields = RecordsConnectionFields::new(base_query.clone().into_query());
 
-                match (pagination_input.get_cursor(), sort.map(|s| s.field)) {
-                    (Some(MapRecordCursor::Date(_)), _)
-                    | (_, Some(MapRecordSortableField::Date)) => base_query.paginate_cursor_by((
+            let query = match (pagination_input.get_cursor(), sort.map(|s| s.field)) {
+                (Some(MapRecordCursor::Player(_)), _)
+                | (_, Some(MapRecordSortableField::Player)) => paginate_by_player_name(base_query),
+                (Some(MapRecordCursor::Date(_)), _) | (_, Some(MapRecordSortableField::Date)) => {
+                    without_player_name(base_query).paginate_cursor_by((
                         global_event_records::Column::RecordDate,
                         global_event_records::Column::RecordId,
-                    )),
-                    _ => base_query.paginate_cursor_by((
+                    ))
+                }
+                (Some(MapRecordCursor::Time(_)), _) | (_, Some(MapRecordSortableField::Time)) => {
+                    without_player_name(base_query).paginate_cursor_by((
                         global_event_records::Column::Time,
-                        global_event_records::Column::RecordDate,
                         global_event_records::Column::RecordId,
-                    )),
+                    ))
                 }
-                .into_model::<records::Model>()
+                _ => without_player_name(base_query).paginate_cursor_by((
+                    global_event_records::Column::Time,
+                    global_event_records::Column::RecordDate,
+                    global_event_records::Column::RecordId,
+                )),
             }
+            .into_model::<RecordWithPlayerName>();
 
-            None => {
-                let base_query = apply_filter(
-                    global_records::Entity::find().filter(global_records::Column::MapId.eq(map_id)),
-                    filter.as_ref(),
-                );
+            (query, fields)
+        }
+
+        None => {
+            let base_query = apply_filter(
+                global_records::Entity::find().filter(
+                    global_records::Column::MapId.eq(map_id).and(
+                        global_records::Column::RecordPlayerId
+                            .not_in_subquery(ranks::banned_players_query()),
+                    ),
+                ),
+                filter.as_ref(),
+            );
+            let fields = RecordsConnectionFields::new(base_query.clone().into_query());
 
-                match (pagination_input.get_cursor(), sort.map(|s| s.field)) {
-                    (Some(MapRecordCursor::Date(_)), _)
-                    | (_, Some(MapRecordSortableField::Date)) => base_query.paginate_cursor_by((
+            let query = match (pagination_input.get_cursor(), sort.map(|s| s.field)) {
+                (Some(MapRecordCursor::Player(_)), _)
+                | (_, Some(MapRecordSortableField::Player)) => paginate_by_player_name(base_query),
+                (Some(MapRecordCursor::Date(_)), _) | (_, Some(MapRecordSortableField::Date)) => {
+                    without_player_name(base_query).paginate_cursor_by((
                         global_event_records::Column::RecordDate,
                         global_event_records::Column::RecordId,
-                    )),
-                    _ => base_query.paginate_cursor_by((
+                    ))
+                }
+                (Some(MapRecordCursor::Time(_)), _) | (_, Some(MapRecordSortableField::Time)) => {
+                    without_player_name(base_query).paginate_cursor_by((
                         global_records::Column::Time,
-                        global_records::Column::RecordDate,
                         global_records::Column::RecordId,
-                    )),
+                    ))
                 }
-                .into_model::<records::Model>()
+                _ => without_player_name(base_query).paginate_cursor_by((
+                    global_records::Column::Time,
+                    global_records::Column::RecordDate,
+                    global_records::Column::RecordId,
+                )),
             }
-        };
+            .into_model::<RecordWithPlayerName>();
+
+            (query, fields)
+        }
+    };
 
     apply_cursor_input(&mut query, &pagination_input);
 
@@ -316,15 +476,164 @@ pub(crate) async fn get_map_records_connection<C: ConnectionTrait + StreamTrait>
 
     let mut redis_conn = redis_pool.get().await?;
 
-    for record in records {
+    for row in records {
+        let cursor = (cursor_encoder)(&row);
+        let record = row.record;
         let rank = ranks::get_rank(&mut redis_conn, record.map_id, record.time, event).await?;
 
         connection.edges.push(connection::Edge::new(
-            ID((cursor_encoder)(&record)),
+            ID(cursor),
             records::RankedRecord { rank, record }.into(),
         ));
     }
 
+    Ok(fields.attach(connection))
+}
+
+#[derive(FromQueryResult)]
+struct RawMapWithRecordCount {
+    #[sea_orm(nested)]
+    map: maps::Model,
+    record_count: i64,
+}
+
+/// Returns the connection of the maps sorted by their amount of records, the most played first.
+///
+/// Every record of a player is counted, not only their best one, but the hidden records are
+/// ignored.
+pub(crate) async fn most_played_connection<C: ConnectionTrait>(
+    conn: &C,
+    connection_parameters: ConnectionParameters<RecordCountCursor>,
+) -> GqlResult<connection::Connection<ID, MapWithRecordCount>> {
+    let pagination_input = PaginationInput::try_from_input(
+        connection_parameters,
+        crate::config::maps_default_limit(),
+    )?;
+
+    let mut query = maps::Entity::find()
+        .join(
+            JoinType::LeftJoin,
+            maps::Relation::Records.def().on_condition(|_, right| {
+                Expr::col((right, records::Column::IsHidden))
+                    .eq(false)
+                    .into_condition()
+            }),
+        )
+        .expr_as(
+            Func::count(Expr::col((records::Entity, records::Column::RecordId))),
+            "record_count",
+        )
+        .group_by(maps::Column::Id);
+    let query = SelectStatement::new()
+        .expr(Expr::col(("map", Asterisk)))
+        .from_subquery(QuerySelect::query(&mut query).take(), "map")
+        .take();
+
+    let mut query = CursorQueryBuilder::new(
+        query,
+        "map".into_iden(),
+        Identity::Binary("record_count".into_iden(), maps::Column::Id.into_iden()),
+    )
+    .into_model::<RawMapWithRecordCount>();
+
+    apply_cursor_input(&mut query, &pagination_input);
+    query.desc();
+
+    let PaginationResult {
+        mut connection,
+        iter: maps,
+    } = get_paginated(conn, query, &pagination_input).await?;
+
+    connection.edges.reserve(maps.len());
+
+    for RawMapWithRecordCount { map, record_count } in maps {
+        let record_count = record_count as u64;
+        connection.edges.push(connection::Edge::new(
+            ID(RecordCountCursor {
+                record_count,
+                data: map.id,
+            }
+            .encode_cursor()),
+            MapWithRecordCount {
+                record_count,
+                map: map.into(),
+            },
+        ));
+    }
+
+    Ok(connection)
+}
+
+#[derive(FromQueryResult)]
+struct MapWithUnstyledName {
+    #[sea_orm(nested)]
+    map: maps::Model,
+    unstyled_map_name: String,
+}
+
+/// Returns the connection of the maps matching the provided condition, sorted by their unstyled
+/// name.
+///
+/// Each map is converted to the node type with the provided function.
+pub(crate) async fn maps_by_name_connection<C, T, F>(
+    conn: &C,
+    condition: impl IntoCondition,
+    connection_parameters: ConnectionParameters<TextCursor>,
+    mut f: F,
+) -> GqlResult<connection::Connection<ID, T>>
+where
+    C: ConnectionTrait,
+    T: OutputType,
+    F: FnMut(maps::Model) -> T,
+{
+    let pagination_input = PaginationInput::try_from_input(
+        connection_parameters,
+        crate::config::maps_default_limit(),
+    )?;
+
+    let mut query = maps::Entity::find()
+        .filter(condition)
+        .expr_as(functions::unstyled(maps::Column::Name), "unstyled_map_name");
+    let query = SelectStatement::new()
+        .expr(Expr::col(("map", Asterisk)))
+        .from_subquery(QuerySelect::query(&mut query).take(), "map")
+        .take();
+
+    let mut query = CursorQueryBuilder::new(
+        query,
+        "map".into_iden(),
+        Identity::Binary(
+            "unstyled_map_name".into_iden(),
+            maps::Column::Id.into_iden(),
+        ),
+    )
+    .into_model::<MapWithUnstyledName>();
+
+    apply_cursor_input(&mut query, &pagination_input);
+    query.asc();
+
+    let PaginationResult {
+        mut connection,
+        iter: maps,
+    } = get_paginated(conn, query, &pagination_input).await?;
+
+    connection.edges.reserve(maps.len());
+
+    for MapWithUnstyledName {
+        map,
+        unstyled_map_name,
+    } in maps
+    {
+        connection.edges.push(connection::Edge::new(
+            ID(TextCursor {
+                text: unstyled_map_name,
+                data: map.id,
+            }
+            .encode_cursor()),
+            f(map),
+        ));
+    }
+
     Ok(connection)
 }
 
@@ -335,9 +644,12 @@ impl Map {
         event: OptEvent<'_>,
         rank_sort_by: Option<SortState>,
         date_sort_by: Option<SortState>,
+        limit: usize,
     ) -> GqlResult<Vec<RankedRecord>> {
         let db = gql_ctx.data_unchecked::<Database>();
 
+        // The leaderboard is synchronized with the primary database, because a lagging replica
+        // would rebuild it from stale rows
         records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
             get_map_records(
                 txn,
@@ -346,6 +658,7 @@ impl Map {
                 event,
                 rank_sort_by,
                 date_sort_by,
+                limit,
             )
             .await
         }))
@@ -363,7 +676,7 @@ impl Map {
         last: Option<i32>,
         sort: Option<MapRecordSort>,
         filter: Option<RecordsFilter>,
-    ) -> GqlResult<connection::Connection<ID, RankedRecord>> {
+    ) -> GqlResult<RecordsConnection> {
         let db = gql_ctx.data_unchecked::<Database>();
 
         connection::query_with(
@@ -433,6 +746,23 @@ impl Map {
         self.inner.score
     }
 
+    async fn finisher_count(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<u64> {
+        let conn = ctx.data_unchecked::<DbConn>();
+        let count = records_lib::map::finisher_count(conn, self.inner.id).await?;
+        Ok(count)
+    }
+
+    /// The stats of the records of the map, only recomputed when they changed.
+    async fn stats(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<MapStats> {
+        let db = ctx.data_unchecked::<Database>();
+        let mut redis_conn = db.redis_pool.get().await?;
+        let stats =
+            player_map_ranking::cached_map_stats(&db.sql_conn, &mut redis_conn, self.inner.id)
+                .await
+                .map_err(|e| internal!("Couldn't get the stats of the map: {e:#}"))?;
+        Ok(stats.into())
+    }
+
     async fn related_event_editions(
         &self,
         ctx: &async_graphql::Context<'_>,
@@ -507,8 +837,14 @@ impl Map {
         rank_sort_by: Option<SortState>,
         date_sort_by: Option<SortState>,
     ) -> GqlResult<Vec<RankedRecord>> {
-        self.get_records(ctx, Default::default(), rank_sort_by, date_sort_by)
-            .await
+        self.get_records(
+            ctx,
+            Default::default(),
+            rank_sort_by,
+            date_sort_by,
+            MAP_RECORDS_MAX_LIMIT,
+        )
+        .await
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -524,7 +860,7 @@ impl Map {
         #[graphql(desc = "Number of records to fetch from the end (for backward pagination)")] last: Option<i32>,
         sort: Option<MapRecordSort>,
         filter: Option<RecordsFilter>,
-    ) -> GqlResult<connection::Connection<ID, RankedRecord>> {
+    ) -> GqlResult<RecordsConnection> {
         self.get_records_connection(
             ctx,
             Default::default(),
@@ -537,4 +873,50 @@ impl Map {
         )
         .await
     }
+
+    async fn records_by_flag(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        #[graphql(desc = "The bits that must all be set in the flags of the records")]
+        flag_mask: u32,
+        #[graphql(
+            desc = "Number of records to fetch (default: the default page size of the records \
+                connections, max: the maximum page size of the connections)"
+        )]
+        limit: Option<usize>,
+    ) -> GqlResult<Vec<RankedRecord>> {
+        let db = ctx.data_unchecked::<Database>();
+        let conn = &db.sql_conn;
+
+        let limit = limit
+            .unwrap_or_else(crate::config::records_default_limit)
+            .min(crate::config::config().cursor_max_limit.get());
+
+        let records = leaderboard::records_by_flag(
+            conn,
+            self.inner.id,
+            flag_mask,
+            limit as _,
+            Default::default(),
+        )
+        .await?;
+
+        update_leaderboard(conn, &db.redis_pool, self.inner.id, Default::default()).await?;
+
+        let mut redis_conn = db.redis_pool.get().await?;
+
+        let times = records
+            .iter()
+            .map(|record| (record.map_id, record.time))
+            .collect::<Vec<_>>();
+        let ranks = ranks::get_ranks(&mut redis_conn, &times, Default::default()).await?;
+
+        let ranked_records = records
+            .into_iter()
+            .zip(ranks)
+            .map(|(record, rank)| records::RankedRecord { rank, record }.into())
+            .collect();
+
+        Ok(ranked_records)
+    }
 }
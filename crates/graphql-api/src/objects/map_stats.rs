@@ -0,0 +1,34 @@
+/// The stats of the records of a map, with the times in seconds.
+#[derive(Clone, Copy)]
+pub struct MapStats {
+    pub inner: player_map_ranking::MapStats,
+}
+
+impl From<player_map_ranking::MapStats> for MapStats {
+    fn from(inner: player_map_ranking::MapStats) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_graphql::Object]
+impl MapStats {
+    async fn records_count(&self) -> u64 {
+        self.inner.records_count as _
+    }
+
+    async fn min_record(&self) -> f64 {
+        self.inner.min_record
+    }
+
+    async fn max_record(&self) -> f64 {
+        self.inner.max_record
+    }
+
+    async fn average_record(&self) -> f64 {
+        self.inner.average_record
+    }
+
+    async fn median_record(&self) -> f64 {
+        self.inner.median_record
+    }
+}
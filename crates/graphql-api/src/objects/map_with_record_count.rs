@@ -0,0 +1,9 @@
+use async_graphql::SimpleObject;
+
+use crate::objects::map::Map;
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct MapWithRecordCount {
+    pub record_count: u64,
+    pub map: Map,
+}
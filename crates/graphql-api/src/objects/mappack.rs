@@ -1,21 +1,30 @@
 use mkenv::prelude::*;
 use std::time::SystemTime;
 
-use deadpool_redis::redis::{self, AsyncCommands as _};
+use async_graphql::{ID, connection};
+use deadpool_redis::redis::{self, AsyncCommands as _, SetExpiry, SetOptions};
+use entity::{event, event_edition, maps};
 use records_lib::{
     Database, RedisPool,
     error::{RecordsError, RecordsResult},
+    expirable::refresh_if_stale,
     internal, map,
-    mappack::{AnyMappackId, update_mappack},
+    mappack::{self, AnyMappackId, update_mappack},
     must, player,
     redis_key::{
         mappack_key, mappack_lb_key, mappack_mx_created_key, mappack_mx_name_key,
         mappack_mx_username_key, mappack_nb_map_key, mappack_time_key,
     },
 };
-use sea_orm::{ConnectionTrait, DbConn};
+use sea_orm::{ColumnTrait as _, ConnectionTrait, DbConn};
 
-use crate::{error::GqlResult, objects::mappack_player::MappackPlayer};
+use crate::{
+    cursors::ConnectionParameters,
+    error::{self, GqlResult},
+    objects::{
+        map::maps_by_name_connection, mappack_map::MappackMap, mappack_player::MappackPlayer,
+    },
+};
 
 #[derive(serde::Deserialize)]
 #[allow(non_snake_case)]
@@ -63,12 +72,18 @@ async fn fill_mappack<C: ConnectionTrait>(
     // These keys would probably be null for some mappacks, because they would belong
     // to an event edition, so these info would be retrieved from our information system.
 
-    pipe.set(mappack_mx_username_key(mappack), info.Username)
+    let set_options = match mappack.get_ttl() {
+        Some(ttl) => SetOptions::default().with_expiration(SetExpiry::EX(ttl as _)),
+        None => SetOptions::default(),
+    };
+
+    pipe.set_options(mappack_mx_username_key(mappack), info.Username, set_options)
         .ignore();
 
-    pipe.set(mappack_mx_name_key(mappack), info.Name).ignore();
+    pipe.set_options(mappack_mx_name_key(mappack), info.Name, set_options)
+        .ignore();
 
-    pipe.set(mappack_mx_created_key(mappack), info.Created)
+    pipe.set_options(mappack_mx_created_key(mappack), info.Created, set_options)
         .ignore();
 
     let mut redis_conn = redis_pool.get().await?;
@@ -80,6 +95,8 @@ async fn fill_mappack<C: ConnectionTrait>(
 pub struct Mappack {
     pub(crate) event_has_expired: bool,
     pub(crate) mappack_id: String,
+    /// The event edition of the mappack, if it belongs to one.
+    pub(crate) event: Option<(event::Model, event_edition::Model)>,
 }
 
 impl From<String> for Mappack {
@@ -87,6 +104,7 @@ impl From<String> for Mappack {
         Self {
             mappack_id,
             event_has_expired: false,
+            event: None,
         }
     }
 }
@@ -160,7 +178,7 @@ impl Mappack {
         let mut out = Vec::with_capacity(leaderboard.len());
 
         for id in leaderboard {
-            let player = player::get_player_from_id(&db.sql_conn, id).await?;
+            let player = player::get_player_from_id(db.read_conn(), id).await?;
             out.push(MappackPlayer {
                 inner: player.into(),
                 mappack: self,
@@ -170,19 +188,117 @@ impl Mappack {
         Ok(out)
     }
 
+    /// The maps of the mappack sorted by name.
+    async fn maps(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> GqlResult<connection::Connection<ID, MappackMap>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        let map_uids: Vec<String> = {
+            let mut redis_conn = db.redis_pool.get().await?;
+            redis_conn
+                .smembers(mappack_key(AnyMappackId::Id(&self.mappack_id)))
+                .await?
+        };
+
+        connection::query_with(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                maps_by_name_connection(
+                    db.read_conn(),
+                    maps::Column::GameId.is_in(map_uids),
+                    ConnectionParameters {
+                        after,
+                        before,
+                        first,
+                        last,
+                    },
+                    |map| MappackMap {
+                        rank: None,
+                        map: map.into(),
+                        mappack_id: self.mappack_id.clone(),
+                        event: self.event.clone(),
+                    },
+                )
+                .await
+            },
+        )
+        .await
+        .map_err(error::map_gql_err)
+    }
+
+    /// The standing of the player with the provided login in the mappack, or `null` if they
+    /// don't have any record on its maps.
     async fn player<'a>(
         &'a self,
         ctx: &async_graphql::Context<'_>,
         login: String,
-    ) -> GqlResult<MappackPlayer<'a>> {
+    ) -> GqlResult<Option<MappackPlayer<'a>>> {
         let conn = ctx.data_unchecked::<DbConn>();
+        let redis_pool = ctx.data_unchecked::<RedisPool>();
 
         let player = must::have_player(conn, &login).await?;
 
-        Ok(MappackPlayer {
+        let rank: Option<u32> = {
+            let mut redis_conn = redis_pool.get().await?;
+            redis_conn
+                .zscore(
+                    mappack_lb_key(AnyMappackId::Id(&self.mappack_id)),
+                    player.id,
+                )
+                .await?
+        };
+
+        Ok(rank.map(|_| MappackPlayer {
             inner: player.into(),
             mappack: self,
-        })
+        }))
+    }
+
+    /// The date of the last computation of the scores of the mappack.
+    async fn last_computed(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> GqlResult<Option<chrono::DateTime<chrono::Utc>>> {
+        let redis_pool = ctx.data_unchecked::<RedisPool>();
+        let redis_conn = &mut redis_pool.get().await?;
+        let last_computed =
+            mappack::last_computed(redis_conn, AnyMappackId::Id(&self.mappack_id)).await?;
+        Ok(last_computed
+            .and_then(|last| i64::try_from(last).ok())
+            .and_then(|last| chrono::DateTime::from_timestamp(last, 0)))
+    }
+
+    /// Whether the scores of the mappack are older than the refresh interval.
+    ///
+    /// The scores of the mappack of an expired event are never stale, because they don't change
+    /// anymore.
+    async fn stale(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<bool> {
+        if self.event_has_expired {
+            return Ok(false);
+        }
+
+        let redis_pool = ctx.data_unchecked::<RedisPool>();
+        let redis_conn = &mut redis_pool.get().await?;
+        let last_computed =
+            mappack::last_computed(redis_conn, AnyMappackId::Id(&self.mappack_id)).await?;
+        let now = SystemTime::UNIX_EPOCH
+            .elapsed()
+            .unwrap_or_default()
+            .as_secs();
+        Ok(mappack::is_stale(
+            last_computed,
+            now,
+            records_lib::env().event_scores_interval.get(),
+        ))
     }
 
     async fn next_update_in(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<Option<u64>> {
@@ -245,6 +361,20 @@ pub async fn get_mappack(
             Default::default(),
         )
         .await?;
+    } else {
+        // The maps of the mappack are kept, but its scores may have expired
+        let last_computed = {
+            let mut redis_conn = db.redis_pool.get().await?;
+            mappack::last_computed(&mut redis_conn, mappack).await?
+        };
+        refresh_if_stale(
+            &mappack.scores_cache_entry(last_computed),
+            chrono::Utc::now().naive_utc(),
+            async || {
+                update_mappack(&db.sql_conn, &db.redis_pool, mappack, Default::default()).await
+            },
+        )
+        .await?;
     }
 
     Ok(From::from(mappack_id))
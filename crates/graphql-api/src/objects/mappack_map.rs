@@ -1,10 +1,58 @@
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, SimpleObject};
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{event, event_edition};
+use records_lib::{
+    RedisPool, mappack::AnyMappackId, opt_event::OptEvent, redis_key::mappack_map_last_rank,
+};
 
-use crate::objects::map::Map;
+use crate::{
+    error::GqlResult,
+    objects::{
+        map::{MAP_RECORDS_MAX_LIMIT, Map},
+        ranked_record::RankedRecord,
+    },
+};
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct MappackMap {
-    pub rank: i32,
-    pub last_rank: i32,
+    /// The rank of the player on the map, when the map is listed from the ranks of a player.
+    pub rank: Option<i32>,
     pub map: Map,
+    #[graphql(skip)]
+    pub(crate) mappack_id: String,
+    #[graphql(skip)]
+    pub(crate) event: Option<(event::Model, event_edition::Model)>,
+}
+
+#[ComplexObject]
+impl MappackMap {
+    async fn last_rank(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<i32> {
+        let redis_pool = ctx.data_unchecked::<RedisPool>();
+        let redis_conn = &mut redis_pool.get().await?;
+        let last_rank = redis_conn
+            .get(mappack_map_last_rank(
+                AnyMappackId::Id(&self.mappack_id),
+                &self.map.inner.game_id,
+            ))
+            .await?;
+        Ok(last_rank)
+    }
+
+    /// The best records of the map, in the event edition of the mappack if it belongs to one.
+    async fn leaderboard(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        #[graphql(desc = "Number of records to fetch (default and max: 100)")] limit: Option<usize>,
+    ) -> GqlResult<Vec<RankedRecord>> {
+        let event = match &self.event {
+            Some((event, edition)) => OptEvent::new(event, edition),
+            None => Default::default(),
+        };
+        let limit = limit
+            .unwrap_or(MAP_RECORDS_MAX_LIMIT)
+            .min(MAP_RECORDS_MAX_LIMIT);
+
+        self.map.get_records(ctx, event, None, None, limit).await
+    }
 }
@@ -4,8 +4,8 @@ use records_lib::{
     mappack::AnyMappackId,
     must,
     redis_key::{
-        mappack_lb_key, mappack_map_last_rank, mappack_player_map_finished_key,
-        mappack_player_rank_avg_key, mappack_player_ranks_key, mappack_player_worst_rank_key,
+        mappack_lb_key, mappack_player_map_finished_key, mappack_player_rank_avg_key,
+        mappack_player_ranks_key, mappack_player_worst_rank_key,
     },
 };
 
@@ -111,18 +111,13 @@ impl MappackPlayer<'_> {
                     self.mappack.mappack_id
                 )
             })?;
-            let last_rank = redis_conn
-                .get(mappack_map_last_rank(
-                    AnyMappackId::Id(&self.mappack.mappack_id),
-                    game_id,
-                ))
-                .await?;
-            let map = must::have_map(&db.sql_conn, game_id).await?;
+            let map = must::have_map(db.read_conn(), game_id).await?;
 
             out.push(MappackMap {
+                rank: Some(rank),
                 map: map.into(),
-                rank,
-                last_rank,
+                mappack_id: self.mappack.mappack_id.clone(),
+                event: self.mappack.event.clone(),
             });
         }
 
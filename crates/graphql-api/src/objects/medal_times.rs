@@ -1,5 +1,6 @@
 use records_lib::event;
 
+#[derive(Clone)]
 pub struct MedalTimes {
     pub inner: event::MedalTimes,
 }
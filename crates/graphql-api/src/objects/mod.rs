@@ -1,13 +1,16 @@
 pub mod checkpoint_time;
 pub mod medal_times;
 pub mod ranked_record;
+pub mod records_connection;
 pub mod root;
 
 pub mod map_filter;
 pub mod player_filter;
 pub mod records_filter;
 
+pub mod map_with_record_count;
 pub mod map_with_score;
+pub mod player_best_rank;
 pub mod player_with_score;
 
 pub mod sort;
@@ -29,9 +32,11 @@ pub mod mappack_map;
 pub mod mappack_player;
 
 pub mod map;
+pub mod map_stats;
 pub mod related_edition;
 
 pub mod player_rating;
 pub mod rating_kind;
 
+pub mod banishment;
 pub mod player;
@@ -1,6 +1,6 @@
 use async_graphql::{Enum, ID, connection};
-use entity::{global_records, players, records, role};
-use records_lib::{Database, ranks};
+use entity::{global_records, maps, players, records, role};
+use records_lib::{Database, player, ranks, time::Time};
 use records_lib::{RedisPool, error::RecordsError, internal, opt_event::OptEvent, sync};
 use sea_orm::{
     ColumnTrait as _, ConnectionTrait, DbConn, EntityTrait as _, FromQueryResult, QueryFilter as _,
@@ -8,6 +8,8 @@ use sea_orm::{
 };
 
 use crate::cursors::RecordDateCursor;
+use crate::objects::map::{Map, maps_by_name_connection};
+use crate::objects::player_best_rank::PlayerBestRank;
 use crate::objects::records_filter::RecordsFilter;
 use crate::objects::root::get_records_connection_impl;
 use crate::objects::sort::UnorderedRecordSort;
@@ -15,7 +17,11 @@ use crate::utils::records_filter::apply_filter;
 use crate::{
     cursors::ConnectionParameters,
     error::GqlResult,
-    objects::{ranked_record::RankedRecord, sort_state::SortState},
+    objects::{
+        ranked_record::{RankedRecord, RecordRelations},
+        records_connection::RecordsConnection,
+        sort_state::SortState,
+    },
 };
 
 use crate::error;
@@ -94,7 +100,7 @@ impl Player {
     ) -> GqlResult<Vec<RankedRecord>> {
         let db = ctx.data_unchecked::<Database>();
 
-        records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
+        records_lib::assert_future_send(sync::transaction(db.read_conn(), async |txn| {
             get_player_records(
                 txn,
                 &db.redis_pool,
@@ -107,6 +113,98 @@ impl Player {
         .await
     }
 
+    async fn percentile(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<Option<f64>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
+            let percentile = ranks::player_percentile(txn, &db.redis_pool, self.inner.id).await?;
+            GqlResult::Ok(percentile)
+        }))
+        .await
+    }
+
+    /// The median of the ranks of the player on all the maps where they have a record.
+    async fn median_rank(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<Option<i32>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
+            let median_rank = ranks::median_rank(txn, &db.redis_pool, self.inner.id).await?;
+            GqlResult::Ok(median_rank)
+        }))
+        .await
+    }
+
+    /// The sum of the best times of the player on all the maps, formatted as `HH:MM:SS.cc`.
+    async fn total_best_time(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<String> {
+        let conn = ctx.data_unchecked::<DbConn>();
+        let total = player::total_best_time(conn, self.inner.id).await?;
+        // The formatting only supports times up to about 596 hours
+        let total = Time(total.try_into().unwrap_or(i32::MAX));
+        Ok(total.to_string())
+    }
+
+    async fn best_rank(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> GqlResult<Option<PlayerBestRank>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
+            let Some((map_id, rank)) =
+                ranks::best_rank_achieved(txn, &db.redis_pool, self.inner.id).await?
+            else {
+                return Ok(None);
+            };
+            let map = records_lib::must::have_map_by_id(txn, map_id).await?;
+            GqlResult::Ok(Some(PlayerBestRank {
+                rank,
+                map: map.into(),
+            }))
+        }))
+        .await
+    }
+
+    /// The maps where the player holds the world record, sorted by name.
+    async fn world_records_connection(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> GqlResult<connection::Connection<ID, Map>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        let map_ids =
+            records_lib::assert_future_send(sync::transaction(&db.sql_conn, async |txn| {
+                ranks::world_records_of(txn, &db.redis_pool, self.inner.id).await
+            }))
+            .await?;
+
+        connection::query_with(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                maps_by_name_connection(
+                    db.read_conn(),
+                    maps::Column::Id.is_in(map_ids),
+                    ConnectionParameters {
+                        after,
+                        before,
+                        first,
+                        last,
+                    },
+                    Map::from,
+                )
+                .await
+            },
+        )
+        .await
+        .map_err(error::map_gql_err)
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn records_connection(
         &self,
@@ -120,8 +218,10 @@ impl Player {
         #[graphql(desc = "Number of records to fetch from the end (for backward pagination)")] last: Option<i32>,
         sort: Option<UnorderedRecordSort>,
         filter: Option<RecordsFilter>,
-    ) -> GqlResult<connection::Connection<ID, RankedRecord>> {
+    ) -> GqlResult<RecordsConnection> {
         let db = ctx.data_unchecked::<Database>();
+        let relations =
+            RecordRelations::from_look_ahead(ctx.look_ahead().field("edges").field("node"));
 
         connection::query_with(
             after,
@@ -130,7 +230,7 @@ impl Player {
             last,
             |after, before, first, last| async move {
                 get_player_records_connection(
-                    &db.sql_conn,
+                    db.read_conn(),
                     &db.redis_pool,
                     self.inner.id,
                     Default::default(),
@@ -142,6 +242,7 @@ impl Player {
                     },
                     sort,
                     filter,
+                    relations,
                 )
                 .await
             },
@@ -200,7 +301,8 @@ pub(crate) async fn get_player_records_connection<C>(
     connection_parameters: ConnectionParameters<RecordDateCursor>,
     sort: Option<UnorderedRecordSort>,
     filter: Option<RecordsFilter>,
-) -> GqlResult<connection::Connection<ID, RankedRecord>>
+    relations: RecordRelations,
+) -> GqlResult<RecordsConnection>
 where
     C: ConnectionTrait + TransactionTrait,
 {
@@ -216,6 +318,7 @@ where
         event,
         sort,
         base_query,
+        relations,
     )
     .await
 }
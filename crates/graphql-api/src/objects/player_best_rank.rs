@@ -0,0 +1,9 @@
+use async_graphql::SimpleObject;
+
+use crate::objects::map::Map;
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PlayerBestRank {
+    pub rank: i32,
+    pub map: Map,
+}
@@ -1,6 +1,6 @@
-use async_graphql::{Context, dataloader::DataLoader};
+use async_graphql::{Context, Lookahead, dataloader::DataLoader};
 use entity::{checkpoint_times, records};
-use records_lib::internal;
+use records_lib::{error::RecordsError, internal};
 use sea_orm::{
     ColumnTrait as _, DbConn, EntityTrait as _, QueryFilter as _, QueryOrder as _,
     QuerySelect as _, prelude::Expr, sea_query::Func,
@@ -8,18 +8,47 @@ use sea_orm::{
 
 use crate::{
     error::GqlResult,
-    loaders::{map::MapLoader, player::PlayerLoader},
-    objects::{checkpoint_time::CheckpointTime, map::Map, player::Player},
+    loaders::{map::MapLoader, player::PlayerLoader, record_editions::RecordEditionsLoader},
+    objects::{
+        checkpoint_time::CheckpointTime, event_edition::EventEdition, map::Map, player::Player,
+    },
 };
 
+/// The relations of the records to load with them in the same query, because they're requested.
+///
+/// This avoids resolving the player and the map of each record separately.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RecordRelations {
+    pub(crate) player: bool,
+    pub(crate) map: bool,
+}
+
+impl RecordRelations {
+    /// Returns the relations requested on the provided record selection.
+    pub(crate) fn from_look_ahead(record: Lookahead<'_>) -> Self {
+        Self {
+            player: record.field("player").exists(),
+            map: record.field("map").exists(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RankedRecord {
     pub inner: records::RankedRecord,
+    /// The player of the record, if it was loaded with the record.
+    pub(crate) player: Option<Player>,
+    /// The map of the record, if it was loaded with the record.
+    pub(crate) map: Option<Map>,
 }
 
 impl From<records::RankedRecord> for RankedRecord {
     fn from(inner: records::RankedRecord) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            player: None,
+            map: None,
+        }
     }
 }
 
@@ -34,23 +63,24 @@ impl RankedRecord {
     }
 
     async fn map(&self, ctx: &Context<'_>) -> GqlResult<Map> {
-        let opt_map = ctx
+        if let Some(map) = &self.map {
+            return Ok(map.clone());
+        }
+
+        let map = ctx
             .data_unchecked::<DataLoader<MapLoader>>()
             .load_one(self.inner.record.map_id)
-            .await?;
-
-        let map = opt_map.ok_or_else(|| {
-            internal!(
-                "map of record {} not found: {}",
-                self.inner.record.record_id,
-                self.inner.record.map_id
-            )
-        })?;
+            .await?
+            .ok_or(RecordsError::MapIdNotFound(self.inner.record.map_id))?;
 
         Ok(map)
     }
 
     async fn player(&self, ctx: &Context<'_>) -> GqlResult<Player> {
+        if let Some(player) = &self.player {
+            return Ok(player.clone());
+        }
+
         let opt_player = ctx
             .data_unchecked::<DataLoader<PlayerLoader>>()
             .load_one(self.inner.record.record_player_id)
@@ -150,4 +180,15 @@ impl RankedRecord {
     async fn flags(&self) -> u32 {
         self.inner.record.flags
     }
+
+    /// The event editions the record counts toward.
+    async fn editions(&self, ctx: &Context<'_>) -> GqlResult<Vec<EventEdition<'static>>> {
+        let editions = ctx
+            .data_unchecked::<DataLoader<RecordEditionsLoader>>()
+            .load_one(self.inner.record.record_id)
+            .await?
+            .unwrap_or_default();
+
+        Ok(editions)
+    }
 }
@@ -0,0 +1,62 @@
+use async_graphql::{ID, connection};
+use records_lib::Database;
+use sea_orm::{
+    ConnectionTrait as _,
+    prelude::Expr,
+    sea_query::{Asterisk, ExprTrait as _, Query, SelectStatement},
+};
+
+use crate::{error::GqlResult, objects::ranked_record::RankedRecord};
+
+/// A connection of records, with their total count.
+pub(crate) type RecordsConnection =
+    connection::Connection<ID, RankedRecord, RecordsConnectionFields>;
+
+/// The additional fields of the connections of records.
+pub(crate) struct RecordsConnectionFields {
+    /// The query of the records matching the filters of the connection, without the pagination.
+    query: SelectStatement,
+}
+
+impl RecordsConnectionFields {
+    pub(crate) fn new(query: SelectStatement) -> Self {
+        Self { query }
+    }
+
+    /// Returns the provided connection with these fields.
+    pub(crate) fn attach(
+        self,
+        page: connection::Connection<ID, RankedRecord>,
+    ) -> RecordsConnection {
+        let mut connection = connection::Connection::with_additional_fields(
+            page.has_previous_page,
+            page.has_next_page,
+            self,
+        );
+        connection.edges = page.edges;
+        connection
+    }
+}
+
+#[async_graphql::Object]
+impl RecordsConnectionFields {
+    /// The total amount of records matching the filters, regardless of the pagination.
+    ///
+    /// It's only counted when requested.
+    async fn total_count(&self, ctx: &async_graphql::Context<'_>) -> GqlResult<u64> {
+        let conn = ctx.data_unchecked::<Database>().read_conn();
+
+        let query = Query::select()
+            .expr(Expr::col(Asterisk).count())
+            .from_subquery(self.query.clone(), "filtered")
+            .take();
+        let stmt = conn.get_database_backend().build(&query);
+
+        let count = match conn.query_one(stmt).await? {
+            Some(result) => result.try_get_by_index::<i64>(0)?,
+            None => 0,
+        };
+
+        Ok(count as _)
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use async_graphql::{
-    ID,
+    ID, Lookahead,
     connection::{self, CursorType},
 };
 use deadpool_redis::redis::{AsyncCommands, ToRedisArgs};
@@ -17,8 +17,9 @@ use records_lib::{
     sync,
 };
 use sea_orm::{
-    ColumnTrait, ConnectionTrait, DbConn, EntityTrait, FromQueryResult, Identity, QueryFilter as _,
-    QueryOrder as _, QuerySelect, QueryTrait, RelationTrait as _, Select, SelectModel, StreamTrait,
+    ColumnTrait, ConnectionTrait, DbConn, DbErr, EntityTrait, FromQueryResult, IdenStatic as _,
+    Identity, Iterable as _, JoinType, QueryFilter as _, QueryOrder as _, QueryResult, QuerySelect,
+    QueryTrait, RelationDef, RelationTrait as _, Select, SelectModel, StreamTrait,
     TransactionTrait,
     prelude::Expr,
     sea_query::{Asterisk, ExprTrait as _, Func, IntoIden, IntoValueTuple, SelectStatement},
@@ -26,26 +27,31 @@ use sea_orm::{
 
 use crate::{
     cursors::{
-        ConnectionParameters, F64Cursor, RecordDateCursor, TextCursor, expr_tuple::IntoExprTuple,
-        query_builder::CursorQueryBuilder, query_trait::CursorPaginable,
+        ConnectionParameters, F64Cursor, RecordDateCursor, RecordSortCursor, SortKeyValue,
+        TextCursor, expr_tuple::IntoExprTuple, query_builder::CursorQueryBuilder,
+        query_trait::CursorPaginable,
     },
     error::{self, ApiGqlError, CursorDecodeError, CursorDecodeErrorKind, GqlResult},
     objects::{
         event::Event,
         event_edition::EventEdition,
-        map::Map,
+        map::{self, Map},
         map_filter::MapsFilter,
+        map_with_record_count::MapWithRecordCount,
         map_with_score::MapWithScore,
         mappack::{self, Mappack},
         player::Player,
         player_filter::PlayersFilter,
         player_with_score::PlayerWithScore,
-        ranked_record::RankedRecord,
+        ranked_record::{RankedRecord, RecordRelations},
+        records_connection::{RecordsConnection, RecordsConnectionFields},
         records_filter::RecordsFilter,
-        sort::{PlayerMapRankingSort, UnorderedRecordSort},
+        sort::{PlayerMapRankingSort, RecordSort, UnorderedRecordSort},
         sort_order::SortOrder,
         sort_state::SortState,
-        sortable_fields::{PlayerMapRankingSortableField, UnorderedRecordSortableField},
+        sortable_fields::{
+            PlayerMapRankingSortableField, RecordSortableField, UnorderedRecordSortableField,
+        },
     },
     utils::{
         connection_input::{ConnectionInput, ConnectionInputBuilder},
@@ -80,13 +86,24 @@ async fn get_record<C: ConnectionTrait + StreamTrait>(
     Ok(out)
 }
 
+/// The default amount of records returned by the legacy `records` query.
+const LEGACY_RECORDS_DEFAULT_LIMIT: usize = 100;
+
 async fn get_records<C: ConnectionTrait + StreamTrait>(
     conn: &C,
     redis_pool: &RedisPool,
     date_sort_by: Option<SortState>,
+    limit: Option<usize>,
     event: OptEvent<'_>,
 ) -> GqlResult<Vec<RankedRecord>> {
+    let limit = limit
+        .unwrap_or(LEGACY_RECORDS_DEFAULT_LIMIT)
+        .min(crate::config::config().cursor_max_limit.get());
+
     let records = global_records::Entity::find()
+        .filter(
+            global_records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()),
+        )
         .order_by(
             global_records::Column::RecordDate,
             match date_sort_by {
@@ -94,7 +111,7 @@ async fn get_records<C: ConnectionTrait + StreamTrait>(
                 _ => sea_orm::Order::Desc,
             },
         )
-        .limit(100)
+        .limit(limit as u64)
         .all(conn)
         .await?;
 
@@ -117,6 +134,70 @@ async fn get_records<C: ConnectionTrait + StreamTrait>(
     Ok(ranked_records)
 }
 
+const EAGER_PLAYER_PREFIX: &str = "eager_player_";
+const EAGER_MAP_PREFIX: &str = "eager_map_";
+
+/// A record with its player and its map, if they were joined in the query.
+struct RecordWithRelations {
+    record: global_records::Model,
+    player: Option<players::Model>,
+    map: Option<maps::Model>,
+}
+
+impl FromQueryResult for RecordWithRelations {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        // The columns of the relations are only selected if they were joined
+        let joined = |prefix: &str| res.try_get::<u32>(prefix, "id").is_ok();
+
+        Ok(Self {
+            record: global_records::Model::from_query_result(res, pre)?,
+            player: joined(EAGER_PLAYER_PREFIX)
+                .then(|| players::Model::from_query_result(res, EAGER_PLAYER_PREFIX))
+                .transpose()?,
+            map: joined(EAGER_MAP_PREFIX)
+                .then(|| maps::Model::from_query_result(res, EAGER_MAP_PREFIX))
+                .transpose()?,
+        })
+    }
+}
+
+/// Joins the table of the provided relation, and selects its columns with the provided prefix.
+fn join_relation<E: EntityTrait>(
+    query: Select<global_records::Entity>,
+    relation: RelationDef,
+    prefix: &'static str,
+) -> Select<global_records::Entity> {
+    let query = query.join_as(JoinType::InnerJoin, relation, prefix);
+    E::Column::iter().fold(query, |query, column| {
+        query.expr_as(
+            Expr::col((prefix, column)),
+            format!("{prefix}{}", column.as_str()),
+        )
+    })
+}
+
+/// Joins the relations of the records requested by the query.
+fn with_relations(
+    query: Select<global_records::Entity>,
+    relations: RecordRelations,
+) -> Select<global_records::Entity> {
+    query
+        .apply_if(relations.player.then_some(()), |query, _| {
+            join_relation::<players::Entity>(
+                query,
+                global_records::Relation::Players.def(),
+                EAGER_PLAYER_PREFIX,
+            )
+        })
+        .apply_if(relations.map.then_some(()), |query, _| {
+            join_relation::<maps::Entity>(
+                query,
+                global_records::Relation::Maps.def(),
+                EAGER_MAP_PREFIX,
+            )
+        })
+}
+
 pub(crate) async fn get_records_connection_impl<C: ConnectionTrait + TransactionTrait>(
     conn: &C,
     redis_pool: &RedisPool,
@@ -124,13 +205,21 @@ pub(crate) async fn get_records_connection_impl<C: ConnectionTrait + Transaction
     event: OptEvent<'_>,
     sort: Option<UnorderedRecordSort>,
     base_query: Select<global_records::Entity>,
-) -> GqlResult<connection::Connection<ID, RankedRecord>> {
-    let pagination_input = PaginationInput::try_from_input(connection_parameters)?;
+    relations: RecordRelations,
+) -> GqlResult<RecordsConnection> {
+    let pagination_input = PaginationInput::try_from_input(
+        connection_parameters,
+        crate::config::records_default_limit(),
+    )?;
+
+    let fields = RecordsConnectionFields::new(base_query.clone().into_query());
 
-    let mut query = base_query.paginate_cursor_by((
-        global_records::Column::RecordDate,
-        global_records::Column::RecordId,
-    ));
+    let mut query = with_relations(base_query, relations)
+        .paginate_cursor_by((
+            global_records::Column::RecordDate,
+            global_records::Column::RecordId,
+        ))
+        .into_model::<RecordWithRelations>();
 
     apply_cursor_input(&mut query, &pagination_input);
 
@@ -159,7 +248,12 @@ pub(crate) async fn get_records_connection_impl<C: ConnectionTrait + Transaction
 
     let mut redis_conn = redis_pool.get().await?;
 
-    for record in records {
+    for RecordWithRelations {
+        record,
+        player,
+        map,
+    } in records
+    {
         let rank = ranks::get_rank(&mut redis_conn, record.map_id, record.time, event).await?;
 
         connection.edges.push(connection::Edge::new(
@@ -168,15 +262,18 @@ pub(crate) async fn get_records_connection_impl<C: ConnectionTrait + Transaction
                 data: record.record_id,
             }
             .encode_cursor()),
-            records::RankedRecord {
-                rank,
-                record: record.into(),
-            }
-            .into(),
+            RankedRecord {
+                inner: records::RankedRecord {
+                    rank,
+                    record: record.into(),
+                },
+                player: player.map(From::from),
+                map: map.map(From::from),
+            },
         ));
     }
 
-    Ok(connection)
+    Ok(fields.attach(connection))
 }
 
 pub(crate) async fn get_records_connection<C: ConnectionTrait + TransactionTrait>(
@@ -186,8 +283,14 @@ pub(crate) async fn get_records_connection<C: ConnectionTrait + TransactionTrait
     event: OptEvent<'_>,
     sort: Option<UnorderedRecordSort>,
     filter: Option<RecordsFilter>,
-) -> GqlResult<connection::Connection<ID, RankedRecord>> {
-    let base_query = apply_filter(global_records::Entity::find(), filter.as_ref());
+    relations: RecordRelations,
+) -> GqlResult<RecordsConnection> {
+    let base_query = apply_filter(
+        global_records::Entity::find().filter(
+            global_records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()),
+        ),
+        filter.as_ref(),
+    );
 
     get_records_connection_impl(
         conn,
@@ -196,10 +299,161 @@ pub(crate) async fn get_records_connection<C: ConnectionTrait + TransactionTrait
         event,
         sort,
         base_query,
+        relations,
     )
     .await
 }
 
+/// The maximum amount of keys the records can be sorted by.
+const MAX_RECORD_SORT_KEYS: usize = 2;
+
+fn invalid_sort_error(message: &str) -> ApiGqlError {
+    ApiGqlError::from_gql_error(async_graphql::Error::new(message))
+}
+
+fn sort_column(field: RecordSortableField) -> global_records::Column {
+    match field {
+        RecordSortableField::Map => global_records::Column::MapId,
+        RecordSortableField::Date => global_records::Column::RecordDate,
+        RecordSortableField::Time => global_records::Column::Time,
+    }
+}
+
+fn sort_key_value(field: RecordSortableField, record: &global_records::Model) -> SortKeyValue {
+    match field {
+        RecordSortableField::Map => SortKeyValue::Map(record.map_id),
+        RecordSortableField::Date => SortKeyValue::Date(record.record_date.and_utc()),
+        RecordSortableField::Time => SortKeyValue::Time(record.time),
+    }
+}
+
+fn sort_key_field(value: &SortKeyValue) -> RecordSortableField {
+    match value {
+        SortKeyValue::Map(_) => RecordSortableField::Map,
+        SortKeyValue::Date(_) => RecordSortableField::Date,
+        SortKeyValue::Time(_) => RecordSortableField::Time,
+    }
+}
+
+/// Returns the connection of the records, sorted by the provided keys, then by their ID.
+///
+/// There can be at most [`MAX_RECORD_SORT_KEYS`] keys, and they must share the same order, so
+/// the records can be compared with their cursor as a single tuple.
+pub(crate) async fn get_sorted_records_connection<C: ConnectionTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    connection_parameters: ConnectionParameters<RecordSortCursor>,
+    event: OptEvent<'_>,
+    sorts: &[RecordSort],
+    filter: Option<RecordsFilter>,
+    relations: RecordRelations,
+) -> GqlResult<RecordsConnection> {
+    if sorts.is_empty() || sorts.len() > MAX_RECORD_SORT_KEYS {
+        return Err(invalid_sort_error(&format!(
+            "the records must be sorted by 1 to {MAX_RECORD_SORT_KEYS} keys"
+        )));
+    }
+    if sorts
+        .iter()
+        .enumerate()
+        .any(|(i, sort)| sorts[..i].iter().any(|prev| prev.field == sort.field))
+    {
+        return Err(invalid_sort_error(
+            "the records can't be sorted twice by the same key",
+        ));
+    }
+    let order = sorts[0].order.unwrap_or(SortOrder::Ascending);
+    if sorts
+        .iter()
+        .any(|sort| sort.order.unwrap_or(SortOrder::Ascending) != order)
+    {
+        return Err(invalid_sort_error(
+            "the sort keys of the records must share the same order",
+        ));
+    }
+
+    let pagination_input = PaginationInput::try_from_input(
+        connection_parameters,
+        crate::config::records_default_limit(),
+    )?;
+
+    if let Some(cursor) = pagination_input.get_cursor()
+        && !cursor
+            .keys
+            .iter()
+            .map(sort_key_field)
+            .eq(sorts.iter().map(|sort| sort.field))
+    {
+        return Err(invalid_sort_error(
+            "the cursor doesn't match the sort of the records",
+        ));
+    }
+
+    let base_query = apply_filter(
+        global_records::Entity::find().filter(
+            global_records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()),
+        ),
+        filter.as_ref(),
+    );
+    let fields = RecordsConnectionFields::new(base_query.clone().into_query());
+
+    let order_columns = sorts
+        .iter()
+        .map(|sort| sort_column(sort.field).into_iden())
+        .chain([global_records::Column::RecordId.into_iden()])
+        .collect();
+
+    let mut query = with_relations(base_query, relations)
+        .paginate_cursor_by(Identity::Many(order_columns))
+        .into_model::<RecordWithRelations>();
+
+    apply_cursor_input(&mut query, &pagination_input);
+
+    match order {
+        SortOrder::Ascending => query.asc(),
+        SortOrder::Descending => query.desc(),
+    };
+
+    let PaginationResult {
+        mut connection,
+        iter: records,
+    } = get_paginated(conn, query, &pagination_input).await?;
+
+    connection.edges.reserve(records.len());
+
+    let mut redis_conn = redis_pool.get().await?;
+
+    for RecordWithRelations {
+        record,
+        player,
+        map,
+    } in records
+    {
+        let rank = ranks::get_rank(&mut redis_conn, record.map_id, record.time, event).await?;
+
+        connection.edges.push(connection::Edge::new(
+            ID(RecordSortCursor {
+                keys: sorts
+                    .iter()
+                    .map(|sort| sort_key_value(sort.field, &record))
+                    .collect(),
+                data: record.record_id,
+            }
+            .encode_cursor()),
+            RankedRecord {
+                inner: records::RankedRecord {
+                    rank,
+                    record: record.into(),
+                },
+                player: player.map(From::from),
+                map: map.map(From::from),
+            },
+        ));
+    }
+
+    Ok(fields.attach(connection))
+}
+
 #[async_graphql::Object]
 impl QueryRoot {
     async fn event_edition_from_mx_id(
@@ -349,16 +603,24 @@ impl QueryRoot {
             .map(From::from)
     }
 
+    #[graphql(
+        deprecation = "Use `recordsConnection` instead, which supports pagination. \
+            `dateSortBy: REVERSE` is equivalent to `sort: { field: DATE, order: DESCENDING }`."
+    )]
     async fn records(
         &self,
         ctx: &async_graphql::Context<'_>,
         date_sort_by: Option<SortState>,
+        #[graphql(
+            desc = "Number of records to fetch (default: 100, max: the maximum page size of the connections)"
+        )]
+        limit: Option<usize>,
     ) -> GqlResult<Vec<RankedRecord>> {
         let db = ctx.data_unchecked::<Database>();
         let conn = ctx.data_unchecked::<DbConn>();
 
         sync::transaction(conn, async |txn| {
-            get_records(txn, &db.redis_pool, date_sort_by, Default::default()).await
+            get_records(txn, &db.redis_pool, date_sort_by, limit, Default::default()).await
         })
         .await
     }
@@ -393,7 +655,7 @@ impl QueryRoot {
                 .with_sort(sort)
                 .build(player_ranking());
 
-                get_players_connection(&db.sql_conn, &mut redis_conn, input).await
+                get_players_connection(db.read_conn(), &mut redis_conn, input).await
             },
         )
         .await
@@ -413,6 +675,7 @@ impl QueryRoot {
     ) -> GqlResult<connection::Connection<ID, MapWithScore>> {
         let db = ctx.data_unchecked::<Database>();
         let mut redis_conn = db.redis_pool.get().await?;
+        let projection = MapsProjection::from_look_ahead(ctx.look_ahead());
 
         connection::query_with(
             after,
@@ -430,7 +693,39 @@ impl QueryRoot {
                 .with_sort(sort)
                 .build(map_ranking());
 
-                get_maps_connection(&db.sql_conn, &mut redis_conn, input).await
+                get_maps_connection(db.read_conn(), &mut redis_conn, input, projection).await
+            },
+        )
+        .await
+        .map_err(error::map_gql_err)
+    }
+
+    async fn most_played_maps(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> GqlResult<connection::Connection<ID, MapWithRecordCount>> {
+        let db = ctx.data_unchecked::<Database>();
+
+        connection::query_with(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                map::most_played_connection(
+                    db.read_conn(),
+                    ConnectionParameters {
+                        after,
+                        before,
+                        first,
+                        last,
+                    },
+                )
+                .await
             },
         )
         .await
@@ -449,9 +744,49 @@ impl QueryRoot {
         #[graphql(desc = "Number of records to fetch (default: 50, max: 100)")] first: Option<i32>,
         #[graphql(desc = "Number of records to fetch from the end (for backward pagination)")] last: Option<i32>,
         sort: Option<UnorderedRecordSort>,
+        #[graphql(
+            desc = "The keys to sort the records by, in order of priority (at most 2, sharing the same order). This can't be used with `sort`."
+        )]
+        sorts: Option<Vec<RecordSort>>,
         filter: Option<RecordsFilter>,
-    ) -> GqlResult<connection::Connection<ID, RankedRecord>> {
+    ) -> GqlResult<RecordsConnection> {
         let db = ctx.data_unchecked::<Database>();
+        let relations =
+            RecordRelations::from_look_ahead(ctx.look_ahead().field("edges").field("node"));
+
+        if let Some(sorts) = sorts {
+            if sort.is_some() {
+                return Err(invalid_sort_error(
+                    "`sort` and `sorts` can't be used together",
+                ));
+            }
+
+            return connection::query_with(
+                after,
+                before,
+                first,
+                last,
+                |after, before, first, last| async move {
+                    get_sorted_records_connection(
+                        db.read_conn(),
+                        &db.redis_pool,
+                        ConnectionParameters {
+                            after,
+                            before,
+                            first,
+                            last,
+                        },
+                        Default::default(),
+                        &sorts,
+                        filter,
+                        relations,
+                    )
+                    .await
+                },
+            )
+            .await
+            .map_err(error::map_gql_err);
+        }
 
         connection::query_with(
             after,
@@ -460,7 +795,7 @@ impl QueryRoot {
             last,
             |after, before, first, last| async move {
                 get_records_connection(
-                    &db.sql_conn,
+                    db.read_conn(),
                     &db.redis_pool,
                     ConnectionParameters {
                         after,
@@ -471,6 +806,7 @@ impl QueryRoot {
                     Default::default(),
                     sort,
                     filter,
+                    relations,
                 )
                 .await
             },
@@ -560,7 +896,10 @@ where
     C: ConnectionTrait,
     S: ToRedisArgs + Send + Sync,
 {
-    let pagination_input = PaginationInput::try_from_input(input.connection_parameters)?;
+    let pagination_input = PaginationInput::try_from_input(
+        input.connection_parameters,
+        crate::config::players_default_limit(),
+    )?;
     let cursor_encoder = match input.sort.map(|s| s.field) {
         Some(PlayerMapRankingSortableField::Name) => |player: &PlayerWithUnstyledName| {
             TextCursor {
@@ -669,46 +1008,118 @@ where
 pub(crate) type MapsConnectionInput<S = MapRanking> =
     ConnectionInput<PlayerMapRankingCursor, MapsFilter, PlayerMapRankingSort, S>;
 
+/// The columns of the maps retrieved by the maps connection, depending on the fields requested
+/// by the GraphQL query.
+///
+/// The ID, the author and the score of the maps are always retrieved, because they're used by
+/// the cursors, the ranks and the related objects. The columns that aren't retrieved are
+/// selected as `NULL`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MapsProjection {
+    pub(crate) game_id: bool,
+    pub(crate) name: bool,
+    pub(crate) cps_number: bool,
+}
+
+impl Default for MapsProjection {
+    fn default() -> Self {
+        Self {
+            game_id: true,
+            name: true,
+            cps_number: true,
+        }
+    }
+}
+
+impl MapsProjection {
+    /// Returns the projection matching the fields requested on the maps of the provided
+    /// connection selection.
+    ///
+    /// The maps can be requested through both the edges and the nodes of the connection, so a
+    /// column is retrieved if it's requested by any of them.
+    pub(crate) fn from_look_ahead(connection: Lookahead<'_>) -> Self {
+        let maps = [
+            connection.field("edges").field("node").field("map"),
+            connection.field("nodes").field("map"),
+        ];
+        let is_requested = |field: &str| maps.iter().any(|map| map.field(field).exists());
+
+        Self {
+            game_id: is_requested("gameId"),
+            name: is_requested("name"),
+            cps_number: is_requested("cpsNumber"),
+        }
+    }
+
+    fn apply(self, query: &mut SelectStatement) {
+        query.columns(
+            [
+                maps::Column::Id,
+                maps::Column::PlayerId,
+                maps::Column::Score,
+            ]
+            .map(|column| ("map", column)),
+        );
+
+        for (column, is_selected) in [
+            (maps::Column::GameId, self.game_id),
+            (maps::Column::Name, self.name),
+            (maps::Column::CpsNumber, self.cps_number),
+        ] {
+            if is_selected {
+                query.column(("map", column));
+            } else {
+                query.expr_as(Expr::value(Option::<String>::None), column);
+            }
+        }
+
+        query.column(("map", "unstyled_map_name"));
+    }
+}
+
 #[derive(FromQueryResult)]
 struct MapWithUnstyledName {
-    #[sea_orm(nested)]
-    map: maps::Model,
+    id: u32,
+    player_id: u32,
+    score: f64,
+    game_id: Option<String>,
+    name: Option<String>,
+    cps_number: Option<u32>,
     unstyled_map_name: String,
 }
 
-pub(crate) async fn get_maps_connection<C, S>(
-    conn: &C,
-    redis_conn: &mut RedisConnection,
-    input: MapsConnectionInput<S>,
-) -> GqlResult<connection::Connection<ID, MapWithScore>>
-where
-    C: ConnectionTrait,
-    S: ToRedisArgs + Send + Sync,
-{
-    let pagination_input = PaginationInput::try_from_input(input.connection_parameters)?;
-    let cursor_encoder = match input.sort.map(|s| s.field) {
-        Some(PlayerMapRankingSortableField::Name) => |map: &MapWithUnstyledName| {
-            TextCursor {
-                text: map.unstyled_map_name.clone(),
-                data: map.map.id,
-            }
-            .encode_cursor()
-        },
-        _ => |map: &MapWithUnstyledName| {
-            F64Cursor {
-                score: map.map.score,
-                data: map.map.id,
-            }
-            .encode_cursor()
-        },
-    };
+impl From<MapWithUnstyledName> for maps::Model {
+    fn from(map: MapWithUnstyledName) -> Self {
+        Self {
+            id: map.id,
+            game_id: map.game_id.unwrap_or_default(),
+            player_id: map.player_id,
+            name: map.name.unwrap_or_default(),
+            cps_number: map.cps_number,
+            linked_map: None,
+            bronze_time: None,
+            silver_time: None,
+            gold_time: None,
+            author_time: None,
+            score: map.score,
+            max_respawn_count: None,
+            created_at: Default::default(),
+        }
+    }
+}
 
+/// Returns the SQL query of the maps connection, before the pagination is applied.
+pub(crate) fn maps_connection_query(
+    filter: Option<MapsFilter>,
+    projection: MapsProjection,
+) -> SelectStatement {
     let mut query =
         maps::Entity::find().expr_as(functions::unstyled(maps::Column::Name), "unstyled_map_name");
-    let query = SelectStatement::new()
-        .expr(Expr::col(("map", Asterisk)))
+    let mut statement = SelectStatement::new();
+    projection.apply(&mut statement);
+    statement
         .from_subquery(QuerySelect::query(&mut query).take(), "map")
-        .apply_if(input.filter, |query, filter| {
+        .apply_if(filter, |query, filter| {
             query
                 .apply_if(filter.author, |query, filter| {
                     query
@@ -743,7 +1154,41 @@ where
                     );
                 });
         })
-        .take();
+        .take()
+}
+
+pub(crate) async fn get_maps_connection<C, S>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    input: MapsConnectionInput<S>,
+    projection: MapsProjection,
+) -> GqlResult<connection::Connection<ID, MapWithScore>>
+where
+    C: ConnectionTrait,
+    S: ToRedisArgs + Send + Sync,
+{
+    let pagination_input = PaginationInput::try_from_input(
+        input.connection_parameters,
+        crate::config::maps_default_limit(),
+    )?;
+    let cursor_encoder = match input.sort.map(|s| s.field) {
+        Some(PlayerMapRankingSortableField::Name) => |map: &MapWithUnstyledName| {
+            TextCursor {
+                text: map.unstyled_map_name.clone(),
+                data: map.id,
+            }
+            .encode_cursor()
+        },
+        _ => |map: &MapWithUnstyledName| {
+            F64Cursor {
+                score: map.score,
+                data: map.id,
+            }
+            .encode_cursor()
+        },
+    };
+
+    let query = maps_connection_query(input.filter, projection);
 
     let mut query = match (
         pagination_input.get_cursor(),
@@ -798,12 +1243,12 @@ where
     connection.edges.reserve(maps.len());
 
     for map in maps {
-        let rank: i32 = redis_conn.zrevrank(&input.source, map.map.id).await?;
+        let rank: i32 = redis_conn.zrevrank(&input.source, map.id).await?;
         connection.edges.push(connection::Edge::new(
             ID((cursor_encoder)(&map)),
             MapWithScore {
                 rank: rank + 1,
-                map: map.map.into(),
+                map: maps::Model::from(map).into(),
             },
         ));
     }
@@ -3,7 +3,8 @@ use async_graphql::InputObject;
 use crate::objects::{
     sort_order::SortOrder,
     sortable_fields::{
-        MapRecordSortableField, PlayerMapRankingSortableField, UnorderedRecordSortableField,
+        MapRecordSortableField, PlayerMapRankingSortableField, RecordSortableField,
+        UnorderedRecordSortableField,
     },
 };
 
@@ -19,6 +20,12 @@ pub(crate) struct MapRecordSort {
     pub order: Option<SortOrder>,
 }
 
+#[derive(Debug, InputObject, Clone, Copy)]
+pub(crate) struct RecordSort {
+    pub field: RecordSortableField,
+    pub order: Option<SortOrder>,
+}
+
 #[derive(Debug, InputObject, Clone, Copy)]
 pub(crate) struct PlayerMapRankingSort {
     pub field: PlayerMapRankingSortableField,
@@ -9,6 +9,15 @@ pub(crate) enum UnorderedRecordSortableField {
 pub(crate) enum MapRecordSortableField {
     Date,
     Rank,
+    Time,
+    Player,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Enum)]
+pub(crate) enum RecordSortableField {
+    Map,
+    Date,
+    Time,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Enum)]
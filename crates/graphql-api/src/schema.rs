@@ -1,26 +1,31 @@
+use std::{sync::Arc, time::Duration};
+
 use async_graphql::{
-    EmptyMutation, SchemaBuilder, dataloader::DataLoader, extensions::ApolloTracing,
+    Executor, SchemaBuilder, ServerError, dataloader::DataLoader, extensions::ApolloTracing,
 };
 use records_lib::{
     Database,
+    pool::clone_dbconn,
     records_notifier::{LatestRecordsSubscription, RecordsNotifier},
 };
 
 use crate::{
+    error::ApiGqlError,
     loaders::{
         event::EventLoader, event_category::EventCategoryLoader, map::MapLoader,
-        player::PlayerLoader,
+        medal_times::MedalTimesLoader, player::PlayerLoader, record_editions::RecordEditionsLoader,
     },
+    mutations::root::MutationRoot,
     objects::root::QueryRoot,
     subscriptions::root::SubscriptionRoot,
 };
 
-pub type Schema = async_graphql::Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+pub type Schema = async_graphql::Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
 
 fn create_schema_impl(
     records_sub: LatestRecordsSubscription,
-) -> SchemaBuilder<QueryRoot, EmptyMutation, SubscriptionRoot> {
-    async_graphql::Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot::new(records_sub))
+) -> SchemaBuilder<QueryRoot, MutationRoot, SubscriptionRoot> {
+    async_graphql::Schema::build(QueryRoot, MutationRoot, SubscriptionRoot::new(records_sub))
 }
 
 pub fn create_schema_standalone() -> Schema {
@@ -38,25 +43,51 @@ pub fn create_schema(
     create_schema_impl(records_sub)
         .extension(ApolloTracing)
         .data(DataLoader::new(
-            PlayerLoader(db.clone().sql_conn),
+            PlayerLoader(clone_dbconn(db.read_conn())),
             tokio::spawn,
         ))
         .data(DataLoader::new(
-            MapLoader(db.clone().sql_conn),
+            MapLoader(clone_dbconn(db.read_conn())),
             tokio::spawn,
         ))
         .data(DataLoader::new(
-            EventLoader(db.clone().sql_conn),
+            EventLoader(clone_dbconn(db.read_conn())),
             tokio::spawn,
         ))
         .data(DataLoader::new(
-            EventCategoryLoader(db.clone().sql_conn),
+            EventCategoryLoader(clone_dbconn(db.read_conn())),
             tokio::spawn,
         ))
-        .data(db_clone.sql_conn)
+        .data(DataLoader::new(
+            MedalTimesLoader(clone_dbconn(db.read_conn())),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            RecordEditionsLoader(clone_dbconn(db.read_conn())),
+            tokio::spawn,
+        ))
+        .data(clone_dbconn(db_clone.read_conn()))
         .data(db_clone.redis_pool)
         .data(db)
         .data(client)
         .limit_depth(16)
         .finish()
 }
+
+/// Executes the GraphQL request, and returns a timeout error if it takes longer than the
+/// provided duration.
+pub async fn execute_with_timeout<E: Executor>(
+    executor: &E,
+    request: async_graphql::Request,
+    timeout: Duration,
+) -> async_graphql::Response {
+    match tokio::time::timeout(timeout, executor.execute(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            let error = ApiGqlError::from_timeout_error(timeout);
+            let mut server_error = ServerError::new(error.to_string(), None);
+            server_error.source = Some(Arc::new(error));
+            async_graphql::Response::from_errors(vec![server_error])
+        }
+    }
+}
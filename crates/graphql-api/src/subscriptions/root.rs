@@ -36,12 +36,10 @@ impl SubscriptionRoot {
                         )
                     })?;
 
-                Ok(RankedRecord {
-                    inner: records::RankedRecord {
-                        rank: new_record.rank,
-                        record,
-                    },
-                })
+                Ok(RankedRecord::from(records::RankedRecord {
+                    rank: new_record.rank,
+                    record,
+                }))
             })
     }
 }
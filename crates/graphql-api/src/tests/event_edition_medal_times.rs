@@ -0,0 +1,95 @@
+use async_graphql::dataloader::Loader as _;
+use entity::{event, event_edition, event_edition_maps, maps, players};
+use records_lib::pool::clone_dbconn;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::loaders::medal_times::MedalTimesLoader;
+
+#[tokio::test]
+async fn load_medal_times_batch() -> anyhow::Result<()> {
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("player_login".to_owned()),
+        name: Set("player_name".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let maps = (1..=3).map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The third map doesn't have medal times
+    let event_maps = (1..=3).map(|map_id| {
+        let medal_time = |factor: i32| (map_id != 3).then_some(map_id as i32 * 1000 * factor);
+        event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(map_id),
+            order: Set(map_id),
+            bronze_time: Set(medal_time(4)),
+            silver_time: Set(medal_time(3)),
+            gold_time: Set(medal_time(2)),
+            author_time: Set(medal_time(1)),
+            ..Default::default()
+        }
+    });
+
+    test_env::wrap(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        event_edition_maps::Entity::insert_many(event_maps)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let loader = MedalTimesLoader(clone_dbconn(&db.sql_conn));
+
+        // The unknown map is ignored too
+        let medal_times = loader
+            .load(&[(1, 1, 1), (1, 1, 2), (1, 1, 3), (1, 1, 4)])
+            .await?;
+
+        assert_eq!(medal_times.len(), 2);
+        for map_id in 1..=2 {
+            let times = &medal_times[&(1, 1, map_id)].inner;
+            let base = map_id as i32 * 1000;
+            assert_eq!(
+                (
+                    times.bronze_time,
+                    times.silver_time,
+                    times.gold_time,
+                    times.champion_time
+                ),
+                (base * 4, base * 3, base * 2, base)
+            );
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
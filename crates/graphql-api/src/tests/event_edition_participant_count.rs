@@ -0,0 +1,117 @@
+use entity::{event, event_edition, event_edition_records, maps, players, records};
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn participant_count_distinct_players() -> anyhow::Result<()> {
+    setup();
+
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // (player_id, is_event_record)
+    // The player 1 made two records in the edition, and the player 4 didn't participate.
+    let records_info = [(1, true), (1, true), (2, true), (3, true), (4, false)];
+
+    let records = records_info
+        .iter()
+        .enumerate()
+        .map(|(record_id, (player_id, _))| records::ActiveModel {
+            record_id: Set(record_id as u32 + 1),
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(5000 + record_id as i32),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    let event_records = records_info
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, is_event_record))| *is_event_record)
+        .map(|(record_id, _)| event_edition_records::ActiveModel {
+            record_id: Set(record_id as u32 + 1),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+    test_env::wrap(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert_many(event_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(
+                "{ event(handle: \"event_handle\") { edition(editionId: 1) { participantCount } } }",
+            )
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        assert_eq!(data["event"]["edition"]["participantCount"], 3);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+use crate::{
+    error::{ApiGqlError, ApiGqlErrorKind},
+    schema::execute_with_timeout,
+};
+
+struct SlowQuery;
+
+#[Object]
+impl SlowQuery {
+    async fn slow(&self) -> i32 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        0
+    }
+
+    async fn fast(&self) -> i32 {
+        0
+    }
+}
+
+#[tokio::test]
+async fn slow_request_times_out() {
+    let schema = Schema::new(SlowQuery, EmptyMutation, EmptySubscription);
+
+    let response =
+        execute_with_timeout(&schema, "{ slow }".into(), Duration::from_millis(50)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    let error = response.errors[0]
+        .source::<ApiGqlError>()
+        .expect("error should be an API error");
+    assert!(
+        matches!(error.kind(), ApiGqlErrorKind::Timeout { timeout } if timeout.as_millis() == 50),
+        "unexpected error: {error}"
+    );
+}
+
+#[tokio::test]
+async fn fast_request_doesnt_time_out() {
+    let schema = Schema::new(SlowQuery, EmptyMutation, EmptySubscription);
+
+    let response =
+        execute_with_timeout(&schema, "{ fast }".into(), Duration::from_millis(50)).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+}
@@ -0,0 +1,107 @@
+use entity::{banishments, maps, players, records};
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn banned_player_records_excluded() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The third player has the best time, but is banned
+    let records = [(1, 6000), (2, 5000), (3, 4000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    let ban = banishments::ActiveModel {
+        date_ban: Set(chrono::Utc::now().naive_utc()),
+        duration: Set(None),
+        was_reprieved: Set(0),
+        reason: Set("cheating".to_owned()),
+        player_id: Set(Some(3)),
+        banished_by: Set(Some(1)),
+        ..Default::default()
+    };
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        banishments::Entity::insert(ban).exec(&db.sql_conn).await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ map(gameId: \"map_{map_id}_uid\") {{ \
+                    records(dateSortBy: SORT) {{ rank time }} \
+                    recordsByFlag(flagMask: 682) {{ rank time }} \
+                    recordsConnection {{ nodes {{ rank time }} }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let ranked_times = |records: &serde_json::Value| {
+            let mut times = records
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?
+                .iter()
+                .map(|record| (record["rank"].clone(), record["time"].clone()))
+                .collect::<Vec<_>>();
+            times.sort_by_key(|(_, time)| time.as_i64());
+            anyhow::Ok(times)
+        };
+
+        let expected = [(1.into(), 5000.into()), (2.into(), 6000.into())];
+        assert_eq!(ranked_times(&data["map"]["records"])?, expected);
+        assert_eq!(ranked_times(&data["map"]["recordsByFlag"])?, expected);
+        assert_eq!(
+            ranked_times(&data["map"]["recordsConnection"]["nodes"])?,
+            expected
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,125 @@
+use entity::{maps, players, records};
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn records_filtered_by_flag() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // Only the records of the players 2 and 4 have the lowest bit set
+    let records_info = [
+        (1, 4000, 682),
+        (2, 5000, 683),
+        (3, 6000, 682),
+        (4, 7000, 683),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(player_id, time, flags)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(*flags),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ map(gameId: \"map_{map_id}_uid\") {{ recordsByFlag(flagMask: 1) \
+                    {{ rank time flags }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let records = data["map"]["recordsByFlag"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?
+            .iter()
+            .map(|record| (record["rank"].clone(), record["time"].clone()))
+            .collect::<Vec<_>>();
+
+        // The ranks are still computed against the whole leaderboard
+        assert_eq!(records, [(2.into(), 5000.into()), (4.into(), 7000.into())]);
+
+        let response = schema
+            .execute(format!(
+                "{{ map(gameId: \"map_{map_id}_uid\") {{ recordsByFlag(flagMask: 682) \
+                    {{ rank }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        assert_eq!(
+            data["map"]["recordsByFlag"].as_array().map(Vec::len),
+            Some(4)
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ map(gameId: \"map_{map_id}_uid\") {{ recordsByFlag(flagMask: 682, limit: 2) \
+                    {{ rank time }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let records = data["map"]["recordsByFlag"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?
+            .iter()
+            .map(|record| (record["rank"].clone(), record["time"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(records, [(1.into(), 4000.into()), (2.into(), 5000.into())]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
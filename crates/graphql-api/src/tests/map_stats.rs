@@ -0,0 +1,89 @@
+use entity::{maps, players, records};
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn map_stats_of_records() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The first player also has a slower record, which isn't part of the stats
+    let records =
+        [(1, 1000), (2, 2000), (3, 3000), (4, 6000), (1, 9000)].map(|(player_id, time)| {
+            records::ActiveModel {
+                record_player_id: Set(player_id),
+                map_id: Set(map_id),
+                time: Set(time),
+                respawn_count: Set(0),
+                flags: Set(682),
+                record_date: Set(chrono::Utc::now().naive_utc()),
+                ..Default::default()
+            }
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ map(gameId: \"map_{map_id}_uid\") {{ stats {{ \
+                    recordsCount minRecord maxRecord averageRecord medianRecord }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        assert_eq!(
+            data["map"]["stats"],
+            serde_json::json!({
+                "recordsCount": 4,
+                "minRecord": 1.,
+                "maxRecord": 6.,
+                "averageRecord": 3.,
+                "medianRecord": 3.,
+            })
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,279 @@
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{event, event_edition, event_edition_records, maps, players, records};
+use records_lib::{
+    mappack::AnyMappackId,
+    records_notifier::RecordsNotifier,
+    redis_key::{mappack_key, mappack_time_key},
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn mappack_maps_with_leaderboards() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids
+        .iter()
+        .enumerate()
+        .map(|(i, map_id)| maps::ActiveModel {
+            id: Set(*map_id),
+            game_id: Set(format!("map_{map_id}_uid")),
+            // The maps are sorted by name
+            name: Set(format!("map_{i}_name")),
+            player_id: Set(1),
+            ..Default::default()
+        });
+
+    // The 3 players finished the first map, and only the first player finished the second one
+    let records_info = [
+        (map_ids[0], 1, 5000),
+        (map_ids[0], 2, 4000),
+        (map_ids[0], 3, 6000),
+        (map_ids[1], 1, 7000),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(map_id, player_id, time)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(*map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // The ID of the first map makes the mappack unique to this test
+        let mappack_id = format!("mappack_{}", map_ids[0]);
+        {
+            let mut redis_conn = db.redis_pool.get().await?;
+            let _: () = redis_conn
+                .sadd(
+                    mappack_key(AnyMappackId::Id(&mappack_id)),
+                    map_ids
+                        .iter()
+                        .map(|map_id| format!("map_{map_id}_uid"))
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+        }
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ mappack(mappackId: \"{mappack_id}\") {{ maps(first: 2) {{ \
+                    pageInfo {{ hasNextPage }} \
+                    nodes {{ map {{ gameId }} leaderboard(limit: 2) {{ time }} }} }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let maps = &data["mappack"]["maps"];
+        assert_eq!(maps["pageInfo"]["hasNextPage"], false);
+
+        let maps = maps["nodes"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?
+            .iter()
+            .map(|map| {
+                let times = map["leaderboard"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|record| record["time"].clone())
+                    .collect::<Vec<_>>();
+                (map["map"]["gameId"].clone(), times)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            maps,
+            [
+                (
+                    format!("map_{}_uid", map_ids[0]).into(),
+                    vec![4000.into(), 5000.into()]
+                ),
+                (format!("map_{}_uid", map_ids[1]).into(), vec![7000.into()]),
+            ]
+        );
+
+        // The maps list is paginated
+        let response = schema
+            .execute(format!(
+                "{{ mappack(mappackId: \"{mappack_id}\") {{ maps(first: 1) {{ \
+                    pageInfo {{ hasNextPage }} nodes {{ map {{ gameId }} }} }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let maps = &data["mappack"]["maps"];
+        assert_eq!(maps["pageInfo"]["hasNextPage"], true);
+        assert_eq!(maps["nodes"].as_array().map(Vec::len), Some(1));
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn event_mappack_maps_leaderboard() -> anyhow::Result<()> {
+    setup();
+
+    let map_id = test_env::get_map_id();
+
+    let event = event::ActiveModel {
+        id: Set(1),
+        handle: Set("event_handle".to_owned()),
+        ..Default::default()
+    };
+
+    // The MX ID of the edition makes the mappack unique to this test
+    let edition = event_edition::ActiveModel {
+        event_id: Set(1),
+        id: Set(1),
+        name: Set("event_1_1_name".to_owned()),
+        start_date: Set(chrono::Utc::now().naive_utc() - chrono::Duration::days(1)),
+        mx_id: Set(Some(map_id as _)),
+        is_transparent: Set(0),
+        save_non_event_record: Set(0),
+        non_original_maps: Set(0),
+        ..Default::default()
+    };
+
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // Only the record of the first player was saved for the edition
+    let records =
+        [(1, 1, 5000), (2, 2, 4000)].map(|(record_id, player_id, time)| records::ActiveModel {
+            record_id: Set(record_id),
+            record_player_id: Set(player_id),
+            map_id: Set(map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    let event_record = event_edition_records::ActiveModel {
+        record_id: Set(1),
+        event_id: Set(1),
+        edition_id: Set(1),
+    };
+
+    test_env::wrap(async |db| {
+        event::Entity::insert(event).exec(&db.sql_conn).await?;
+        event_edition::Entity::insert(edition)
+            .exec(&db.sql_conn)
+            .await?;
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+        event_edition_records::Entity::insert(event_record)
+            .exec(&db.sql_conn)
+            .await?;
+
+        {
+            // They're the only event and edition of the database
+            let event = event::Entity::find()
+                .one(&db.sql_conn)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing event"))?;
+            let edition = event_edition::Entity::find()
+                .one(&db.sql_conn)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing edition"))?;
+            let mappack = AnyMappackId::Event(&event, &edition);
+
+            let mut redis_conn = db.redis_pool.get().await?;
+            let _: () = redis_conn
+                .sadd(mappack_key(mappack), format!("map_{map_id}_uid"))
+                .await?;
+            let _: () = redis_conn
+                .set(mappack_time_key(mappack), chrono::Utc::now().timestamp())
+                .await?;
+        }
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(
+                "{ event(handle: \"event_handle\") { edition(editionId: 1) { mappack { \
+                    maps { nodes { leaderboard { time player { login } } } } } } } }",
+            )
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let leaderboard = data["event"]["edition"]["mappack"]["maps"]["nodes"][0]["leaderboard"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?
+            .iter()
+            .map(|record| (record["player"]["login"].clone(), record["time"].clone()))
+            .collect::<Vec<_>>();
+
+        // The leaderboard is the one of the edition
+        assert_eq!(leaderboard, [("player_1_login".into(), 5000.into())]);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,131 @@
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{maps, players, records};
+use mkenv::prelude::*;
+use records_lib::{
+    LibEnv,
+    mappack::{self, AnyMappackId},
+    records_notifier::RecordsNotifier,
+    redis_key::mappack_key,
+};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{config::InitError, schema::create_schema};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+
+    // The computation of the mappack scores reads the library environment
+    let lib_env = LibEnv::define();
+    if let Err(e) = lib_env.try_init() {
+        panic!("error during test setup: {e}");
+    }
+    records_lib::init_env(lib_env);
+}
+
+#[tokio::test]
+async fn mappack_player_standing() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The player 1 is the fastest on both maps, and the player 3 has no record
+    let records_info = [
+        (map_ids[0], 1, 1000),
+        (map_ids[0], 2, 2000),
+        (map_ids[1], 1, 3000),
+        (map_ids[1], 2, 4000),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(map_id, player_id, time)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(*map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // The ID of the first map makes the mappack unique to this test
+        let mappack_id = format!("mappack_{}", map_ids[0]);
+        let mappack = AnyMappackId::Id(&mappack_id);
+        {
+            let mut redis_conn = db.redis_pool.get().await?;
+            let _: () = redis_conn
+                .sadd(
+                    mappack_key(mappack),
+                    map_ids
+                        .iter()
+                        .map(|map_id| format!("map_{map_id}_uid"))
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+        }
+        mappack::update_mappack(&db.sql_conn, &db.redis_pool, mappack, Default::default()).await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let response = schema
+            .execute(format!(
+                "{{ mappack(mappackId: \"{mappack_id}\") {{ \
+                    second: player(login: \"player_2_login\") {{ \
+                        rank rankAvg mapFinished ranks {{ rank }} }} \
+                    third: player(login: \"player_3_login\") {{ rank }} }} }}"
+            ))
+            .await;
+        anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json()?;
+        let second = &data["mappack"]["second"];
+        assert_eq!(second["rank"], 2);
+        assert_eq!(second["rankAvg"], 2.);
+        assert_eq!(second["mapFinished"], 2);
+        assert_eq!(
+            second["ranks"]
+                .as_array()
+                .map(|ranks| ranks.iter().map(|rank| rank["rank"].clone()).collect()),
+            Some(vec![2.into(), 2.into()])
+        );
+
+        // The player without any record isn't part of the mappack
+        assert!(data["mappack"]["third"].is_null());
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -1,4 +1,3 @@
-use mkenv::prelude::*;
 use std::time::Duration;
 
 use async_graphql::connection::CursorType;
@@ -9,9 +8,13 @@ use sea_orm::{ActiveValue::Set, EntityTrait};
 
 use crate::{
     config::InitError,
-    cursors::{ConnectionParameters, RecordDateCursor, RecordRankCursor},
+    cursors::{
+        ConnectionParameters, RecordDateCursor, RecordRankCursor, RecordTimeCursor, TextCursor,
+    },
     objects::{
-        map::get_map_records_connection, sort::MapRecordSort, sort_order::SortOrder,
+        map::{MapRecordCursor, get_map_records_connection},
+        sort::MapRecordSort,
+        sort_order::SortOrder,
         sortable_fields::MapRecordSortableField,
     },
 };
@@ -42,7 +45,7 @@ struct Record {
 async fn default_page() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     let players = (1..=record_amount).map(|i| players::ActiveModel {
@@ -144,7 +147,7 @@ async fn default_page() -> anyhow::Result<()> {
 async fn default_page_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     let players = (1..=record_amount).map(|i| players::ActiveModel {
@@ -250,7 +253,7 @@ async fn default_page_desc() -> anyhow::Result<()> {
 async fn default_page_date() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     let players = (1..=record_amount).map(|i| players::ActiveModel {
@@ -361,7 +364,7 @@ async fn default_page_date() -> anyhow::Result<()> {
 async fn default_page_date_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     let players = (1..=record_amount).map(|i| players::ActiveModel {
@@ -793,7 +796,7 @@ async fn first_x_after_y_date_desc() -> anyhow::Result<()> {
 async fn after_y() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_first_x_after_y(record_amount, false, None, 5, default_limit, true).await?;
@@ -823,7 +826,7 @@ async fn after_y() -> anyhow::Result<()> {
 async fn after_y_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_first_x_after_y(record_amount, true, None, 5, default_limit, true).await?;
@@ -853,7 +856,7 @@ async fn after_y_desc() -> anyhow::Result<()> {
 async fn after_y_date() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_first_x_after_y_date(record_amount, false, None, 5, default_limit, true).await?;
@@ -883,7 +886,7 @@ async fn after_y_date() -> anyhow::Result<()> {
 async fn after_y_date_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_first_x_after_y_date(record_amount, true, None, 5, default_limit, true).await?;
@@ -1221,7 +1224,7 @@ async fn last_x_before_y_date_desc() -> anyhow::Result<()> {
 async fn before_x() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_last_x_before_y(
@@ -1259,7 +1262,7 @@ async fn before_x() -> anyhow::Result<()> {
 async fn before_x_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_last_x_before_y(
@@ -1297,7 +1300,7 @@ async fn before_x_desc() -> anyhow::Result<()> {
 async fn before_x_date() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_last_x_before_y_date(
@@ -1335,7 +1338,7 @@ async fn before_x_date() -> anyhow::Result<()> {
 async fn before_x_date_desc() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     test_last_x_before_y_date(
@@ -1368,3 +1371,282 @@ async fn before_x_date_desc() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tracing::instrument]
+async fn test_pages_time(is_desc: bool, page_size: usize) -> anyhow::Result<()> {
+    // Some players share the same time, so their records are sorted by their ID
+    let times = [3000, 1000, 2000, 1000, 5000, 4000, 2000, 6000, 3000, 7000];
+    let record_amount = times.len();
+
+    let players = (1..=record_amount).map(|i| players::ActiveModel {
+        id: Set(i as _),
+        login: Set(format!("player_{i}_login")),
+        name: Set(format!("player_{i}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The higher the record ID, the less recent the record, so sorting the records sharing
+    // the same time by their date would give the opposite order
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let record_dates = (0..record_amount)
+        .map(|i| now - Duration::from_secs(3600 * (i as u64 + 1)))
+        .collect_vec();
+
+    let records = (0..record_amount).map(|i| records::ActiveModel {
+        record_id: Set((i + 1) as _),
+        map_id: Set(map_id),
+        record_player_id: Set((i + 1) as _),
+        flags: Set(682),
+        time: Set(times[i]),
+        respawn_count: Set(0),
+        record_date: Set(record_dates[i]),
+        ..Default::default()
+    });
+
+    let mut expected = (0..record_amount)
+        .map(|i| {
+            let record_date = record_dates[i].and_utc();
+            Record {
+                record_id: i as u32 + 1,
+                cursor: RecordTimeCursor {
+                    time: times[i],
+                    data: i as u32 + 1,
+                }
+                .encode_cursor(),
+                rank: times.iter().filter(|time| **time < times[i]).count() as i32 + 1,
+                map_id,
+                flags: 682,
+                player_id: i as u32 + 1,
+                record_date,
+                respawn_count: 0,
+                time: times[i],
+            }
+        })
+        .collect_vec();
+
+    expected.sort_by_key(|record| (record.time, record.record_id));
+    if is_desc {
+        expected.reverse();
+    }
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mut after = None;
+        let mut pages = Vec::new();
+
+        loop {
+            let result = get_map_records_connection(
+                &db.sql_conn,
+                &db.redis_pool,
+                map_id,
+                Default::default(),
+                ConnectionParameters {
+                    first: Some(page_size),
+                    after,
+                    ..Default::default()
+                },
+                Some(MapRecordSort {
+                    field: MapRecordSortableField::Time,
+                    order: is_desc.then_some(SortOrder::Descending),
+                }),
+                Default::default(),
+            )
+            .await?;
+
+            assert_eq!(result.has_previous_page, !pages.is_empty());
+            assert!(result.edges.len() <= page_size);
+
+            after = result
+                .edges
+                .last()
+                .map(|edge| MapRecordCursor::decode_cursor(&edge.cursor.0))
+                .transpose()?;
+
+            let has_next_page = result.has_next_page;
+
+            pages.push(
+                result
+                    .edges
+                    .into_iter()
+                    .map(|edge| Record {
+                        record_id: edge.node.inner.record.record_id,
+                        cursor: edge.cursor.0,
+                        rank: edge.node.inner.rank,
+                        map_id: edge.node.inner.record.map_id,
+                        player_id: edge.node.inner.record.record_player_id,
+                        flags: edge.node.inner.record.flags,
+                        record_date: edge.node.inner.record.record_date.and_utc(),
+                        respawn_count: edge.node.inner.record.respawn_count,
+                        time: edge.node.inner.record.time,
+                    })
+                    .collect_vec(),
+            );
+
+            if !has_next_page {
+                break;
+            }
+        }
+
+        assert_eq!(pages.len(), record_amount.div_ceil(page_size));
+        itertools::assert_equal(pages.into_iter().flatten(), expected);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn pages_time() -> anyhow::Result<()> {
+    setup();
+
+    test_pages_time(false, 3).await?;
+    test_pages_time(false, 5).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pages_time_desc() -> anyhow::Result<()> {
+    setup();
+
+    test_pages_time(true, 3).await?;
+    test_pages_time(true, 5).await?;
+
+    Ok(())
+}
+
+#[tracing::instrument]
+async fn test_pages_player(is_desc: bool) -> anyhow::Result<()> {
+    // The names are compared without their style, and two players share the same name,
+    // so their records are sorted by the ID of the player
+    let names = ["$f00Charlie", "$oalice", "Bob", "$i$0f0dave", "Bob"];
+    let unstyled_names = ["Charlie", "alice", "Bob", "dave", "Bob"];
+    let page_size = 2;
+
+    let players = (1..=names.len()).map(|i| players::ActiveModel {
+        id: Set(i as _),
+        login: Set(format!("player_{i}_login")),
+        name: Set(names[i - 1].to_owned()),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = (1..=names.len()).map(|i| records::ActiveModel {
+        record_id: Set(i as _),
+        map_id: Set(map_id),
+        record_player_id: Set(i as _),
+        flags: Set(682),
+        time: Set(1000 * i as i32),
+        respawn_count: Set(0),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    // The names are compared case-insensitively
+    let mut expected = vec![2, 3, 5, 1, 4];
+    if is_desc {
+        expected.reverse();
+    }
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mut after = None;
+        let mut player_ids = Vec::new();
+
+        loop {
+            let result = get_map_records_connection(
+                &db.sql_conn,
+                &db.redis_pool,
+                map_id,
+                Default::default(),
+                ConnectionParameters {
+                    first: Some(page_size),
+                    after,
+                    ..Default::default()
+                },
+                Some(MapRecordSort {
+                    field: MapRecordSortableField::Player,
+                    order: is_desc.then_some(SortOrder::Descending),
+                }),
+                Default::default(),
+            )
+            .await?;
+
+            for edge in &result.edges {
+                let player_id = edge.node.inner.record.record_player_id;
+                let expected_cursor = TextCursor {
+                    text: unstyled_names[player_id as usize - 1].to_owned(),
+                    data: player_id,
+                };
+                assert_eq!(edge.cursor.0, expected_cursor.encode_cursor());
+
+                match MapRecordCursor::decode_cursor(&edge.cursor.0)? {
+                    MapRecordCursor::Player(cursor) => assert_eq!(cursor, expected_cursor),
+                    _ => panic!("the cursor of the record should be a player cursor"),
+                }
+
+                player_ids.push(player_id);
+            }
+
+            after = result
+                .edges
+                .last()
+                .map(|edge| MapRecordCursor::decode_cursor(&edge.cursor.0))
+                .transpose()?;
+
+            if !result.has_next_page {
+                break;
+            }
+        }
+
+        assert_eq!(player_ids, expected);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn pages_player() -> anyhow::Result<()> {
+    setup();
+    test_pages_player(false).await
+}
+
+#[tokio::test]
+async fn pages_player_desc() -> anyhow::Result<()> {
+    setup();
+    test_pages_player(true).await
+}
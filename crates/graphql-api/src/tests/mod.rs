@@ -1,6 +1,20 @@
 mod queryroot_maps_connection;
+mod queryroot_most_played_maps;
 mod queryroot_players_connection;
+mod queryroot_records;
 mod queryroot_records_connection;
 
+mod event_edition_medal_times;
+mod event_edition_participant_count;
+mod map_records_banned_players;
+mod map_records_by_flag;
+mod map_stats;
+mod mappack_maps;
+mod mappack_player;
 mod maps_records_connection;
 mod players_records_connection;
+
+mod mutationroot_ban_player;
+mod mutationroot_unban_player;
+
+mod execute_timeout;
@@ -0,0 +1,168 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use entity::{banishments, players};
+use records_lib::{Database, records_notifier::RecordsNotifier};
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+use crate::{
+    auth::{AdminAuth, LazyAdminAuth},
+    schema::{Schema, create_schema},
+};
+
+fn get_schema(db: Database) -> Schema {
+    create_schema(
+        db,
+        reqwest::Client::new(),
+        RecordsNotifier::default().get_subscription(),
+    )
+}
+
+fn ban_request(reason: &str, admin_auth: Option<AdminAuth>) -> async_graphql::Request {
+    let request = async_graphql::Request::new(format!(
+        r#"mutation {{
+            banPlayer(login: "player_2_login", reason: "{reason}", durationDays: 7) {{
+                reason
+                duration
+                player {{ login }}
+                banishedBy {{ login }}
+            }}
+        }}"#
+    ));
+
+    match admin_auth {
+        Some(admin_auth) => request.data(LazyAdminAuth::from(admin_auth)),
+        None => request,
+    }
+}
+
+fn players() -> impl Iterator<Item = players::ActiveModel> {
+    (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(if player_id == 1 { 2 } else { 0 }),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn ban_player() -> anyhow::Result<()> {
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players())
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = get_schema(db.clone());
+
+        let admin_auth = Some(AdminAuth::Admin { player_id: 1 });
+        let response = schema.execute(ban_request("cheating", admin_auth)).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json()?,
+            serde_json::json!({
+                "banPlayer": {
+                    "reason": "cheating",
+                    "duration": 7 * 24 * 60 * 60,
+                    "player": { "login": "player_2_login" },
+                    "banishedBy": { "login": "player_1_login" },
+                },
+            }),
+        );
+
+        // Banning an already banned player updates their current banishment
+        let response = schema
+            .execute(ban_request("cheating again", admin_auth))
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let bans = banishments::Entity::find()
+            .filter(banishments::Column::PlayerId.eq(2))
+            .all(&db.sql_conn)
+            .await?;
+        assert_eq!(bans.len(), 1);
+        assert_eq!(bans[0].reason, "cheating again");
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn ban_player_unauthorized() -> anyhow::Result<()> {
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players())
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = get_schema(db.clone());
+
+        let response = schema.execute(ban_request("cheating", None)).await;
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "unauthorized");
+
+        let response = schema
+            .execute(ban_request("cheating", Some(AdminAuth::Forbidden)))
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            response.errors[0].message,
+            "you don't have the permission to perform this action"
+        );
+
+        let bans_count = banishments::Entity::find()
+            .filter(banishments::Column::PlayerId.eq(2))
+            .all(&db.sql_conn)
+            .await?
+            .len();
+        assert_eq!(bans_count, 0);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn admin_auth_checked_on_demand() -> anyhow::Result<()> {
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players())
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = get_schema(db.clone());
+
+        let checks = Arc::new(AtomicUsize::new(0));
+        let lazy_auth = || {
+            let checks = checks.clone();
+            LazyAdminAuth::new(move || {
+                checks.fetch_add(1, Ordering::Relaxed);
+                async { Ok(AdminAuth::Admin { player_id: 1 }) }
+            })
+        };
+
+        // The queries don't require the admin authentication
+        let request =
+            async_graphql::Request::new(r#"{ player(login: "player_2_login") { login } }"#)
+                .data(lazy_auth());
+        let response = schema.execute(request).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(checks.load(Ordering::Relaxed), 0);
+
+        // The authentication is only checked once, even if several mutations require it
+        let request = async_graphql::Request::new(
+            r#"mutation {
+                first: banPlayer(login: "player_2_login", reason: "cheating") { reason }
+                second: banPlayer(login: "player_2_login", reason: "cheating again") { reason }
+            }"#,
+        )
+        .data(lazy_auth());
+        let response = schema.execute(request).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(checks.load(Ordering::Relaxed), 1);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,111 @@
+use entity::{current_bans, maps, players, records};
+use records_lib::{Database, leaderboard, records_notifier::RecordsNotifier};
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+use crate::{
+    auth::{AdminAuth, LazyAdminAuth},
+    schema::{Schema, create_schema},
+};
+
+fn get_schema(db: Database) -> Schema {
+    create_schema(
+        db,
+        reqwest::Client::new(),
+        RecordsNotifier::default().get_subscription(),
+    )
+}
+
+async fn execute(schema: &Schema, query: &str) -> anyhow::Result<serde_json::Value> {
+    let request = async_graphql::Request::new(query)
+        .data(LazyAdminAuth::from(AdminAuth::Admin { player_id: 1 }));
+    let response = schema.execute(request).await;
+    anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+    Ok(response.data.into_json()?)
+}
+
+#[tokio::test]
+async fn ban_then_unban_player() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(if player_id == 1 { 2 } else { 0 }),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let records = [(1, 5000), (2, 4000)].map(|(player_id, time)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = get_schema(db.clone());
+
+        execute(
+            &schema,
+            r#"mutation { banPlayer(login: "player_2_login", reason: "cheating") { id } }"#,
+        )
+        .await?;
+
+        let data = execute(
+            &schema,
+            r#"mutation { unbanPlayer(login: "player_2_login") }"#,
+        )
+        .await?;
+        assert_eq!(data, serde_json::json!({ "unbanPlayer": true }));
+
+        let current_ban = current_bans::Entity::find()
+            .filter(current_bans::Column::PlayerId.eq(2))
+            .one(&db.sql_conn)
+            .await?;
+        assert!(current_ban.is_none());
+
+        // The player isn't banned anymore
+        let data = execute(
+            &schema,
+            r#"mutation { unbanPlayer(login: "player_2_login") }"#,
+        )
+        .await?;
+        assert_eq!(data, serde_json::json!({ "unbanPlayer": false }));
+
+        let lb = leaderboard::leaderboard(
+            &db.sql_conn,
+            &db.redis_pool,
+            map_id,
+            None,
+            None,
+            Default::default(),
+        )
+        .await?;
+
+        itertools::assert_equal(
+            lb.iter().map(|row| (row.rank, row.login.as_str())),
+            [(1, "player_2_login"), (2, "player_1_login")],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
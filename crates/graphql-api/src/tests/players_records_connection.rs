@@ -1,4 +1,3 @@
-use mkenv::prelude::*;
 use rand::Rng;
 use std::time::Duration;
 
@@ -41,7 +40,7 @@ struct Record {
 
 #[tracing::instrument]
 async fn test_default_page(is_desc: bool) -> anyhow::Result<()> {
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let record_amount = default_limit * 2;
 
     let player = players::ActiveModel {
@@ -107,6 +106,7 @@ async fn test_default_page(is_desc: bool) -> anyhow::Result<()> {
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -248,7 +248,7 @@ async fn test_first_x_after_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .first
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::records_default_limit);
 
     let computed_has_next_page = params.after_idx + 1 + limit < params.record_amount;
     if has_next_page != computed_has_next_page {
@@ -345,6 +345,7 @@ async fn test_first_x_after_y(
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -421,7 +422,7 @@ async fn test_last_x_before_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .last
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::records_default_limit);
 
     let computed_has_previous_page = limit < params.before_idx;
     if has_previous_page != computed_has_previous_page {
@@ -514,6 +515,7 @@ async fn test_last_x_before_y(
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -628,7 +630,7 @@ async fn first_x_after_y_desc() -> anyhow::Result<()> {
 #[tokio::test]
 async fn after_x() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_first_x_after_y(
         FirstXAfterYParams {
@@ -670,7 +672,7 @@ async fn after_x() -> anyhow::Result<()> {
 #[tokio::test]
 async fn after_x_desc() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_first_x_after_y(
         FirstXAfterYParams {
@@ -794,7 +796,7 @@ async fn last_x_before_y_desc() -> anyhow::Result<()> {
 #[tokio::test]
 async fn before_x() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_last_x_before_y(
         LastXBeforeYParams {
@@ -836,7 +838,7 @@ async fn before_x() -> anyhow::Result<()> {
 #[tokio::test]
 async fn before_x_desc() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_last_x_before_y(
         LastXBeforeYParams {
@@ -874,3 +876,127 @@ async fn before_x_desc() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tracing::instrument]
+async fn test_pages_same_date(is_desc: bool) -> anyhow::Result<()> {
+    let record_amount = 6;
+    let page_size = 2;
+
+    let player = players::ActiveModel {
+        id: Set(1),
+        login: Set("boogalogin".to_owned()),
+        name: Set("booganame".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let map_ids = rand::rng()
+        .sample_iter(rand::distr::StandardUniform)
+        .take(record_amount)
+        .collect_vec();
+
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // Every record has the same date, so they're only sorted by their ID
+    let record_date = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+
+    let records = (0..record_amount)
+        .zip(map_ids.iter())
+        .map(|(i, map_id)| records::ActiveModel {
+            record_id: Set((i + 1) as _),
+            map_id: Set(*map_id),
+            record_player_id: Set(1),
+            flags: Set(682),
+            time: Set(1000),
+            respawn_count: Set(0),
+            record_date: Set(record_date),
+            ..Default::default()
+        });
+
+    // The records are sorted from the most recent one by default
+    let expected = if is_desc {
+        (1..=record_amount as u32).collect_vec()
+    } else {
+        (1..=record_amount as u32).rev().collect_vec()
+    };
+
+    test_env::wrap(async |db| {
+        players::Entity::insert(player).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mut after = None;
+        let mut record_ids = Vec::new();
+
+        loop {
+            let result = get_player_records_connection(
+                &db.sql_conn,
+                &db.redis_pool,
+                1,
+                Default::default(),
+                ConnectionParameters {
+                    first: Some(page_size),
+                    after,
+                    ..Default::default()
+                },
+                is_desc.then_some(UnorderedRecordSort {
+                    field: UnorderedRecordSortableField::Date,
+                    order: Some(SortOrder::Descending),
+                }),
+                None,
+                Default::default(),
+            )
+            .await?;
+
+            assert_eq!(result.has_previous_page, !record_ids.is_empty());
+
+            for edge in &result.edges {
+                let record_id = edge.node.inner.record.record_id;
+                assert_eq!(
+                    edge.cursor.0,
+                    RecordDateCursor {
+                        record_date: record_date.and_utc(),
+                        data: record_id,
+                    }
+                    .encode_cursor()
+                );
+                record_ids.push(record_id);
+            }
+
+            after = result
+                .edges
+                .last()
+                .map(|edge| RecordDateCursor::decode_cursor(&edge.cursor.0))
+                .transpose()?;
+
+            if !result.has_next_page {
+                break;
+            }
+        }
+
+        assert_eq!(record_ids, expected);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn pages_same_date() -> anyhow::Result<()> {
+    setup();
+    test_pages_same_date(false).await
+}
+
+#[tokio::test]
+async fn pages_same_date_desc() -> anyhow::Result<()> {
+    setup();
+    test_pages_same_date(true).await
+}
@@ -1,15 +1,14 @@
 use async_graphql::connection::CursorType;
 use deadpool_redis::redis::{self, ToRedisArgs};
 use entity::{maps, players};
-use mkenv::Layer as _;
 use rand::Rng;
 use records_lib::RedisConnection;
-use sea_orm::{ActiveValue::Set, EntityTrait};
+use sea_orm::{ActiveValue::Set, ConnectionTrait as _, EntityTrait};
 
 use crate::{
     config::InitError,
     cursors::{ConnectionParameters, F64Cursor},
-    objects::root::get_maps_connection,
+    objects::root::{MapsProjection, get_maps_connection, maps_connection_query},
     utils::connection_input::ConnectionInputBuilder,
 };
 
@@ -80,7 +79,7 @@ where
 async fn default_page() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::maps_default_limit();
     let map_amount = default_limit * 2;
 
     let author = players::ActiveModel {
@@ -118,6 +117,7 @@ async fn default_page() -> anyhow::Result<()> {
             &db.sql_conn,
             &mut redis_conn,
             ConnectionInputBuilder::default().build(source),
+            Default::default(),
         )
         .await?;
 
@@ -170,7 +170,7 @@ async fn test_first_x_after_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .first
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::maps_default_limit);
 
     let computed_has_next_page = params.after_idx + 1 + limit < params.map_amount;
     if has_next_page != computed_has_next_page {
@@ -238,6 +238,7 @@ async fn test_first_x_after_y(
                 ..Default::default()
             })
             .build(source),
+            Default::default(),
         )
         .await?;
 
@@ -293,7 +294,7 @@ async fn test_last_x_before_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .last
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::maps_default_limit);
 
     let computed_has_previous_page = limit < params.before_idx;
     if has_previous_page != computed_has_previous_page {
@@ -356,6 +357,7 @@ async fn test_last_x_before_y(
                 ..Default::default()
             })
             .build(source),
+            Default::default(),
         )
         .await?;
 
@@ -432,7 +434,7 @@ async fn first_x_after_y() -> anyhow::Result<()> {
 async fn after_x() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::maps_default_limit();
     let map_amount = default_limit * 2;
 
     test_first_x_after_y(
@@ -511,7 +513,7 @@ async fn last_x_before_y() -> anyhow::Result<()> {
 async fn last_x() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::maps_default_limit();
     let map_amount = default_limit * 2;
 
     test_last_x_before_y(
@@ -547,3 +549,95 @@ async fn last_x() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn projection_of_name_only() -> anyhow::Result<()> {
+    setup();
+
+    let author = players::ActiveModel {
+        id: Set(1),
+        login: Set("boogalogin".to_owned()),
+        name: Set("booganame".to_owned()),
+        role: Set(0),
+        ..Default::default()
+    };
+
+    let maps = (0..3).map(|i| maps::ActiveModel {
+        id: Set(i + 1),
+        game_id: Set(format!("map_{i}_uid")),
+        name: Set(format!("map_{i}_name")),
+        score: Set((3 - i) as _),
+        player_id: Set(1),
+        cps_number: Set(Some(5)),
+        ..Default::default()
+    });
+
+    let source = gen_map_ranking_key();
+
+    let projection = MapsProjection {
+        game_id: false,
+        name: true,
+        cps_number: false,
+    };
+
+    test_env::wrap(async |db| {
+        players::Entity::insert(author).exec(&db.sql_conn).await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+
+        let mut redis_conn = db.redis_pool.get().await?;
+        fill_redis_lb(&mut redis_conn, &source, (1..=3).zip((0..3).rev())).await?;
+
+        // Only keep the columns selected by the outer query, without the quotes of the backend
+        let selected_columns = |projection| {
+            let sql = db
+                .sql_conn
+                .get_database_backend()
+                .build(&maps_connection_query(None, projection))
+                .to_string()
+                .replace(['`', '"'], "");
+            sql[..sql.find(" FROM ").unwrap_or(sql.len())].to_owned()
+        };
+
+        let full_select = selected_columns(MapsProjection::default());
+        assert!(full_select.contains("map.game_id"), "{full_select}");
+        assert!(full_select.contains("map.cps_number"), "{full_select}");
+
+        let narrow_select = selected_columns(projection);
+        assert!(narrow_select.contains("map.name"), "{narrow_select}");
+        assert!(!narrow_select.contains("map.game_id"), "{narrow_select}");
+        assert!(!narrow_select.contains("map.cps_number"), "{narrow_select}");
+
+        let result = get_maps_connection(
+            &db.sql_conn,
+            &mut redis_conn,
+            ConnectionInputBuilder::default().build(source),
+            projection,
+        )
+        .await?;
+
+        itertools::assert_equal(
+            result.edges.into_iter().map(|edge| {
+                let map = edge.node.map.inner;
+                (
+                    edge.node.rank,
+                    map.id,
+                    map.name,
+                    map.game_id,
+                    map.cps_number,
+                )
+            }),
+            (0..3).map(|i| {
+                (
+                    (i + 1) as i32,
+                    i + 1,
+                    format!("map_{i}_name"),
+                    String::new(),
+                    None,
+                )
+            }),
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
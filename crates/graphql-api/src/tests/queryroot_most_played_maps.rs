@@ -0,0 +1,129 @@
+use async_graphql::connection::CursorType;
+use entity::{maps, players, records};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{
+    config::InitError,
+    cursors::{ConnectionParameters, RecordCountCursor},
+    objects::map::most_played_connection,
+};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn paginate_most_played_maps() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let maps = (1..=4).map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (map_id, player_id, is_hidden)
+    // The map 2 has the most records, counting the two records of the player 1, and the hidden
+    // record on the map 3 is ignored, so the maps 1 and 3 are tied.
+    let records_info = [
+        (1, 1, false),
+        (2, 1, false),
+        (2, 1, false),
+        (2, 2, false),
+        (3, 1, false),
+        (3, 2, true),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(map_id, player_id, is_hidden)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(*map_id),
+            time: Set(5000),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            is_hidden: Set(*is_hidden),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let result = most_played_connection(
+            &db.sql_conn,
+            ConnectionParameters {
+                first: Some(2),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        assert!(result.has_next_page);
+        itertools::assert_equal(
+            result.edges.iter().map(|edge| {
+                (
+                    edge.cursor.0.clone(),
+                    edge.node.map.inner.id,
+                    edge.node.record_count,
+                )
+            }),
+            [(2, 3), (3, 1)].map(|(map_id, record_count)| {
+                (
+                    RecordCountCursor {
+                        record_count,
+                        data: map_id,
+                    }
+                    .encode_cursor(),
+                    map_id,
+                    record_count,
+                )
+            }),
+        );
+
+        let after = RecordCountCursor::decode_cursor(&result.edges[1].cursor.0)?;
+        let result = most_played_connection(
+            &db.sql_conn,
+            ConnectionParameters {
+                after: Some(after),
+                first: Some(2),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // The map 4 has no record but is still listed last
+        assert!(!result.has_next_page);
+        itertools::assert_equal(
+            result
+                .edges
+                .iter()
+                .map(|edge| (edge.node.map.inner.id, edge.node.record_count)),
+            [(1, 1), (4, 0)],
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -1,7 +1,6 @@
 use async_graphql::connection::CursorType;
 use deadpool_redis::redis::{self, ToRedisArgs};
 use entity::players;
-use mkenv::Layer as _;
 use rand::Rng;
 use records_lib::RedisConnection;
 use sea_orm::{ActiveValue::Set, EntityTrait};
@@ -80,7 +79,7 @@ where
 async fn default_page() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::players_default_limit();
     let player_amount = default_limit * 2;
 
     let players = (0..player_amount).map(|i| players::ActiveModel {
@@ -163,7 +162,7 @@ async fn test_first_x_after_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .first
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::players_default_limit);
 
     let computed_has_next_page = params.after_idx + 1 + limit < params.player_amount;
     if has_next_page != computed_has_next_page {
@@ -279,7 +278,7 @@ async fn test_last_x_before_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .last
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::players_default_limit);
 
     let computed_has_previous_page = limit < params.before_idx;
     if has_previous_page != computed_has_previous_page {
@@ -410,7 +409,7 @@ async fn first_x_after_y() -> anyhow::Result<()> {
 async fn after_x() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::players_default_limit();
     let player_amount = default_limit * 2;
 
     test_first_x_after_y(
@@ -489,7 +488,7 @@ async fn last_x_before_y() -> anyhow::Result<()> {
 async fn last_x() -> anyhow::Result<()> {
     setup();
 
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::players_default_limit();
     let player_amount = default_limit * 2;
 
     test_last_x_before_y(
@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use chrono::SubsecRound as _;
+use entity::{maps, players, records};
+use records_lib::records_notifier::RecordsNotifier;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+use crate::{
+    config::InitError,
+    schema::{create_schema, create_schema_standalone},
+};
+
+fn setup() {
+    match crate::init_config() {
+        Ok(_) | Err(InitError::ConfigAlreadySet) => (),
+        Err(InitError::Config(e)) => {
+            panic!("error during test setup: {e}");
+        }
+    }
+}
+
+#[test]
+fn records_deprecated() {
+    let sdl = create_schema_standalone().sdl();
+
+    let records_field = sdl
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("records("))
+        .expect("QueryRoot should have a `records` field");
+
+    assert!(
+        records_field.contains("@deprecated"),
+        "`records` field should be deprecated: {records_field}"
+    );
+}
+
+async fn query_ids(schema: &crate::schema::Schema, query: &str) -> anyhow::Result<Vec<String>> {
+    let response = schema.execute(query).await;
+    anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let data = response.data.into_json()?;
+    let records = data
+        .as_object()
+        .and_then(|data| data.values().next())
+        .and_then(|value| value.get("nodes").unwrap_or(value).as_array())
+        .ok_or_else(|| anyhow::anyhow!("unexpected response: {data}"))?;
+
+    Ok(records
+        .iter()
+        .map(|record| format!("{}:{}", record["id"], record["rank"]))
+        .collect())
+}
+
+#[tokio::test]
+async fn records_connection_reproduces_legacy_records() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=10).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let records = (1..=10).map(|i| records::ActiveModel {
+        record_id: Set(i),
+        map_id: Set(map_id),
+        record_player_id: Set(i),
+        flags: Set(682),
+        time: Set(1000 + (i as i32 % 4) * 100),
+        respawn_count: Set(0),
+        record_date: Set(now - Duration::from_secs(3600 * i as u64)),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let legacy = query_ids(&schema, "{ records { id rank } }").await?;
+        let connection = query_ids(
+            &schema,
+            "{ recordsConnection(first: 100) { nodes { id rank } } }",
+        )
+        .await?;
+        assert_eq!(legacy.len(), 10);
+        assert_eq!(legacy, connection);
+
+        let legacy = query_ids(&schema, "{ records(dateSortBy: REVERSE) { id rank } }").await?;
+        let connection = query_ids(
+            &schema,
+            "{ recordsConnection(first: 100, sort: { field: DATE, order: DESCENDING }) \
+                { nodes { id rank } } }",
+        )
+        .await?;
+        assert_eq!(legacy.len(), 10);
+        assert_eq!(legacy, connection);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn records_limit() -> anyhow::Result<()> {
+    setup();
+
+    let max_limit = crate::config::config().cursor_max_limit.get();
+    let record_amount = max_limit as u32 + 5;
+
+    let players = (1..=record_amount).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The higher the record ID, the less recent the record
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let records = (1..=record_amount).map(|i| records::ActiveModel {
+        record_id: Set(i),
+        map_id: Set(map_id),
+        record_player_id: Set(i),
+        flags: Set(682),
+        time: Set(1000 + i as i32),
+        respawn_count: Set(0),
+        record_date: Set(now - Duration::from_secs(3600 * i as u64)),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        // Only the IDs are compared, the ranks aren't relevant here
+        let ids = |records: Vec<String>| {
+            records
+                .into_iter()
+                .map(|record| record.split(':').next().unwrap_or_default().to_owned())
+                .collect::<Vec<_>>()
+        };
+        let expected = (1..=record_amount)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+
+        let records = ids(query_ids(&schema, "{ records(limit: 3) { id rank } }").await?);
+        assert_eq!(records, expected[..3]);
+
+        // The records are still sorted by date with a limit
+        let records = ids(query_ids(
+            &schema,
+            "{ records(dateSortBy: REVERSE, limit: 3) { id rank } }",
+        )
+        .await?);
+        itertools::assert_equal(&records, expected.iter().rev().take(3));
+
+        // The limit is capped
+        let records = ids(query_ids(&schema, "{ records(limit: 1000) { id rank } }").await?);
+        assert_eq!(records, expected[..max_limit]);
+
+        let records = query_ids(&schema, "{ records { id rank } }").await?;
+        assert_eq!(records.len(), max_limit.min(100));
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn records_connection_total_count() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=10).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids.map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The records are split between both maps
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let records = (1..=10).map(|i| records::ActiveModel {
+        record_id: Set(i),
+        map_id: Set(map_ids[i as usize % 2]),
+        record_player_id: Set(i),
+        flags: Set(682),
+        time: Set(1000 + i as i32 * 100),
+        respawn_count: Set(0),
+        record_date: Set(now - Duration::from_secs(3600 * i as u64)),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let schema = create_schema(
+            db,
+            reqwest::Client::new(),
+            RecordsNotifier::default().get_subscription(),
+        );
+
+        let query = async |query: &str| {
+            let response = schema.execute(query).await;
+            anyhow::ensure!(response.errors.is_empty(), "{:?}", response.errors);
+            anyhow::Ok(response.data.into_json()?)
+        };
+
+        // The total count isn't limited by the size of the page
+        let data = query("{ recordsConnection(first: 2) { totalCount nodes { id } } }").await?;
+        let connection = &data["recordsConnection"];
+        assert_eq!(connection["nodes"].as_array().map(Vec::len), Some(2));
+        assert_eq!(connection["totalCount"], 10);
+
+        // But it's limited by the filter
+        let data = query(
+            "{ recordsConnection(first: 2, filter: { timeLt: 1550 }) { totalCount nodes { id } } }",
+        )
+        .await?;
+        let connection = &data["recordsConnection"];
+        assert_eq!(connection["nodes"].as_array().map(Vec::len), Some(2));
+        assert_eq!(connection["totalCount"], 5);
+
+        // The connection of the records of a map only counts the records of the map
+        let data = query(&format!(
+            "{{ map(gameId: \"map_{}_uid\") {{ recordsConnection(first: 2) {{ totalCount }} }} }}",
+            map_ids[0]
+        ))
+        .await?;
+        assert_eq!(data["map"]["recordsConnection"]["totalCount"], 5);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -1,4 +1,3 @@
-use mkenv::prelude::*;
 use std::time::Duration;
 
 use async_graphql::connection::CursorType;
@@ -9,10 +8,13 @@ use sea_orm::{ActiveValue::Set, EntityTrait};
 
 use crate::{
     config::InitError,
-    cursors::{ConnectionParameters, RecordDateCursor},
+    cursors::{ConnectionParameters, RecordDateCursor, RecordSortCursor, SortKeyValue},
     objects::{
-        root::get_records_connection, sort::UnorderedRecordSort, sort_order::SortOrder,
-        sortable_fields::UnorderedRecordSortableField,
+        ranked_record::RecordRelations,
+        root::{get_records_connection, get_sorted_records_connection},
+        sort::{RecordSort, UnorderedRecordSort},
+        sort_order::SortOrder,
+        sortable_fields::{RecordSortableField, UnorderedRecordSortableField},
     },
 };
 
@@ -40,7 +42,7 @@ struct Record {
 
 #[tracing::instrument]
 async fn test_default_page(is_desc: bool) -> anyhow::Result<()> {
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
     let player_amount = default_limit * 2;
 
     let players = (0..player_amount).map(|i| players::ActiveModel {
@@ -101,6 +103,7 @@ async fn test_default_page(is_desc: bool) -> anyhow::Result<()> {
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -242,7 +245,7 @@ async fn test_first_x_after_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .first
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::records_default_limit);
 
     let computed_has_next_page = params.after_idx + 1 + limit < params.player_amount;
     if has_next_page != computed_has_next_page {
@@ -335,6 +338,7 @@ async fn test_first_x_after_y(
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -411,7 +415,7 @@ async fn test_last_x_before_y(
 ) -> anyhow::Result<()> {
     let limit = params
         .last
-        .unwrap_or_else(|| crate::config().cursor_default_limit.get());
+        .unwrap_or_else(crate::config::records_default_limit);
 
     let computed_has_previous_page = limit < params.before_idx;
     if has_previous_page != computed_has_previous_page {
@@ -499,6 +503,7 @@ async fn test_last_x_before_y(
                 order: Some(SortOrder::Descending),
             }),
             None,
+            Default::default(),
         )
         .await?;
 
@@ -613,7 +618,7 @@ async fn first_x_after_y_desc() -> anyhow::Result<()> {
 #[tokio::test]
 async fn after_x() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_first_x_after_y(
         FirstXAfterYParams {
@@ -655,7 +660,7 @@ async fn after_x() -> anyhow::Result<()> {
 #[tokio::test]
 async fn after_x_desc() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_first_x_after_y(
         FirstXAfterYParams {
@@ -779,7 +784,7 @@ async fn last_x_before_y_desc() -> anyhow::Result<()> {
 #[tokio::test]
 async fn before_x() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_last_x_before_y(
         LastXBeforeYParams {
@@ -821,7 +826,7 @@ async fn before_x() -> anyhow::Result<()> {
 #[tokio::test]
 async fn before_x_desc() -> anyhow::Result<()> {
     setup();
-    let default_limit = crate::config().cursor_default_limit.get();
+    let default_limit = crate::config::records_default_limit();
 
     test_last_x_before_y(
         LastXBeforeYParams {
@@ -859,3 +864,325 @@ async fn before_x_desc() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn eager_load_relations() -> anyhow::Result<()> {
+    setup();
+
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set("map_uid".to_owned()),
+        name: Set("map_name".to_owned()),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let records = (1..=3).map(|player_id| records::ActiveModel {
+        record_id: Set(player_id),
+        map_id: Set(map_id),
+        record_player_id: Set(player_id),
+        flags: Set(682),
+        time: Set(1000),
+        respawn_count: Set(0),
+        record_date: Set(now - Duration::from_secs(3600 * player_id as u64)),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // Only the player is requested, so only the players table is joined
+        let result = get_records_connection(
+            &db.sql_conn,
+            &db.redis_pool,
+            Default::default(),
+            Default::default(),
+            None,
+            None,
+            RecordRelations {
+                player: true,
+                map: false,
+            },
+        )
+        .await?;
+
+        itertools::assert_equal(
+            result.edges.iter().map(|edge| {
+                (
+                    edge.node.inner.record.record_player_id,
+                    edge.node.player.as_ref().map(|p| p.inner.name.as_str()),
+                    edge.node.map.is_some(),
+                )
+            }),
+            [
+                (1, Some("player_1_name"), false),
+                (2, Some("player_2_name"), false),
+                (3, Some("player_3_name"), false),
+            ],
+        );
+
+        let result = get_records_connection(
+            &db.sql_conn,
+            &db.redis_pool,
+            Default::default(),
+            Default::default(),
+            None,
+            None,
+            RecordRelations {
+                player: true,
+                map: true,
+            },
+        )
+        .await?;
+
+        for edge in &result.edges {
+            let player = edge.node.player.as_ref().map(|p| p.inner.id);
+            let map = edge.node.map.as_ref().map(|m| m.inner.name.as_str());
+            assert_eq!(player, Some(edge.node.inner.record.record_player_id));
+            assert_eq!(map, Some("map_name"));
+        }
+
+        // Without any relation requested, they're resolved separately
+        let result = get_records_connection(
+            &db.sql_conn,
+            &db.redis_pool,
+            Default::default(),
+            Default::default(),
+            None,
+            None,
+            Default::default(),
+        )
+        .await?;
+
+        assert_eq!(result.edges.len(), 3);
+        for edge in &result.edges {
+            assert!(edge.node.player.is_none());
+            assert!(edge.node.map.is_none());
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+fn sorted_by_map_then_time(order: SortOrder) -> [RecordSort; 2] {
+    [
+        RecordSort {
+            field: RecordSortableField::Map,
+            order: Some(order),
+        },
+        RecordSort {
+            field: RecordSortableField::Time,
+            order: Some(order),
+        },
+    ]
+}
+
+#[tracing::instrument]
+async fn test_sorted_by_map_then_time(is_desc: bool) -> anyhow::Result<()> {
+    let players = (1..=3).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids.map(|map_id| maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // (record ID, map ID, player ID, time), with tied times on both maps
+    let fixtures = [
+        (1, map_ids[1], 1, 2000),
+        (2, map_ids[0], 1, 3000),
+        (3, map_ids[0], 2, 1000),
+        (4, map_ids[1], 2, 1000),
+        (5, map_ids[0], 3, 1000),
+        (6, map_ids[1], 3, 2000),
+    ];
+
+    let now = chrono::Utc::now().naive_utc().trunc_subsecs(0);
+    let records =
+        fixtures.iter().map(
+            |&(record_id, map_id, player_id, time)| records::ActiveModel {
+                record_id: Set(record_id),
+                map_id: Set(map_id),
+                record_player_id: Set(player_id),
+                flags: Set(682),
+                time: Set(time),
+                respawn_count: Set(0),
+                record_date: Set(now),
+                ..Default::default()
+            },
+        );
+
+    let mut expected = fixtures
+        .iter()
+        .sorted_by_key(|&&(record_id, map_id, _, time)| (map_id, time, record_id))
+        .map(|&(record_id, map_id, _, time)| {
+            (
+                record_id,
+                RecordSortCursor {
+                    keys: vec![SortKeyValue::Map(map_id), SortKeyValue::Time(time)],
+                    data: record_id,
+                }
+                .encode_cursor(),
+            )
+        })
+        .collect_vec();
+    if is_desc {
+        expected.reverse();
+    }
+
+    let sorts = sorted_by_map_then_time(if is_desc {
+        SortOrder::Descending
+    } else {
+        SortOrder::Ascending
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        // Go through the pages by following the cursor of the last record of each page
+        let mut after = None;
+        let mut result = Vec::new();
+
+        for page in 0..3 {
+            let connection = get_sorted_records_connection(
+                &db.sql_conn,
+                &db.redis_pool,
+                ConnectionParameters {
+                    after: after.take(),
+                    before: None,
+                    first: Some(2),
+                    last: None,
+                },
+                Default::default(),
+                &sorts,
+                None,
+                Default::default(),
+            )
+            .await?;
+
+            assert_eq!(connection.has_next_page, page < 2);
+            after = connection
+                .edges
+                .last()
+                .map(|edge| RecordSortCursor::decode_cursor(&edge.cursor.0))
+                .transpose()?;
+            result.extend(
+                connection
+                    .edges
+                    .into_iter()
+                    .map(|edge| (edge.node.inner.record.record_id, edge.cursor.0)),
+            );
+        }
+
+        itertools::assert_equal(result, expected);
+
+        anyhow::Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn sorted_by_map_then_time() -> anyhow::Result<()> {
+    setup();
+    test_sorted_by_map_then_time(false).await
+}
+
+#[tokio::test]
+async fn sorted_by_map_then_time_desc() -> anyhow::Result<()> {
+    setup();
+    test_sorted_by_map_then_time(true).await
+}
+
+#[tokio::test]
+async fn invalid_sorts() -> anyhow::Result<()> {
+    setup();
+
+    let [map_sort, time_sort] = sorted_by_map_then_time(SortOrder::Ascending);
+    let date_sort = RecordSort {
+        field: RecordSortableField::Date,
+        order: None,
+    };
+
+    test_env::wrap(async |db| {
+        let invalid_sorts: [&[RecordSort]; 4] = [
+            &[],
+            &[map_sort, time_sort, date_sort],
+            &[map_sort, map_sort],
+            &[
+                map_sort,
+                RecordSort {
+                    order: Some(SortOrder::Descending),
+                    ..time_sort
+                },
+            ],
+        ];
+
+        for sorts in invalid_sorts {
+            let result = get_sorted_records_connection(
+                &db.sql_conn,
+                &db.redis_pool,
+                Default::default(),
+                Default::default(),
+                sorts,
+                None,
+                Default::default(),
+            )
+            .await;
+            assert!(result.is_err(), "{sorts:?} should be rejected");
+        }
+
+        // The cursor must have been built with the same sort
+        let result = get_sorted_records_connection(
+            &db.sql_conn,
+            &db.redis_pool,
+            ConnectionParameters {
+                after: Some(RecordSortCursor {
+                    keys: vec![SortKeyValue::Time(1000)],
+                    data: 1,
+                }),
+                ..Default::default()
+            },
+            Default::default(),
+            &[map_sort, time_sort],
+            None,
+            Default::default(),
+        )
+        .await;
+        assert!(result.is_err());
+
+        anyhow::Ok(())
+    })
+    .await
+}
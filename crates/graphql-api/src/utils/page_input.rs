@@ -26,7 +26,9 @@ impl<C> PaginationInput<C> {
 }
 
 impl<C> PaginationInput<C> {
-    pub fn try_from_input(input: ConnectionParameters<C>) -> GqlResult<Self> {
+    /// Converts the connection parameters, using the provided page size if neither `first`
+    /// nor `last` is given.
+    pub fn try_from_input(input: ConnectionParameters<C>, default_limit: usize) -> GqlResult<Self> {
         match input {
             ConnectionParameters {
                 first,
@@ -36,7 +38,7 @@ impl<C> PaginationInput<C> {
             } => {
                 let limit = first
                     .map(|t| t.min(crate::config().cursor_max_limit.get()))
-                    .unwrap_or(crate::config().cursor_default_limit.get());
+                    .unwrap_or(default_limit);
                 Ok(Self {
                     limit,
                     dir: PaginationDirection::After { cursor: after },
@@ -50,7 +52,7 @@ impl<C> PaginationInput<C> {
             } => {
                 let limit = last
                     .map(|t| t.min(crate::config().cursor_max_limit.get()))
-                    .unwrap_or(crate::config().cursor_default_limit.get());
+                    .unwrap_or(default_limit);
                 Ok(Self {
                     limit,
                     dir: PaginationDirection::Before { cursor: before },
@@ -10,6 +10,11 @@ mod m20251002_100827_add_rm_mp_style_func;
 mod m20251004_214656_add_maps_medal_times;
 mod m20260103_142202_players_maps_score;
 mod m20260109_101455_refactor_rm_mp_style;
+mod m20261016_093012_add_records_is_hidden;
+mod m20261016_141530_add_max_respawn_count;
+mod m20261016_160245_add_event_edition_no_respawn_only;
+mod m20261016_183320_add_maps_created_at;
+mod m20261016_204512_exclude_hidden_records_from_views;
 
 use sea_orm_migration::prelude::*;
 
@@ -33,6 +38,11 @@ impl MigratorTrait for Migrator {
             Box::new(m20251004_214656_add_maps_medal_times::Migration),
             Box::new(m20260103_142202_players_maps_score::Migration),
             Box::new(m20260109_101455_refactor_rm_mp_style::Migration),
+            Box::new(m20261016_093012_add_records_is_hidden::Migration),
+            Box::new(m20261016_141530_add_max_respawn_count::Migration),
+            Box::new(m20261016_160245_add_event_edition_no_respawn_only::Migration),
+            Box::new(m20261016_183320_add_maps_created_at::Migration),
+            Box::new(m20261016_204512_exclude_hidden_records_from_views::Migration),
         ]
     }
 }
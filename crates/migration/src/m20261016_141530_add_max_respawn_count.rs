@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Maps::Table)
+                    .add_column(
+                        ColumnDef::new(Maps::MaxRespawnCount)
+                            .null()
+                            .unsigned()
+                            .take(),
+                    )
+                    .take(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventEdition::Table)
+                    .add_column(
+                        ColumnDef::new(EventEdition::MaxRespawnCount)
+                            .null()
+                            .unsigned()
+                            .take(),
+                    )
+                    .take(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventEdition::Table)
+                    .drop_column(EventEdition::MaxRespawnCount)
+                    .take(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Maps::Table)
+                    .drop_column(Maps::MaxRespawnCount)
+                    .take(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Maps {
+    Table,
+    MaxRespawnCount,
+}
+
+#[derive(DeriveIden)]
+enum EventEdition {
+    Table,
+    MaxRespawnCount,
+}
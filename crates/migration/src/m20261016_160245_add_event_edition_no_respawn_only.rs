@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventEdition::Table)
+                    .add_column(
+                        ColumnDef::new(EventEdition::NoRespawnOnly)
+                            .boolean()
+                            .not_null()
+                            .default(false)
+                            .take(),
+                    )
+                    .take(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventEdition::Table)
+                    .drop_column(EventEdition::NoRespawnOnly)
+                    .take(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventEdition {
+    Table,
+    NoRespawnOnly,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Maps::Table)
+                    .add_column(
+                        ColumnDef::new(Maps::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp())
+                            .take(),
+                    )
+                    .take(),
+            )
+            .await?;
+
+        // The existing maps are considered created at the date of their first record
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE maps SET created_at = COALESCE(\
+                    (SELECT MIN(r.record_date) FROM records r WHERE r.map_id = maps.id), \
+                    created_at)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Maps::Table)
+                    .drop_column(Maps::CreatedAt)
+                    .take(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Maps {
+    Table,
+    CreatedAt,
+}
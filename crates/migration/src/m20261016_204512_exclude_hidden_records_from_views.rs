@@ -0,0 +1,39 @@
+mod view_management;
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("drop view global_event_records")
+            .await?;
+        conn.execute_unprepared("drop view global_records").await?;
+
+        conn.execute_unprepared(view_management::CREATE_NEW_VIEW_GLOBAL_RECORDS)
+            .await?;
+        conn.execute_unprepared(view_management::CREATE_NEW_VIEW_GLOBAL_EVENT_RECORDS)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("drop view global_event_records")
+            .await?;
+        conn.execute_unprepared("drop view global_records").await?;
+
+        conn.execute_unprepared(view_management::CREATE_OLD_VIEW_GLOBAL_RECORDS)
+            .await?;
+        conn.execute_unprepared(view_management::CREATE_OLD_VIEW_GLOBAL_EVENT_RECORDS)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+pub const CREATE_OLD_VIEW_GLOBAL_RECORDS: &str =
+    include_str!("./create_old_view_global_records.sql");
+pub const CREATE_NEW_VIEW_GLOBAL_RECORDS: &str =
+    include_str!("./create_new_view_global_records.sql");
+pub const CREATE_OLD_VIEW_GLOBAL_EVENT_RECORDS: &str =
+    include_str!("./create_old_view_global_event_records.sql");
+pub const CREATE_NEW_VIEW_GLOBAL_EVENT_RECORDS: &str =
+    include_str!("./create_new_view_global_event_records.sql");
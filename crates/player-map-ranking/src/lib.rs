@@ -1,14 +1,18 @@
 use std::{collections::HashMap, hash::Hash};
 
 use anyhow::Context as _;
+use deadpool_redis::redis::AsyncCommands as _;
 use entity::{functions, global_records, maps, players};
+use futures::{StreamExt as _, TryStreamExt as _, stream};
+use records_lib::{RedisConnection, redis_key::player_ranking_map_stats};
 use sea_orm::{
-    ColumnTrait as _, ConnectionTrait, EntityTrait as _, QueryFilter as _, QueryOrder, QuerySelect,
-    QueryTrait,
+    ColumnTrait as _, ConnectionTrait, EntityTrait as _, PaginatorTrait as _, QueryFilter as _,
+    QueryOrder, QuerySelect, QueryTrait,
+    sea_query::Func,
     sqlx::types::chrono::{DateTime, Utc},
 };
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct MapStats {
     pub records_count: f64,
     pub min_record: f64,
@@ -21,9 +25,56 @@ fn ms_to_sec(time: i32) -> f64 {
     time as f64 / 1000.
 }
 
-fn compute_score(r: f64, rn: f64, t: f64, average_record: f64) -> f64 {
-    let record_score = (1000.0 * (rn * rn)).log10() + ((average_record - t).powi(2) + 1.0).log10();
-    record_score * ((rn / r) + 1.0).log10().powi(3)
+/// The coefficients of the formula used to compute the score of a record.
+///
+/// With `n` the amount of records on the map, `r` the rank of the record, `t` its time, and `avg`
+/// the average time on the map, the score of the record is:
+///
+/// ```text
+/// (log10(records_count_weight * n^records_count_exponent)
+///     + log10(time_gap_weight * (avg - max(t, avg))^2 + 1))
+///     * log10(n / r + 1)^rank_exponent
+/// ```
+///
+/// The default parameters correspond to the original formula.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoringParams {
+    /// The weight of the amount of records on the map.
+    ///
+    /// It is applied to every record of the map, so it mostly changes the score of the maps
+    /// between each other.
+    pub records_count_weight: f64,
+    /// The exponent of the amount of records on the map.
+    ///
+    /// The higher it is, the more the records on crowded maps are worth.
+    pub records_count_exponent: i32,
+    /// The weight of the gap between the time of the record and the average time on the map.
+    ///
+    /// Only the records slower than the average have a gap, so the higher it is, the more
+    /// the long maps are worth.
+    pub time_gap_weight: f64,
+    /// The exponent of the factor based on the rank of the record.
+    ///
+    /// The higher it is, the more the best ranks are favored over the others.
+    pub rank_exponent: i32,
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        Self {
+            records_count_weight: 1000.,
+            records_count_exponent: 2,
+            time_gap_weight: 1.,
+            rank_exponent: 3,
+        }
+    }
+}
+
+fn compute_score(r: f64, rn: f64, t: f64, average_record: f64, params: ScoringParams) -> f64 {
+    let record_score = (params.records_count_weight * rn.powi(params.records_count_exponent))
+        .log10()
+        + (params.time_gap_weight * (average_record - t).powi(2) + 1.0).log10();
+    record_score * ((rn / r) + 1.0).log10().powi(params.rank_exponent)
 }
 
 #[derive(sea_orm::FromQueryResult)]
@@ -79,16 +130,174 @@ impl PartialEq for HashableMap {
 
 impl Eq for HashableMap {}
 
+#[derive(Default)]
 pub struct Scores {
     pub player_scores: HashMap<HashablePlayer, f64>,
     pub map_scores: HashMap<HashableMap, f64>,
+    /// The score of each player on each map, by map ID then by player ID.
+    pub player_map_scores: HashMap<u32, HashMap<u32, f64>>,
+}
+
+/// The scores saved by a previous run, used by [`compute_scores_incremental`].
+#[derive(Default)]
+pub struct PriorScores {
+    /// The total score of each player.
+    pub player_scores: HashMap<u32, f64>,
+    /// The score of each player on each map, by map ID then by player ID.
+    pub player_map_scores: HashMap<u32, HashMap<u32, f64>>,
+}
+
+struct MapScores {
+    stats: MapStats,
+    map_score: f64,
+    player_scores: HashMap<u32, f64>,
+}
+
+/// Computes the stats of a map, based on its records sorted by time.
+///
+/// Returns `None` if the map has no record, as the stats would be undefined.
+fn compute_map_stats(map_records: &[global_records::Model]) -> Option<MapStats> {
+    if map_records.is_empty() {
+        return None;
+    }
+
+    let mut stats = MapStats {
+        records_count: map_records.len() as _,
+        min_record: ms_to_sec(map_records[0].time),
+        max_record: ms_to_sec(map_records[0].time),
+        ..Default::default()
+    };
+
+    for record in map_records {
+        stats.min_record = stats.min_record.min(ms_to_sec(record.time));
+        stats.max_record = stats.max_record.max(ms_to_sec(record.time));
+        stats.average_record += ms_to_sec(record.time);
+    }
+
+    stats.average_record /= stats.records_count;
+    stats.median_record = ms_to_sec(map_records[map_records.len() / 2].time);
+
+    Some(stats)
+}
+
+/// Computes the scores of a map, based on its records sorted by time.
+///
+/// Returns `None` if the map has no record, as the stats would be undefined.
+fn compute_map_scores(
+    map_records: &[global_records::Model],
+    params: ScoringParams,
+) -> Option<MapScores> {
+    let stats = compute_map_stats(map_records)?;
+
+    let mut map_score = 0.;
+    let mut player_scores = HashMap::new();
+
+    for (i, record) in map_records.iter().enumerate() {
+        let r = (i + 1) as f64;
+        let t = ms_to_sec(record.time).max(stats.average_record);
+        let score = compute_score(r, stats.records_count, t, stats.average_record, params);
+
+        map_score += score;
+        *player_scores.entry(record.record_player_id).or_insert(0.) += score;
+    }
+
+    Some(MapScores {
+        stats,
+        map_score,
+        player_scores,
+    })
+}
+
+/// The default maximum amount of maps whose records are fetched at the same time.
+pub const DEFAULT_MAX_CONCURRENT_MAPS: usize = 8;
+
+/// The estimated cost of a computation of the scores from scratch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringCost {
+    /// The amount of maps to process.
+    pub map_count: u64,
+    /// The amount of records to fetch, which is one per player on each map.
+    pub record_count: u64,
+}
+
+/// Estimates the cost of a computation of the scores from scratch, by counting the maps and the
+/// records it would fetch, without fetching them.
+pub async fn estimate_cost<C: ConnectionTrait>(conn: &C) -> anyhow::Result<ScoringCost> {
+    let map_count = maps::Entity::find()
+        .count(conn)
+        .await
+        .context("couldn't count the maps")?;
+    let record_count = global_records::Entity::find()
+        .count(conn)
+        .await
+        .context("couldn't count the records")?;
+
+    Ok(ScoringCost {
+        map_count,
+        record_count,
+    })
 }
 
 pub async fn compute_scores<C: ConnectionTrait>(
     conn: &C,
     from: Option<DateTime<Utc>>,
+    params: ScoringParams,
+) -> anyhow::Result<Scores> {
+    compute_scores_with_concurrency(conn, from, params, DEFAULT_MAX_CONCURRENT_MAPS).await
+}
+
+/// Same as [`compute_scores`], but fetches the records of at most `max_concurrent_maps` maps
+/// at the same time.
+pub async fn compute_scores_with_concurrency<C: ConnectionTrait>(
+    conn: &C,
+    from: Option<DateTime<Utc>>,
+    params: ScoringParams,
+    max_concurrent_maps: usize,
+) -> anyhow::Result<Scores> {
+    compute_scores_impl(conn, from, params, max_concurrent_maps, None).await
+}
+
+/// The range of the maps processed by [`compute_scores_chunk`], the maps being sorted by ID.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MapsRange {
+    /// The amount of maps to skip.
+    pub offset: u64,
+    /// The maximum amount of maps to process, or `None` to process all the remaining maps.
+    pub max_maps: Option<u64>,
+}
+
+/// Computes the scores of the maps in the provided range only, so that the computation can be
+/// split across several runs.
+///
+/// Unlike [`compute_scores`], only the players having a record on these maps are returned, with
+/// the sum of their scores on these maps. Summing these scores over all the chunks gives the
+/// total scores of the players.
+///
+/// The returned map scores contain every map of the range, so the last chunk is reached when
+/// there are fewer of them than the maximum.
+pub async fn compute_scores_chunk<C: ConnectionTrait>(
+    conn: &C,
+    from: Option<DateTime<Utc>>,
+    params: ScoringParams,
+    range: MapsRange,
+) -> anyhow::Result<Scores> {
+    compute_scores_impl(conn, from, params, DEFAULT_MAX_CONCURRENT_MAPS, Some(range)).await
+}
+
+async fn compute_scores_impl<C: ConnectionTrait>(
+    conn: &C,
+    from: Option<DateTime<Utc>>,
+    params: ScoringParams,
+    max_concurrent_maps: usize,
+    range: Option<MapsRange>,
 ) -> anyhow::Result<Scores> {
     let mut maps = maps::Entity::find()
+        .apply_if(range, |query, range| {
+            query
+                .order_by_asc(maps::Column::Id)
+                .offset(range.offset)
+                .limit(range.max_maps)
+        })
         .expr_as(functions::unstyled(maps::Column::Name), "unstyled_name")
         .into_model::<RawMap>()
         .all(conn)
@@ -111,50 +320,43 @@ pub async fn compute_scores<C: ConnectionTrait>(
     let mut map_stats = HashMap::<u32, MapStats>::new();
     let mut map_scores = HashMap::<u32, f64>::new();
     let mut player_scores = HashMap::<u32, f64>::new();
+    let mut player_map_scores = HashMap::new();
 
-    for map in maps.values() {
-        let map_records = global_records::Entity::find()
-            .filter(global_records::Column::MapId.eq(map.inner.id))
-            .apply_if(from, |query, from| {
-                query.filter(global_records::Column::RecordDate.gte(from))
-            })
-            .order_by_asc(global_records::Column::Time)
-            .all(conn)
-            .await
-            .with_context(|| format!("couldn't get records of map ID: {}", map.inner.id))?;
+    let mut maps_scores = stream::iter(maps.keys().copied())
+        .map(|map_id| async move {
+            let map_records = global_records::Entity::find()
+                .filter(global_records::Column::MapId.eq(map_id))
+                .apply_if(from, |query, from| {
+                    query.filter(global_records::Column::RecordDate.gte(from))
+                })
+                .order_by_asc(global_records::Column::Time)
+                .order_by_asc(global_records::Column::RecordId)
+                .all(conn)
+                .await
+                .with_context(|| format!("couldn't get records of map ID: {map_id}"))?;
 
-        if map_records.is_empty() {
-            continue;
-        }
+            anyhow::Ok((map_id, compute_map_scores(&map_records, params)))
+        })
+        .buffer_unordered(max_concurrent_maps.max(1))
+        .try_collect::<Vec<_>>()
+        .await?;
 
-        let mut stats = MapStats {
-            records_count: map_records.len() as _,
-            min_record: ms_to_sec(map_records[0].time),
-            max_record: ms_to_sec(map_records[0].time),
-            ..Default::default()
-        };
+    // The scores are summed in the same order whatever the order in which the maps were
+    // processed, so that the result is deterministic.
+    maps_scores.sort_unstable_by_key(|(map_id, _)| *map_id);
 
-        for record in &map_records {
-            stats.min_record = stats.min_record.min(ms_to_sec(record.time));
-            stats.max_record = stats.max_record.max(ms_to_sec(record.time));
-            stats.average_record += ms_to_sec(record.time);
-        }
-
-        stats.average_record /= stats.records_count;
-        stats.median_record = ms_to_sec(map_records[map_records.len() / 2].time);
-
-        for (i, record) in map_records.iter().enumerate() {
-            let r = (i + 1) as f64;
-            let t = ms_to_sec(record.time).max(stats.average_record);
-            let score = compute_score(r, stats.records_count, t, stats.average_record);
+    for (map_id, scores) in maps_scores {
+        let Some(scores) = scores else {
+            continue;
+        };
 
-            let map_score = map_scores.entry(record.map_id).or_insert(0.);
-            let player_score = player_scores.entry(record.record_player_id).or_insert(0.);
-            *map_score += score;
-            *player_score += score;
+        map_scores.insert(map_id, scores.map_score);
+        for (player_id, score) in &scores.player_scores {
+            *player_scores.entry(*player_id).or_insert(0.) += score;
         }
 
-        map_stats.insert(map.inner.id, stats);
+        map_stats.insert(map_id, scores.stats);
+        player_map_scores.insert(map_id, scores.player_scores);
     }
 
     let mut output = Scores {
@@ -185,17 +387,21 @@ pub async fn compute_scores<C: ConnectionTrait>(
                 )
             })
             .collect(),
+        player_map_scores,
     };
 
-    // Insert remaining players and maps, having 0 as score
-    for (_, player) in players {
-        output.player_scores.insert(
-            HashablePlayer {
-                inner: player.inner,
-                unstyled_name: player.unstyled_name,
-            },
-            0.,
-        );
+    // Insert remaining players and maps, having 0 as score. The players without any record on
+    // a chunk of maps may have a score on the others, so they're left out.
+    if range.is_none() {
+        for (_, player) in players {
+            output.player_scores.insert(
+                HashablePlayer {
+                    inner: player.inner,
+                    unstyled_name: player.unstyled_name,
+                },
+                0.,
+            );
+        }
     }
     for (_, map) in maps {
         output.map_scores.insert(
@@ -210,3 +416,234 @@ pub async fn compute_scores<C: ConnectionTrait>(
 
     Ok(output)
 }
+
+/// Computes the scores incrementally, by only recomputing the maps that received new records
+/// since the provided date.
+///
+/// The `load_prior` function is called with the IDs of these maps and the IDs of the players
+/// having a record on them, and must return the scores saved by the previous run. The scores
+/// of the players are then adjusted with the difference on each recomputed map.
+///
+/// The returned scores only contain the recomputed maps and the affected players.
+pub async fn compute_scores_incremental<C, F>(
+    conn: &C,
+    since: DateTime<Utc>,
+    params: ScoringParams,
+    load_prior: F,
+) -> anyhow::Result<Scores>
+where
+    C: ConnectionTrait,
+    F: AsyncFnOnce(&[u32], &[u32]) -> anyhow::Result<PriorScores>,
+{
+    let map_ids = global_records::Entity::find()
+        .select_only()
+        .column(global_records::Column::MapId)
+        .distinct()
+        .filter(global_records::Column::RecordDate.gte(since))
+        .into_tuple::<u32>()
+        .all(conn)
+        .await
+        .context("couldn't retrieve the maps with new records")?;
+
+    if map_ids.is_empty() {
+        return Ok(Scores::default());
+    }
+
+    let mut map_records = HashMap::<u32, Vec<_>>::new();
+    for record in global_records::Entity::find()
+        .filter(global_records::Column::MapId.is_in(map_ids.iter().copied()))
+        .order_by_asc(global_records::Column::Time)
+        .order_by_asc(global_records::Column::RecordId)
+        .all(conn)
+        .await
+        .context("couldn't get records of the updated maps")?
+    {
+        map_records.entry(record.map_id).or_default().push(record);
+    }
+
+    let mut player_ids = map_records
+        .values()
+        .flatten()
+        .map(|record| record.record_player_id)
+        .collect::<Vec<_>>();
+    player_ids.sort_unstable();
+    player_ids.dedup();
+
+    let mut prior = load_prior(&map_ids, &player_ids)
+        .await
+        .context("couldn't load the prior scores")?;
+
+    let mut map_stats = HashMap::<u32, MapStats>::new();
+    let mut map_scores = HashMap::<u32, f64>::new();
+    let mut player_map_scores = HashMap::new();
+    let mut player_deltas = HashMap::<u32, f64>::new();
+
+    for map_id in &map_ids {
+        let (stats, map_score, scores) = map_records
+            .get(map_id)
+            .and_then(|records| compute_map_scores(records, params))
+            .map(|scores| (scores.stats, scores.map_score, scores.player_scores))
+            .unwrap_or_default();
+
+        let prior_scores = prior.player_map_scores.remove(map_id).unwrap_or_default();
+        for (player_id, score) in &prior_scores {
+            *player_deltas.entry(*player_id).or_insert(0.) -= score;
+        }
+        for (player_id, score) in &scores {
+            *player_deltas.entry(*player_id).or_insert(0.) += score;
+        }
+
+        map_stats.insert(*map_id, stats);
+        map_scores.insert(*map_id, map_score);
+        player_map_scores.insert(*map_id, scores);
+    }
+
+    let players = players::Entity::find()
+        .filter(players::Column::Id.is_in(player_deltas.keys().copied()))
+        .expr_as(functions::unstyled(players::Column::Name), "unstyled_name")
+        .into_model::<RawPlayer>()
+        .all(conn)
+        .await
+        .context("couldn't retrieve the updated players")?;
+
+    let maps = maps::Entity::find()
+        .filter(maps::Column::Id.is_in(map_ids.iter().copied()))
+        .expr_as(functions::unstyled(maps::Column::Name), "unstyled_name")
+        .into_model::<RawMap>()
+        .all(conn)
+        .await
+        .context("couldn't retrieve the updated maps")?;
+
+    Ok(Scores {
+        player_scores: players
+            .into_iter()
+            .map(|player| {
+                let prior_score = prior
+                    .player_scores
+                    .get(&player.inner.id)
+                    .copied()
+                    .unwrap_or_default();
+                let score = prior_score + player_deltas[&player.inner.id];
+                (
+                    HashablePlayer {
+                        inner: player.inner,
+                        unstyled_name: player.unstyled_name,
+                    },
+                    score,
+                )
+            })
+            .collect(),
+        map_scores: maps
+            .into_iter()
+            .map(|map| {
+                let score = map_scores[&map.inner.id];
+                (
+                    HashableMap {
+                        stats: map_stats.remove(&map.inner.id).unwrap_or_default(),
+                        inner: map.inner,
+                        unstyled_name: map.unstyled_name,
+                    },
+                    score,
+                )
+            })
+            .collect(),
+        player_map_scores,
+    })
+}
+
+/// Returns the fingerprint of the records of a map, which changes whenever a record is added,
+/// improved or removed, without fetching them.
+///
+/// The amount of records and the latest of them aren't enough: when the best record of a player
+/// is removed, their previous record replaces it with the same amount of records, and the latest
+/// record may be unchanged. The sums of the IDs and of the times of the records change in this
+/// case, so they're part of the fingerprint too.
+async fn records_fingerprint<C: ConnectionTrait>(conn: &C, map_id: u32) -> anyhow::Result<String> {
+    let (records_count, last_record_id, record_ids_sum, times_sum) = global_records::Entity::find()
+        .select_only()
+        .column_as(global_records::Column::RecordId.count(), "records_count")
+        .column_as(global_records::Column::RecordId.max(), "last_record_id")
+        .expr_as(
+            Func::cast_as(global_records::Column::RecordId.sum(), "SIGNED"),
+            "record_ids_sum",
+        )
+        .expr_as(
+            Func::cast_as(global_records::Column::Time.sum(), "SIGNED"),
+            "times_sum",
+        )
+        .filter(global_records::Column::MapId.eq(map_id))
+        .into_tuple::<(i64, Option<u32>, Option<i64>, Option<i64>)>()
+        .one(conn)
+        .await
+        .with_context(|| {
+            format!("couldn't get the fingerprint of the records of map ID: {map_id}")
+        })?
+        .unwrap_or_default();
+
+    Ok(format!(
+        "{records_count}:{}:{}:{}",
+        last_record_id.unwrap_or_default(),
+        record_ids_sum.unwrap_or_default(),
+        times_sum.unwrap_or_default(),
+    ))
+}
+
+/// Returns the stats of the records of a map, cached in Redis.
+///
+/// The stats are only recomputed if the records of the map changed since they were cached, so
+/// the records of an unchanged map aren't fetched.
+pub async fn cached_map_stats<C: ConnectionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    map_id: u32,
+) -> anyhow::Result<MapStats> {
+    let key = player_ranking_map_stats(map_id);
+    let fingerprint = records_fingerprint(conn, map_id).await?;
+
+    let cached: HashMap<String, String> = redis_conn
+        .hgetall(&key)
+        .await
+        .with_context(|| format!("couldn't get the cached stats of map ID: {map_id}"))?;
+
+    if cached.get("fingerprint") == Some(&fingerprint) {
+        let field = |name: &str| -> f64 {
+            cached
+                .get(name)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+        return Ok(MapStats {
+            records_count: field("records_count"),
+            min_record: field("min_record"),
+            average_record: field("average_record"),
+            median_record: field("median_record"),
+            max_record: field("max_record"),
+        });
+    }
+
+    let map_records = global_records::Entity::find()
+        .filter(global_records::Column::MapId.eq(map_id))
+        .order_by_asc(global_records::Column::Time)
+        .order_by_asc(global_records::Column::RecordId)
+        .all(conn)
+        .await
+        .with_context(|| format!("couldn't get records of map ID: {map_id}"))?;
+    let stats = compute_map_stats(&map_records).unwrap_or_default();
+
+    let _: () = redis_conn
+        .hset_multiple(
+            &key,
+            &[
+                ("fingerprint", fingerprint),
+                ("records_count", stats.records_count.to_string()),
+                ("min_record", stats.min_record.to_string()),
+                ("average_record", stats.average_record.to_string()),
+                ("median_record", stats.median_record.to_string()),
+                ("max_record", stats.max_record.to_string()),
+            ],
+        )
+        .await
+        .with_context(|| format!("couldn't cache the stats of map ID: {map_id}"))?;
+
+    Ok(stats)
+}
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use entity::{maps, players, records};
+use itertools::iproduct;
+use player_map_ranking::{MapsRange, compute_scores, compute_scores_chunk};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+#[tokio::test]
+async fn chunks_cover_full_computation() -> anyhow::Result<()> {
+    let players = (1..=10).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(20)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The last player only has records on a few maps, so they may be missing from a chunk
+    let records = iproduct!(map_ids.iter().enumerate(), 1..=10)
+        .filter(|((i, _), player_id)| *player_id < 10 || i % 7 == 0)
+        .map(|((i, map_id), player_id)| records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(1000 + ((i * 7919 + player_id as usize * 104729) % 30000) as i32),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let full = compute_scores(&db.sql_conn, None, Default::default()).await?;
+
+        let first = compute_scores_chunk(
+            &db.sql_conn,
+            None,
+            Default::default(),
+            MapsRange {
+                offset: 0,
+                max_maps: Some(12),
+            },
+        )
+        .await?;
+        assert_eq!(first.map_scores.len(), 12);
+
+        let second = compute_scores_chunk(
+            &db.sql_conn,
+            None,
+            Default::default(),
+            MapsRange {
+                offset: 12,
+                max_maps: Some(12),
+            },
+        )
+        .await?;
+        // Less maps than the maximum, so it's the last chunk
+        assert_eq!(second.map_scores.len(), 8);
+
+        // The chunks are disjoint, and cover every map
+        let first_maps = first
+            .map_scores
+            .keys()
+            .map(|map| map.inner.id)
+            .collect::<HashSet<_>>();
+        let second_maps = second
+            .map_scores
+            .keys()
+            .map(|map| map.inner.id)
+            .collect::<HashSet<_>>();
+        assert!(first_maps.is_disjoint(&second_maps));
+        assert_eq!(
+            first_maps
+                .union(&second_maps)
+                .copied()
+                .collect::<HashSet<_>>(),
+            map_ids.iter().copied().collect::<HashSet<_>>()
+        );
+
+        let full_map_scores = full
+            .map_scores
+            .iter()
+            .map(|(map, score)| (map.inner.id, *score))
+            .collect::<HashMap<_, _>>();
+        let chunked_map_scores = first
+            .map_scores
+            .iter()
+            .chain(&second.map_scores)
+            .map(|(map, score)| (map.inner.id, *score))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(full_map_scores, chunked_map_scores);
+
+        let mut chunked_player_map_scores = first.player_map_scores;
+        chunked_player_map_scores.extend(second.player_map_scores);
+        assert_eq!(full.player_map_scores, chunked_player_map_scores);
+
+        // The scores are summed in a different order, so they may slightly differ
+        let mut chunked_player_scores = HashMap::<u32, f64>::new();
+        for (player, score) in first.player_scores.iter().chain(&second.player_scores) {
+            *chunked_player_scores.entry(player.inner.id).or_default() += score;
+        }
+        assert_eq!(full.player_scores.len(), chunked_player_scores.len());
+        for (player, score) in &full.player_scores {
+            let chunked = chunked_player_scores[&player.inner.id];
+            assert!(
+                (score - chunked).abs() < 1e-9,
+                "player {}: {score} != {chunked}",
+                player.inner.id
+            );
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use entity::{maps, players, records};
+use itertools::iproduct;
+use player_map_ranking::{Scores, compute_scores_with_concurrency};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+fn player_scores(scores: &Scores) -> HashMap<u32, f64> {
+    scores
+        .player_scores
+        .iter()
+        .map(|(player, score)| (player.inner.id, *score))
+        .collect()
+}
+
+fn map_scores(scores: &Scores) -> HashMap<u32, (f64, f64, f64)> {
+    scores
+        .map_scores
+        .iter()
+        .map(|(map, score)| {
+            (
+                map.inner.id,
+                (*score, map.stats.average_record, map.stats.median_record),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn concurrent_matches_sequential() -> anyhow::Result<()> {
+    let players = (1..=10).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(20)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // Not every player has a record on every map, and the times are spread pseudo-randomly
+    let records = iproduct!(map_ids.iter().enumerate(), 1..=10)
+        .filter(|((i, _), player_id)| (i + *player_id as usize) % 3 != 0)
+        .map(|((i, map_id), player_id)| records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(*map_id),
+            time: Set(1000 + ((i * 7919 + player_id as usize * 104729) % 30000) as i32),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let sequential =
+            compute_scores_with_concurrency(&db.sql_conn, None, Default::default(), 1).await?;
+        let concurrent =
+            compute_scores_with_concurrency(&db.sql_conn, None, Default::default(), 16).await?;
+
+        // The scores are summed in the same order, so they must be exactly the same
+        assert_eq!(player_scores(&sequential), player_scores(&concurrent));
+        assert_eq!(map_scores(&sequential), map_scores(&concurrent));
+        assert_eq!(sequential.player_map_scores, concurrent.player_map_scores);
+
+        anyhow::Ok(())
+    })
+    .await
+}
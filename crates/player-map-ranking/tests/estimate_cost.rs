@@ -0,0 +1,63 @@
+use entity::{maps, players, records};
+use itertools::iproduct;
+use player_map_ranking::{ScoringCost, estimate_cost};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+#[tokio::test]
+async fn estimate_matches_seeded_counts() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(5)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The first player has 2 records on each of the first 3 maps, but only the best one
+    // is fetched by the computation
+    let records = iproduct!(&map_ids[..3], 1..=4)
+        .map(|(map_id, player_id)| (*map_id, player_id, 5000))
+        .chain(map_ids[..3].iter().map(|map_id| (*map_id, 1, 4000)))
+        .map(|(map_id, player_id, time)| records::ActiveModel {
+            record_player_id: Set(player_id),
+            map_id: Set(map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let cost = estimate_cost(&db.sql_conn).await?;
+        assert_eq!(
+            cost,
+            ScoringCost {
+                map_count: 5,
+                record_count: 12,
+            }
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use entity::{maps, players, records};
+use player_map_ranking::{PriorScores, Scores, compute_scores, compute_scores_incremental};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+fn player_scores(scores: &Scores) -> HashMap<u32, f64> {
+    scores
+        .player_scores
+        .iter()
+        .map(|(player, score)| (player.inner.id, *score))
+        .collect()
+}
+
+fn map_scores(scores: &Scores) -> HashMap<u32, f64> {
+    scores
+        .map_scores
+        .iter()
+        .map(|(map, score)| (map.inner.id, *score))
+        .collect()
+}
+
+fn assert_score_eq(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-6, "{a} != {b}");
+}
+
+// The incremental updates are only used when the ranking takes all the records into account, so
+// they're compared with a full computation without any window.
+#[tokio::test]
+async fn incremental_converges_with_full() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(3)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    let old_date = (Utc::now() - Duration::days(2)).naive_utc();
+    let new_date = Utc::now().naive_utc();
+
+    let record = |map_id: u32, player_id: u32, time: i32, date| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(date),
+        ..Default::default()
+    };
+
+    let old_records = map_ids.iter().enumerate().flat_map(|(i, map_id)| {
+        (1..=3).map(move |player_id| {
+            record(
+                *map_id,
+                player_id,
+                5000 + i as i32 * 1000 + player_id as i32 * 700,
+                old_date,
+            )
+        })
+    });
+
+    // The first map gets a new player, and the first player improves on the second map
+    let new_records = [
+        record(map_ids[0], 4, 4000, new_date),
+        record(map_ids[1], 1, 5500, new_date),
+    ];
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(old_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let prior_scores = compute_scores(&db.sql_conn, None, Default::default()).await?;
+        let prior = PriorScores {
+            player_scores: player_scores(&prior_scores),
+            player_map_scores: prior_scores.player_map_scores,
+        };
+
+        records::Entity::insert_many(new_records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let incremental = compute_scores_incremental(
+            &db.sql_conn,
+            Utc::now() - Duration::hours(1),
+            Default::default(),
+            async |updated_map_ids, _| {
+                assert_eq!(updated_map_ids.len(), 2);
+                anyhow::Ok(prior)
+            },
+        )
+        .await?;
+        let full = compute_scores(&db.sql_conn, None, Default::default()).await?;
+
+        // Only the maps with new records are recomputed
+        let incremental_map_scores = map_scores(&incremental);
+        let full_map_scores = map_scores(&full);
+        assert_eq!(incremental_map_scores.len(), 2);
+        assert!(!incremental_map_scores.contains_key(&map_ids[2]));
+        for (map_id, score) in &incremental_map_scores {
+            assert_score_eq(*score, full_map_scores[map_id]);
+        }
+
+        // Every player has a record on the updated maps
+        let incremental_player_scores = player_scores(&incremental);
+        let full_player_scores = player_scores(&full);
+        assert_eq!(incremental_player_scores.len(), 4);
+        for (player_id, score) in &incremental_player_scores {
+            assert_score_eq(*score, full_player_scores[player_id]);
+        }
+
+        for map_id in &map_ids[..2] {
+            let incremental = &incremental.player_map_scores[map_id];
+            let full = &full.player_map_scores[map_id];
+            assert_eq!(incremental.len(), full.len());
+            for (player_id, score) in incremental {
+                assert_score_eq(*score, full[player_id]);
+            }
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,117 @@
+use deadpool_redis::redis::AsyncCommands as _;
+use entity::{maps, players, records};
+use player_map_ranking::{MapStats, cached_map_stats};
+use records_lib::redis_key::player_ranking_map_stats;
+use sea_orm::{ActiveValue::Set, ColumnTrait as _, EntityTrait, QueryFilter as _};
+
+fn record(map_id: u32, player_id: u32, time: i32) -> records::ActiveModel {
+    records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_id),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn unchanged_map_uses_cached_stats() -> anyhow::Result<()> {
+    let players = (1..=4).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_id = test_env::get_map_id();
+    let map = maps::ActiveModel {
+        id: Set(map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    };
+
+    // The player 1 improved their time, so only their second record is taken into account
+    let records = [(1, 1500), (1, 1000), (2, 2000), (3, 3000)]
+        .map(|(player_id, time)| record(map_id, player_id, time));
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert(map).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let mut redis_conn = db.redis_pool.get().await?;
+
+        let stats = cached_map_stats(&db.sql_conn, &mut redis_conn, map_id).await?;
+        assert_eq!(
+            stats,
+            MapStats {
+                records_count: 3.,
+                min_record: 1.,
+                average_record: 2.,
+                median_record: 2.,
+                max_record: 3.,
+            }
+        );
+
+        // Tamper with the cache, so we know the stats are read from it rather than recomputed
+        let _: () = redis_conn
+            .hset(player_ranking_map_stats(map_id), "median_record", 42.)
+            .await?;
+
+        let stats = cached_map_stats(&db.sql_conn, &mut redis_conn, map_id).await?;
+        assert_eq!(stats.median_record, 42.);
+        assert_eq!(stats.records_count, 3.);
+
+        // A new record changes the fingerprint, so the stats are recomputed
+        records::Entity::insert(record(map_id, 4, 500))
+            .exec(&db.sql_conn)
+            .await?;
+
+        let stats = cached_map_stats(&db.sql_conn, &mut redis_conn, map_id).await?;
+        assert_eq!(
+            stats,
+            MapStats {
+                records_count: 4.,
+                min_record: 0.5,
+                average_record: 1.625,
+                median_record: 2.,
+                max_record: 3.,
+            }
+        );
+
+        // Removing the best record of the player 1 brings back their previous one, with the
+        // same amount of records and the same latest record, so the stats are recomputed
+        records::Entity::delete_many()
+            .filter(
+                records::Column::RecordPlayerId
+                    .eq(1)
+                    .and(records::Column::Time.eq(1000)),
+            )
+            .exec(&db.sql_conn)
+            .await?;
+
+        let stats = cached_map_stats(&db.sql_conn, &mut redis_conn, map_id).await?;
+        assert_eq!(
+            stats,
+            MapStats {
+                records_count: 4.,
+                min_record: 0.5,
+                average_record: 1.75,
+                median_record: 2.,
+                max_record: 3.,
+            }
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+}
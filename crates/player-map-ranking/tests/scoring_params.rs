@@ -0,0 +1,97 @@
+use entity::{maps, players, records};
+use player_map_ranking::{Scores, ScoringParams, compute_scores};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+fn player_ranking(scores: &Scores) -> Vec<u32> {
+    let mut ranking = scores
+        .player_scores
+        .iter()
+        .map(|(player, score)| (player.inner.id, *score))
+        .collect::<Vec<_>>();
+    ranking.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranking
+        .into_iter()
+        .map(|(player_id, _)| player_id)
+        .collect()
+}
+
+fn rank_of(ranking: &[u32], player_id: u32) -> usize {
+    ranking
+        .iter()
+        .position(|id| *id == player_id)
+        .expect("player should be in the ranking")
+}
+
+#[tokio::test]
+async fn params_change_ordering() -> anyhow::Result<()> {
+    let players = (1..=7).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = [test_env::get_map_id(), test_env::get_map_id()];
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    // The first player is the best on the first map, and the second player is far behind
+    // the others on the second map.
+    let records_info = [
+        (map_ids[0], 1, 10000),
+        (map_ids[0], 3, 11000),
+        (map_ids[0], 4, 12000),
+        (map_ids[1], 5, 10000),
+        (map_ids[1], 6, 11000),
+        (map_ids[1], 7, 12000),
+        (map_ids[1], 2, 100000),
+    ];
+
+    let records = records_info
+        .iter()
+        .map(|(map_id, player_id, time)| records::ActiveModel {
+            record_player_id: Set(*player_id),
+            map_id: Set(*map_id),
+            time: Set(*time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let default_scores = compute_scores(&db.sql_conn, None, Default::default()).await?;
+        let default_ranking = player_ranking(&default_scores);
+        assert!(rank_of(&default_ranking, 1) < rank_of(&default_ranking, 2));
+
+        // Favoring the gap to the average time over the rank gives the advantage to the
+        // second player
+        let params = ScoringParams {
+            time_gap_weight: 100.,
+            rank_exponent: 1,
+            ..Default::default()
+        };
+        let custom_scores = compute_scores(&db.sql_conn, None, params).await?;
+        let custom_ranking = player_ranking(&custom_scores);
+        assert!(rank_of(&custom_ranking, 2) < rank_of(&custom_ranking, 1));
+
+        assert_ne!(default_ranking, custom_ranking);
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -0,0 +1,83 @@
+use chrono::{Duration, Utc};
+use entity::{maps, players, records};
+use player_map_ranking::compute_scores;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+
+#[tokio::test]
+async fn window_ignores_older_records() -> anyhow::Result<()> {
+    let players = (1..=2).map(|player_id| players::ActiveModel {
+        id: Set(player_id),
+        login: Set(format!("player_{player_id}_login")),
+        name: Set(format!("player_{player_id}_name")),
+        role: Set(0),
+        ..Default::default()
+    });
+
+    let map_ids = std::iter::repeat_with(test_env::get_map_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let maps = map_ids.iter().map(|map_id| maps::ActiveModel {
+        id: Set(*map_id),
+        game_id: Set(format!("map_{map_id}_uid")),
+        name: Set(format!("map_{map_id}_name")),
+        player_id: Set(1),
+        ..Default::default()
+    });
+
+    let old_date = (Utc::now() - Duration::days(2)).naive_utc();
+    let new_date = Utc::now().naive_utc();
+
+    // (map index, player ID, time, date)
+    // The first player only has old records, and the second map doesn't have any new record
+    let records = [
+        (0, 1, 4000, old_date),
+        (0, 2, 5000, new_date),
+        (1, 1, 6000, old_date),
+        (1, 2, 7000, old_date),
+    ]
+    .map(|(i, player_id, time, date)| records::ActiveModel {
+        record_player_id: Set(player_id),
+        map_id: Set(map_ids[i]),
+        time: Set(time),
+        respawn_count: Set(0),
+        flags: Set(682),
+        record_date: Set(date),
+        ..Default::default()
+    });
+
+    test_env::wrap(async |db| {
+        players::Entity::insert_many(players)
+            .exec(&db.sql_conn)
+            .await?;
+        maps::Entity::insert_many(maps).exec(&db.sql_conn).await?;
+        records::Entity::insert_many(records)
+            .exec(&db.sql_conn)
+            .await?;
+
+        let scores = compute_scores(
+            &db.sql_conn,
+            Some(Utc::now() - Duration::days(1)),
+            Default::default(),
+        )
+        .await?;
+
+        for (player, score) in &scores.player_scores {
+            match player.inner.id {
+                1 => assert_eq!(*score, 0.),
+                _ => assert!(*score > 0.),
+            }
+        }
+
+        for (map, score) in &scores.map_scores {
+            if map.inner.id == map_ids[0] {
+                assert!(*score > 0.);
+                assert_eq!(map.stats.records_count, 1.);
+            } else {
+                assert_eq!(*score, 0.);
+            }
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+}
@@ -11,6 +11,17 @@ mkenv::make_config! {
         pub db_url: {
             var_name: "DATABASE_URL",
             description: "The URL to the MySQL/MariaDB database",
+        },
+
+        /// The optional URL to the read-replica database.
+        pub replica_db_url: {
+            var_name: "REPLICA_DATABASE_URL",
+            layers: [
+                parsed<Option<String>>(|s| Ok(Some(s.to_owned()))),
+                or_default(),
+            ],
+            description: "The URL to the MySQL/MariaDB read-replica database",
+            default_val_fmt: "empty",
         }
     }
 }
@@ -25,6 +36,19 @@ mkenv::make_config! {
                 file_read(),
             ],
             description: "The path to the file containing the URL to the MySQL/MariaDB database",
+        },
+
+        /// The optional path to the file containing the read-replica database URL.
+        pub replica_db_url: {
+            var_name: "REPLICA_DATABASE_URL",
+            layers: [
+                file_read(),
+                parsed<Option<String>>(|s| Ok(Some(s.to_owned()))),
+                or_default(),
+            ],
+            description: "The path to the file containing the URL to the MySQL/MariaDB \
+                read-replica database",
+            default_val_fmt: "empty",
         }
     }
 }
@@ -36,6 +60,20 @@ mkenv::make_config! {
         pub redis_url: {
             var_name: "REDIS_URL",
             description: "The URL to the Redis database",
+        },
+
+        /// The duration after which an idle Redis connection is replaced when acquired.
+        pub redis_health_check_interval: {
+            var_name: "REDIS_HEALTH_CHECK_INTERVAL_SECONDS",
+            layers: [
+                parsed<Duration>(|input| {
+                    input.parse().map(Duration::from_secs).map_err(From::from)
+                }),
+                or_default_val(|| crate::pool::DEFAULT_REDIS_HEALTH_CHECK_INTERVAL),
+            ],
+            description: "The duration in seconds after which an idle Redis connection is \
+                considered stale, and replaced by a new one when acquired",
+            default_val_fmt: "60s",
         }
     }
 }
@@ -210,6 +248,19 @@ mkenv::make_config! {
             ],
             description: "The interval of the update of the player/map ranking scores, in seconds",
             default_val_fmt: "every week",
+        },
+
+        /// Whether the records of the banned players are hidden from the leaderboards, or shown
+        /// as unranked.
+        pub hide_banned_players: {
+            var_name: "RECORDS_API_HIDE_BANNED_PLAYERS",
+            layers: [
+                parsed_from_str<bool>(),
+                or_default_val(|| true)
+            ],
+            description: "Whether the records of the banned players are hidden from the leaderboards \
+                (true), or shown at the end of them without a rank (false) (boolean)",
+            default_val_fmt: "true",
         }
     }
 }
@@ -230,3 +281,80 @@ pub fn init_env(env: LibEnv) {
 pub fn env() -> &'static LibEnv {
     ENV.get().unwrap()
 }
+
+/// Returns the values of the global library environment, paired with the name of their
+/// environment variable.
+///
+/// **Caution**: To use this function, the [`init_env()`] function must have been called at the start
+/// of the program.
+pub fn env_dump() -> Vec<(&'static str, String)> {
+    let env = env();
+    vec![
+        ("RECORDS_API_MAPPACK_TTL", env.mappack_ttl.get().to_string()),
+        (
+            "RECORDS_API_INGAME_DEFAULT_TITLES_ALIGN",
+            env.ingame_default_titles_align.get().to_char().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_LB_LINK_ALIGN",
+            env.ingame_default_lb_link_align.get().to_char().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_AUTHORS_ALIGN",
+            env.ingame_default_authors_align.get().to_char().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_TITLES_POS_X",
+            env.ingame_default_titles_pos_x.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_TITLES_POS_Y",
+            env.ingame_default_titles_pos_y.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_SUBTITLE_ON_NEWLINE",
+            env.ingame_default_subtitle_on_newline.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_LB_LINK_POS_X",
+            env.ingame_default_lb_link_pos_x.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_LB_LINK_POS_Y",
+            env.ingame_default_lb_link_pos_y.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_AUTHORS_POS_X",
+            env.ingame_default_authors_pos_x.get().to_string(),
+        ),
+        (
+            "RECORDS_API_INGAME_DEFAULT_AUTHORS_POS_Y",
+            env.ingame_default_authors_pos_y.get().to_string(),
+        ),
+        (
+            "EVENT_SCORES_INTERVAL_SECONDS",
+            env.event_scores_interval.get().as_secs().to_string(),
+        ),
+        (
+            "PLAYER_MAP_RANKING_SCORES_INTERVAL",
+            env.player_map_ranking_scores_interval
+                .get()
+                .as_secs()
+                .to_string(),
+        ),
+        (
+            "RECORDS_API_HIDE_BANNED_PLAYERS",
+            env.hide_banned_players.get().to_string(),
+        ),
+    ]
+}
+
+/// Returns whether the records of the banned players are hidden from the leaderboards.
+///
+/// Unlike [`env()`], this doesn't require the global library environment to be initialized,
+/// and returns `true` if it isn't.
+pub fn hide_banned_players() -> bool {
+    ENV.get()
+        .map(|env| env.hide_banned_players.get())
+        .unwrap_or(true)
+}
@@ -49,6 +49,12 @@ pub enum RecordsError {
         /// The map UID.
         String,
     ),
+    /// The map with the provided ID was not found.
+    #[error("map with id `{0}` not found in database")]
+    MapIdNotFound(
+        /// The map ID.
+        u32,
+    ),
     /// The event with the provided handle was not found.
     #[error("event `{0}` not found")]
     EventNotFound(
@@ -73,6 +79,34 @@ pub enum RecordsError {
         /// The event edition ID.
         u32,
     ),
+    /// The map is disabled in the provided event edition, so no record can be saved on it.
+    #[error("map with uid `{0}` is not available in event `{1}` edition {2}")]
+    MapNotAvailable(
+        /// The map UID.
+        String,
+        /// The event handle.
+        String,
+        /// The event edition ID.
+        u32,
+    ),
+    /// The map is used by an event edition that hasn't expired yet.
+    #[error("map with uid `{0}` is used by the active event `{1}` edition {2}")]
+    MapInActiveEvent(
+        /// The map UID.
+        String,
+        /// The event handle.
+        String,
+        /// The event edition ID.
+        u32,
+    ),
+    /// The event edition with the provided handle and edition ID already exists.
+    #[error("event edition `{1}` already exists for event `{0}`")]
+    EventEditionAlreadyExists(
+        /// The event handle.
+        String,
+        /// The event edition ID.
+        u32,
+    ),
     /// Parsing error for the ID of a mappack.
     #[error("mappack id should be an integer, got `{0}`")]
     InvalidMappackId(String),
@@ -81,6 +115,19 @@ pub enum RecordsError {
     UnknownRole(u8, String),
 }
 
+impl RecordsError {
+    /// Returns whether this error means that the Redis database couldn't be reached.
+    pub fn is_redis_unavailable(&self) -> bool {
+        match self {
+            Self::PoolError(_) => true,
+            Self::Redis(e) => {
+                e.is_io_error() || e.is_connection_refusal() || e.is_connection_dropped()
+            }
+            _ => false,
+        }
+    }
+}
+
 impl AsRef<RecordsError> for RecordsError {
     fn as_ref(&self) -> &RecordsError {
         self
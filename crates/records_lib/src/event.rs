@@ -1,18 +1,37 @@
 //! This module contains anything related to ShootMania Obstacle events in this library.
 
+use std::collections::{HashMap, hash_map::Entry};
+
+use deadpool_redis::redis::{self, AsyncCommands as _};
 use entity::{
     event, event_category, event_edition, event_edition_admins, event_edition_categories,
-    event_edition_maps, maps, players,
+    event_edition_maps, event_edition_records, in_game_event_edition_params, maps, players,
+    records,
 };
 use futures::{Stream, TryStreamExt as _};
 use sea_orm::{
-    ColumnTrait as _, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, Order, QueryFilter,
-    QueryOrder, QuerySelect, QueryTrait, RelationTrait as _, StreamTrait,
+    ActiveValue::NotSet,
+    ColumnTrait as _, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, IntoActiveModel as _,
+    Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait as _, StreamTrait,
+    TransactionTrait,
     prelude::Expr,
     sea_query::{Asterisk, ExprTrait as _, Func, IntoCondition, Query},
 };
 
-use crate::error::RecordsResult;
+use crate::{
+    RedisPool,
+    error::{RecordsError, RecordsResult},
+    expirable::refresh_if_stale,
+    internal,
+    mappack::{self, AnyMappackId},
+    must,
+    opt_event::OptEvent,
+    ranks,
+    redis_key::{
+        mappack_key, mappack_lb_key, mappack_player_map_finished_key, mappack_player_rank_avg_key,
+    },
+    sync,
+};
 
 /// Represents an item in the event list.
 ///
@@ -195,6 +214,180 @@ pub async fn get_categories_by_edition_id<C: ConnectionTrait>(
     Ok(r)
 }
 
+/// Returns the ID of the top-standing player of each category of the provided event edition,
+/// associated to the handle of the category.
+///
+/// The standings of a category are calculated like the ones of the mappack of the edition,
+/// but only with the maps of the category. The leaderboards of these maps are updated if
+/// needed, and the categories without any record are omitted.
+///
+/// ## Parameters
+///
+/// * `event_id`: the database ID of the event.
+/// * `edition_id` the ID of the edition bound to this event.
+pub async fn category_winners<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    event_id: u32,
+    edition_id: u32,
+) -> RecordsResult<HashMap<String, u32>> {
+    let (event, edition) = must::have_event_edition_from_ids(conn, event_id, edition_id).await?;
+    let opt_event = OptEvent::new(&event, &edition);
+
+    let mut redis_conn = redis_pool.get().await?;
+    let mut winners = HashMap::new();
+
+    for category in get_categories_by_edition_id(conn, event_id, edition_id).await? {
+        let maps = maps::Entity::find()
+            .join_rev(
+                sea_orm::JoinType::InnerJoin,
+                event_edition_maps::Relation::Maps.def(),
+            )
+            .filter(
+                event_edition_maps::Column::EventId
+                    .eq(event_id)
+                    .and(event_edition_maps::Column::EditionId.eq(edition_id))
+                    .and(event_edition_maps::Column::CategoryId.eq(category.id)),
+            )
+            .all(conn)
+            .await?;
+
+        for map in &maps {
+            ranks::update_leaderboard(conn, redis_pool, map.id, opt_event).await?;
+        }
+
+        if let Some(player_id) =
+            mappack::top_player_of(conn, &mut redis_conn, &maps, opt_event).await?
+        {
+            winners.insert(category.handle, player_id);
+        }
+    }
+
+    Ok(winners)
+}
+
+/// The standing of a player on the maps of an event edition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditionStanding {
+    /// The ID of the player.
+    pub player_id: u32,
+    /// The rank of the player in the edition.
+    pub rank: u32,
+    /// The average of the ranks of the player on the maps.
+    pub rank_avg: f64,
+    /// The amount of maps finished by the player.
+    pub maps_finished: usize,
+}
+
+/// A page of the overall standings of the players on the maps of an event edition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditionStandings {
+    /// The total amount of players in the standings.
+    pub total: usize,
+    /// The standings of the players in the page, sorted by their rank.
+    pub players: Vec<EditionStanding>,
+}
+
+/// Returns a page of the overall standings of the players on the maps of the provided event
+/// edition.
+///
+/// The standings are the scores of the mappack of the edition, which are cached and refreshed
+/// periodically. They're only calculated here if they were never cached. The records of a
+/// transparent edition are saved on the maps directly, so its standings are calculated from the
+/// records outside of any event.
+///
+/// ## Parameters
+///
+/// * `offset`: the amount of players to skip, sorted by their rank.
+/// * `limit`: the maximum amount of players to return.
+pub async fn edition_standings<C: TransactionTrait + ConnectionTrait + StreamTrait + Sync>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    event: &event::Model,
+    edition: &event_edition::Model,
+    offset: usize,
+    limit: usize,
+) -> RecordsResult<EditionStandings> {
+    let mappack = AnyMappackId::Event(event, edition);
+    let opt_event = OptEvent::new(event, edition);
+
+    let last_computed = {
+        let mut redis_conn = redis_pool.get().await?;
+        mappack::last_computed(&mut redis_conn, mappack).await?
+    };
+    refresh_if_stale(
+        &mappack.scores_cache_entry(last_computed),
+        chrono::Utc::now().naive_utc(),
+        async || {
+            let maps = event_edition_maps(conn, event.id, edition.id).await?;
+
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            pipe.del(mappack_key(mappack)).ignore();
+            for map in &maps {
+                pipe.sadd(mappack_key(mappack), &map.game_id).ignore();
+                ranks::update_leaderboard(conn, redis_pool, map.id, opt_event).await?;
+            }
+            {
+                let mut redis_conn = redis_pool.get().await?;
+                pipe.exec_async(&mut redis_conn).await?;
+            }
+
+            mappack::update_mappack(conn, redis_pool, mappack, opt_event).await
+        },
+    )
+    .await?;
+
+    let mut redis_conn = redis_pool.get().await?;
+
+    let total: usize = redis_conn.zcard(mappack_lb_key(mappack)).await?;
+    if limit == 0 || offset >= total {
+        return Ok(EditionStandings {
+            total,
+            players: Vec::new(),
+        });
+    }
+
+    let page: Vec<(u32, u32)> = redis_conn
+        .zrange_withscores(
+            mappack_lb_key(mappack),
+            offset as isize,
+            (offset + limit - 1) as isize,
+        )
+        .await?;
+
+    let rank_avgs: Vec<f64> = redis_conn
+        .mget(
+            page.iter()
+                .map(|(player_id, _)| mappack_player_rank_avg_key(mappack, *player_id))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+    let maps_finished: Vec<usize> = redis_conn
+        .mget(
+            page.iter()
+                .map(|(player_id, _)| mappack_player_map_finished_key(mappack, *player_id))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+
+    let players = page
+        .into_iter()
+        .zip(rank_avgs)
+        .zip(maps_finished)
+        .map(
+            |(((player_id, rank), rank_avg), maps_finished)| EditionStanding {
+                player_id,
+                rank,
+                rank_avg,
+                maps_finished,
+            },
+        )
+        .collect();
+
+    Ok(EditionStandings { total, players })
+}
+
 /// Represents the medal times, in milliseconds.
 #[derive(Clone, Copy)]
 pub struct MedalTimes {
@@ -208,6 +401,300 @@ pub struct MedalTimes {
     pub champion_time: i32,
 }
 
+impl MedalTimes {
+    /// Returns the medal times from the optional times of a map of an event edition, in the order
+    /// bronze, silver, gold, then champion/author, or `None` if any of them is missing.
+    pub fn from_optional_times(
+        (bronze_time, silver_time, gold_time, champion_time): (
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+        ),
+    ) -> Option<Self> {
+        Some(Self {
+            bronze_time: bronze_time?,
+            silver_time: silver_time?,
+            gold_time: gold_time?,
+            champion_time: champion_time?,
+        })
+    }
+
+    /// Returns the medal times at the provided percentiles of the times, which must be sorted by
+    /// ascending order, or `None` if there's no time.
+    ///
+    /// The time of a medal is the lowest time such that the percentage of the times lower or
+    /// equal to it reaches the percentile of the medal.
+    pub fn from_percentiles(sorted_times: &[i32], percentiles: MedalPercentiles) -> Option<Self> {
+        let time_at = |percentile: f64| {
+            let rank = (percentile / 100. * sorted_times.len() as f64).ceil() as usize;
+            sorted_times
+                .get(rank.clamp(1, sorted_times.len()) - 1)
+                .copied()
+        };
+
+        Some(Self {
+            bronze_time: time_at(percentiles.bronze)?,
+            silver_time: time_at(percentiles.silver)?,
+            gold_time: time_at(percentiles.gold)?,
+            champion_time: time_at(percentiles.champion)?,
+        })
+    }
+
+    /// Returns the best medal earned with the provided time, or `None` if it's slower than the
+    /// bronze medal.
+    pub fn medal_of(&self, time: i32) -> Option<Medal> {
+        if time <= self.champion_time {
+            Some(Medal::Champion)
+        } else if time <= self.gold_time {
+            Some(Medal::Gold)
+        } else if time <= self.silver_time {
+            Some(Medal::Silver)
+        } else if time <= self.bronze_time {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+}
+
+/// The percentiles of the best times of the players on a map, used to compute its medal times.
+///
+/// They're expressed between 0 and 100.
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+pub struct MedalPercentiles {
+    /// The percentile of the bronze medal.
+    pub bronze: f64,
+    /// The percentile of the silver medal.
+    pub silver: f64,
+    /// The percentile of the gold medal.
+    pub gold: f64,
+    /// The percentile of the champion/author medal.
+    pub champion: f64,
+}
+
+impl MedalPercentiles {
+    /// Returns whether the percentiles are between 0 and 100, and ordered from the champion
+    /// medal to the bronze medal.
+    pub fn is_valid(&self) -> bool {
+        0. < self.champion
+            && self.champion <= self.gold
+            && self.gold <= self.silver
+            && self.silver <= self.bronze
+            && self.bronze <= 100.
+    }
+}
+
+/// A medal earned on a map of an event edition.
+///
+/// The medals are ordered from the lowest to the highest tier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Medal {
+    /// The bronze medal.
+    Bronze,
+    /// The silver medal.
+    Silver,
+    /// The gold medal.
+    Gold,
+    /// The champion/author medal.
+    Champion,
+}
+
+/// The amount of players of an event edition by the best medal they earned on its maps.
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct MedalDistribution {
+    /// The amount of players whose best medal is the bronze medal.
+    pub bronze: u64,
+    /// The amount of players whose best medal is the silver medal.
+    pub silver: u64,
+    /// The amount of players whose best medal is the gold medal.
+    pub gold: u64,
+    /// The amount of players who earned the champion/author medal.
+    pub champion: u64,
+    /// The amount of players who didn't earn any medal.
+    pub no_medal: u64,
+}
+
+impl MedalDistribution {
+    fn add(&mut self, medal: Option<Medal>) {
+        let count = match medal {
+            Some(Medal::Bronze) => &mut self.bronze,
+            Some(Medal::Silver) => &mut self.silver,
+            Some(Medal::Gold) => &mut self.gold,
+            Some(Medal::Champion) => &mut self.champion,
+            None => &mut self.no_medal,
+        };
+        *count += 1;
+    }
+}
+
+/// Returns the distribution of the players of the provided event edition by the best medal they
+/// earned on its maps.
+///
+/// Each player is counted once, in the tier of the best medal they earned on any map of the
+/// edition. The maps without medal times and the hidden records are ignored, so the players
+/// who only have records on them aren't counted.
+///
+/// ## Parameters
+///
+/// * `event_id`: the database ID of the event.
+/// * `edition_id` the ID of the edition bound to this event.
+pub async fn medal_distribution<C: ConnectionTrait>(
+    conn: &C,
+    event_id: u32,
+    edition_id: u32,
+) -> RecordsResult<MedalDistribution> {
+    let maps_medal_times = medal_times_for_edition(conn, event_id, edition_id).await?;
+
+    let best_times: Vec<(u32, u32, i32)> = records::Entity::find()
+        .inner_join(event_edition_records::Entity)
+        .filter(
+            event_edition_records::Column::EventId
+                .eq(event_id)
+                .and(event_edition_records::Column::EditionId.eq(edition_id))
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .group_by(records::Column::MapId)
+        .group_by(records::Column::RecordPlayerId)
+        .select_only()
+        .column(records::Column::MapId)
+        .column(records::Column::RecordPlayerId)
+        .column_as(records::Column::Time.min(), "time")
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let mut best_medals = HashMap::<u32, Option<Medal>>::new();
+
+    for (map_id, player_id, time) in best_times {
+        if let Some(medal_times) = maps_medal_times.get(&map_id) {
+            let medal = medal_times.medal_of(time);
+            let best_medal = best_medals.entry(player_id).or_default();
+            *best_medal = (*best_medal).max(medal);
+        }
+    }
+
+    let mut distribution = MedalDistribution::default();
+
+    for medal in best_medals.into_values() {
+        distribution.add(medal);
+    }
+
+    Ok(distribution)
+}
+
+/// Recomputes the medal times of the maps of the provided event edition from the best times of
+/// the players, at the provided percentiles, and saves them.
+///
+/// The maps without any record are left untouched. The hidden records are ignored. It returns
+/// the new medal times, associated to the ID of the map.
+///
+/// ## Parameters
+///
+/// * `event_id`: the database ID of the event.
+/// * `edition_id` the ID of the edition bound to this event.
+pub async fn recompute_medal_times<C: ConnectionTrait>(
+    conn: &C,
+    event_id: u32,
+    edition_id: u32,
+    percentiles: MedalPercentiles,
+) -> RecordsResult<HashMap<u32, MedalTimes>> {
+    let best_times: Vec<(u32, i32)> = records::Entity::find()
+        .inner_join(event_edition_records::Entity)
+        .filter(
+            event_edition_records::Column::EventId
+                .eq(event_id)
+                .and(event_edition_records::Column::EditionId.eq(edition_id))
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .group_by(records::Column::MapId)
+        .group_by(records::Column::RecordPlayerId)
+        .select_only()
+        .column(records::Column::MapId)
+        .column_as(records::Column::Time.min(), "time")
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let mut maps_times = HashMap::<u32, Vec<i32>>::new();
+    for (map_id, time) in best_times {
+        maps_times.entry(map_id).or_default().push(time);
+    }
+
+    let mut maps_medal_times = HashMap::with_capacity(maps_times.len());
+
+    for (map_id, mut times) in maps_times {
+        times.sort_unstable();
+        let Some(medal_times) = MedalTimes::from_percentiles(&times, percentiles) else {
+            continue;
+        };
+
+        event_edition_maps::Entity::update_many()
+            .col_expr(
+                event_edition_maps::Column::BronzeTime,
+                Expr::value(medal_times.bronze_time),
+            )
+            .col_expr(
+                event_edition_maps::Column::SilverTime,
+                Expr::value(medal_times.silver_time),
+            )
+            .col_expr(
+                event_edition_maps::Column::GoldTime,
+                Expr::value(medal_times.gold_time),
+            )
+            .col_expr(
+                event_edition_maps::Column::AuthorTime,
+                Expr::value(medal_times.champion_time),
+            )
+            .filter(
+                event_edition_maps::Column::EventId
+                    .eq(event_id)
+                    .and(event_edition_maps::Column::EditionId.eq(edition_id))
+                    .and(event_edition_maps::Column::MapId.eq(map_id)),
+            )
+            .exec(conn)
+            .await?;
+
+        maps_medal_times.insert(map_id, medal_times);
+    }
+
+    Ok(maps_medal_times)
+}
+
+/// Returns the amount of distinct players who made at least one record in the provided
+/// event edition.
+///
+/// The hidden records are ignored.
+///
+/// ## Parameters
+///
+/// * `event_id`: the database ID of the event.
+/// * `edition_id` the ID of the edition bound to this event.
+pub async fn participant_count<C: ConnectionTrait>(
+    conn: &C,
+    event_id: u32,
+    edition_id: u32,
+) -> RecordsResult<u64> {
+    let count: Option<i64> = records::Entity::find()
+        .inner_join(event_edition_records::Entity)
+        .filter(
+            event_edition_records::Column::EventId
+                .eq(event_id)
+                .and(event_edition_records::Column::EditionId.eq(edition_id))
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .select_only()
+        .expr(Func::count_distinct(Expr::col((
+            records::Entity,
+            records::Column::RecordPlayerId,
+        ))))
+        .into_tuple()
+        .one(conn)
+        .await?;
+
+    Ok(count.unwrap_or_default() as u64)
+}
+
 /// Returns the medal times of the provided map bound to the event edition.
 ///
 /// ## Parameters
@@ -221,39 +708,71 @@ pub async fn get_medal_times_of<C: ConnectionTrait>(
     edition_id: u32,
     map_id: u32,
 ) -> RecordsResult<Option<MedalTimes>> {
-    let (bronze_time, silver_time, gold_time, champion_time) =
-        event_edition_maps::Entity::find_by_id((event_id, edition_id, map_id))
+    let times = event_edition_maps::Entity::find_by_id((event_id, edition_id, map_id))
+        .select_only()
+        .columns([
+            event_edition_maps::Column::BronzeTime,
+            event_edition_maps::Column::SilverTime,
+            event_edition_maps::Column::GoldTime,
+            event_edition_maps::Column::AuthorTime,
+        ])
+        .into_tuple()
+        .one(conn)
+        .await?
+        .unwrap_or_default();
+
+    Ok(MedalTimes::from_optional_times(times))
+}
+
+/// Returns the medal times of all the maps of the provided event edition, associated to the ID
+/// of the map.
+///
+/// The maps without medal times are omitted.
+///
+/// ## Parameters
+///
+/// * `event_id`: the database ID of the event.
+/// * `edition_id` the ID of the edition bound to this event.
+pub async fn medal_times_for_edition<C: ConnectionTrait>(
+    conn: &C,
+    event_id: u32,
+    edition_id: u32,
+) -> RecordsResult<HashMap<u32, MedalTimes>> {
+    let maps_medal_times: Vec<(u32, Option<i32>, Option<i32>, Option<i32>, Option<i32>)> =
+        event_edition_maps::Entity::find()
+            .filter(
+                event_edition_maps::Column::EventId
+                    .eq(event_id)
+                    .and(event_edition_maps::Column::EditionId.eq(edition_id)),
+            )
             .select_only()
             .columns([
+                event_edition_maps::Column::MapId,
                 event_edition_maps::Column::BronzeTime,
                 event_edition_maps::Column::SilverTime,
                 event_edition_maps::Column::GoldTime,
                 event_edition_maps::Column::AuthorTime,
             ])
             .into_tuple()
-            .one(conn)
-            .await?
-            .unwrap_or_default();
+            .all(conn)
+            .await?;
 
-    let Some(bronze_time) = bronze_time else {
-        return Ok(None);
-    };
-    let Some(silver_time) = silver_time else {
-        return Ok(None);
-    };
-    let Some(gold_time) = gold_time else {
-        return Ok(None);
-    };
-    let Some(champion_time) = champion_time else {
-        return Ok(None);
-    };
+    let maps_medal_times = maps_medal_times
+        .into_iter()
+        .filter_map(
+            |(map_id, bronze_time, silver_time, gold_time, champion_time)| {
+                let medal_times = MedalTimes::from_optional_times((
+                    bronze_time,
+                    silver_time,
+                    gold_time,
+                    champion_time,
+                ))?;
+                Some((map_id, medal_times))
+            },
+        )
+        .collect();
 
-    Ok(Some(MedalTimes {
-        bronze_time,
-        silver_time,
-        gold_time,
-        champion_time,
-    }))
+    Ok(maps_medal_times)
 }
 
 /// Returns the admins/authors of the provided event edition.
@@ -339,6 +858,8 @@ pub struct EventMap {
     ///
     /// For example for the Benchmark, this would be the ID of the map with a normal UID.
     pub original_map_id: Option<u32>,
+    /// Whether the map is disabled in the event edition, so no record can be saved on it.
+    pub is_disabled: bool,
 }
 
 /// Returns the map bound to an event edition from its UID or its original version UID.
@@ -353,13 +874,40 @@ pub struct EventMap {
 ///
 /// For example for the Benchmark, with `map_uid` as `"X"` or `"X_benchmark"`, the function returns
 /// the map with the UID `X_benchmark`, and the ID of the map with UID `X`.
+///
+/// The maps disabled in the edition are ignored. See [`get_map_in_edition_including_disabled`] to
+/// retrieve them too.
 pub async fn get_map_in_edition<C: ConnectionTrait>(
     conn: &C,
     map_uid: &str,
     event_id: u32,
     edition_id: u32,
 ) -> RecordsResult<Option<EventMap>> {
-    let map = event_edition_maps::Entity::find()
+    find_map_in_edition(conn, map_uid, event_id, edition_id, false).await
+}
+
+/// Returns the map bound to an event edition from its UID or its original version UID, even if
+/// it's disabled in the edition.
+///
+/// This is the same as [`get_map_in_edition`], but the caller must check the
+/// [`EventMap::is_disabled`] flag before saving a record on the map.
+pub async fn get_map_in_edition_including_disabled<C: ConnectionTrait>(
+    conn: &C,
+    map_uid: &str,
+    event_id: u32,
+    edition_id: u32,
+) -> RecordsResult<Option<EventMap>> {
+    find_map_in_edition(conn, map_uid, event_id, edition_id, true).await
+}
+
+async fn find_map_in_edition<C: ConnectionTrait>(
+    conn: &C,
+    map_uid: &str,
+    event_id: u32,
+    edition_id: u32,
+    include_disabled: bool,
+) -> RecordsResult<Option<EventMap>> {
+    let query = event_edition_maps::Entity::find()
         .join_as(
             sea_orm::JoinType::InnerJoin,
             event_edition_maps::Relation::Maps.def(),
@@ -386,15 +934,267 @@ pub async fn get_map_in_edition<C: ConnectionTrait>(
                     Expr::col(("map", maps::Column::Id))
                         .equals(("original_map", maps::Column::Id))
                         .or(event_edition_maps::Column::TransitiveSave.ne(0)),
-                )
-                .and(event_edition_maps::Column::IsDisabled.eq(0)),
-        )
+                ),
+        );
+
+    let query = if include_disabled {
+        query
+    } else {
+        query.filter(event_edition_maps::Column::IsDisabled.eq(0))
+    };
+
+    let map = query
         .select_only()
         .expr(Expr::col(("map", Asterisk)))
         .column(event_edition_maps::Column::OriginalMapId)
+        .column(event_edition_maps::Column::IsDisabled)
         .into_model()
         .one(conn)
         .await?;
 
     Ok(map)
 }
+
+/// Copies the structure of the provided event edition into a new edition of the same event,
+/// with the ID `to_edition_id`.
+///
+/// The edition, its categories, its maps and its in-game parameters are copied, but not its
+/// records. The MX mappack of the edition isn't copied either, as it is bound to a single
+/// edition.
+///
+/// If the target edition already exists, this returns a
+/// [`RecordsError::EventEditionAlreadyExists`] error.
+pub async fn clone_edition<C: TransactionTrait>(
+    conn: &C,
+    event: &event::Model,
+    from: &event_edition::Model,
+    to_edition_id: u32,
+) -> RecordsResult<event_edition::Model> {
+    sync::transaction(conn, async |txn| {
+        let existing = event_edition::Entity::find_by_id((to_edition_id, event.id))
+            .one(txn)
+            .await?;
+        if existing.is_some() {
+            return Err(RecordsError::EventEditionAlreadyExists(
+                event.handle.clone(),
+                to_edition_id,
+            ));
+        }
+
+        let ingame_params_id = match from.ingame_params_id {
+            Some(params_id) => {
+                let params = in_game_event_edition_params::Entity::find_by_id(params_id)
+                    .one(txn)
+                    .await?
+                    .ok_or_else(|| internal!("In-game params {params_id} should be in database"))?;
+                let mut params = params.into_active_model();
+                params.id = NotSet;
+                let res = in_game_event_edition_params::Entity::insert(params)
+                    .exec(txn)
+                    .await?;
+                Some(res.last_insert_id)
+            }
+            None => None,
+        };
+
+        let edition = event_edition::Model {
+            id: to_edition_id,
+            mx_id: None,
+            mx_secret: None,
+            ingame_params_id,
+            ..from.clone()
+        };
+        event_edition::Entity::insert(edition.clone().into_active_model())
+            .exec(txn)
+            .await?;
+
+        let categories = event_edition_categories::Entity::find()
+            .filter(
+                event_edition_categories::Column::EventId
+                    .eq(from.event_id)
+                    .and(event_edition_categories::Column::EditionId.eq(from.id)),
+            )
+            .all(txn)
+            .await?;
+        if !categories.is_empty() {
+            event_edition_categories::Entity::insert_many(categories.into_iter().map(|category| {
+                event_edition_categories::Model {
+                    edition_id: to_edition_id,
+                    ..category
+                }
+                .into_active_model()
+            }))
+            .exec(txn)
+            .await?;
+        }
+
+        let maps = event_edition_maps::Entity::find()
+            .filter(
+                event_edition_maps::Column::EventId
+                    .eq(from.event_id)
+                    .and(event_edition_maps::Column::EditionId.eq(from.id)),
+            )
+            .all(txn)
+            .await?;
+        if !maps.is_empty() {
+            event_edition_maps::Entity::insert_many(maps.into_iter().map(|map| {
+                event_edition_maps::Model {
+                    edition_id: to_edition_id,
+                    ..map
+                }
+                .into_active_model()
+            }))
+            .exec(txn)
+            .await?;
+        }
+
+        Ok(edition)
+    })
+    .await
+}
+
+/// The best record of a player on a map of an event edition, returned in
+/// a [`PlayerEditionRecords`].
+#[derive(Debug, serde::Serialize)]
+pub struct PlayerEditionRecord {
+    /// The UID of the map.
+    pub map_uid: String,
+    /// The name of the map.
+    pub map_name: String,
+    /// The time of the record.
+    pub time: i32,
+    /// The rank of the record in the leaderboard of the map in the edition.
+    pub rank: i32,
+    /// The UTC date of the record.
+    pub record_date: chrono::NaiveDateTime,
+}
+
+/// The records of a player in an event edition, returned by the [`player_records_by_edition`]
+/// function.
+#[derive(Debug, serde::Serialize)]
+pub struct PlayerEditionRecords {
+    /// The handle of the event.
+    pub event_handle: String,
+    /// The ID of the edition.
+    pub edition_id: u32,
+    /// The name of the edition.
+    pub edition_name: String,
+    /// The amount of maps of the edition on which the player has a record.
+    pub records_count: usize,
+    /// The best rank of the player among the maps of the edition.
+    pub best_rank: Option<i32>,
+    /// The best record of the player on each map of the edition, sorted by map ID.
+    pub records: Vec<PlayerEditionRecord>,
+}
+
+/// Returns the records of the player with the provided ID, grouped by the event editions they
+/// were made in.
+///
+/// Only the best record of the player on each map of an edition is kept, and the hidden records
+/// are ignored. The leaderboards of the maps are updated if needed. The editions are sorted by
+/// their start date, the most recent first.
+pub async fn player_records_by_edition<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Vec<PlayerEditionRecords>> {
+    let associations = event_edition_records::Entity::find()
+        .find_also_related(records::Entity)
+        .filter(
+            records::Column::RecordPlayerId
+                .eq(player_id)
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .all(conn)
+        .await?;
+
+    let mut best_records = HashMap::<(u32, u32), HashMap<u32, records::Model>>::new();
+    for (association, record) in associations {
+        let Some(record) = record else {
+            continue;
+        };
+        let edition_records = best_records
+            .entry((association.event_id, association.edition_id))
+            .or_default();
+        match edition_records.entry(record.map_id) {
+            Entry::Occupied(mut best) if best.get().time > record.time => {
+                best.insert(record);
+            }
+            Entry::Occupied(_) => (),
+            Entry::Vacant(entry) => {
+                entry.insert(record);
+            }
+        }
+    }
+
+    let maps = maps::Entity::find()
+        .filter(
+            maps::Column::Id.is_in(
+                best_records
+                    .values()
+                    .flat_map(HashMap::keys)
+                    .copied()
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|map| (map.id, map))
+        .collect::<HashMap<_, _>>();
+
+    let mut redis_conn = redis_pool.get().await?;
+    let mut editions = Vec::with_capacity(best_records.len());
+
+    for ((event_id, edition_id), records) in best_records {
+        let (event, edition) =
+            must::have_event_edition_from_ids(conn, event_id, edition_id).await?;
+        let opt_event = OptEvent::new(&event, &edition);
+
+        let mut records = records.into_values().collect::<Vec<_>>();
+        records.sort_unstable_by_key(|record| record.map_id);
+
+        for record in &records {
+            ranks::update_leaderboard(conn, redis_pool, record.map_id, opt_event).await?;
+        }
+
+        let times = records
+            .iter()
+            .map(|record| (record.map_id, record.time))
+            .collect::<Vec<_>>();
+        let ranks = ranks::get_ranks(&mut redis_conn, &times, opt_event).await?;
+
+        let records = records
+            .into_iter()
+            .zip(ranks)
+            .map(|(record, rank)| {
+                let map = maps
+                    .get(&record.map_id)
+                    .ok_or_else(|| internal!("Map {} should be in database", record.map_id))?;
+                Ok(PlayerEditionRecord {
+                    map_uid: map.game_id.clone(),
+                    map_name: map.name.clone(),
+                    time: record.time,
+                    rank,
+                    record_date: record.record_date,
+                })
+            })
+            .collect::<RecordsResult<Vec<_>>>()?;
+
+        editions.push((
+            edition.start_date,
+            PlayerEditionRecords {
+                event_handle: event.handle,
+                edition_id: edition.id,
+                edition_name: edition.name,
+                records_count: records.len(),
+                best_rank: records.iter().map(|record| record.rank).min(),
+                records,
+            },
+        ));
+    }
+
+    editions.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    Ok(editions.into_iter().map(|(_, edition)| edition).collect())
+}
@@ -1,3 +1,8 @@
+//! This module contains the [`Expirable`] trait, implemented by the types having an expire date,
+//! like the event editions or the cached values.
+
+use std::time::Duration;
+
 use entity::event_edition;
 
 /// Represents a type that has an expire date.
@@ -5,17 +10,28 @@ pub trait Expirable {
     /// Returns the UTC expire date.
     fn expire_date(&self) -> Option<chrono::NaiveDateTime>;
 
-    /// Returns the number of seconds until the edition expires from now.
+    /// Returns the number of seconds until it expires from the provided UTC date.
+    ///
+    /// If it doesn't expire (it hasn't a TTL), it returns `None`.
+    fn expires_in_at(&self, now: chrono::NaiveDateTime) -> Option<i64> {
+        self.expire_date().map(|d| (d - now).num_seconds())
+    }
+
+    /// Returns the number of seconds until it expires from now.
     ///
-    /// If the edition doesn't expire (it hasn't a TTL), it returns `None`.
+    /// If it doesn't expire (it hasn't a TTL), it returns `None`.
     fn expires_in(&self) -> Option<i64> {
-        self.expire_date()
-            .map(|d| (d - chrono::Utc::now().naive_utc()).num_seconds())
+        self.expires_in_at(chrono::Utc::now().naive_utc())
     }
 
-    /// Returns whether the edition has expired or not.
+    /// Returns whether it has expired or not at the provided UTC date.
+    fn has_expired_at(&self, now: chrono::NaiveDateTime) -> bool {
+        self.expires_in_at(now).filter(|n| *n < 0).is_some()
+    }
+
+    /// Returns whether it has expired or not.
     fn has_expired(&self) -> bool {
-        self.expires_in().filter(|n| *n < 0).is_some()
+        self.has_expired_at(chrono::Utc::now().naive_utc())
     }
 }
 
@@ -27,3 +43,130 @@ impl Expirable for event_edition::Model {
         })
     }
 }
+
+/// A value computed periodically and cached, which expires once older than its lifetime.
+///
+/// A value that was never computed is considered expired.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheEntry {
+    /// The UTC date of the last computation of the value, if any.
+    pub computed_at: Option<chrono::NaiveDateTime>,
+    /// The duration after which the value must be computed again.
+    pub lifetime: Duration,
+}
+
+impl CacheEntry {
+    /// Returns the cache entry of a value computed at the provided UNIX timestamp, in seconds.
+    ///
+    /// This is the format in which the computation times are saved in the Redis database.
+    pub fn from_timestamp(computed_at: Option<u64>, lifetime: Duration) -> Self {
+        Self {
+            computed_at: computed_at
+                .and_then(|timestamp| i64::try_from(timestamp).ok())
+                .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+                .map(|date| date.naive_utc()),
+            lifetime,
+        }
+    }
+}
+
+impl Expirable for CacheEntry {
+    fn expire_date(&self) -> Option<chrono::NaiveDateTime> {
+        let Some(computed_at) = self.computed_at else {
+            return Some(chrono::DateTime::UNIX_EPOCH.naive_utc());
+        };
+        let lifetime = chrono::Duration::from_std(self.lifetime).unwrap_or(chrono::Duration::MAX);
+        Some(
+            computed_at
+                .checked_add_signed(lifetime)
+                .unwrap_or(chrono::NaiveDateTime::MAX),
+        )
+    }
+}
+
+/// Recomputes the value of the provided entry with the `refresh` function if it has expired at
+/// the provided UTC date.
+///
+/// It returns the recomputed value, or `None` if the entry is still fresh.
+pub async fn refresh_if_stale<E, F, T, Err>(
+    entry: &E,
+    now: chrono::NaiveDateTime,
+    refresh: F,
+) -> Result<Option<T>, Err>
+where
+    E: Expirable + ?Sized,
+    F: AsyncFnOnce() -> Result<T, Err>,
+{
+    if entry.has_expired_at(now) {
+        refresh().await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use super::{CacheEntry, refresh_if_stale};
+
+    /// A clock that only moves forward when told so.
+    struct FakeClock(Cell<chrono::NaiveDateTime>);
+
+    impl FakeClock {
+        fn now(&self) -> chrono::NaiveDateTime {
+            self.0.get()
+        }
+
+        fn advance(&self, secs: i64) {
+            self.0.set(self.0.get() + chrono::Duration::seconds(secs));
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_only_once_stale() {
+        let clock = FakeClock(Cell::new(
+            chrono::DateTime::from_timestamp(1_700_000_000, 0)
+                .unwrap()
+                .naive_utc(),
+        ));
+        let refreshes = Cell::new(0);
+        let refresh = async || {
+            refreshes.set(refreshes.get() + 1);
+            Ok::<_, ()>(clock.now())
+        };
+
+        // Never computed, so it's refreshed right away
+        let mut entry = CacheEntry {
+            computed_at: None,
+            lifetime: Duration::from_secs(60),
+        };
+        let computed_at = refresh_if_stale(&entry, clock.now(), &refresh).await;
+        assert_eq!(computed_at, Ok(Some(clock.now())));
+        entry.computed_at = computed_at.unwrap();
+
+        // Still fresh until the end of its lifetime
+        clock.advance(60);
+        assert_eq!(
+            refresh_if_stale(&entry, clock.now(), &refresh).await,
+            Ok(None)
+        );
+        assert_eq!(refreshes.get(), 1);
+
+        clock.advance(1);
+        assert_eq!(
+            refresh_if_stale(&entry, clock.now(), &refresh).await,
+            Ok(Some(clock.now()))
+        );
+        assert_eq!(refreshes.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn refresh_error() {
+        let entry = CacheEntry::from_timestamp(Some(0), Duration::from_secs(60));
+        let now = chrono::DateTime::from_timestamp(61, 0).unwrap().naive_utc();
+
+        let result = refresh_if_stale(&entry, now, async || Err::<(), _>("failed")).await;
+        assert_eq!(result, Err("failed"));
+    }
+}
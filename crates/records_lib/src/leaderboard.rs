@@ -1,5 +1,7 @@
 //! This module contains various utility items to retrieve leaderboards information.
 
+use std::collections::{BTreeMap, HashMap};
+
 use deadpool_redis::redis::AsyncCommands as _;
 use entity::{event_edition_records, players, records};
 use sea_orm::{
@@ -118,10 +120,13 @@ struct RecordQueryRow {
     time: i32,
 }
 
+/// The rank of the rows of the banned players, when they're shown in the leaderboards.
+pub const UNRANKED: i32 = 0;
+
 /// The type yielded by the [`leaderboard`] function.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Row {
-    /// The rank of the record.
+    /// The rank of the record, or [`UNRANKED`] if its player is banned.
     pub rank: i32,
     /// The login of the player.
     pub login: String,
@@ -131,7 +136,56 @@ pub struct Row {
     pub time: i32,
 }
 
+/// Extends the provided vec with the unranked records of the currently banned players on a map.
+///
+/// This is used when the records of the banned players aren't hidden from the leaderboards.
+async fn unranked_rows_into<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    rows: &mut Vec<Row>,
+    event: OptEvent<'_>,
+) -> RecordsResult<()> {
+    let result = records::Entity::find()
+        .inner_join(players::Entity)
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.in_subquery(ranks::banned_players_query())),
+        )
+        .group_by(records::Column::RecordPlayerId)
+        .order_by(records::Column::Time.min(), Order::Asc)
+        .order_by(records::Column::RecordPlayerId, Order::Asc)
+        .apply_if(event.get(), |query, (ev, ed)| {
+            query.reverse_join(event_edition_records::Entity).filter(
+                event_edition_records::Column::EventId
+                    .eq(ev.id)
+                    .and(event_edition_records::Column::EditionId.eq(ed.id)),
+            )
+        })
+        .select_only()
+        .column_as(players::Column::Login, "login")
+        .column_as(players::Column::Name, "nickname")
+        .column_as(Expr::col(records::Column::Time).min(), "time")
+        .into_model::<RecordQueryRow>()
+        .all(conn)
+        .await?;
+
+    rows.extend(result.into_iter().map(|r| Row {
+        rank: UNRANKED,
+        login: r.login,
+        nickname: r.nickname,
+        time: r.time,
+    }));
+
+    Ok(())
+}
+
 /// Gets the leaderboard of a map and extends it to the provided vec.
+///
+/// The records of the currently banned players are either omitted, or appended without a rank
+/// if the range reaches the end of the leaderboard, depending on the
+/// [`hide_banned_players`](crate::hide_banned_players) configuration.
 pub async fn leaderboard_into<C: ConnectionTrait + StreamTrait>(
     conn: &C,
     redis_pool: &RedisPool,
@@ -144,23 +198,115 @@ pub async fn leaderboard_into<C: ConnectionTrait + StreamTrait>(
     let start = start.unwrap_or_default();
     let end = end.unwrap_or(-1);
 
-    let player_ids: Vec<i32> = {
+    let ranked_count = ranked_rows_into(conn, redis_pool, map_id, start, end, rows, event).await?;
+
+    let reaches_end = end < 0 || end >= ranked_count - 1;
+    if reaches_end && !crate::hide_banned_players() {
+        unranked_rows_into(conn, map_id, rows, event).await?;
+    }
+
+    Ok(())
+}
+
+/// Extends the provided vec with the ranked rows of the leaderboard of a map, between the
+/// provided indexes of the Redis leaderboard, both included.
+///
+/// It returns the amount of ranked players on the map.
+async fn ranked_rows_into<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map_id: u32,
+    start: i32,
+    end: i32,
+    rows: &mut Vec<Row>,
+    event: OptEvent<'_>,
+) -> RecordsResult<i32> {
+    let key = map_key(map_id, event).to_string();
+
+    let (player_ids, ranked_count): (Vec<i32>, i32) = {
         let mut redis_conn = redis_pool.get().await?;
-        redis_conn
-            .zrange(map_key(map_id, event), start as isize, end as isize)
-            .await?
+        let player_ids = redis_conn
+            .zrange(&key, start as isize, end as isize)
+            .await?;
+        let ranked_count = redis_conn.zcard(&key).await?;
+        (player_ids, ranked_count)
     };
 
-    if player_ids.is_empty() {
-        return Ok(());
+    if !player_ids.is_empty() {
+        let result = records::Entity::find()
+            .inner_join(players::Entity)
+            .filter(
+                records::Column::MapId
+                    .eq(map_id)
+                    .and(records::Column::RecordPlayerId.is_in(player_ids))
+                    .and(records::Column::IsHidden.eq(false))
+                    .and(
+                        records::Column::RecordPlayerId
+                            .not_in_subquery(ranks::banned_players_query()),
+                    ),
+            )
+            .group_by(records::Column::RecordPlayerId)
+            .order_by(records::Column::Time.min(), Order::Asc)
+            .order_by(records::Column::RecordPlayerId, Order::Asc)
+            .apply_if(event.get(), |query, (ev, ed)| {
+                query.reverse_join(event_edition_records::Entity).filter(
+                    event_edition_records::Column::EventId
+                        .eq(ev.id)
+                        .and(event_edition_records::Column::EditionId.eq(ed.id)),
+                )
+            })
+            .select_only()
+            .column_as(records::Column::RecordPlayerId, "player_id")
+            .column_as(players::Column::Login, "login")
+            .column_as(players::Column::Name, "nickname")
+            .column_as(Expr::col(records::Column::Time).min(), "time")
+            .into_model::<RecordQueryRow>()
+            .all(conn)
+            .await?;
+
+        rows.reserve(result.len());
+
+        let mut redis_conn = redis_pool.get().await?;
+
+        for r in result {
+            rows.push(Row {
+                rank: ranks::get_rank(&mut redis_conn, map_id, r.time, event).await?,
+                login: r.login,
+                nickname: r.nickname,
+                time: r.time,
+            });
+        }
     }
 
+    Ok(ranked_count)
+}
+
+/// Gets the leaderboard of a map from the SQL database only, and extends it to the provided vec.
+///
+/// This is slower than [`leaderboard_into`], and should only be used if the Redis database
+/// is unavailable.
+pub async fn leaderboard_from_db_into<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    start: Option<i32>,
+    end: Option<i32>,
+    rows: &mut Vec<Row>,
+    event: OptEvent<'_>,
+) -> RecordsResult<()> {
+    let start = start.unwrap_or_default().max(0);
+    let limit = end
+        .filter(|end| *end >= 0)
+        .map(|end| (end - start + 1).max(0) as u64);
+
     let result = records::Entity::find()
         .inner_join(players::Entity)
         .filter(
             records::Column::MapId
                 .eq(map_id)
-                .and(records::Column::RecordPlayerId.is_in(player_ids)),
+                .and(records::Column::IsHidden.eq(false))
+                .and(
+                    records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()),
+                ),
         )
         .group_by(records::Column::RecordPlayerId)
         .order_by(records::Column::Time.min(), Order::Asc)
@@ -172,8 +318,9 @@ pub async fn leaderboard_into<C: ConnectionTrait + StreamTrait>(
                     .and(event_edition_records::Column::EditionId.eq(ed.id)),
             )
         })
+        .offset(start as u64)
+        .limit(limit)
         .select_only()
-        .column_as(records::Column::RecordPlayerId, "player_id")
         .column_as(players::Column::Login, "login")
         .column_as(players::Column::Name, "nickname")
         .column_as(Expr::col(records::Column::Time).min(), "time")
@@ -181,22 +328,67 @@ pub async fn leaderboard_into<C: ConnectionTrait + StreamTrait>(
         .all(conn)
         .await?;
 
-    rows.reserve(result.len());
+    let reaches_end = limit.is_none_or(|limit| (result.len() as u64) < limit);
 
-    let mut redis_conn = redis_pool.get().await?;
+    rows.reserve(result.len());
 
     for r in result {
         rows.push(Row {
-            rank: ranks::get_rank(&mut redis_conn, map_id, r.time, event).await?,
+            rank: ranks::get_rank_from_db(conn, map_id, r.time, event).await?,
             login: r.login,
             nickname: r.nickname,
             time: r.time,
         });
     }
 
+    if reaches_end && !crate::hide_banned_players() {
+        unranked_rows_into(conn, map_id, rows, event).await?;
+    }
+
     Ok(())
 }
 
+/// Returns the records of the map with the provided ID whose flags contain all the bits of
+/// the provided mask, ordered by time.
+///
+/// Unlike the leaderboard, this returns every record matching the mask, not only the best one
+/// of each player, up to the provided limit. The records of the banned players are excluded.
+pub async fn records_by_flag<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    flag_mask: u32,
+    limit: u64,
+    event: OptEvent<'_>,
+) -> RecordsResult<Vec<records::Model>> {
+    let result = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.not_in_subquery(ranks::banned_players_query()))
+                .and(
+                    Expr::expr(
+                        Expr::col((records::Entity, records::Column::Flags)).bit_and(flag_mask),
+                    )
+                    .eq(flag_mask),
+                ),
+        )
+        .apply_if(event.get(), |query, (ev, ed)| {
+            query.reverse_join(event_edition_records::Entity).filter(
+                event_edition_records::Column::EventId
+                    .eq(ev.id)
+                    .and(event_edition_records::Column::EditionId.eq(ed.id)),
+            )
+        })
+        .order_by(records::Column::Time, Order::Asc)
+        .order_by(records::Column::RecordDate, Order::Asc)
+        .limit(limit)
+        .all(conn)
+        .await?;
+
+    Ok(result)
+}
+
 /// Returns the leaderboard of a map.
 pub async fn leaderboard<C: ConnectionTrait + StreamTrait>(
     conn: &C,
@@ -210,3 +402,197 @@ pub async fn leaderboard<C: ConnectionTrait + StreamTrait>(
     leaderboard_into(conn, redis_pool, map_id, start, end, &mut out, event).await?;
     Ok(out)
 }
+
+/// The closest competitors of a player on a map, returned by the [`neighbors`] function.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Neighbors {
+    /// The players immediately faster than the player, sorted by rank, so the closest one is
+    /// the last.
+    pub above: Vec<Row>,
+    /// The players immediately slower than the player, sorted by rank, so the closest one is
+    /// the first.
+    pub below: Vec<Row>,
+}
+
+/// Returns at most `radius` players immediately faster, and `radius` players immediately slower
+/// than the player with the provided ID on a map.
+///
+/// The leaderboard of the map is updated if needed. The currently banned players are never
+/// included, and both lists are empty if the player has no ranked record on the map.
+pub async fn neighbors<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map_id: u32,
+    player_id: u32,
+    radius: usize,
+    event: OptEvent<'_>,
+) -> RecordsResult<Neighbors> {
+    ranks::update_leaderboard(conn, redis_pool, map_id, event).await?;
+
+    let index: Option<i32> = {
+        let mut redis_conn = redis_pool.get().await?;
+        redis_conn.zrank(map_key(map_id, event), player_id).await?
+    };
+
+    let mut out = Neighbors::default();
+
+    let Some(index) = index else {
+        return Ok(out);
+    };
+    let radius = i32::try_from(radius).unwrap_or(i32::MAX);
+    if radius == 0 {
+        return Ok(out);
+    }
+
+    if index > 0 {
+        let start = index.saturating_sub(radius).max(0);
+        ranked_rows_into(
+            conn,
+            redis_pool,
+            map_id,
+            start,
+            index - 1,
+            &mut out.above,
+            event,
+        )
+        .await?;
+    }
+
+    ranked_rows_into(
+        conn,
+        redis_pool,
+        map_id,
+        index + 1,
+        index.saturating_add(radius),
+        &mut out.below,
+        event,
+    )
+    .await?;
+
+    Ok(out)
+}
+
+/// The movement of a player on the leaderboard of a map, returned by the [`movement`] function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerMovement {
+    /// The ID of the player.
+    pub player_id: u32,
+    /// The ID of the map.
+    pub map_id: u32,
+    /// The rank of the player at the reference date, or `None` if they didn't have a record on
+    /// the map yet.
+    pub previous_rank: Option<i32>,
+    /// The current rank of the player.
+    pub rank: i32,
+}
+
+impl PlayerMovement {
+    /// Returns the amount of ranks gained by the player since the reference date, which is
+    /// negative if they were overtaken, or `None` if they didn't have a rank yet.
+    pub fn delta(&self) -> Option<i32> {
+        self.previous_rank
+            .map(|previous_rank| previous_rank - self.rank)
+    }
+}
+
+/// Returns the movement of the players with the provided IDs on all the maps where they have a
+/// record, since the provided date.
+///
+/// The previous ranks are computed from the records saved until this date, with a single query
+/// per map for all the players. The current ranks come from the Redis leaderboards, which are
+/// updated if needed.
+///
+/// The movements are sorted by player ID, then by map ID. This only concerns the leaderboards
+/// outside of any event.
+pub async fn movement<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    players: &[u32],
+    since: chrono::NaiveDateTime,
+) -> RecordsResult<Vec<PlayerMovement>> {
+    if players.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let best_times = |until: Option<chrono::NaiveDateTime>| {
+        records::Entity::find()
+            .filter(
+                records::Column::RecordPlayerId
+                    .is_in(players.iter().copied())
+                    .and(records::Column::IsHidden.eq(false)),
+            )
+            .apply_if(until, |query, until| {
+                query.filter(records::Column::RecordDate.lte(until))
+            })
+            .group_by(records::Column::RecordPlayerId)
+            .group_by(records::Column::MapId)
+            .select_only()
+            .columns([records::Column::RecordPlayerId, records::Column::MapId])
+            .column_as(Expr::col(records::Column::Time).min(), "time")
+            .into_tuple::<(u32, u32, i32)>()
+    };
+
+    let current_times = best_times(None).all(conn).await?;
+    let previous_times = best_times(Some(since))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(player_id, map_id, time)| ((player_id, map_id), time))
+        .collect::<HashMap<_, _>>();
+
+    let mut by_map = BTreeMap::<u32, Vec<(u32, i32)>>::new();
+    for (player_id, map_id, time) in current_times {
+        by_map.entry(map_id).or_default().push((player_id, time));
+    }
+
+    let mut out = Vec::new();
+    let mut redis_conn = redis_pool.get().await?;
+
+    for (map_id, players_times) in by_map {
+        ranks::update_leaderboard(conn, redis_pool, map_id, Default::default()).await?;
+
+        // The leaderboard of the map at the reference date, which is only needed if one of the
+        // players already had a record
+        let previous_leaderboard: Vec<i32> = if players_times
+            .iter()
+            .any(|(player_id, _)| previous_times.contains_key(&(*player_id, map_id)))
+        {
+            records::Entity::find()
+                .filter(
+                    records::Column::MapId
+                        .eq(map_id)
+                        .and(records::Column::IsHidden.eq(false))
+                        .and(records::Column::RecordDate.lte(since))
+                        .and(
+                            records::Column::RecordPlayerId
+                                .not_in_subquery(ranks::banned_players_query()),
+                        ),
+                )
+                .group_by(records::Column::RecordPlayerId)
+                .order_by(records::Column::Time.min(), Order::Asc)
+                .select_only()
+                .column_as(Expr::col(records::Column::Time).min(), "time")
+                .into_tuple()
+                .all(conn)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        for (player_id, time) in players_times {
+            let previous_rank = previous_times
+                .get(&(player_id, map_id))
+                .map(|time| previous_leaderboard.partition_point(|t| t < time) as i32 + 1);
+            out.push(PlayerMovement {
+                player_id,
+                map_id,
+                previous_rank,
+                rank: ranks::get_rank(&mut redis_conn, map_id, time, Default::default()).await?,
+            });
+        }
+    }
+
+    out.sort_by_key(|movement| (movement.player_id, movement.map_id));
+
+    Ok(out)
+}
@@ -10,11 +10,11 @@
 #![cfg_attr(nightly, feature(doc_cfg))]
 
 mod env;
-mod expirable;
 mod mptypes;
 
 pub mod error;
 pub mod event;
+pub mod expirable;
 pub mod leaderboard;
 pub mod map;
 pub mod mappack;
@@ -23,6 +23,7 @@ pub mod opt_event;
 pub mod player;
 pub mod pool;
 pub mod ranks;
+pub mod record;
 pub mod records_notifier;
 pub mod redis_key;
 pub mod sync;
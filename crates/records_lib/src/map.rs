@@ -2,10 +2,23 @@
 
 use core::fmt;
 
-use entity::maps;
-use sea_orm::{ColumnTrait as _, ConnectionTrait, EntityTrait as _, QueryFilter as _};
+use deadpool_redis::redis;
+use entity::{event_edition_maps, event_edition_records, global_records, maps, players, records};
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait, EntityTrait as _, PaginatorTrait as _, QueryFilter as _,
+    QueryOrder as _, QuerySelect as _, QueryTrait as _, TransactionTrait,
+    sea_query::{Expr, Func},
+};
 
-use crate::{error::RecordsResult, internal};
+use crate::{
+    Expirable as _, RedisPool,
+    error::{RecordsError, RecordsResult},
+    internal, must,
+    opt_event::OptEvent,
+    player,
+    redis_key::{alone_map_key, event_map_key},
+    sync,
+};
 
 /// Returns the map bound to the provided ID.
 pub async fn get_map_from_id<C: ConnectionTrait>(
@@ -35,6 +48,236 @@ pub async fn get_map_from_uid<C: ConnectionTrait>(
     Ok(map)
 }
 
+/// Returns the earliest record ever made on the map with the provided ID, if any.
+///
+/// The hidden records are ignored. In an event context, only the records saved for the event
+/// edition are considered.
+pub async fn get_first_record<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    event: OptEvent<'_>,
+) -> RecordsResult<Option<records::Model>> {
+    let record = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .apply_if(event.get(), |query, (ev, ed)| {
+            query.reverse_join(event_edition_records::Entity).filter(
+                event_edition_records::Column::EventId
+                    .eq(ev.id)
+                    .and(event_edition_records::Column::EditionId.eq(ed.id)),
+            )
+        })
+        .order_by_asc(records::Column::RecordDate)
+        .order_by_asc(records::Column::RecordId)
+        .one(conn)
+        .await?;
+    Ok(record)
+}
+
+/// Represents the improvement of a player's personal best on a map.
+#[derive(Debug, Clone)]
+pub struct MapImprovement {
+    /// The player who made the improvement.
+    pub player: players::Model,
+    /// The time of the personal best before the improvement.
+    pub from_time: i32,
+    /// The time of the personal best after the improvement.
+    pub to_time: i32,
+    /// The difference between the two times, in milliseconds.
+    pub delta: i32,
+}
+
+/// Returns the largest personal best improvement made by any player on the map with the
+/// provided ID.
+///
+/// An improvement goes from a record of a player to a later one with a better time. The records
+/// that don't beat the personal best of the player at their date are ignored, like the hidden
+/// records.
+pub async fn biggest_improvement<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+) -> RecordsResult<Option<MapImprovement>> {
+    let records: Vec<(u32, i32)> = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .order_by_asc(records::Column::RecordPlayerId)
+        .order_by_asc(records::Column::RecordDate)
+        .order_by_asc(records::Column::RecordId)
+        .select_only()
+        .columns([records::Column::RecordPlayerId, records::Column::Time])
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    // (player ID, from time, to time)
+    let mut biggest: Option<(u32, i32, i32)> = None;
+    // (player ID, personal best)
+    let mut current_pb: Option<(u32, i32)> = None;
+
+    for (player_id, time) in records {
+        match current_pb {
+            Some((pb_player_id, pb)) if pb_player_id == player_id => {
+                if time >= pb {
+                    continue;
+                }
+
+                if biggest.is_none_or(|(_, from, to)| pb - time > from - to) {
+                    biggest = Some((player_id, pb, time));
+                }
+            }
+            _ => (),
+        }
+
+        current_pb = Some((player_id, time));
+    }
+
+    let Some((player_id, from_time, to_time)) = biggest else {
+        return Ok(None);
+    };
+
+    let player = player::get_player_from_id(conn, player_id).await?;
+
+    Ok(Some(MapImprovement {
+        player,
+        from_time,
+        to_time,
+        delta: from_time - to_time,
+    }))
+}
+
+/// Returns the amount of distinct players who finished the map with the provided ID.
+///
+/// Unlike [`count_records_map`](crate::ranks::count_records_map), the banned players are counted,
+/// but the hidden records are still ignored.
+pub async fn finisher_count<C: ConnectionTrait>(conn: &C, map_id: u32) -> RecordsResult<u64> {
+    let count: Option<i64> = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .select_only()
+        .expr(Func::count_distinct(Expr::col((
+            records::Entity,
+            records::Column::RecordPlayerId,
+        ))))
+        .into_tuple()
+        .one(conn)
+        .await?;
+
+    Ok(count.unwrap_or_default() as u64)
+}
+
+/// Compares the amount of records of the map with the provided ID in the `global_records` view
+/// with the amount of players having a non-hidden record on it in the `records` table.
+///
+/// Each of these players should have their best record in the view, so a mismatch indicates
+/// an inconsistency in the database, like a best record missing from the view.
+///
+/// It returns `(view_count, best_count)` if the counts differ, or `None` otherwise.
+pub async fn record_count_mismatch<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+) -> RecordsResult<Option<(u64, u64)>> {
+    let view_count = global_records::Entity::find()
+        .filter(global_records::Column::MapId.eq(map_id))
+        .count(conn)
+        .await?;
+
+    let best_count = finisher_count(conn, map_id).await?;
+
+    Ok((view_count != best_count).then_some((view_count, best_count)))
+}
+
+/// Deletes the provided map, along with its records and its links to event editions.
+///
+/// The event maps that use this map as their original map stay in their editions, but they lose
+/// their original map.
+///
+/// If the map is used by an event edition that hasn't expired yet, this returns
+/// a [`RecordsError::MapInActiveEvent`] error, unless `force` is `true`.
+///
+/// The rows are deleted in a single transaction, and the Redis leaderboards of the map are removed
+/// once it's committed. It returns the amount of deleted records.
+pub async fn delete_map<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map: &maps::Model,
+    force: bool,
+) -> RecordsResult<u64> {
+    let links: Vec<(u32, u32)> = event_edition_maps::Entity::find()
+        .filter(event_edition_maps::Column::MapId.eq(map.id))
+        .select_only()
+        .columns([
+            event_edition_maps::Column::EventId,
+            event_edition_maps::Column::EditionId,
+        ])
+        .distinct()
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let mut editions = Vec::with_capacity(links.len());
+    for (event_id, edition_id) in links {
+        editions.push(must::have_event_edition_from_ids(conn, event_id, edition_id).await?);
+    }
+
+    if !force
+        && let Some((event, edition)) = editions.iter().find(|(_, edition)| !edition.has_expired())
+    {
+        return Err(RecordsError::MapInActiveEvent(
+            map.game_id.clone(),
+            event.handle.clone(),
+            edition.id,
+        ));
+    }
+
+    let deleted_records = sync::transaction(conn, async |txn| {
+        event_edition_maps::Entity::delete_many()
+            .filter(event_edition_maps::Column::MapId.eq(map.id))
+            .exec(txn)
+            .await?;
+
+        event_edition_maps::Entity::update_many()
+            .col_expr(
+                event_edition_maps::Column::OriginalMapId,
+                Expr::value(Option::<u32>::None),
+            )
+            .filter(event_edition_maps::Column::OriginalMapId.eq(map.id))
+            .exec(txn)
+            .await?;
+
+        // The checkpoint times and the event links of the records are deleted by cascade
+        let deleted_records = records::Entity::delete_many()
+            .filter(records::Column::MapId.eq(map.id))
+            .exec(txn)
+            .await?
+            .rows_affected;
+
+        maps::Entity::delete_by_id(map.id).exec(txn).await?;
+
+        RecordsResult::Ok(deleted_records)
+    })
+    .await?;
+
+    let mut pipe = redis::pipe();
+    pipe.del(alone_map_key(map.id));
+    for (event, edition) in &editions {
+        pipe.del(event_map_key(map.id, &event.handle, edition.id));
+    }
+
+    let mut redis_conn = redis_pool.get().await?;
+    let _: () = pipe.query_async(&mut redis_conn).await?;
+
+    Ok(deleted_records)
+}
+
 /// Represents an item returned by a request to the MX API related to maps.
 #[derive(serde::Deserialize)]
 #[allow(non_snake_case)]
@@ -1,10 +1,14 @@
 //! This module contains anything related to mappacks in this library.
 
 use mkenv::prelude::*;
-use std::{fmt, time::SystemTime};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    time::{Duration, SystemTime},
+};
 
 use deadpool_redis::redis::{self, AsyncCommands, SetExpiry, SetOptions, ToRedisArgs};
-use entity::{event, event_edition, global_event_records, global_records, players, records};
+use entity::{event, event_edition, global_event_records, global_records, maps, players, records};
 use sea_orm::{
     ConnectionTrait, FromQueryResult, Order, StreamTrait, TransactionTrait,
     prelude::Expr,
@@ -12,15 +16,16 @@ use sea_orm::{
 };
 
 use crate::{
-    RedisPool,
+    Expirable as _, RedisConnection, RedisPool,
     error::RecordsResult,
+    expirable::CacheEntry,
     internal, must,
     opt_event::OptEvent,
     ranks,
     redis_key::{
         mappack_key, mappack_lb_key, mappack_map_last_rank, mappack_nb_map_key,
         mappack_player_map_finished_key, mappack_player_rank_avg_key, mappack_player_ranks_key,
-        mappack_player_worst_rank_key, mappack_time_key, mappacks_key,
+        mappack_player_worst_rank_key, mappack_scores_key, mappack_time_key, mappacks_key,
     },
     sync,
 };
@@ -67,6 +72,83 @@ struct RankedRecordRow {
     record: RecordRow,
 }
 
+/// The time-to-live, in seconds, of the cached scores of an ad-hoc mappack.
+pub const AD_HOC_MAPPACK_TTL: u64 = 300;
+
+/// The scores of the players on an ad-hoc mappack, meaning a list of maps that isn't saved
+/// as a mappack.
+///
+/// It contains the same data as the one saved in the Redis database for a regular mappack.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AdHocMappackScores {
+    /// The maps of the mappack.
+    pub maps: Vec<AdHocMappackMap>,
+    /// The scores of the players, sorted by their rank.
+    pub scores: Vec<AdHocPlayerScore>,
+}
+
+/// A map of an ad-hoc mappack.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AdHocMappackMap {
+    /// The UID of the map.
+    pub map_uid: String,
+    /// The last rank on the map.
+    pub last_rank: i32,
+}
+
+/// The score of a player on an ad-hoc mappack.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AdHocPlayerScore {
+    /// The ID of the player.
+    pub player_id: u32,
+    /// The rank of the player on the mappack.
+    pub rank: u32,
+    /// The average of the ranks of the player on the maps.
+    pub rank_avg: f64,
+    /// The amount of maps finished by the player.
+    pub maps_finished: usize,
+    /// The worst rank of the player.
+    pub worst_rank: i32,
+    /// The rank of the player on each map, associated to the map UID.
+    pub ranks: Vec<(String, i32)>,
+}
+
+impl From<MappackScores> for AdHocMappackScores {
+    fn from(scores: MappackScores) -> Self {
+        Self {
+            scores: scores
+                .scores
+                .into_iter()
+                .map(|score| AdHocPlayerScore {
+                    player_id: score.player_id,
+                    rank: score.rank,
+                    rank_avg: rank_avg(score.score),
+                    maps_finished: score.maps_finished,
+                    worst_rank: score.worst.rank,
+                    ranks: score
+                        .ranks
+                        .into_iter()
+                        .map(|rank| (scores.maps[rank.map_idx].map_id.clone(), rank.rank))
+                        .collect(),
+                })
+                .collect(),
+            maps: scores
+                .maps
+                .into_iter()
+                .map(|map| AdHocMappackMap {
+                    map_uid: map.map_id,
+                    last_rank: map.last_rank,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns the rank average saved for a player, from their score.
+fn rank_avg(score: f64) -> f64 {
+    ((score + f64::EPSILON) * 100.).round() / 100.
+}
+
 /// Represents any mappack ID, meaning an event or a regular MX mappack.
 ///
 /// If it is an event without an associated mappack, the mappack ID is `__X__Y__` where X
@@ -79,6 +161,10 @@ pub enum AnyMappackId<'a> {
     Event(&'a event::Model, &'a event_edition::Model),
     /// The mappack is a regular MX mappack.
     Id(&'a str),
+    /// The mappack is ephemeral, identified by the hash of its set of maps.
+    ///
+    /// See [`AnyMappackId::hash_of`] to get the hash of a set of maps.
+    Hashed(&'a str),
 }
 
 impl fmt::Debug for AnyMappackId<'_> {
@@ -103,6 +189,7 @@ impl fmt::Display for MappackIdDisp<'_, '_> {
                 }
             }
             AnyMappackId::Id(id) => f.write_str(id),
+            AnyMappackId::Hashed(hash) => write!(f, "ad_hoc:{hash}"),
         }
     }
 }
@@ -122,19 +209,73 @@ impl AnyMappackId<'_> {
         MappackIdDisp { mappack_id: self }
     }
 
+    /// Returns the hash identifying the provided set of maps, to be used with
+    /// [`AnyMappackId::Hashed`].
+    ///
+    /// The order and the duplicates of the map UIDs don't matter.
+    pub fn hash_of(map_uids: &[String]) -> String {
+        let mut map_uids = map_uids.iter().map(String::as_str).collect::<Vec<_>>();
+        map_uids.sort_unstable();
+        map_uids.dedup();
+        sha256::digest(map_uids.join("\n"))
+    }
+
     /// Returns whether the mappack has a time-to-live or not.
     ///
-    /// Only regular MX mappacks have a time-to-live.
+    /// Only regular MX mappacks and ephemeral mappacks have a time-to-live.
     fn has_ttl(&self) -> bool {
-        matches!(self, Self::Id(_))
+        matches!(self, Self::Id(_) | Self::Hashed(_))
     }
 
     /// Returns the optional time-to-live, in seconds, of the mappack.
     ///
-    /// Only regular MX mappacks have a time-to-live.
-    fn get_ttl(&self) -> Option<i64> {
-        self.has_ttl().then_some(crate::env().mappack_ttl.get())
+    /// Only regular MX mappacks and ephemeral mappacks have a time-to-live.
+    pub fn get_ttl(&self) -> Option<i64> {
+        match self {
+            Self::Event(..) => None,
+            Self::Id(_) => Some(crate::env().mappack_ttl.get()),
+            Self::Hashed(_) => Some(AD_HOC_MAPPACK_TTL as _),
+        }
     }
+
+    /// Returns the cache entry of the scores of the mappack, last computed at the provided UNIX
+    /// timestamp.
+    ///
+    /// The scores expire with the time-to-live of the mappack, if any.
+    pub fn scores_cache_entry(&self, last_computed: Option<u64>) -> CacheEntry {
+        let lifetime = self
+            .get_ttl()
+            .and_then(|ttl| u64::try_from(ttl).ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::MAX);
+        CacheEntry::from_timestamp(last_computed, lifetime)
+    }
+}
+
+/// Returns the UNIX timestamp, in seconds, of the last computation of the scores of the
+/// provided mappack.
+///
+/// It returns `None` if the scores were never computed, or if they expired.
+pub async fn last_computed(
+    redis_conn: &mut RedisConnection,
+    mappack: AnyMappackId<'_>,
+) -> RecordsResult<Option<u64>> {
+    let time = redis_conn.get(mappack_time_key(mappack)).await?;
+    Ok(time)
+}
+
+/// Returns whether the scores of a mappack, last computed at the provided UNIX timestamp, are
+/// older than the refresh interval at the time `now`.
+///
+/// Scores that were never computed are considered stale.
+pub fn is_stale(last_computed: Option<u64>, now: u64, refresh_interval: Duration) -> bool {
+    let Some(now) = i64::try_from(now)
+        .ok()
+        .and_then(|now| chrono::DateTime::from_timestamp(now, 0))
+    else {
+        return true;
+    };
+    CacheEntry::from_timestamp(last_computed, refresh_interval).has_expired_at(now.naive_utc())
 }
 
 /// Calculates the scores of the players on the provided mappack, and save the results
@@ -168,8 +309,9 @@ pub async fn update_mappack<C: TransactionTrait + Sync>(
     // Then save them to the Redis database for cache-handling
     save(mappack, scores, redis_pool).await?;
 
-    // And we save it to the registered mappacks set.
-    if mappack.has_ttl() {
+    // And we save it to the registered mappacks set. The ephemeral mappacks aren't
+    // registered, because they aren't meant to be refreshed.
+    if let AnyMappackId::Id(_) = mappack {
         let mut redis_conn = redis_pool.get().await?;
 
         // The mappack has a TTL, so its member will be removed from the set when
@@ -182,6 +324,53 @@ pub async fn update_mappack<C: TransactionTrait + Sync>(
     Ok(total_scores)
 }
 
+/// Calculates the scores of the players on the provided list of map UIDs, without requiring
+/// a saved mappack.
+///
+/// The result is cached in the Redis database for [`AD_HOC_MAPPACK_TTL`] seconds, under the
+/// key of the [`AnyMappackId::Hashed`] mappack of the map UIDs, so their order doesn't matter.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(conn, redis_pool), err))]
+pub async fn calc_ad_hoc_scores<C: TransactionTrait + Sync>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map_uids: &[String],
+) -> RecordsResult<AdHocMappackScores> {
+    let hash = AnyMappackId::hash_of(map_uids);
+    let key = mappack_scores_key(AnyMappackId::Hashed(&hash));
+
+    let mut redis_conn = redis_pool.get().await?;
+
+    let cached: Option<String> = redis_conn.get(&key).await?;
+    if let Some(scores) = cached.and_then(|cached| serde_json::from_str(&cached).ok()) {
+        return Ok(scores);
+    }
+
+    let scores = crate::assert_future_send(sync::transaction_with_config(
+        conn,
+        Some(sea_orm::IsolationLevel::RepeatableRead),
+        Some(sea_orm::AccessMode::ReadOnly),
+        async |txn| {
+            let mut mappack = Vec::with_capacity(map_uids.len());
+            for map_uid in map_uids.iter().collect::<BTreeSet<_>>() {
+                mappack.push(must::have_map(txn, map_uid).await?);
+            }
+
+            calc_maps_scores(txn, &mut redis_conn, &mappack, Default::default()).await
+        },
+    ))
+    .await?;
+
+    let scores = AdHocMappackScores::from(scores);
+
+    let serialized = serde_json::to_string(&scores)
+        .map_err(|e| internal!("Failed to serialize the ad-hoc mappack scores: {e}"))?;
+    let _: () = redis_conn
+        .set_ex(&key, serialized, AD_HOC_MAPPACK_TTL)
+        .await?;
+
+    Ok(scores)
+}
+
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(scores, redis_pool)))]
 async fn save(
     mappack: AnyMappackId<'_>,
@@ -239,11 +428,9 @@ async fn save(
 
         // --- Save the rank average
 
-        let rank_avg = ((score.score + f64::EPSILON) * 100.).round() / 100.;
-
         pipe.set_options(
             mappack_player_rank_avg_key(mappack, score.player_id),
-            rank_avg,
+            rank_avg(score.score),
             set_options,
         )
         .ignore();
@@ -338,9 +525,7 @@ async fn calc_scores<C: ConnectionTrait + StreamTrait>(
     let mappack_key = mappack_key(mappack);
     let mappack_uids: Vec<String> = redis_conn.smembers(&mappack_key).await?;
 
-    let mut maps = Vec::with_capacity(mappack_uids.len().max(5));
-
-    let mappack = if mappack_uids.is_empty() {
+    if mappack_uids.is_empty() {
         // If the mappack is empty, it means either that it's an invalid/unknown mappack ID,
         // or that its TTL has expired. So we remove its entry in the registered mappacks set.
         // The other keys related to this mappack were set with a TTL so they should
@@ -349,24 +534,49 @@ async fn calc_scores<C: ConnectionTrait + StreamTrait>(
             .srem(mappacks_key(), mappack.mappack_id())
             .await?;
         return Ok(None);
-    } else {
-        let mut out = Vec::with_capacity(mappack_uids.len());
-
-        for map_uid in &mappack_uids {
-            let map = must::have_map(conn, map_uid).await?;
-            maps.push(MappackMap {
-                map_id: map.game_id.clone(),
-                last_rank: 0,
-                records: None,
-            });
-            out.push(map);
-        }
-        out
-    };
+    }
 
-    let mut scores = Vec::<PlayerScore>::with_capacity(mappack.len());
+    let mut mappack = Vec::with_capacity(mappack_uids.len());
+    for map_uid in &mappack_uids {
+        mappack.push(must::have_map(conn, map_uid).await?);
+    }
 
-    let mut redis_conn = redis_pool.get().await?;
+    calc_maps_scores(conn, &mut redis_conn, &mappack, event)
+        .await
+        .map(Some)
+}
+
+/// Returns the ID of the player ranked first on the provided list of maps, with their scores
+/// calculated like for a mappack.
+///
+/// It returns `None` if no player finished any of the maps.
+pub(crate) async fn top_player_of<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    maps: &[maps::Model],
+    event: OptEvent<'_>,
+) -> RecordsResult<Option<u32>> {
+    let scores = calc_maps_scores(conn, redis_conn, maps, event).await?;
+    Ok(scores.scores.first().map(|score| score.player_id))
+}
+
+/// Calculates the scores of the players on the provided list of maps.
+async fn calc_maps_scores<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    mappack: &[maps::Model],
+    event: OptEvent<'_>,
+) -> RecordsResult<MappackScores> {
+    let mut maps = mappack
+        .iter()
+        .map(|map| MappackMap {
+            map_id: map.game_id.clone(),
+            last_rank: 0,
+            records: None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut scores = Vec::<PlayerScore>::with_capacity(mappack.len());
 
     for (i, map) in mappack.iter().enumerate() {
         let mut query = Query::select();
@@ -421,7 +631,7 @@ async fn calc_scores<C: ConnectionTrait + StreamTrait>(
             }
 
             let record = RankedRecordRow {
-                rank: ranks::get_rank(&mut redis_conn, map.id, record.record.time, event).await?,
+                rank: ranks::get_rank(redis_conn, map.id, record.record.time, event).await?,
                 record,
             };
             records.push(record);
@@ -511,5 +721,5 @@ async fn calc_scores<C: ConnectionTrait + StreamTrait>(
         old_rank = player.rank;
     }
 
-    Ok(Some(MappackScores { maps, scores }))
+    Ok(MappackScores { maps, scores })
 }
@@ -43,7 +43,7 @@ pub async fn have_event_edition_from_ids<C: ConnectionTrait>(
             internal!("have_event_edition_from_ids: Event with ID {event_id} must be in database")
         })?;
 
-    let edition = event_edition::Entity::find_by_id((event_id, edition_id)).one(conn).await?
+    let edition = event_edition::Entity::find_by_id((edition_id, event_id)).one(conn).await?
         .ok_or_else(|| internal!("Event edition with event ID {event_id} and edition ID {edition_id} must be in database"))?;
 
     Ok((event, edition))
@@ -84,6 +84,17 @@ pub async fn have_map<C: ConnectionTrait>(conn: &C, map_uid: &str) -> RecordsRes
         .ok_or_else(|| RecordsError::MapNotFound(map_uid.to_owned()))
 }
 
+/// Returns the map in the database bound to the provided map ID.
+pub async fn have_map_by_id<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+) -> RecordsResult<maps::Model> {
+    maps::Entity::find_by_id(map_id)
+        .one(conn)
+        .await?
+        .ok_or(RecordsError::MapIdNotFound(map_id))
+}
+
 /// Returns the event and its edition bound to their IDs and that contain a specific map.
 ///
 /// ## Parameters
@@ -100,6 +111,8 @@ pub async fn have_map<C: ConnectionTrait>(conn: &C, map_uid: &str) -> RecordsRes
 /// For example, for the Benchmark, if the given map UID is `X`, the returned map will be the one
 /// with the UID `X_benchmark`. If the given map UID is already `X_benchmark`, it will
 /// simply be the corresponding map.
+///
+/// The maps disabled in the edition aren't found, see [`event::get_map_in_edition`].
 pub async fn have_event_edition_with_map<C: ConnectionTrait>(
     conn: &C,
     map_uid: &str,
@@ -120,3 +133,32 @@ pub async fn have_event_edition_with_map<C: ConnectionTrait>(
 
     Ok((event, event_edition, map))
 }
+
+/// Returns the event and its edition bound to their IDs and that contain a specific map, even if
+/// it's disabled in the edition.
+///
+/// This is the same as [`have_event_edition_with_map`], but the caller must check the
+/// [`EventMap::is_disabled`][1] flag before saving a record on the map.
+///
+/// [1]: event::EventMap::is_disabled
+pub async fn have_event_edition_with_map_including_disabled<C: ConnectionTrait>(
+    conn: &C,
+    map_uid: &str,
+    event_handle: &str,
+    edition_id: u32,
+) -> RecordsResult<(event_entity::Model, event_edition::Model, event::EventMap)> {
+    let (event, event_edition) = have_event_edition(conn, event_handle, edition_id).await?;
+
+    let map =
+        event::get_map_in_edition_including_disabled(conn, map_uid, event.id, event_edition.id)
+            .await?
+            .ok_or_else(|| {
+                RecordsError::MapNotInEventEdition(
+                    map_uid.to_string(),
+                    event_handle.to_string(),
+                    event_edition.id,
+                )
+            })?;
+
+    Ok((event, event_edition, map))
+}
@@ -1,11 +1,20 @@
 //! This module contains anything related to in-game players in this library.
 
-use entity::{global_event_records, global_records, players};
-use sea_orm::{ColumnTrait as _, ConnectionTrait, EntityTrait as _, QueryFilter as _, QuerySelect};
+use std::collections::HashMap;
+
+use entity::{
+    banishments, global_event_records, global_records, maps, player_rating, players, players_ips,
+    records,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, ConnectionTrait, EntityTrait as _, FromQueryResult,
+    QueryFilter as _, QueryOrder as _, QuerySelect, TransactionTrait, prelude::DateTime,
+};
 
 use crate::error::RecordsResult;
 use crate::internal;
 use crate::opt_event::OptEvent;
+use crate::sync;
 
 /// Returns the time of a player on a map.
 pub async fn get_time_on_map<C: ConnectionTrait>(
@@ -48,6 +57,27 @@ pub async fn get_time_on_map<C: ConnectionTrait>(
     Ok(time)
 }
 
+/// Returns the sum of the best times of the player with the provided ID on all the maps where
+/// they have a record, in milliseconds.
+///
+/// The hidden records are ignored, and it returns 0 if the player doesn't have any record.
+pub async fn total_best_time<C: ConnectionTrait>(conn: &C, player_id: u32) -> RecordsResult<i64> {
+    let times: Vec<i32> = records::Entity::find()
+        .filter(
+            records::Column::RecordPlayerId
+                .eq(player_id)
+                .and(records::Column::IsHidden.eq(false)),
+        )
+        .group_by(records::Column::MapId)
+        .select_only()
+        .column_as(records::Column::Time.min(), "time")
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    Ok(times.into_iter().map(i64::from).sum())
+}
+
 /// Returns the optional player from the provided login.
 pub async fn get_player_from_login<C: ConnectionTrait>(
     conn: &C,
@@ -60,6 +90,28 @@ pub async fn get_player_from_login<C: ConnectionTrait>(
     Ok(player)
 }
 
+/// Returns the players with the provided logins, mapped by their login, with a single query.
+///
+/// The unknown logins are absent from the returned map.
+pub async fn resolve_logins<C: ConnectionTrait>(
+    conn: &C,
+    logins: &[&str],
+) -> RecordsResult<HashMap<String, players::Model>> {
+    if logins.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let players = players::Entity::find()
+        .filter(players::Column::Login.is_in(logins.iter().copied()))
+        .all(conn)
+        .await?;
+
+    Ok(players
+        .into_iter()
+        .map(|player| (player.login.clone(), player))
+        .collect())
+}
+
 /// Returns the player from the provided ID.
 ///
 /// The return of this function isn't optional as if an ID is provided, the player most likely
@@ -74,3 +126,224 @@ pub async fn get_player_from_id<C: ConnectionTrait>(
         .ok_or_else(|| internal!("Player with ID {player_id} not found in get_player_from_id - this should not happen as the player is expected to exist"))?;
     Ok(player)
 }
+
+/// The player row in a [`PlayerExport`].
+#[derive(Debug, serde::Serialize)]
+pub struct ExportedPlayer {
+    /// The player ID.
+    pub id: u32,
+    /// The player login.
+    pub login: String,
+    /// The player name, with its TrackMania format.
+    pub name: String,
+    /// The UTC date when the player joined.
+    pub join_date: Option<DateTime>,
+    /// The zone path of the player.
+    pub zone_path: Option<String>,
+    /// The note of the admins about the player.
+    pub admins_note: Option<String>,
+    /// The ID of the role of the player.
+    pub role: u8,
+    /// The score of the player in the player/map ranking.
+    pub score: f64,
+}
+
+impl From<players::Model> for ExportedPlayer {
+    fn from(player: players::Model) -> Self {
+        Self {
+            id: player.id,
+            login: player.login,
+            name: player.name,
+            join_date: player.join_date,
+            zone_path: player.zone_path,
+            admins_note: player.admins_note,
+            role: player.role,
+            score: player.score,
+        }
+    }
+}
+
+/// A record in a [`PlayerExport`].
+#[derive(Debug, serde::Serialize, FromQueryResult)]
+pub struct ExportedRecord {
+    /// The record ID.
+    pub record_id: u32,
+    /// The UID of the map.
+    pub map_uid: String,
+    /// The time in milliseconds of the run.
+    pub time: i32,
+    /// The amount of respawns.
+    pub respawn_count: i32,
+    /// The UTC date of the record.
+    pub record_date: DateTime,
+    /// The various flags of the run.
+    pub flags: u32,
+    /// The amount of tries.
+    pub try_count: Option<u32>,
+    /// Whether the record was hidden by a moderator.
+    pub is_hidden: bool,
+}
+
+/// A banishment in a [`PlayerExport`].
+///
+/// The author of the banishment isn't included, as it concerns another player.
+#[derive(Debug, serde::Serialize)]
+pub struct ExportedBan {
+    /// The UTC date of the banishment.
+    pub date_ban: DateTime,
+    /// The duration of the banishment in seconds, if it isn't permanent.
+    pub duration: Option<i64>,
+    /// Whether the player was already banned before.
+    pub was_reprieved: bool,
+    /// The reason of the banishment.
+    pub reason: String,
+}
+
+/// A map rating in a [`PlayerExport`].
+#[derive(Debug, serde::Serialize, FromQueryResult)]
+pub struct ExportedRating {
+    /// The UID of the rated map.
+    pub map_uid: String,
+    /// The ID of the rating kind.
+    pub kind: u8,
+    /// The value of the rating, between 0 and 1.
+    pub rating: f32,
+}
+
+/// A known IP address of the player in a [`PlayerExport`].
+#[derive(Debug, serde::Serialize)]
+pub struct ExportedIp {
+    /// The hexadecimal hash of the IP address. The address itself isn't stored.
+    pub ip_hash: String,
+    /// The UTC date when the IP address was saved.
+    pub ip_date: DateTime,
+}
+
+/// All the data related to a player, returned by the [`export_all`] function.
+#[derive(Debug, serde::Serialize)]
+pub struct PlayerExport {
+    /// The player row.
+    pub player: ExportedPlayer,
+    /// All the records of the player, including the hidden ones.
+    pub records: Vec<ExportedRecord>,
+    /// The banishments of the player.
+    pub bans: Vec<ExportedBan>,
+    /// The map ratings of the player.
+    pub ratings: Vec<ExportedRating>,
+    /// The known IP addresses of the player.
+    pub ips: Vec<ExportedIp>,
+}
+
+/// Returns all the data related to the player with the provided ID, for a data-subject request.
+///
+/// The data of the other players, like the authors of the banishments, isn't included.
+pub async fn export_all<C: ConnectionTrait>(
+    conn: &C,
+    player_id: u32,
+) -> RecordsResult<PlayerExport> {
+    let player = get_player_from_id(conn, player_id).await?;
+
+    let records = records::Entity::find()
+        .inner_join(maps::Entity)
+        .filter(records::Column::RecordPlayerId.eq(player_id))
+        .order_by_asc(records::Column::RecordDate)
+        .select_only()
+        .columns([
+            records::Column::RecordId,
+            records::Column::Time,
+            records::Column::RespawnCount,
+            records::Column::RecordDate,
+            records::Column::Flags,
+            records::Column::TryCount,
+            records::Column::IsHidden,
+        ])
+        .column_as(maps::Column::GameId, "map_uid")
+        .into_model()
+        .all(conn)
+        .await?;
+
+    let bans = banishments::Entity::find()
+        .filter(banishments::Column::PlayerId.eq(player_id))
+        .order_by_asc(banishments::Column::DateBan)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|ban| ExportedBan {
+            date_ban: ban.date_ban,
+            duration: ban.duration,
+            was_reprieved: ban.was_reprieved != 0,
+            reason: ban.reason,
+        })
+        .collect();
+
+    let ratings = player_rating::Entity::find()
+        .inner_join(maps::Entity)
+        .filter(player_rating::Column::PlayerId.eq(player_id))
+        .select_only()
+        .columns([player_rating::Column::Kind, player_rating::Column::Rating])
+        .column_as(maps::Column::GameId, "map_uid")
+        .into_model()
+        .all(conn)
+        .await?;
+
+    let ips = players_ips::Entity::find()
+        .filter(players_ips::Column::PlayerId.eq(player_id))
+        .order_by_asc(players_ips::Column::IpDate)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|ip| ExportedIp {
+            ip_hash: ip.ip_hash.iter().map(|b| format!("{b:02x}")).collect(),
+            ip_date: ip.ip_date,
+        })
+        .collect();
+
+    Ok(PlayerExport {
+        player: player.into(),
+        records,
+        bans,
+        ratings,
+        ips,
+    })
+}
+
+/// Returns the placeholder login of the player with the provided ID once anonymized.
+///
+/// It stays unique because it contains the ID of the player.
+pub fn anonymized_login(player_id: u32) -> String {
+    format!("anonymized_player_{player_id}")
+}
+
+/// Removes the personal data of the player with the provided ID, for a data-subject request.
+///
+/// The login and the name are replaced by placeholders, the zone path is cleared, and the known
+/// IP addresses are deleted. The records of the player are kept untouched, so the leaderboards
+/// don't change.
+///
+/// Everything happens in a single transaction. It returns the new login of the player.
+pub async fn anonymize<C: TransactionTrait>(conn: &C, player_id: u32) -> RecordsResult<String> {
+    sync::transaction(conn, async |txn| {
+        // Makes sure the player exists before updating anything
+        get_player_from_id(txn, player_id).await?;
+
+        let login = anonymized_login(player_id);
+
+        players::Entity::update(players::ActiveModel {
+            id: Set(player_id),
+            login: Set(login.clone()),
+            name: Set("Anonymized player".to_owned()),
+            zone_path: Set(None),
+            ..Default::default()
+        })
+        .exec(txn)
+        .await?;
+
+        players_ips::Entity::delete_many()
+            .filter(players_ips::Column::PlayerId.eq(player_id))
+            .exec(txn)
+            .await?;
+
+        Ok(login)
+    })
+    .await
+}
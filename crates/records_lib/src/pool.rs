@@ -1,6 +1,8 @@
 //! Contains types to represent database pools.
 
-use deadpool_redis::Runtime;
+use std::time::Duration;
+
+use deadpool_redis::{CreatePoolError, Hook, HookError, Runtime};
 use sea_orm::DbConn;
 
 use crate::RedisPool;
@@ -9,6 +11,10 @@ use crate::RedisPool;
 pub struct Database {
     /// The SQL database connection pool. This can also be a testing database [`DbConn::MockDatabaseConnection`].
     pub sql_conn: DbConn,
+    /// The optional connection pool to the read-replica of the SQL database.
+    ///
+    /// Use [`Database::read_conn`] to get the connection to use for read-only queries.
+    pub replica_sql_conn: Option<DbConn>,
     /// The Redis pool.
     pub redis_pool: RedisPool,
 }
@@ -30,25 +36,55 @@ const fn empty_query_results() -> std::iter::Empty<std::iter::Empty<sea_orm::Moc
     std::iter::empty::<std::iter::Empty<sea_orm::MockRow>>()
 }
 
+/// The default duration after which an idle Redis connection is considered stale.
+pub const DEFAULT_REDIS_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 impl Database {
     fn from_db_conn(
         db_conn: DbConn,
         redis_url: String,
+        redis_health_check_interval: Duration,
     ) -> Result<Self, deadpool_redis::CreatePoolError> {
-        let redis_pool = get_redis_pool(redis_url)?;
+        let redis_pool = get_redis_pool(redis_url, redis_health_check_interval)?;
         Ok(Self {
             sql_conn: db_conn,
+            replica_sql_conn: None,
             redis_pool,
         })
     }
 
     /// Returns the database from the URL to the SQL and Redis databases.
+    ///
+    /// If a URL to a read-replica is provided, the read-only queries made using
+    /// [`Database::read_conn`] are sent to it.
+    ///
+    /// See [`get_redis_pool`] for the meaning of the `redis_health_check_interval` parameter.
     pub async fn from_db_url(
         db_url: String,
+        replica_db_url: Option<String>,
         redis_url: String,
+        redis_health_check_interval: Duration,
     ) -> Result<Self, DatabaseCreationError> {
         let db_conn = sea_orm::Database::connect(db_url).await?;
-        Self::from_db_conn(db_conn, redis_url).map_err(From::from)
+        let replica_conn = match replica_db_url {
+            Some(url) => Some(sea_orm::Database::connect(url).await?),
+            None => None,
+        };
+        let mut db = Self::from_db_conn(db_conn, redis_url, redis_health_check_interval)?;
+        db.replica_sql_conn = replica_conn;
+        Ok(db)
+    }
+
+    /// Returns the SQL connection to use for read-only queries.
+    ///
+    /// This is the connection to the read-replica if there is one, otherwise the connection
+    /// to the primary database.
+    ///
+    /// It must not be used to synchronize the Redis leaderboards, like with
+    /// [`update_leaderboard`](crate::ranks::update_leaderboard), because they're shared with
+    /// the game API, and a lagging replica would rebuild them from stale rows.
+    pub fn read_conn(&self) -> &DbConn {
+        self.replica_sql_conn.as_ref().unwrap_or(&self.sql_conn)
     }
 
     /// Returns the database from the URL of the Redis database, and the backend of the SQL database,
@@ -71,7 +107,7 @@ impl Database {
             .append_query_results(query_results)
             .append_exec_results(exec_results)
             .into_connection();
-        Self::from_db_conn(db_conn, redis_url)
+        Self::from_db_conn(db_conn, redis_url, DEFAULT_REDIS_HEALTH_CHECK_INTERVAL)
     }
 
     /// Returns the database from the URL of the Redis database, and the backend of the SQL database,
@@ -154,16 +190,35 @@ impl Clone for Database {
         Self {
             redis_pool: self.redis_pool.clone(),
             sql_conn: clone_dbconn(&self.sql_conn),
+            replica_sql_conn: self.replica_sql_conn.as_ref().map(clone_dbconn),
         }
     }
 }
 
 /// Creates and returns the Redis pool with the provided URL.
-pub fn get_redis_pool(url: String) -> Result<RedisPool, deadpool_redis::CreatePoolError> {
+///
+/// The connections are checked with a `PING` when they're recycled. Additionally, the connections
+/// that weren't used for longer than `health_check_interval` are considered stale, as they might
+/// have been closed by the server. They're dropped when acquired, and replaced by new ones.
+pub fn get_redis_pool(
+    url: String,
+    health_check_interval: Duration,
+) -> Result<RedisPool, CreatePoolError> {
     let cfg = deadpool_redis::Config {
         url: Some(url),
         connection: None,
         pool: None,
     };
-    cfg.create_pool(Some(Runtime::Tokio1))
+    cfg.builder()
+        .map_err(CreatePoolError::Config)?
+        .runtime(Runtime::Tokio1)
+        .pre_recycle(Hook::sync_fn(move |_, metrics| {
+            if metrics.last_used() > health_check_interval {
+                Err(HookError::Message("stale Redis connection".into()))
+            } else {
+                Ok(())
+            }
+        }))
+        .build()
+        .map_err(CreatePoolError::Build)
 }
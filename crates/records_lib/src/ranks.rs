@@ -1,24 +1,47 @@
 //! Module which contains utility functions used to update maps leaderboards and get players ranks.
+//!
+//! The leaderboards are always sorted by ascending time: the best time of a player is their lowest
+//! one, and it's used as their score in the Redis sorted sets. The maps don't have any reversed
+//! mode where the highest time would be the best.
 
 use crate::{
-    RedisConnection, RedisPool, error::RecordsResult, opt_event::OptEvent, redis_key::map_key,
+    RedisConnection, RedisPool, error::RecordsResult, must, opt_event::OptEvent, redis_key::map_key,
 };
 use deadpool_redis::redis::{self, AsyncCommands};
-use entity::{event_edition_records, records};
+use entity::{current_bans, event_edition_records, records};
 use futures::TryStreamExt;
 use sea_orm::{
     ColumnTrait as _, ConnectionTrait, EntityTrait, Order, PaginatorTrait, QueryFilter as _,
     QueryOrder as _, QuerySelect, QueryTrait as _, SelectModel, Selector, StreamTrait,
-    sea_query::expr,
+    sea_query::{Func, Query, SelectStatement, expr},
 };
 
-async fn count_records_map<C: ConnectionTrait>(
+/// Returns the query selecting the IDs of the players who are currently banned.
+///
+/// The records of these players are excluded from the leaderboards and the ranks.
+pub fn banned_players_query() -> SelectStatement {
+    Query::select()
+        .column(current_bans::Column::PlayerId)
+        .from(current_bans::Entity)
+        .and_where(current_bans::Column::PlayerId.is_not_null())
+        .to_owned()
+}
+
+/// Returns the amount of players who have a record on the map with the provided ID.
+///
+/// The currently banned players aren't counted. This only uses the SQL database.
+pub async fn count_records_map<C: ConnectionTrait>(
     conn: &C,
     map_id: u32,
     event: OptEvent<'_>,
 ) -> RecordsResult<u64> {
     let query = records::Entity::find()
-        .filter(records::Column::MapId.eq(map_id))
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.not_in_subquery(banned_players_query())),
+        )
         .group_by(records::Column::RecordPlayerId);
 
     let query = match event.get() {
@@ -54,12 +77,199 @@ pub async fn update_leaderboard<C: ConnectionTrait + StreamTrait>(
     let redis_count: u64 = redis_conn.zcount(key, "-inf", "+inf").await?;
 
     if redis_count != mysql_count {
-        force_update_locked(conn, redis_pool, map_id, event).await?;
+        force_update(conn, redis_pool, map_id, event).await?;
     }
 
     Ok(mysql_count)
 }
 
+/// Ensures the leaderboards of all the maps on which the player with the provided ID has
+/// a record are cached in the Redis database.
+///
+/// This only concerns the leaderboards outside of any event. It returns the number of warmed maps.
+pub async fn warm_player<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<usize> {
+    let map_ids: Vec<u32> = records::Entity::find()
+        .filter(records::Column::RecordPlayerId.eq(player_id))
+        .select_only()
+        .column(records::Column::MapId)
+        .distinct()
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    for map_id in &map_ids {
+        update_leaderboard(conn, redis_pool, *map_id, Default::default()).await?;
+    }
+
+    Ok(map_ids.len())
+}
+
+/// Returns the best time of the player with the provided ID on each map where they have a
+/// record, outside of any event.
+///
+/// It's empty if the player is currently banned, because their records aren't ranked.
+async fn player_best_times<C: ConnectionTrait>(
+    conn: &C,
+    player_id: u32,
+) -> RecordsResult<Vec<(u32, i32)>> {
+    let times = records::Entity::find()
+        .filter(
+            records::Column::RecordPlayerId
+                .eq(player_id)
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.not_in_subquery(banned_players_query())),
+        )
+        .group_by(records::Column::MapId)
+        .select_only()
+        .column(records::Column::MapId)
+        .column_as(expr::Expr::col(records::Column::Time).min(), "time")
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    Ok(times)
+}
+
+/// The rank of a player on a map where they have a record.
+struct PlayerMapRank {
+    map_id: u32,
+    rank: i32,
+    /// The amount of players in the leaderboard of the map.
+    count: u64,
+}
+
+/// Returns the rank of the player with the provided ID on each map where they have a record,
+/// outside of any event.
+///
+/// The leaderboards of the maps are updated if needed. It's empty if the player is currently
+/// banned, and the maps with an empty leaderboard are skipped.
+async fn player_map_ranks<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Vec<PlayerMapRank>> {
+    let times = player_best_times(conn, player_id).await?;
+
+    let mut ranked_times = Vec::with_capacity(times.len());
+    let mut counts = Vec::with_capacity(times.len());
+
+    for (map_id, time) in times {
+        let count = update_leaderboard(conn, redis_pool, map_id, Default::default()).await?;
+        if count > 0 {
+            ranked_times.push((map_id, time));
+            counts.push(count);
+        }
+    }
+
+    let mut redis_conn = redis_pool.get().await?;
+    let ranks = get_ranks(&mut redis_conn, &ranked_times, Default::default()).await?;
+
+    Ok(ranked_times
+        .into_iter()
+        .zip(counts)
+        .zip(ranks)
+        .map(|(((map_id, _), count), rank)| PlayerMapRank {
+            map_id,
+            rank,
+            count,
+        })
+        .collect())
+}
+
+/// Returns the overall percentile of the player with the provided ID, or `None` if they don't
+/// have any record.
+///
+/// The percentile of the player on a map is the percentage of the players of its leaderboard
+/// who are ranked at or below them, so it's 100 if they have the best time. The overall
+/// percentile is the average of their percentiles on all the maps where they have a record.
+///
+/// This only concerns the leaderboards outside of any event, which are updated if needed.
+pub async fn player_percentile<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Option<f64>> {
+    let ranks = player_map_ranks(conn, redis_pool, player_id).await?;
+
+    let percentiles_sum = ranks
+        .iter()
+        .map(|rank| (rank.count as f64 - rank.rank as f64 + 1.) / rank.count as f64 * 100.)
+        .sum::<f64>();
+
+    Ok((!ranks.is_empty()).then(|| percentiles_sum / ranks.len() as f64))
+}
+
+/// Returns the ID of the map where the player with the provided ID holds their best rank, with
+/// this rank, or `None` if they don't have any record.
+///
+/// If the player has the same rank on several maps, the map with the lowest ID is returned.
+///
+/// This only concerns the leaderboards outside of any event, which are updated if needed.
+pub async fn best_rank_achieved<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Option<(u32, i32)>> {
+    let best = player_map_ranks(conn, redis_pool, player_id)
+        .await?
+        .into_iter()
+        .min_by_key(|rank| (rank.rank, rank.map_id))
+        .map(|rank| (rank.map_id, rank.rank));
+
+    Ok(best)
+}
+
+/// Returns the median of the ranks of the player with the provided ID on all the maps where they
+/// have a record, or `None` if they don't have any record.
+///
+/// If the player has a record on an even amount of maps, the better of the two middle ranks is
+/// returned.
+///
+/// This only concerns the leaderboards outside of any event, which are updated if needed.
+pub async fn median_rank<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Option<i32>> {
+    let mut ranks = player_map_ranks(conn, redis_pool, player_id)
+        .await?
+        .into_iter()
+        .map(|rank| rank.rank)
+        .collect::<Vec<_>>();
+
+    ranks.sort_unstable();
+
+    Ok((!ranks.is_empty()).then(|| ranks[(ranks.len() - 1) / 2]))
+}
+
+/// Returns the IDs of the maps where the player with the provided ID holds the world record,
+/// sorted in ascending order.
+///
+/// A player holds the world record of a map when they're ranked first on it, so several players
+/// can hold it in case of equality.
+///
+/// This only concerns the leaderboards outside of any event, which are updated if needed.
+pub async fn world_records_of<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    player_id: u32,
+) -> RecordsResult<Vec<u32>> {
+    let mut map_ids = player_map_ranks(conn, redis_pool, player_id)
+        .await?
+        .into_iter()
+        .filter(|rank| rank.rank == 1)
+        .map(|rank| rank.map_id)
+        .collect::<Vec<_>>();
+
+    map_ids.sort_unstable();
+
+    Ok(map_ids)
+}
+
 /// A leaderboard row.
 #[derive(Debug, sea_orm::FromQueryResult)]
 pub struct DbLeaderboardItem {
@@ -74,7 +284,12 @@ fn get_mariadb_lb_query(
     event: OptEvent<'_>,
 ) -> Selector<SelectModel<DbLeaderboardItem>> {
     records::Entity::find()
-        .filter(records::Column::MapId.eq(map_id))
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.not_in_subquery(banned_players_query())),
+        )
         .group_by(records::Column::RecordPlayerId)
         .order_by(records::Column::Time.min(), Order::Asc)
         .order_by(records::Column::RecordPlayerId, Order::Asc)
@@ -91,7 +306,9 @@ fn get_mariadb_lb_query(
         .into_model()
 }
 
-async fn force_update_locked<C: ConnectionTrait + StreamTrait>(
+/// Regenerates the Redis leaderboard of the map with the provided ID completely from
+/// the SQL database.
+pub async fn force_update<C: ConnectionTrait + StreamTrait>(
     conn: &C,
     redis_pool: &RedisPool,
     map_id: u32,
@@ -120,6 +337,65 @@ async fn force_update_locked<C: ConnectionTrait + StreamTrait>(
     Ok(())
 }
 
+/// Hides or unhides the record with the provided ID, and regenerates the leaderboards
+/// it belongs to.
+///
+/// Hidden records are kept in the database, but they don't count toward the leaderboards and
+/// ranks. The records cloned from this record for the original maps of an event are also
+/// affected.
+///
+/// It returns `false` if the record doesn't exist.
+pub async fn set_record_hidden<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    record_id: u32,
+    hidden: bool,
+) -> RecordsResult<bool> {
+    let condition = records::Column::RecordId
+        .eq(record_id)
+        .or(records::Column::EventRecordId.eq(record_id));
+
+    let affected_records = records::Entity::find()
+        .filter(condition.clone())
+        .all(conn)
+        .await?;
+
+    if affected_records.is_empty() {
+        return Ok(false);
+    }
+
+    records::Entity::update_many()
+        .col_expr(records::Column::IsHidden, expr::Expr::value(hidden))
+        .filter(condition)
+        .exec(conn)
+        .await?;
+
+    for record in affected_records {
+        force_update(conn, redis_pool, record.map_id, Default::default()).await?;
+
+        let event_record = event_edition_records::Entity::find_by_id(record.record_id)
+            .one(conn)
+            .await?;
+        if let Some(event_record) = event_record {
+            let (event, edition) = must::have_event_edition_from_ids(
+                conn,
+                event_record.event_id,
+                event_record.edition_id,
+            )
+            .await?;
+            force_update(
+                conn,
+                redis_pool,
+                record.map_id,
+                OptEvent::new(&event, &edition),
+            )
+            .await?;
+        }
+    }
+
+    Ok(true)
+}
+
 /// Gets the rank of the time of a player on a map.
 ///
 /// ## Example
@@ -142,3 +418,122 @@ pub async fn get_rank(
     let count: i32 = redis_conn.zcount(key, "-inf", time - 1).await?;
     Ok(count + 1)
 }
+
+/// Gets the ranks of many times at once, each one on the map with the provided ID.
+///
+/// This is like calling [`get_rank`] for each time, but with a single round trip to the Redis
+/// database. The ranks are returned in the same order as the provided times.
+pub async fn get_ranks(
+    redis_conn: &mut RedisConnection,
+    times: &[(u32, i32)],
+    event: OptEvent<'_>,
+) -> RecordsResult<Vec<i32>> {
+    if times.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut pipe = redis::pipe();
+    for (map_id, time) in times {
+        pipe.zcount(map_key(*map_id, event), "-inf", time - 1);
+    }
+
+    let counts: Vec<i32> = pipe.query_async(redis_conn).await?;
+    Ok(counts.into_iter().map(|count| count + 1).collect())
+}
+
+/// Returns the rank the provided time would achieve on the map with the provided ID, without
+/// saving it.
+///
+/// The leaderboard of the map is updated if needed. A time equal to an existing one shares its
+/// rank.
+pub async fn hypothetical_rank<C: ConnectionTrait + StreamTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map_id: u32,
+    time: i32,
+    event: OptEvent<'_>,
+) -> RecordsResult<i32> {
+    update_leaderboard(conn, redis_pool, map_id, event).await?;
+    let mut redis_conn = redis_pool.get().await?;
+    get_rank(&mut redis_conn, map_id, time, event).await
+}
+
+/// Gets the rank of the time of a player on a map, only by using the SQL database.
+///
+/// This is slower than [`get_rank`], and should only be used if the Redis database is unavailable.
+pub async fn get_rank_from_db<C: ConnectionTrait>(
+    conn: &C,
+    map_id: u32,
+    time: i32,
+    event: OptEvent<'_>,
+) -> RecordsResult<i32> {
+    let count: Option<i64> = records::Entity::find()
+        .filter(
+            records::Column::MapId
+                .eq(map_id)
+                .and(records::Column::Time.lt(time))
+                .and(records::Column::IsHidden.eq(false))
+                .and(records::Column::RecordPlayerId.not_in_subquery(banned_players_query())),
+        )
+        .apply_if(event.get(), |query, (ev, ed)| {
+            query.reverse_join(event_edition_records::Entity).filter(
+                event_edition_records::Column::EventId
+                    .eq(ev.id)
+                    .and(event_edition_records::Column::EditionId.eq(ed.id)),
+            )
+        })
+        .select_only()
+        .expr(Func::count_distinct(expr::Expr::col((
+            records::Entity,
+            records::Column::RecordPlayerId,
+        ))))
+        .into_tuple()
+        .one(conn)
+        .await?;
+
+    Ok(count.unwrap_or_default() as i32 + 1)
+}
+
+/// The rank of a time on a map, returned by the [`get_rank_or_fallback`] function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rank {
+    /// The rank itself.
+    pub rank: i32,
+    /// Whether the rank was computed from the SQL database, because Redis was unavailable.
+    pub degraded: bool,
+}
+
+/// Gets the rank of the time of a player on a map.
+///
+/// If the Redis database is unavailable, the rank is computed from the SQL database instead, and
+/// the returned rank is flagged as degraded.
+pub async fn get_rank_or_fallback<C: ConnectionTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    map_id: u32,
+    time: i32,
+    event: OptEvent<'_>,
+) -> RecordsResult<Rank> {
+    let result = async {
+        let mut redis_conn = redis_pool.get().await?;
+        get_rank(&mut redis_conn, map_id, time, event).await
+    }
+    .await;
+
+    match result {
+        Ok(rank) => Ok(Rank {
+            rank,
+            degraded: false,
+        }),
+        Err(e) if e.is_redis_unavailable() => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Redis unavailable, computing rank from SQL database: {e}");
+            let rank = get_rank_from_db(conn, map_id, time, event).await?;
+            Ok(Rank {
+                rank,
+                degraded: true,
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
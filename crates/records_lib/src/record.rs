@@ -0,0 +1,63 @@
+//! This module contains anything related to the records of the players in this library.
+
+use entity::{event_edition_records, maps, players, records};
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait, EntityTrait as _, QueryFilter as _, QuerySelect as _,
+};
+
+use crate::{error::RecordsResult, internal};
+
+/// Returns the record with the provided ID, along with its map and its player.
+///
+/// Unlike fetching them one after another, this only does a single query to the database.
+pub async fn get_full<C: ConnectionTrait>(
+    conn: &C,
+    record_id: u32,
+) -> RecordsResult<Option<(records::Model, maps::Model, players::Model)>> {
+    let result = records::Entity::find_by_id(record_id)
+        .inner_join(maps::Entity)
+        .inner_join(players::Entity)
+        .select_also(maps::Entity)
+        .select_also(players::Entity)
+        .one(conn)
+        .await?;
+
+    let Some((record, map, player)) = result else {
+        return Ok(None);
+    };
+
+    let map = map.ok_or_else(|| {
+        internal!(
+            "Map with ID {} should be joined to record {record_id}",
+            record.map_id
+        )
+    })?;
+    let player = player.ok_or_else(|| {
+        internal!(
+            "Player with ID {} should be joined to record {record_id}",
+            record.record_player_id
+        )
+    })?;
+
+    Ok(Some((record, map, player)))
+}
+
+/// Returns the event editions the record with the provided ID counts toward, as pairs of event
+/// ID and edition ID.
+pub async fn editions_of<C: ConnectionTrait>(
+    conn: &C,
+    record_id: u32,
+) -> RecordsResult<Vec<(u32, u32)>> {
+    let editions = event_edition_records::Entity::find()
+        .filter(event_edition_records::Column::RecordId.eq(record_id))
+        .select_only()
+        .columns([
+            event_edition_records::Column::EventId,
+            event_edition_records::Column::EditionId,
+        ])
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    Ok(editions)
+}
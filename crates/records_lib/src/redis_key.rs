@@ -13,13 +13,51 @@ use deadpool_redis::redis::{RedisWrite, ToRedisArgs};
 
 use crate::{mappack::AnyMappackId, opt_event::OptEvent};
 
-const V3_KEY_PREFIX: &str = "v3";
+/// The version of the schema of the Redis keys.
+///
+/// All the keys are prefixed by it, so bumping it invalidates all the cached values at once.
+pub const KEY_VERSION: u32 = 3;
+
+/// The prefix of the keys of a version of their schema, e.g. `v3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPrefix(pub u32);
+
+impl fmt::Display for KeyPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl KeyPrefix {
+    /// Returns the key with the provided name, in the namespace of this version.
+    pub fn key<N: fmt::Display>(self, name: N) -> String {
+        format!("{self}:{name}")
+    }
+
+    /// Returns the glob-style pattern matching all the keys of this version.
+    pub fn pattern(self) -> String {
+        self.key("*")
+    }
+}
+
+const KEY_PREFIX: KeyPrefix = KeyPrefix(KEY_VERSION);
+
+/// Returns the key with the provided name, in the namespace of the current version.
+pub fn versioned_key<N: fmt::Display>(name: N) -> String {
+    KEY_PREFIX.key(name)
+}
+
+/// Returns the prefixes of the versions prior to the current one, whose keys are obsolete.
+pub fn prior_prefixes() -> impl Iterator<Item = KeyPrefix> {
+    (0..KEY_VERSION).map(KeyPrefix)
+}
 
 const V3_MAPPACK_KEY_PREFIX: &str = "mappack";
 
 const V3_MAP_KEY_PREFIX: &str = "lb";
 
 const V3_EVENT_KEY_PREFIX: &str = "event";
+const V3_EVENT_SUMMARY_SENT: &str = "summary_sent";
 
 const V3_TOKEN_KEY_PREFIX: &str = "token";
 const V3_TOKEN_WEB_KEY_PREFIX: &str = "web";
@@ -35,6 +73,7 @@ const V3_MAPPACK_LB_RANK_AVG: &str = "rank_avg";
 const V3_MAPPACK_LB_MAP_FINISHED: &str = "map_finished";
 const V3_MAPPACK_LB_WORST_RANK: &str = "worst_rank";
 const V3_MAPPACK_LB_RANKS: &str = "ranks";
+const V3_MAPPACK_SCORES: &str = "scores";
 
 const V3_MAPPACK_MAP_KEY_PREFIX: &str = "map";
 const V3_MAPPACK_MAP_LAST_RANK: &str = "last_rank";
@@ -43,6 +82,29 @@ const V3_CACHED: &str = "cached";
 
 const V3_PLAYER_RANKING: &str = "player_ranking";
 const V3_MAP_RANKING: &str = "map_ranking";
+const V3_PLAYER_RANKING_MAP: &str = "map";
+const V3_PLAYER_RANKING_MAP_STATS: &str = "map_stats";
+const V3_PLAYER_RANKING_LAST_UPDATE: &str = "last_update";
+const V3_PLAYER_RANKING_CHUNK: &str = "chunk";
+const V3_PLAYER_RANKING_CHUNK_OFFSET: &str = "offset";
+const V3_PLAYER_RANKING_CHUNK_STARTED_AT: &str = "started_at";
+const V3_PLAYER_RANKING_CHUNK_SCORES: &str = "scores";
+
+/// Returns the prefixes of the namespaces of the keys, e.g. `v3:mappack`.
+///
+/// This is used to inspect the keys of a namespace.
+pub fn namespaces() -> [String; 7] {
+    [
+        V3_MAPPACK_KEY_PREFIX,
+        V3_MAP_KEY_PREFIX,
+        V3_EVENT_KEY_PREFIX,
+        V3_TOKEN_KEY_PREFIX,
+        V3_CACHED,
+        V3_PLAYER_RANKING,
+        V3_MAP_RANKING,
+    ]
+    .map(versioned_key)
+}
 
 macro_rules! create_key {
     (
@@ -96,12 +158,12 @@ create_key! {
         /// The cached key name.
         key_name: String,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_CACHED}:{}", self.key_name)
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_CACHED}:{}", self.key_name)
 }
 
 create_key! {
     struct MappacksKey = mappacks_key;;
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}")
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}")
 }
 
 create_key! {
@@ -113,7 +175,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}",
         self.mappack.mappack_id()
     )
 }
@@ -128,7 +190,7 @@ create_key! {
         /// The ID of the map.
         map_id: u32,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAP_KEY_PREFIX}:{}", self.map_id)
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAP_KEY_PREFIX}:{}", self.map_id)
 }
 
 /// The `EventMapKey` Redis key.
@@ -166,12 +228,28 @@ impl fmt::Display for EventMapKey<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{V3_KEY_PREFIX}:{V3_EVENT_KEY_PREFIX}:{}:{}:{}",
+            "{KEY_PREFIX}:{V3_EVENT_KEY_PREFIX}:{}:{}:{}",
             self.event_handle, self.edition_id, self.map_key
         )
     }
 }
 
+create_key! {
+    ///
+    /// This key is set once the summary of an expired event edition was sent.
+    struct EventEditionSummaryKey<'a => '_> = event_edition_summary_key {
+        /// The event handle.
+        event_handle: &'a str,
+        /// The edition ID.
+        edition_id: u32,
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_EVENT_KEY_PREFIX}:{}:{}:{V3_EVENT_SUMMARY_SENT}",
+        self.event_handle, self.edition_id
+    )
+}
+
 /// The `MapKey` Redis key.
 ///
 /// This is a generic version of the [`AloneMapKey`] and [`EventMapKey`] structs.
@@ -258,7 +336,7 @@ impl fmt::Display for TokenKey<'_, { token_kind::WEB }> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{V3_KEY_PREFIX}:{V3_TOKEN_KEY_PREFIX}:{V3_TOKEN_WEB_KEY_PREFIX}:{}",
+            "{KEY_PREFIX}:{V3_TOKEN_KEY_PREFIX}:{V3_TOKEN_WEB_KEY_PREFIX}:{}",
             self.login
         )
     }
@@ -268,7 +346,7 @@ impl fmt::Display for TokenKey<'_, { token_kind::MP }> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{V3_KEY_PREFIX}:{V3_TOKEN_KEY_PREFIX}:{V3_TOKEN_MP_KEY_PREFIX}:{}",
+            "{KEY_PREFIX}:{V3_TOKEN_KEY_PREFIX}:{V3_TOKEN_MP_KEY_PREFIX}:{}",
             self.login
         )
     }
@@ -281,7 +359,18 @@ create_key! {
         /// The mappack.
         mappack: AnyMappackId<'a>,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_TIME}", self.mappack.mappack_id())
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_TIME}", self.mappack.mappack_id())
+}
+
+create_key! {
+    ///
+    /// This key points to the serialized scores of an ephemeral mappack, computed without
+    /// saving them in the other mappack keys.
+    struct MappackScoresKey<'a => '_> = mappack_scores_key {
+        /// The mappack.
+        mappack: AnyMappackId<'a>,
+    }
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_SCORES}", self.mappack.mappack_id())
 }
 
 create_key! {
@@ -293,7 +382,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_NB_MAP}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_NB_MAP}",
         self.mappack.mappack_id()
     )
 }
@@ -306,7 +395,7 @@ create_key! {
         /// The mappack.
         mappack: AnyMappackId<'a>,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_USERNAME}",
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_USERNAME}",
         self.mappack.mappack_id()
     )
 }
@@ -319,7 +408,7 @@ create_key! {
         /// The mappack.
         mappack: AnyMappackId<'a>,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_NAME}",
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_NAME}",
         self.mappack.mappack_id()
     )
 }
@@ -332,7 +421,7 @@ create_key! {
         /// The mappack.
         mappack: AnyMappackId<'a>,
     }
-    |self, f| write!(f, "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_CREATED}",
+    |self, f| write!(f, "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MX_CREATED}",
         self.mappack.mappack_id()
     )
 }
@@ -346,7 +435,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}",
         self.mappack.mappack_id()
     )
 }
@@ -362,7 +451,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_RANK_AVG}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_RANK_AVG}",
         self.mappack.mappack_id(), self.player_id
     )
 }
@@ -379,7 +468,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_MAP_FINISHED}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_MAP_FINISHED}",
         self.mappack.mappack_id(), self.player_id
     )
 }
@@ -395,7 +484,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_WORST_RANK}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_WORST_RANK}",
         self.mappack.mappack_id(), self.player_id
     )
 }
@@ -412,7 +501,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_RANKS}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_LB}:{}:{V3_MAPPACK_LB_RANKS}",
         self.mappack.mappack_id(), self.player_id
     )
 }
@@ -428,7 +517,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MAP_KEY_PREFIX}:{}:{V3_MAPPACK_MAP_LAST_RANK}",
+        "{KEY_PREFIX}:{V3_MAPPACK_KEY_PREFIX}:{}:{V3_MAPPACK_MAP_KEY_PREFIX}:{}:{V3_MAPPACK_MAP_LAST_RANK}",
         self.mappack.mappack_id(), self.map_uid
     )
 }
@@ -440,7 +529,7 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_PLAYER_RANKING}",
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}",
     )
 }
 
@@ -451,6 +540,142 @@ create_key! {
     }
     |self, f| write!(
         f,
-        "{V3_KEY_PREFIX}:{V3_MAP_RANKING}",
+        "{KEY_PREFIX}:{V3_MAP_RANKING}",
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the ZSET of the scores of the players on the provided map, used to
+    /// update the player ranking incrementally.
+    struct PlayerMapRanking = player_map_ranking {
+        /// The map ID.
+        map_id: u32,
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_MAP}:{}",
+        self.map_id
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the HASH of the cached stats of the records of the provided map,
+    /// along with the fingerprint of these records.
+    struct PlayerRankingMapStats = player_ranking_map_stats {
+        /// The map ID.
+        map_id: u32,
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_MAP_STATS}:{}",
+        self.map_id
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the UNIX timestamp of the last update of the player ranking.
+    struct PlayerRankingLastUpdate = player_ranking_last_update {
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_LAST_UPDATE}",
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the amount of maps already processed by the player ranking computation
+    /// split across several cycles.
+    struct PlayerRankingChunkOffset = player_ranking_chunk_offset {
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_CHUNK}:{V3_PLAYER_RANKING_CHUNK_OFFSET}",
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the UNIX timestamp of the start of the player ranking computation
+    /// split across several cycles.
+    struct PlayerRankingChunkStartedAt = player_ranking_chunk_started_at {
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_CHUNK}:{V3_PLAYER_RANKING_CHUNK_STARTED_AT}",
+    )
+}
+
+create_key! {
+    ///
+    /// This key points to the ZSET of the scores of the players summed over the maps already
+    /// processed by the player ranking computation split across several cycles.
+    struct PlayerRankingChunkScores = player_ranking_chunk_scores {
+    }
+    |self, f| write!(
+        f,
+        "{KEY_PREFIX}:{V3_PLAYER_RANKING}:{V3_PLAYER_RANKING_CHUNK}:{V3_PLAYER_RANKING_CHUNK_SCORES}",
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::mappack::AnyMappackId;
+
+    use super::{KEY_VERSION, KeyPrefix, mappack_key, mappack_scores_key, prior_prefixes};
+
+    fn uids(uids: &[&str]) -> Vec<String> {
+        uids.iter().map(|uid| (*uid).to_owned()).collect()
+    }
+
+    #[test]
+    fn hashed_mappack_keys() {
+        let first = AnyMappackId::hash_of(&uids(&["map_1_uid", "map_2_uid"]));
+        let same = AnyMappackId::hash_of(&uids(&["map_2_uid", "map_1_uid", "map_1_uid"]));
+        let other = AnyMappackId::hash_of(&uids(&["map_1_uid", "map_3_uid"]));
+
+        let key = |hash| mappack_key(AnyMappackId::Hashed(hash)).to_string();
+
+        // The same set of maps produces a stable key
+        assert_eq!(key(&first), key(&same));
+        assert_eq!(
+            key(&first),
+            format!("v3:mappack:ad_hoc:{first}"),
+            "unexpected key namespace"
+        );
+        // A different set of maps produces a different key
+        assert_ne!(key(&first), key(&other));
+
+        // The keys don't collide with the ones of a regular mappack
+        assert_ne!(
+            mappack_scores_key(AnyMappackId::Hashed(&first)).to_string(),
+            mappack_scores_key(AnyMappackId::Id(&first)).to_string()
+        );
+    }
+
+    #[test]
+    fn versioned_namespaces_dont_overlap() {
+        // Matches a key against a glob-style pattern only made of a prefix followed by `*`
+        let matches = |pattern: &str, key: &str| key.starts_with(pattern.trim_end_matches('*'));
+
+        for (first, second) in [(3, 4), (1, 10)] {
+            let (first, second) = (KeyPrefix(first), KeyPrefix(second));
+            let first_key = first.key("mappack:1");
+            let second_key = second.key("mappack:1");
+
+            assert_ne!(first_key, second_key);
+            assert!(matches(&first.pattern(), &first_key));
+            assert!(!matches(&first.pattern(), &second_key));
+            assert!(!matches(&second.pattern(), &first_key));
+        }
+
+        // The keys are built in the namespace of the current version, which isn't cleared with
+        // the prior versions
+        let key = mappack_key(AnyMappackId::Id("1")).to_string();
+        assert!(matches(&KeyPrefix(KEY_VERSION).pattern(), &key));
+        assert!(prior_prefixes().all(|prefix| !matches(&prefix.pattern(), &key)));
+    }
+}
@@ -57,3 +57,62 @@ where
 {
     transaction_with_config(conn, None, None, f).await
 }
+
+/// The result of a [`savepoint`] call.
+#[derive(Debug)]
+pub enum SavepointResult<T, E> {
+    /// The function succeeded, and the savepoint was released.
+    Released(T),
+    /// The function returned an error, and the changes it made were rolled back.
+    RolledBack(E),
+}
+
+impl<T, E> SavepointResult<T, E> {
+    /// Returns whether the savepoint was rolled back.
+    pub fn is_rolled_back(&self) -> bool {
+        matches!(self, Self::RolledBack(_))
+    }
+
+    /// Converts this result into a regular [`Result`].
+    pub fn into_result(self) -> Result<T, E> {
+        match self {
+            Self::Released(t) => Ok(t),
+            Self::RolledBack(e) => Err(e),
+        }
+    }
+}
+
+/// Wraps the call of the provided function with an SQL savepoint.
+///
+/// If `conn` is already a transaction, this issues a `SAVEPOINT` before calling the function,
+/// then either releases it, or rolls back to it if the function returned an error. This way,
+/// the function can fail without aborting the enclosing transaction. Otherwise, this behaves
+/// like [`transaction`].
+///
+/// Unlike [`transaction`], the error of the function is not propagated, but returned in the
+/// [`SavepointResult::RolledBack`] variant. The returned [`DbErr`] is only about the savepoint
+/// management.
+///
+/// ## Arguments
+///
+/// * `conn`: the connection to the database, which is forwarded to the provided function
+///   as a nested transaction.
+/// * `f`: the function itself.
+pub async fn savepoint<F, C, T, E>(conn: &C, f: F) -> Result<SavepointResult<T, E>, DbErr>
+where
+    F: for<'a> AsyncFnOnce(&'a DatabaseTransaction) -> Result<T, E>,
+    C: TransactionTrait,
+{
+    let txn = conn.begin().await?;
+
+    match f(&txn).await {
+        Ok(ret) => {
+            txn.commit().await?;
+            Ok(SavepointResult::Released(ret))
+        }
+        Err(e) => {
+            txn.rollback().await?;
+            Ok(SavepointResult::RolledBack(e))
+        }
+    }
+}
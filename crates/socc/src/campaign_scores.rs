@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use deadpool_redis::redis::{self, AsyncCommands};
 use records_lib::{
     Database, RedisPool, event,
@@ -7,6 +9,8 @@ use records_lib::{
 };
 use sea_orm::{ConnectionTrait, StreamTrait, TransactionTrait};
 
+use crate::progress::{self, Progress};
+
 #[tracing::instrument(skip(conn, redis_pool), fields(mappack = %mappack.mappack_id()))]
 async fn update_mappack<C: TransactionTrait + Sync>(
     conn: &C,
@@ -23,45 +27,52 @@ async fn update_event_mappacks<C>(conn: &C, redis_pool: &RedisPool) -> anyhow::R
 where
     C: ConnectionTrait + StreamTrait + TransactionTrait + Sync,
 {
+    let mut editions = Vec::new();
     for event in event::event_list(conn, true).await? {
         for edition in event::event_editions_list(conn, &event.handle).await? {
-            tracing::info!(
-                "Got event edition ({}:{}) {:?}",
-                edition.event_id,
-                edition.id,
-                edition.name
-            );
-
-            let mappack = AnyMappackId::Event(&event.event, &edition);
-
-            let mut pipe = redis::pipe();
-            pipe.atomic();
-
-            pipe.del(mappack_key(mappack));
-
-            for map in event::event_edition_maps(conn, event.event.id, edition.id).await? {
-                pipe.sadd(mappack_key(mappack), map.game_id);
-            }
-
-            {
-                let mut redis_conn = redis_pool.get().await?;
-                pipe.exec_async(&mut redis_conn).await?;
-            }
-
-            update_mappack(
-                conn,
-                redis_pool,
-                mappack,
-                OptEvent::new(&event.event, &edition),
-            )
-            .await?;
+            editions.push((event.event.clone(), edition));
         }
     }
 
+    let mut progress = Progress::new("event editions", editions.len());
+
+    for (event, edition) in &editions {
+        tracing::info!(
+            "Got event edition ({}:{}) {:?}",
+            edition.event_id,
+            edition.id,
+            edition.name
+        );
+
+        let mappack = AnyMappackId::Event(event, edition);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+
+        pipe.del(mappack_key(mappack));
+
+        for map in event::event_edition_maps(conn, event.id, edition.id).await? {
+            pipe.sadd(mappack_key(mappack), map.game_id);
+        }
+
+        {
+            let mut redis_conn = redis_pool.get().await?;
+            pipe.exec_async(&mut redis_conn).await?;
+        }
+
+        update_mappack(conn, redis_pool, mappack, OptEvent::new(event, edition)).await?;
+
+        progress.inc();
+    }
+
+    progress.finish();
+
     Ok(())
 }
 
 pub async fn update(db: Database) -> anyhow::Result<()> {
+    let start = Instant::now();
+
     update_event_mappacks(&db.sql_conn, &db.redis_pool).await?;
 
     let mappacks: Vec<String> = {
@@ -69,6 +80,8 @@ pub async fn update(db: Database) -> anyhow::Result<()> {
         redis_conn.smembers(mappacks_key()).await?
     };
 
+    let mut progress = Progress::new("mappacks", mappacks.len());
+
     for mappack_id in mappacks {
         update_mappack(
             &db.sql_conn,
@@ -77,9 +90,16 @@ pub async fn update(db: Database) -> anyhow::Result<()> {
             Default::default(),
         )
         .await?;
+
+        progress.inc();
     }
 
-    tracing::info!("End");
+    progress.finish();
+
+    tracing::info!(
+        "Campaign scores updated in {}",
+        progress::elapsed_since(start)
+    );
 
     Ok(())
 }
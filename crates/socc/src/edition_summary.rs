@@ -0,0 +1,428 @@
+//! The summary of the event editions, posted on Discord once they expire.
+
+use std::future::Future;
+
+use deadpool_redis::redis::AsyncCommands as _;
+use dsc_webhook::{WebhookBody, WebhookBodyEmbed, WebhookBodyEmbedField};
+use entity::{event, event_edition, event_edition_records, players, records};
+use records_lib::{
+    Database, Expirable as _, RedisConnection, RedisPool, event as event_utils,
+    mappack::AnyMappackId,
+    redis_key::{event_edition_summary_key, mappack_lb_key},
+    time::Time,
+};
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait, EntityTrait as _, QueryFilter as _, QueryOrder as _,
+    QuerySelect as _, RelationTrait as _, prelude::DateTime,
+};
+
+/// The editions that expired longer than this amount of days ago aren't summarized.
+///
+/// This avoids posting the summary of all the past editions when the task is first run.
+const MAX_EXPIRED_SINCE_DAYS: i64 = 7;
+
+/// The color of the embeds of the summaries.
+const COLOR: u32 = 5814783;
+
+/// The state of the task posting the summaries.
+#[derive(Clone)]
+pub struct Notifier {
+    pub db: Database,
+    pub client: reqwest::Client,
+    pub webhook_url: String,
+}
+
+/// The world record of a map of an edition.
+struct WorldRecord {
+    map_name: String,
+    player_name: String,
+    time: i32,
+}
+
+/// The summary of an expired edition.
+struct EditionSummary {
+    winner: Option<String>,
+    participant_count: u64,
+    world_records: Vec<WorldRecord>,
+}
+
+/// Returns the editions that expired between the last [`MAX_EXPIRED_SINCE_DAYS`] days and `now`.
+async fn expired_editions<C: ConnectionTrait>(
+    conn: &C,
+    now: DateTime,
+) -> anyhow::Result<Vec<(event::Model, event_edition::Model)>> {
+    let min_expire_date = now - chrono::Duration::days(MAX_EXPIRED_SINCE_DAYS);
+
+    let editions = event_edition::Entity::find()
+        .filter(event_edition::Column::Ttl.is_not_null())
+        .find_also_related(event::Entity)
+        .all(conn)
+        .await?
+        .into_iter()
+        .filter_map(|(edition, event)| {
+            edition
+                .expire_date()
+                .is_some_and(|date| date > min_expire_date && date <= now)
+                .then_some((event?, edition))
+        })
+        .collect();
+
+    Ok(editions)
+}
+
+/// Returns the name of the player ranked first on the mappack of the edition.
+///
+/// It returns `None` if the scores of the mappack weren't computed.
+async fn winner_of<C: ConnectionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    event: &event::Model,
+    edition: &event_edition::Model,
+) -> anyhow::Result<Option<String>> {
+    let mappack = AnyMappackId::Event(event, edition);
+    let first: Vec<u32> = redis_conn.zrange(mappack_lb_key(mappack), 0, 0).await?;
+
+    let Some(player_id) = first.first() else {
+        return Ok(None);
+    };
+
+    let player = players::Entity::find_by_id(*player_id).one(conn).await?;
+    Ok(player.map(|player| player.name))
+}
+
+/// Returns the fastest record made in the edition on each of its maps.
+async fn world_records_of<C: ConnectionTrait>(
+    conn: &C,
+    edition: &event_edition::Model,
+) -> anyhow::Result<Vec<WorldRecord>> {
+    let maps = event_utils::event_edition_maps(conn, edition.event_id, edition.id).await?;
+
+    let mut world_records = Vec::with_capacity(maps.len());
+
+    for map in maps {
+        let record = records::Entity::find()
+            .inner_join(event_edition_records::Entity)
+            .join(
+                sea_orm::JoinType::InnerJoin,
+                records::Relation::Players.def(),
+            )
+            .filter(
+                event_edition_records::Column::EventId
+                    .eq(edition.event_id)
+                    .and(event_edition_records::Column::EditionId.eq(edition.id))
+                    .and(records::Column::MapId.eq(map.id))
+                    .and(records::Column::IsHidden.eq(false)),
+            )
+            .order_by_asc(records::Column::Time)
+            .order_by_asc(records::Column::RecordDate)
+            .select_only()
+            .column(players::Column::Name)
+            .column(records::Column::Time)
+            .into_tuple::<(String, i32)>()
+            .one(conn)
+            .await?;
+
+        if let Some((player_name, time)) = record {
+            world_records.push(WorldRecord {
+                map_name: map.name,
+                player_name,
+                time,
+            });
+        }
+    }
+
+    Ok(world_records)
+}
+
+async fn summarize<C: ConnectionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    event: &event::Model,
+    edition: &event_edition::Model,
+) -> anyhow::Result<EditionSummary> {
+    Ok(EditionSummary {
+        winner: winner_of(conn, redis_conn, event, edition).await?,
+        participant_count: event_utils::participant_count(conn, edition.event_id, edition.id)
+            .await?,
+        world_records: world_records_of(conn, edition).await?,
+    })
+}
+
+fn webhook_body(
+    event: &event::Model,
+    edition: &event_edition::Model,
+    summary: EditionSummary,
+) -> WebhookBody {
+    let world_records = if summary.world_records.is_empty() {
+        "None".to_owned()
+    } else {
+        summary
+            .world_records
+            .into_iter()
+            .map(|wr| {
+                format!(
+                    "`{}`: {} by `{}`",
+                    wr.map_name,
+                    Time(wr.time),
+                    wr.player_name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    WebhookBody {
+        content: format!(
+            "🏁 The edition {} of the event `{}` has finished.",
+            edition.id, event.handle
+        ),
+        embeds: vec![WebhookBodyEmbed {
+            title: edition.name.clone(),
+            description: edition.subtitle.clone(),
+            color: COLOR,
+            url: None,
+            fields: Some(vec![
+                WebhookBodyEmbedField {
+                    name: "Winner".to_owned(),
+                    value: summary
+                        .winner
+                        .map(|winner| format!("`{winner}`"))
+                        .unwrap_or_else(|| "None".to_owned()),
+                    inline: Some(true),
+                },
+                WebhookBodyEmbedField {
+                    name: "Participants".to_owned(),
+                    value: summary.participant_count.to_string(),
+                    inline: Some(true),
+                },
+                WebhookBodyEmbedField {
+                    name: "World records".to_owned(),
+                    value: world_records,
+                    inline: None,
+                },
+            ]),
+        }],
+    }
+}
+
+/// Sends the summary of each edition that expired before `now` with the provided function.
+///
+/// A summary is only sent once per edition, which is tracked with a marker in the Redis
+/// database. If sending it fails, the marker is removed so it's sent at the next call.
+///
+/// It returns the amount of sent summaries.
+async fn notify_expired_editions<C, F, Fut>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    now: DateTime,
+    mut send: F,
+) -> anyhow::Result<usize>
+where
+    C: ConnectionTrait,
+    F: FnMut(WebhookBody) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut redis_conn = redis_pool.get().await?;
+    let mut sent = 0;
+
+    for (event, edition) in expired_editions(conn, now).await? {
+        let marker = event_edition_summary_key(&event.handle, edition.id);
+
+        // Claim the edition before sending its summary, so it's only sent once even if
+        // multiple instances are running.
+        let claimed: bool = redis_conn.set_nx(&marker, 1).await?;
+        if !claimed {
+            continue;
+        }
+
+        let result = async {
+            let summary = summarize(conn, &mut redis_conn, &event, &edition).await?;
+            send(webhook_body(&event, &edition, summary)).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            let _: () = redis_conn.del(&marker).await?;
+            return Err(e.context(format!(
+                "When sending the summary of the edition {} of the event {}",
+                edition.id, event.handle
+            )));
+        }
+
+        tracing::info!(
+            "Sent the summary of the edition {} of the event {}",
+            edition.id,
+            event.handle
+        );
+        sent += 1;
+    }
+
+    Ok(sent)
+}
+
+pub async fn update(notifier: Notifier) -> anyhow::Result<()> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let sent = notify_expired_editions(
+        &notifier.db.sql_conn,
+        &notifier.db.redis_pool,
+        now,
+        |body| {
+            let req = notifier.client.post(&notifier.webhook_url).json(&body);
+            async move {
+                req.send().await?.error_for_status()?;
+                Ok(())
+            }
+        },
+    )
+    .await?;
+
+    tracing::info!("Sent {sent} edition summary(ies)");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::{
+        event, event_edition, event_edition_maps, event_edition_records, maps, players, records,
+    };
+    use sea_orm::{ActiveValue::Set, EntityTrait as _};
+
+    use super::notify_expired_editions;
+
+    #[tokio::test]
+    async fn summary_sent_once_after_expiry() -> anyhow::Result<()> {
+        let map_id = test_env::get_map_id();
+        // The ID of the map makes the event unique to this test
+        let event_handle = format!("event_{map_id}_handle");
+
+        let now = chrono::Utc::now().naive_utc();
+
+        let event = event::ActiveModel {
+            id: Set(1),
+            handle: Set(event_handle.clone()),
+            ..Default::default()
+        };
+
+        // The edition expires in one hour
+        let edition = event_edition::ActiveModel {
+            event_id: Set(1),
+            id: Set(1),
+            name: Set("event_1_1_name".to_owned()),
+            start_date: Set(now - chrono::Duration::hours(1)),
+            ttl: Set(Some(2 * 3600)),
+            is_transparent: Set(0),
+            save_non_event_record: Set(0),
+            non_original_maps: Set(0),
+            ..Default::default()
+        };
+
+        let players = (1..=2).map(|player_id| players::ActiveModel {
+            id: Set(player_id),
+            login: Set(format!("player_{player_id}_login")),
+            name: Set(format!("player_{player_id}_name")),
+            role: Set(0),
+            ..Default::default()
+        });
+
+        let map = maps::ActiveModel {
+            id: Set(map_id),
+            game_id: Set(format!("map_{map_id}_uid")),
+            name: Set(format!("map_{map_id}_name")),
+            player_id: Set(1),
+            ..Default::default()
+        };
+
+        let event_map = event_edition_maps::ActiveModel {
+            event_id: Set(1),
+            edition_id: Set(1),
+            map_id: Set(map_id),
+            order: Set(0),
+            ..Default::default()
+        };
+
+        let records = [(1, 6000), (2, 5000)].map(|(player_id, time)| records::ActiveModel {
+            record_id: Set(player_id),
+            record_player_id: Set(player_id),
+            map_id: Set(map_id),
+            time: Set(time),
+            respawn_count: Set(0),
+            flags: Set(682),
+            record_date: Set(now),
+            ..Default::default()
+        });
+
+        let event_records = (1..=2).map(|record_id| event_edition_records::ActiveModel {
+            record_id: Set(record_id),
+            event_id: Set(1),
+            edition_id: Set(1),
+        });
+
+        test_env::wrap(async |db| {
+            event::Entity::insert(event).exec(&db.sql_conn).await?;
+            event_edition::Entity::insert(edition)
+                .exec(&db.sql_conn)
+                .await?;
+            players::Entity::insert_many(players)
+                .exec(&db.sql_conn)
+                .await?;
+            maps::Entity::insert(map).exec(&db.sql_conn).await?;
+            event_edition_maps::Entity::insert(event_map)
+                .exec(&db.sql_conn)
+                .await?;
+            records::Entity::insert_many(records)
+                .exec(&db.sql_conn)
+                .await?;
+            event_edition_records::Entity::insert_many(event_records)
+                .exec(&db.sql_conn)
+                .await?;
+
+            let mut bodies = Vec::new();
+
+            // The edition hasn't expired yet
+            let sent = notify_expired_editions(&db.sql_conn, &db.redis_pool, now, |body| {
+                bodies.push(body);
+                async { Ok(()) }
+            })
+            .await?;
+            assert_eq!(sent, 0);
+
+            // The edition crosses its expiry, and its summary is only sent once
+            for _ in 0..2 {
+                notify_expired_editions(
+                    &db.sql_conn,
+                    &db.redis_pool,
+                    now + chrono::Duration::hours(2),
+                    |body| {
+                        bodies.push(body);
+                        async { Ok(()) }
+                    },
+                )
+                .await?;
+            }
+
+            assert_eq!(bodies.len(), 1);
+            assert!(bodies[0].content.contains(&event_handle));
+
+            let fields = bodies[0].embeds[0]
+                .fields
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("the summary should have fields"))?;
+            let field = |name: &str| {
+                fields
+                    .iter()
+                    .find(|field| field.name == name)
+                    .map(|field| field.value.as_str())
+            };
+            assert_eq!(field("Participants"), Some("2"));
+            assert!(
+                field("World records").is_some_and(|wrs| wrs.contains("`player_2_name`")),
+                "{fields:?}"
+            );
+
+            anyhow::Ok(())
+        })
+        .await
+    }
+}
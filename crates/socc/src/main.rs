@@ -4,37 +4,134 @@
 //! in the database to calculate the scores in certain contexts, then store the results in the Redis
 //! database.
 
-use std::{future::Future, time::Duration};
+use std::{future::Future, num::NonZeroU64, time::Duration};
 
 use anyhow::Context;
 use mkenv::prelude::*;
 use records_lib::{Database, DbEnv, LibEnv};
-use tokio::{task::JoinHandle, time};
-use tracing::info;
+use tokio::{signal, task::JoinSet, time};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument as _, error, info, info_span};
 
 mod campaign_scores;
+mod edition_summary;
 mod player_ranking;
+mod progress;
 
-async fn handle<F, Fut>(db: Database, period: Duration, f: F) -> anyhow::Result<()>
+/// The scheduling of a task run by the [`handle`] function.
+#[derive(Clone, Copy)]
+struct Schedule {
+    /// The period between two calls.
+    period: Duration,
+    /// The upper bound of the random delay before the first call.
+    ///
+    /// This keeps the tasks from running their heavy queries at the same time.
+    max_start_jitter: Duration,
+    /// The amount of consecutive failed calls after which the task stops.
+    max_consecutive_failures: Option<u32>,
+}
+
+/// Calls the function periodically with a clone of the provided state, until the token is
+/// cancelled.
+///
+/// The first call is delayed by a random duration, bounded by the jitter of the schedule.
+///
+/// The cancellation is only checked between two calls, so an in-flight computation is always
+/// finished, and never leaves the Redis keys half-written.
+///
+/// A failed call is logged, and the next one happens at the next tick. If a maximum amount of
+/// consecutive failures is provided, the error of the call reaching it is returned.
+async fn handle<S, F, Fut>(
+    state: S,
+    schedule: Schedule,
+    cancel: CancellationToken,
+    f: F,
+) -> anyhow::Result<()>
 where
-    F: Fn(Database) -> Fut,
+    S: Clone,
+    F: Fn(S) -> Fut,
     Fut: Future<Output = anyhow::Result<()>>,
 {
-    let mut interval = time::interval(period);
+    let start_jitter = rand::random_range(Duration::ZERO..=schedule.max_start_jitter);
+    let mut interval = time::interval_at(time::Instant::now() + start_jitter, schedule.period);
+    let mut failures = 0;
 
     loop {
-        interval.tick().await;
-        f(db.clone()).await?;
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Ok(()),
+            _ = interval.tick() => (),
+        }
+
+        match f(state.clone()).await {
+            Ok(()) => failures = 0,
+            Err(e) => {
+                failures += 1;
+                if schedule
+                    .max_consecutive_failures
+                    .is_some_and(|max| failures >= max)
+                {
+                    return Err(e.context(format!("After {failures} consecutive failures")));
+                }
+                error!("Update failed ({failures} consecutive failure(s)): {e:?}");
+            }
+        }
     }
 }
 
+/// Waits for a Ctrl-C, or a SIGTERM on unix.
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate())
+            .context("When installing the SIGTERM handler")?;
+
+        tokio::select! {
+            res = signal::ctrl_c() => res.context("When listening for Ctrl-C")?,
+            _ = sigterm.recv() => (),
+        }
+    }
+
+    #[cfg(not(unix))]
+    signal::ctrl_c()
+        .await
+        .context("When listening for Ctrl-C")?;
+
+    Ok(())
+}
+
 #[inline]
-async fn join<O>(
-    task: JoinHandle<anyhow::Result<O>>,
-    join_ctx: &'static str,
-    task_ctx: &'static str,
-) -> anyhow::Result<O> {
-    task.await.context(join_ctx)?.context(task_ctx)
+async fn with_context<F>(task: F, task_ctx: &'static str) -> anyhow::Result<()>
+where
+    F: Future<Output = anyhow::Result<()>>,
+{
+    task.await.context(task_ctx)
+}
+
+/// Waits for all the tasks to finish.
+///
+/// As soon as a task fails, the token is cancelled so that the other tasks stop after their
+/// in-flight computation, and the error of the first failed task is returned once they're done.
+async fn join_all(
+    mut tasks: JoinSet<anyhow::Result<()>>,
+    cancel: &CancellationToken,
+) -> anyhow::Result<()> {
+    let mut result = Ok(());
+
+    while let Some(res) = tasks.join_next().await {
+        let Err(e) = res.context("When joining a task").and_then(|res| res) else {
+            continue;
+        };
+
+        if result.is_ok() {
+            cancel.cancel();
+            result = Err(e);
+        } else {
+            error!("{e:?}");
+        }
+    }
+
+    result
 }
 
 fn setup_tracing() -> anyhow::Result<()> {
@@ -48,6 +145,78 @@ mkenv::make_config! {
     struct Env {
         db_env: { DbEnv },
         lib_env: { LibEnv },
+
+        max_consecutive_failures: {
+            var_name: "SOCC_MAX_CONSECUTIVE_FAILURES",
+            layers: [
+                parsed<Option<u32>>(|input| input.parse().map(Some).map_err(From::from)),
+                or_default(),
+            ],
+            description: "The amount of consecutive failed updates after which a task stops \
+                and the service exits",
+            default_val_fmt: "never",
+        },
+
+        max_start_jitter: {
+            var_name: "SOCC_MAX_START_JITTER_SECONDS",
+            layers: [
+                parsed<Duration>(|input| {
+                    input.parse().map(Duration::from_secs).map_err(From::from)
+                }),
+                or_default_val(|| Duration::from_secs(30)),
+            ],
+            description: "The upper bound of the random delay before the first update of each \
+                task, in seconds",
+            default_val_fmt: "30s",
+        },
+
+        player_ranking_all_time: {
+            var_name: "SOCC_PLAYER_RANKING_ALL_TIME",
+            layers: [
+                parsed_from_str<bool>(),
+                or_default_val(|| false),
+            ],
+            description: "Whether the player and map ranking takes all the records into account \
+                and is updated incrementally (true), or only takes the records saved during the \
+                last update interval into account and is recomputed at each update (false) \
+                (boolean)",
+            default_val_fmt: "false",
+        },
+
+        player_ranking_max_maps: {
+            var_name: "SOCC_PLAYER_RANKING_MAX_MAPS",
+            layers: [
+                parsed<Option<NonZeroU64>>(|input| input.parse().map(Some).map_err(From::from)),
+                or_default(),
+            ],
+            description: "The maximum amount of maps processed by a single update of the player \
+                and map ranking when computing it from scratch with all the records, greater \
+                than 0. The computation is then split across several updates",
+            default_val_fmt: "no limit",
+        },
+
+        edition_summary_webhook_url: {
+            var_name: "WEBHOOK_EDITION_SUMMARY_URL",
+            layers: [
+                parsed<Option<String>>(|s| Ok(Some(s.to_owned()))),
+                or_default(),
+            ],
+            description: "The URL to the Discord webhook used to send the summary of the event \
+                editions once they expire. If empty, no summary is sent",
+            default_val_fmt: "empty",
+        },
+
+        edition_summary_interval: {
+            var_name: "SOCC_EDITION_SUMMARY_INTERVAL_SECONDS",
+            layers: [
+                parsed<Duration>(|input| {
+                    input.parse().map(Duration::from_secs).map_err(From::from)
+                }),
+                or_default_val(|| Duration::from_secs(600)),
+            ],
+            description: "The interval of the check of the expired event editions, in seconds",
+            default_val_fmt: "10min",
+        },
     }
 }
 
@@ -57,48 +226,297 @@ async fn main() -> anyhow::Result<()> {
     setup_tracing()?;
     let env = Env::define();
     env.init();
-    let event_scores_interval = env.lib_env.event_scores_interval.get();
-    let player_ranking_scores_interval = env.lib_env.player_map_ranking_scores_interval.get();
+    let event_scores_schedule = Schedule {
+        period: env.lib_env.event_scores_interval.get(),
+        max_start_jitter: env.max_start_jitter.get(),
+        max_consecutive_failures: env.max_consecutive_failures.get(),
+    };
+    let player_ranking_schedule = Schedule {
+        period: env.lib_env.player_map_ranking_scores_interval.get(),
+        ..event_scores_schedule
+    };
+    let edition_summary_schedule = Schedule {
+        period: env.edition_summary_interval.get(),
+        ..event_scores_schedule
+    };
+    let edition_summary_webhook_url = env.edition_summary_webhook_url.get();
+    let player_ranking_window = (!env.player_ranking_all_time.get())
+        .then(|| env.lib_env.player_map_ranking_scores_interval.get());
+    let player_ranking_max_maps = env.player_ranking_max_maps.get();
     records_lib::init_env(env.lib_env);
 
     let db = Database::from_db_url(
         env.db_env.db_url.db_url.get(),
+        env.db_env.db_url.replica_db_url.get(),
         env.db_env.redis_url.redis_url.get(),
+        env.db_env.redis_url.redis_health_check_interval.get(),
     )
     .await?;
 
-    let event_scores_handle = tokio::spawn(handle(
-        db.clone(),
-        event_scores_interval,
-        campaign_scores::update,
-    ));
-
-    let player_map_ranking_handle = tokio::spawn(handle(
-        db.clone(),
-        player_ranking_scores_interval,
-        move |db| {
-            player_ranking::update(
-                db,
-                Some(chrono::Utc::now() - player_ranking_scores_interval),
+    let cancel = CancellationToken::new();
+
+    let mut tasks = JoinSet::new();
+
+    tasks.spawn(
+        with_context(
+            handle(
+                db.clone(),
+                event_scores_schedule,
+                cancel.clone(),
+                campaign_scores::update,
+            ),
+            "When updating campaign scores",
+        )
+        .instrument(info_span!("campaign_scores")),
+    );
+
+    tasks.spawn(
+        with_context(
+            handle(
+                player_ranking::Updater {
+                    db: db.clone(),
+                    window: player_ranking_window,
+                    max_maps: player_ranking_max_maps,
+                },
+                player_ranking_schedule,
+                cancel.clone(),
+                player_ranking::update,
+            ),
+            "When updating player and map ranking scores",
+        )
+        .instrument(info_span!("player_ranking")),
+    );
+
+    if let Some(webhook_url) = edition_summary_webhook_url {
+        tasks.spawn(
+            with_context(
+                handle(
+                    edition_summary::Notifier {
+                        db: db.clone(),
+                        client: reqwest::Client::new(),
+                        webhook_url,
+                    },
+                    edition_summary_schedule,
+                    cancel.clone(),
+                    edition_summary::update,
+                ),
+                "When sending the summary of the expired event editions",
             )
-        },
-    ));
+            .instrument(info_span!("edition_summary")),
+        );
+    }
+
+    let shutdown_cancel = cancel.clone();
+    tokio::spawn(async move {
+        if let Err(e) = shutdown_signal().await {
+            error!("{e:?}");
+        }
+        info!("Shutting down, waiting for the in-flight computations to finish...");
+        shutdown_cancel.cancel();
+    });
 
     info!("Spawned all tasks");
 
-    join(
-        event_scores_handle,
-        "When joining the campaign_scores::update task",
-        "When updating campaign scores",
-    )
-    .await?;
+    join_all(tasks, &cancel).await?;
 
-    join(
-        player_map_ranking_handle,
-        "When joining the player_ranking::update task",
-        "When updating player and map ranking scores",
-    )
-    .await?;
+    info!("All tasks finished");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc, OnceLock,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use tokio::{
+        task::JoinSet,
+        time::{self, Instant},
+    };
+    use tokio_util::sync::CancellationToken;
+
+    use super::{Schedule, handle, join_all};
+
+    fn schedule(max_consecutive_failures: Option<u32>) -> Schedule {
+        Schedule {
+            period: Duration::from_millis(10),
+            max_start_jitter: Duration::ZERO,
+            max_consecutive_failures,
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Calls {
+        started: Arc<AtomicUsize>,
+        finished: Arc<AtomicUsize>,
+    }
+
+    #[tokio::test]
+    async fn handle_exits_when_cancelled() -> anyhow::Result<()> {
+        let calls = Calls::default();
+        let cancel = CancellationToken::new();
+
+        let task = tokio::spawn(handle(
+            calls.clone(),
+            schedule(None),
+            cancel.clone(),
+            |calls: Calls| async move {
+                calls.started.fetch_add(1, Ordering::SeqCst);
+                time::sleep(Duration::from_millis(30)).await;
+                calls.finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        ));
+
+        // Cancel in the middle of a computation
+        time::sleep(Duration::from_millis(50)).await;
+        cancel.cancel();
+
+        time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("the handle loop should exit once cancelled")??;
+
+        let started = calls.started.load(Ordering::SeqCst);
+        assert!(started > 0);
+        // The in-flight computation was finished before exiting
+        assert_eq!(calls.finished.load(Ordering::SeqCst), started);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn handle_continues_after_failure() -> anyhow::Result<()> {
+        let calls = Calls::default();
+        let cancel = CancellationToken::new();
+
+        let task = tokio::spawn(handle(
+            calls.clone(),
+            schedule(Some(2)),
+            cancel.clone(),
+            |calls: Calls| async move {
+                // Only the first call fails
+                if calls.started.fetch_add(1, Ordering::SeqCst) == 0 {
+                    anyhow::bail!("transient failure");
+                }
+                calls.finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        ));
+
+        time::sleep(Duration::from_millis(50)).await;
+        assert!(!task.is_finished(), "the handle loop should keep running");
+        cancel.cancel();
+
+        time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("the handle loop should exit once cancelled")??;
+
+        assert!(calls.finished.load(Ordering::SeqCst) > 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn handle_stops_after_consecutive_failures() {
+        let calls = Calls::default();
+
+        let result = time::timeout(
+            Duration::from_secs(1),
+            handle(
+                calls.clone(),
+                schedule(Some(3)),
+                CancellationToken::new(),
+                |calls: Calls| async move {
+                    calls.started.fetch_add(1, Ordering::SeqCst);
+                    Err::<(), _>(anyhow::anyhow!("persistent failure"))
+                },
+            ),
+        )
+        .await
+        .expect("the handle loop should stop after 3 failures");
+
+        assert!(result.is_err());
+        assert_eq!(calls.started.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn handle_start_jitter() -> anyhow::Result<()> {
+        const MAX_START_JITTER: Duration = Duration::from_millis(200);
+
+        let schedule = Schedule {
+            period: Duration::from_secs(60),
+            max_start_jitter: MAX_START_JITTER,
+            max_consecutive_failures: None,
+        };
+        let cancel = CancellationToken::new();
+        let start = Instant::now();
+
+        let tasks = [0, 1].map(|_| {
+            let first_tick = Arc::new(OnceLock::new());
+            let task = tokio::spawn(handle(
+                first_tick.clone(),
+                schedule,
+                cancel.clone(),
+                |first_tick: Arc<OnceLock<Instant>>| async move {
+                    let _ = first_tick.set(Instant::now());
+                    Ok(())
+                },
+            ));
+            (task, first_tick)
+        });
+
+        time::sleep(MAX_START_JITTER + Duration::from_millis(100)).await;
+        cancel.cancel();
+
+        let mut first_ticks = Vec::with_capacity(tasks.len());
+        for (task, first_tick) in tasks {
+            task.await??;
+            let first_tick = *first_tick.get().expect("the task should have ticked");
+            // Leave some room for the scheduling of the task
+            assert!(first_tick - start <= MAX_START_JITTER + Duration::from_millis(50));
+            first_ticks.push(first_tick);
+        }
+
+        assert_ne!(first_ticks[0], first_ticks[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_all_cancels_on_first_error() {
+        let calls = Calls::default();
+        let cancel = CancellationToken::new();
+        let mut tasks = JoinSet::new();
+
+        // This task only stops once cancelled
+        tasks.spawn(handle(
+            calls.clone(),
+            schedule(None),
+            cancel.clone(),
+            |calls: Calls| async move {
+                calls.finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        ));
+        tasks.spawn(async {
+            time::sleep(Duration::from_millis(30)).await;
+            Err::<(), _>(anyhow::anyhow!("fatal failure"))
+        });
+
+        let result = time::timeout(Duration::from_secs(1), join_all(tasks, &cancel))
+            .await
+            .expect("the other tasks should stop after the first error");
+
+        assert_eq!(
+            result.map_err(|e| e.to_string()),
+            Err("fatal failure".to_owned())
+        );
+        assert!(cancel.is_cancelled());
+        assert!(calls.finished.load(Ordering::SeqCst) > 0);
+    }
+}
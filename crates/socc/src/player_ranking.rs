@@ -1,33 +1,98 @@
+use std::{collections::HashMap, num::NonZeroU64, time::Duration};
+
 use anyhow::Context as _;
 use chrono::{DateTime, Utc};
-use deadpool_redis::redis;
+use deadpool_redis::redis::{self, AsyncCommands as _};
 use entity::{maps, players};
-use player_map_ranking::compute_scores;
+use player_map_ranking::{
+    MapsRange, PriorScores, Scores, compute_scores, compute_scores_chunk,
+    compute_scores_incremental, estimate_cost,
+};
 use records_lib::{
-    Database, RedisPool,
-    redis_key::{map_ranking, player_ranking},
+    Database, RedisConnection, RedisPool,
+    redis_key::{
+        map_ranking, player_map_ranking, player_ranking, player_ranking_chunk_offset,
+        player_ranking_chunk_scores, player_ranking_chunk_started_at, player_ranking_last_update,
+    },
     sync,
 };
 use sea_orm::{
-    ColumnTrait as _, ConnectionTrait, EntityTrait, QueryFilter, TransactionTrait, prelude::Expr,
-    sea_query::CaseStatement,
+    ColumnTrait as _, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect as _,
+    TransactionTrait, prelude::Expr, sea_query::CaseStatement,
 };
 
-async fn do_update<C: ConnectionTrait + TransactionTrait>(
-    conn: &C,
-    redis_pool: &RedisPool,
-    from: Option<DateTime<Utc>>,
-) -> anyhow::Result<()> {
-    let scores = compute_scores(conn, from)
+/// Returns the scores of each player on the provided maps, and the total scores of the provided
+/// players, saved by the previous update.
+async fn load_prior_scores(
+    redis_conn: &mut RedisConnection,
+    map_ids: &[u32],
+    player_ids: &[u32],
+) -> anyhow::Result<PriorScores> {
+    let mut pipe = redis::pipe();
+    for player_id in player_ids {
+        pipe.zscore(player_ranking(), player_id);
+    }
+    let player_scores: Vec<Option<f64>> = pipe
+        .query_async(redis_conn)
         .await
-        .context("couldn't compute the scores")?;
+        .context("couldn't get the prior player scores")?;
 
-    let mut redis_conn = redis_pool
-        .get()
+    let mut pipe = redis::pipe();
+    for map_id in map_ids {
+        pipe.zrange_withscores(player_map_ranking(*map_id), 0, -1);
+    }
+    let player_map_scores: Vec<Vec<(u32, f64)>> = pipe
+        .query_async(redis_conn)
         .await
-        .context("couldn't get redis connection")?;
+        .context("couldn't get the prior player scores on the maps")?;
 
-    let mut pipe = redis::pipe();
+    Ok(PriorScores {
+        player_scores: player_ids
+            .iter()
+            .zip(player_scores)
+            .filter_map(|(player_id, score)| score.map(|score| (*player_id, score)))
+            .collect(),
+        player_map_scores: map_ids
+            .iter()
+            .zip(player_map_scores)
+            .map(|(map_id, scores)| (*map_id, scores.into_iter().collect()))
+            .collect(),
+    })
+}
+
+/// The scores to save, by player ID and by map ID.
+struct ScoresUpdate {
+    player_scores: Vec<(u32, f64)>,
+    map_scores: Vec<(u32, f64)>,
+    player_map_scores: HashMap<u32, HashMap<u32, f64>>,
+}
+
+impl From<Scores> for ScoresUpdate {
+    fn from(scores: Scores) -> Self {
+        Self {
+            player_scores: scores
+                .player_scores
+                .into_iter()
+                .map(|(player, score)| (player.inner.id, score))
+                .collect(),
+            map_scores: scores
+                .map_scores
+                .into_iter()
+                .map(|(map, score)| (map.inner.id, score))
+                .collect(),
+            player_map_scores: scores.player_map_scores,
+        }
+    }
+}
+
+/// Saves the scores to the database and to Redis, along with the commands already queued in
+/// the provided pipeline.
+async fn save_scores<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    scores: ScoresUpdate,
+    pipe: &mut redis::Pipeline,
+) -> anyhow::Result<()> {
     let pipe = pipe.atomic();
 
     // To make the bulk update in SQL, we build a query so it looks like this:
@@ -43,50 +108,57 @@ async fn do_update<C: ConnectionTrait + TransactionTrait>(
     // to u16::MAX. Therefore, we must chunk our query accordingly. Each item gets 3 placeholders:
     // the score, and the ID twice; so the chunk size is u16::MAX / 3.
 
-    let player_scores = scores
+    for (player_id, score) in &scores.player_scores {
+        pipe.zadd(player_ranking(), player_id, score);
+    }
+    for (map_id, score) in &scores.map_scores {
+        pipe.zadd(map_ranking(), map_id, score);
+    }
+
+    for (map_id, map_player_scores) in &scores.player_map_scores {
+        pipe.del(player_map_ranking(*map_id));
+        if !map_player_scores.is_empty() {
+            let map_player_scores = map_player_scores
+                .iter()
+                .map(|(player_id, score)| (*score, *player_id))
+                .collect::<Vec<_>>();
+            pipe.zadd_multiple(player_map_ranking(*map_id), &map_player_scores);
+        }
+    }
+
+    let player_updates = scores
         .player_scores
-        .into_iter()
-        .map(|(player, score)| {
-            pipe.zadd(player_ranking(), player.inner.id, score);
-            (player, score)
-        })
-        .collect::<Vec<_>>();
-    let map_scores = scores
+        .chunks(u16::MAX as usize / 3)
+        .map(|chunk| {
+            (
+                chunk.iter().map(|(player_id, _)| *player_id),
+                chunk
+                    .iter()
+                    .fold(CaseStatement::new(), |case_stmt, (player_id, score)| {
+                        case_stmt.case(
+                            Expr::col((players::Entity, players::Column::Id)).eq(*player_id),
+                            *score,
+                        )
+                    }),
+            )
+        });
+
+    let map_updates = scores
         .map_scores
-        .into_iter()
-        .map(|(map, score)| {
-            pipe.zadd(map_ranking(), map.inner.id, score);
-            (map, score)
-        })
-        .collect::<Vec<_>>();
-
-    let player_updates = player_scores.chunks(u16::MAX as usize / 3).map(|chunk| {
-        (
-            chunk.iter().map(|(player, _)| player.inner.id),
-            chunk
-                .iter()
-                .fold(CaseStatement::new(), |case_stmt, (player, score)| {
-                    case_stmt.case(
-                        Expr::col((players::Entity, players::Column::Id)).eq(player.inner.id),
-                        *score,
-                    )
-                }),
-        )
-    });
-
-    let map_updates = map_scores.chunks(u16::MAX as usize / 3).map(|chunk| {
-        (
-            chunk.iter().map(|(map, _)| map.inner.id),
-            chunk
-                .iter()
-                .fold(CaseStatement::new(), |case_stmt, (map, score)| {
-                    case_stmt.case(
-                        Expr::col((maps::Entity, maps::Column::Id)).eq(map.inner.id),
-                        *score,
-                    )
-                }),
-        )
-    });
+        .chunks(u16::MAX as usize / 3)
+        .map(|chunk| {
+            (
+                chunk.iter().map(|(map_id, _)| *map_id),
+                chunk
+                    .iter()
+                    .fold(CaseStatement::new(), |case_stmt, (map_id, score)| {
+                        case_stmt.case(
+                            Expr::col((maps::Entity, maps::Column::Id)).eq(*map_id),
+                            *score,
+                        )
+                    }),
+            )
+        });
 
     sync::transaction(conn, async |txn| {
         for (player_ids, case_stmt) in player_updates {
@@ -109,15 +181,215 @@ async fn do_update<C: ConnectionTrait + TransactionTrait>(
     })
     .await?;
 
-    pipe.exec_async(&mut redis_conn)
+    pipe.exec_async(redis_conn)
         .await
         .context("couldn't save scores to Redis")?;
 
     Ok(())
 }
 
-pub async fn update(db: Database, from: Option<DateTime<Utc>>) -> anyhow::Result<()> {
-    let res = do_update(&db.sql_conn, &db.redis_pool, from).await;
+/// Processes the next chunk of at most `max_maps` maps of a computation from scratch, and saves
+/// the player ranking once all the maps were processed.
+///
+/// The progress is saved in Redis, so the computation resumes at the next cycle. Meanwhile, the
+/// scores of the maps are saved as they're processed, but the player ranking keeps its previous
+/// value.
+async fn update_chunk<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    max_maps: NonZeroU64,
+) -> anyhow::Result<()> {
+    let (offset, started_at): (Option<u64>, Option<i64>) = redis::pipe()
+        .get(player_ranking_chunk_offset())
+        .get(player_ranking_chunk_started_at())
+        .query_async(redis_conn)
+        .await
+        .context("couldn't get the progress of the computation")?;
+
+    let mut pipe = redis::pipe();
+
+    let (offset, started_at, prior_scores) = match offset.zip(started_at) {
+        Some((offset, started_at)) => {
+            let prior_scores: Vec<(u32, f64)> = redis_conn
+                .zrange_withscores(player_ranking_chunk_scores(), 0, -1)
+                .await
+                .context("couldn't get the partial player scores")?;
+            (offset, started_at, prior_scores)
+        }
+        None => {
+            // Drops the leftovers of a previous computation
+            pipe.del(player_ranking_chunk_scores());
+            (0, Utc::now().timestamp(), Vec::new())
+        }
+    };
+
+    let scores = compute_scores_chunk(
+        conn,
+        None,
+        Default::default(),
+        MapsRange {
+            offset,
+            max_maps: Some(max_maps.get()),
+        },
+    )
+    .await?;
+
+    let processed = scores.map_scores.len() as u64;
+    tracing::info!(
+        "Processed the maps {offset} to {} of the computation from scratch",
+        offset + processed
+    );
+
+    let mut scores = ScoresUpdate::from(scores);
+
+    if processed < max_maps.get() {
+        // This was the last chunk, so the scores of all the players are known
+        let mut player_scores = players::Entity::find()
+            .select_only()
+            .column(players::Column::Id)
+            .into_tuple::<u32>()
+            .all(conn)
+            .await
+            .context("couldn't retrieve all players")?
+            .into_iter()
+            .map(|player_id| (player_id, 0.))
+            .collect::<HashMap<_, _>>();
+        for (player_id, score) in prior_scores.into_iter().chain(scores.player_scores) {
+            *player_scores.entry(player_id).or_insert(0.) += score;
+        }
+        scores.player_scores = player_scores.into_iter().collect();
+
+        pipe.set(player_ranking_last_update(), started_at)
+            .del(player_ranking_chunk_offset())
+            .del(player_ranking_chunk_started_at())
+            .del(player_ranking_chunk_scores());
+    } else {
+        for (player_id, score) in std::mem::take(&mut scores.player_scores) {
+            pipe.zincr(player_ranking_chunk_scores(), player_id, score);
+        }
+
+        pipe.set(player_ranking_chunk_offset(), offset + processed)
+            .set(player_ranking_chunk_started_at(), started_at);
+    }
+
+    save_scores(conn, redis_conn, scores, &mut pipe).await
+}
+
+/// Recomputes the scores with the records saved during the provided window only.
+///
+/// The state of the all-time updates is dropped, as the saved scores don't cover all the records
+/// anymore.
+async fn update_window<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    redis_conn: &mut RedisConnection,
+    window: Duration,
+) -> anyhow::Result<()> {
+    let from = Utc::now() - window;
+    tracing::info!("Computing the scores of the records saved since {from}");
+
+    let scores = compute_scores(conn, Some(from), Default::default())
+        .await
+        .context("couldn't compute the scores")?;
+
+    let mut pipe = redis::pipe();
+    pipe.del(player_ranking_last_update())
+        .del(player_ranking_chunk_offset())
+        .del(player_ranking_chunk_started_at())
+        .del(player_ranking_chunk_scores());
+
+    save_scores(conn, redis_conn, scores.into(), &mut pipe).await
+}
+
+async fn do_update<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    redis_pool: &RedisPool,
+    window: Option<Duration>,
+    max_maps: Option<NonZeroU64>,
+) -> anyhow::Result<()> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .context("couldn't get redis connection")?;
+
+    if let Some(window) = window {
+        return update_window(conn, &mut redis_conn, window).await;
+    }
+
+    let started_at = Utc::now();
+
+    match estimate_cost(conn).await {
+        Ok(cost) => tracing::info!(
+            "Estimated cost of a full computation: {} maps, {} records",
+            cost.map_count,
+            cost.record_count
+        ),
+        Err(e) => tracing::warn!("Couldn't estimate the cost of the computation: {e:#}"),
+    }
+
+    let last_update: Option<i64> = redis_conn
+        .get(player_ranking_last_update())
+        .await
+        .context("couldn't get the date of the last update")?;
+
+    let scores = match (
+        last_update.and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)),
+        max_maps,
+    ) {
+        (Some(since), _) => {
+            tracing::info!("Updating the scores of the maps with new records since {since}");
+            compute_scores_incremental(
+                conn,
+                since,
+                Default::default(),
+                async |map_ids, player_ids| {
+                    load_prior_scores(&mut redis_conn, map_ids, player_ids).await
+                },
+            )
+            .await
+        }
+        (None, Some(max_maps)) => {
+            return update_chunk(conn, &mut redis_conn, max_maps)
+                .await
+                .context("couldn't compute the scores of the next chunk of maps");
+        }
+        (None, None) => compute_scores(conn, None, Default::default()).await,
+    }
+    .context("couldn't compute the scores")?;
+
+    // The progress of a computation in chunks is dropped, in case the maximum amount of maps
+    // was removed in the meantime
+    let mut pipe = redis::pipe();
+    pipe.set(player_ranking_last_update(), started_at.timestamp())
+        .del(player_ranking_chunk_offset())
+        .del(player_ranking_chunk_started_at())
+        .del(player_ranking_chunk_scores());
+
+    save_scores(conn, &mut redis_conn, scores.into(), &mut pipe).await
+}
+
+/// The state of the task updating the player and map ranking.
+#[derive(Clone)]
+pub struct Updater {
+    pub db: Database,
+    /// The duration of the window of the records taken into account, or `None` to take all the
+    /// records into account.
+    ///
+    /// With a window, the scores are recomputed from scratch at each update. Otherwise, only the
+    /// maps with new records are recomputed once the scores were computed a first time.
+    pub window: Option<Duration>,
+    /// The maximum amount of maps processed by a single update when computing the scores of all
+    /// the records from scratch, or `None` to process all of them at once.
+    pub max_maps: Option<NonZeroU64>,
+}
+
+pub async fn update(updater: Updater) -> anyhow::Result<()> {
+    let res = do_update(
+        &updater.db.sql_conn,
+        &updater.db.redis_pool,
+        updater.window,
+        updater.max_maps,
+    )
+    .await;
 
     match &res {
         Ok(_) => tracing::info!("Player and map ranking update completed successfully"),
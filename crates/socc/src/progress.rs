@@ -0,0 +1,154 @@
+//! The logging of the progress of the updates, for the operators to see they're still running.
+
+use std::time::{Duration, Instant};
+
+use records_lib::time::Time;
+
+/// The amount of processed items after which the progress is logged.
+pub const LOG_EVERY_ITEMS: usize = 50;
+
+/// The duration after which the progress is logged, even if less items were processed.
+pub const LOG_EVERY: Duration = Duration::from_secs(30);
+
+/// Returns the time elapsed since the provided instant, to be displayed in the logs.
+pub fn elapsed_since(start: Instant) -> Time {
+    Time(start.elapsed().as_millis().try_into().unwrap_or(i32::MAX))
+}
+
+/// Logs the progress of the processing of a known amount of items.
+///
+/// The progress is logged every `every_items` items or every `every` duration, whichever comes
+/// first, to avoid spamming the logs.
+pub struct Progress {
+    what: &'static str,
+    total: usize,
+    processed: usize,
+    every_items: usize,
+    every: Duration,
+    start: Instant,
+    last_log: Instant,
+}
+
+impl Progress {
+    /// Starts the processing of `total` items, with the default logging cadence.
+    pub fn new(what: &'static str, total: usize) -> Self {
+        Self::with_cadence(what, total, LOG_EVERY_ITEMS, LOG_EVERY)
+    }
+
+    /// Starts the processing of `total` items, with the provided logging cadence.
+    pub fn with_cadence(
+        what: &'static str,
+        total: usize,
+        every_items: usize,
+        every: Duration,
+    ) -> Self {
+        tracing::info!("Processing {total} {what}");
+        let now = Instant::now();
+        Self {
+            what,
+            total,
+            processed: 0,
+            every_items: every_items.max(1),
+            every,
+            start: now,
+            last_log: now,
+        }
+    }
+
+    /// Marks an item as processed, and logs the progress if needed.
+    pub fn inc(&mut self) {
+        self.processed += 1;
+
+        let now = Instant::now();
+        if self.processed % self.every_items == 0 || now - self.last_log >= self.every {
+            tracing::info!("Processed {}/{} {}", self.processed, self.total, self.what);
+            self.last_log = now;
+        }
+    }
+
+    /// Logs the summary of the processing.
+    pub fn finish(self) {
+        tracing::info!(
+            "Processed {}/{} {} in {}",
+            self.processed,
+            self.total,
+            self.what,
+            elapsed_since(self.start)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::Progress;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .without_time()
+            .with_writer({
+                let capture = capture.clone();
+                move || capture.clone()
+            })
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        let out = capture.0.lock().unwrap();
+        String::from_utf8_lossy(&out)
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn progress_logs_start_and_finish() {
+        let logs = capture_logs(|| {
+            let mut progress = Progress::with_cadence("mappacks", 5, 2, Duration::MAX);
+            for _ in 0..5 {
+                progress.inc();
+            }
+            progress.finish();
+        });
+
+        assert_eq!(logs.len(), 4, "{logs:#?}");
+        assert!(logs[0].ends_with("Processing 5 mappacks"));
+        assert!(logs[1].ends_with("Processed 2/5 mappacks"));
+        assert!(logs[2].ends_with("Processed 4/5 mappacks"));
+        assert!(logs[3].contains("Processed 5/5 mappacks in "));
+    }
+
+    #[test]
+    fn progress_logs_after_duration() {
+        let logs = capture_logs(|| {
+            let mut progress = Progress::with_cadence("mappacks", 2, usize::MAX, Duration::ZERO);
+            progress.inc();
+            progress.inc();
+            progress.finish();
+        });
+
+        assert_eq!(logs.len(), 4, "{logs:#?}");
+        assert!(logs[1].ends_with("Processed 1/2 mappacks"));
+    }
+}
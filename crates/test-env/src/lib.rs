@@ -105,7 +105,11 @@ where
 
     let r = panic::AssertUnwindSafe(test(Database {
         sql_conn: db,
-        redis_pool: get_redis_pool(env.redis_url.redis_url.get())?,
+        replica_sql_conn: None,
+        redis_pool: get_redis_pool(
+            env.redis_url.redis_url.get(),
+            env.redis_url.redis_health_check_interval.get(),
+        )?,
     }))
     .catch_unwind()
     .await;